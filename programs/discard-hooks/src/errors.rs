@@ -206,4 +206,422 @@ pub enum HookError {
 
     #[msg("Invalid Inco attestation: TEE verification failed")]
     InvalidIncoAttestation,
+
+    // ========================================================================
+    // Errors added after the first deployment. Anchor numbers variants in
+    // declaration order, so new variants go below and never in a section above.
+    // ========================================================================
+
+    // Authorization Errors
+
+    #[msg("No ownership transfer is pending for this card")]
+    NoPendingOwnerTransfer,
+
+    #[msg("Card already has the maximum number of additional owners")]
+    OwnerListFull,
+
+    #[msg("Card already has the maximum number of delegates")]
+    DelegateListFull,
+
+    #[msg("Authority list is full")]
+    AuthorityListFull,
+
+    #[msg("Source token account is not the card's token account")]
+    SourceAccountMismatch,
+
+    // Card Status Errors
+
+    #[msg("Card is not pending activation")]
+    CardNotPending,
+
+    #[msg("Card is paused by its owner")]
+    CardPaused,
+
+    #[msg("Card is not paused")]
+    CardNotPaused,
+
+    #[msg("Card has expired")]
+    CardExpired,
+
+    #[msg("Only a fully frozen or terminated card, not yet reissued, can be reissued")]
+    CardNotReissuable,
+
+    #[msg("Owner already has the maximum number of cards")]
+    CardLimitReached,
+
+    // Merchant Errors
+
+    #[msg("Merchant record does not match the destination account")]
+    MerchantAccountMismatch,
+
+    // Velocity Limit Errors
+
+    #[msg("Daily transaction count limit exceeded for this merchant")]
+    MerchantVelocityExceeded,
+
+    #[msg("Transaction has already been recorded")]
+    DuplicateTransaction,
+
+    #[msg("Velocity limits were raised too recently; wait for the cooldown")]
+    LimitChangeCooldown,
+
+    // Policy Errors
+
+    #[msg("Transfer exceeds the card's available balance")]
+    InsufficientBalance,
+
+    #[msg("Transfer type not allowed by card policy")]
+    TransferTypeNotAllowed,
+
+    #[msg("Card tracks a balance: the balance account is required")]
+    BalanceAccountRequired,
+
+    // Verification Errors
+
+    #[msg("Step-up attestation does not belong to this card")]
+    InvalidStepUpAttestation,
+
+    #[msg("Invalid step-up attestation validity window")]
+    InvalidStepUpValidity,
+
+    #[msg("Fraud score exceeds the card's threshold or is missing")]
+    FraudScoreExceeded,
+
+    #[msg("Fraud score account does not belong to this card")]
+    InvalidFraudScore,
+
+    #[msg("Transfer exceeds the manual approval threshold and has no valid approval")]
+    ManualApprovalRequired,
+
+    #[msg("Approval token does not belong to this card")]
+    InvalidApprovalToken,
+
+    // Global/System Errors
+
+    #[msg("Token mint does not match the card's configured mint")]
+    MintMismatch,
+
+    #[msg("System transfer account list is full")]
+    SystemTransferAccountsFull,
+
+    #[msg("Card already has the maximum number of open holds")]
+    HoldListFull,
+
+    #[msg("No open hold with this ID")]
+    HoldNotFound,
+
+    #[msg("Protocol kill switch is engaged")]
+    KillSwitchEngaged,
+
+    // Confidential Transfer Errors
+
+    #[msg("Plaintext velocity counters must be zero to enable confidential mode")]
+    PlaintextCountersNotZero,
+
+    #[msg("Confidential spend still counts toward the current velocity periods")]
+    ConfidentialSpendOutstanding,
+
+    // Statement Errors
+
+    #[msg("Statement does not match the card's current period")]
+    StatementPeriodMismatch,
+
+    #[msg("Statement has already been finalized")]
+    StatementAlreadyFinalized,
+
+    #[msg("Statement period is still open")]
+    StatementPeriodOpen,
+
+    // Destination Errors
+
+    #[msg("Destination account is not on the card's allowlist")]
+    DestinationNotAllowed,
+
+    #[msg("Destination allowlist is full")]
+    DestinationAllowlistFull,
+
+    // Balance Errors
+
+    #[msg("Card balance tracking is not enabled in this build")]
+    BalanceTrackingDisabled,
+
+    // Transfer Hook Errors
+
+    #[msg("Transfer hook called outside a Token-2022 transfer")]
+    NotTransferring,
+}
+
+/// Rejection code reported when no `HookError` applies
+pub const REJECTION_CODE_UNKNOWN: u8 = 0;
+
+impl HookError {
+    /// Every variant, in declaration order
+    pub const ALL: &'static [HookError] = &[
+        HookError::Unauthorized,
+        HookError::InvalidDidCommitment,
+        HookError::SessionExpired,
+        HookError::CardNotActive,
+        HookError::CardFrozen,
+        HookError::CardTerminated,
+        HookError::CardPending,
+        HookError::MerchantNotWhitelisted,
+        HookError::MerchantBlocked,
+        HookError::UnknownMerchant,
+        HookError::MerchantWhitelistFull,
+        HookError::MerchantBlocklistFull,
+        HookError::MccNotWhitelisted,
+        HookError::MccBlocked,
+        HookError::InvalidMccCode,
+        HookError::MccWhitelistFull,
+        HookError::MccBlocklistFull,
+        HookError::TransactionLimitExceeded,
+        HookError::DailyLimitExceeded,
+        HookError::WeeklyLimitExceeded,
+        HookError::MonthlyLimitExceeded,
+        HookError::DailyTransactionCountExceeded,
+        HookError::WeeklyTransactionCountExceeded,
+        HookError::MonthlyTransactionCountExceeded,
+        HookError::InternationalNotAllowed,
+        HookError::OnlineNotAllowed,
+        HookError::AtmNotAllowed,
+        HookError::ContactlessNotAllowed,
+        HookError::ContactlessLimitExceeded,
+        HookError::CountryNotAllowed,
+        HookError::CountryBlocked,
+        HookError::BiometricRequired,
+        HookError::TwoFactorRequired,
+        HookError::StepUpAuthRequired,
+        HookError::GloballyPaused,
+        HookError::InvalidConfiguration,
+        HookError::AccountAlreadyExists,
+        HookError::AccountNotFound,
+        HookError::InvalidSlot,
+        HookError::Overflow,
+        HookError::Underflow,
+        HookError::DivisionByZero,
+        HookError::ConfidentialModeNotEnabled,
+        HookError::InvalidProofData,
+        HookError::VelocityProofFailed,
+        HookError::EncryptedCounterOverflow,
+        HookError::IncoCheckFailed,
+        HookError::IncoNetworkError,
+        HookError::InvalidIncoHandle,
+        HookError::IncoEpochExpired,
+        HookError::IncoNotEnabled,
+        HookError::IncoCpiFailed,
+        HookError::InvalidIncoAttestation,
+        HookError::NoPendingOwnerTransfer,
+        HookError::OwnerListFull,
+        HookError::DelegateListFull,
+        HookError::AuthorityListFull,
+        HookError::SourceAccountMismatch,
+        HookError::CardNotPending,
+        HookError::CardPaused,
+        HookError::CardNotPaused,
+        HookError::CardExpired,
+        HookError::CardNotReissuable,
+        HookError::CardLimitReached,
+        HookError::MerchantAccountMismatch,
+        HookError::MerchantVelocityExceeded,
+        HookError::DuplicateTransaction,
+        HookError::LimitChangeCooldown,
+        HookError::InsufficientBalance,
+        HookError::TransferTypeNotAllowed,
+        HookError::BalanceAccountRequired,
+        HookError::InvalidStepUpAttestation,
+        HookError::InvalidStepUpValidity,
+        HookError::FraudScoreExceeded,
+        HookError::InvalidFraudScore,
+        HookError::ManualApprovalRequired,
+        HookError::InvalidApprovalToken,
+        HookError::MintMismatch,
+        HookError::SystemTransferAccountsFull,
+        HookError::HoldListFull,
+        HookError::HoldNotFound,
+        HookError::KillSwitchEngaged,
+        HookError::PlaintextCountersNotZero,
+        HookError::ConfidentialSpendOutstanding,
+        HookError::StatementPeriodMismatch,
+        HookError::StatementAlreadyFinalized,
+        HookError::StatementPeriodOpen,
+        HookError::DestinationNotAllowed,
+        HookError::DestinationAllowlistFull,
+        HookError::BalanceTrackingDisabled,
+        HookError::NotTransferring,
+    ];
+
+    /// Stable one-byte decline code written to return data on rejection
+    ///
+    /// Codes are grouped in tens by error section and must never be
+    /// renumbered; new variants take the next free code in their section.
+    pub const fn rejection_code(&self) -> u8 {
+        match self {
+            // Authorization
+            HookError::Unauthorized => 10,
+            HookError::InvalidDidCommitment => 11,
+            HookError::SessionExpired => 12,
+            HookError::NoPendingOwnerTransfer => 13,
+            HookError::OwnerListFull => 14,
+            HookError::DelegateListFull => 15,
+            HookError::AuthorityListFull => 16,
+            HookError::SourceAccountMismatch => 17,
+
+            // Card Status
+            HookError::CardNotActive => 20,
+            HookError::CardFrozen => 21,
+            HookError::CardTerminated => 22,
+            HookError::CardPending => 23,
+            HookError::CardPaused => 24,
+            HookError::CardNotPaused => 25,
+            HookError::CardExpired => 26,
+            HookError::CardNotReissuable => 27,
+            HookError::CardLimitReached => 28,
+            HookError::CardNotPending => 29,
+
+            // Merchant
+            HookError::MerchantNotWhitelisted => 30,
+            HookError::MerchantBlocked => 31,
+            HookError::UnknownMerchant => 32,
+            HookError::MerchantWhitelistFull => 33,
+            HookError::MerchantBlocklistFull => 34,
+            HookError::MerchantAccountMismatch => 35,
+
+            // MCC (Merchant Category Code)
+            HookError::MccNotWhitelisted => 40,
+            HookError::MccBlocked => 41,
+            HookError::InvalidMccCode => 42,
+            HookError::MccWhitelistFull => 43,
+            HookError::MccBlocklistFull => 44,
+
+            // Velocity Limit
+            HookError::TransactionLimitExceeded => 50,
+            HookError::DailyLimitExceeded => 51,
+            HookError::WeeklyLimitExceeded => 52,
+            HookError::MonthlyLimitExceeded => 53,
+            HookError::DailyTransactionCountExceeded => 54,
+            HookError::WeeklyTransactionCountExceeded => 55,
+            HookError::MonthlyTransactionCountExceeded => 56,
+            HookError::MerchantVelocityExceeded => 57,
+            HookError::DuplicateTransaction => 58,
+            HookError::LimitChangeCooldown => 59,
+
+            // Policy
+            HookError::InternationalNotAllowed => 60,
+            HookError::OnlineNotAllowed => 61,
+            HookError::AtmNotAllowed => 62,
+            HookError::ContactlessNotAllowed => 63,
+            HookError::ContactlessLimitExceeded => 64,
+            HookError::CountryNotAllowed => 65,
+            HookError::CountryBlocked => 66,
+            HookError::InsufficientBalance => 67,
+            HookError::TransferTypeNotAllowed => 68,
+            HookError::BalanceAccountRequired => 69,
+
+            // Verification
+            HookError::BiometricRequired => 70,
+            HookError::TwoFactorRequired => 71,
+            HookError::StepUpAuthRequired => 72,
+            HookError::InvalidStepUpAttestation => 73,
+            HookError::InvalidStepUpValidity => 74,
+            HookError::FraudScoreExceeded => 75,
+            HookError::InvalidFraudScore => 76,
+            HookError::ManualApprovalRequired => 77,
+            HookError::InvalidApprovalToken => 78,
+
+            // Global/System
+            HookError::GloballyPaused => 80,
+            HookError::InvalidConfiguration => 81,
+            HookError::AccountAlreadyExists => 82,
+            HookError::AccountNotFound => 83,
+            HookError::InvalidSlot => 84,
+            HookError::MintMismatch => 85,
+            HookError::SystemTransferAccountsFull => 86,
+            HookError::HoldListFull => 87,
+            HookError::HoldNotFound => 88,
+            HookError::KillSwitchEngaged => 89,
+
+            // Arithmetic
+            HookError::Overflow => 90,
+            HookError::Underflow => 91,
+            HookError::DivisionByZero => 92,
+
+            // Confidential Transfer
+            HookError::ConfidentialModeNotEnabled => 100,
+            HookError::InvalidProofData => 101,
+            HookError::VelocityProofFailed => 102,
+            HookError::EncryptedCounterOverflow => 103,
+            HookError::PlaintextCountersNotZero => 104,
+            HookError::ConfidentialSpendOutstanding => 105,
+
+            // Inco Lightning
+            HookError::IncoCheckFailed => 110,
+            HookError::IncoNetworkError => 111,
+            HookError::InvalidIncoHandle => 112,
+            HookError::IncoEpochExpired => 113,
+            HookError::IncoNotEnabled => 114,
+            HookError::IncoCpiFailed => 115,
+            HookError::InvalidIncoAttestation => 116,
+
+            // Statement
+            HookError::StatementPeriodMismatch => 120,
+            HookError::StatementAlreadyFinalized => 121,
+            HookError::StatementPeriodOpen => 122,
+
+            // Destination
+            HookError::DestinationNotAllowed => 130,
+            HookError::DestinationAllowlistFull => 131,
+
+            // Balance
+            HookError::BalanceTrackingDisabled => 140,
+
+            // Transfer Hook
+            HookError::NotTransferring => 150,
+        }
+    }
+
+    /// Look up the variant for an Anchor error code number
+    pub fn from_error_code(code: u32) -> Option<HookError> {
+        Self::ALL.iter().copied().find(|err| u32::from(*err) == code)
+    }
+}
+
+/// Stable decline code for any error raised by the hook
+pub fn rejection_code(err: &Error) -> u8 {
+    match err {
+        Error::AnchorError(e) => HookError::from_error_code(e.error_code_number)
+            .map_or(REJECTION_CODE_UNKNOWN, |hook_err| hook_err.rejection_code()),
+        Error::ProgramError(_) => REJECTION_CODE_UNKNOWN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_lists_every_variant_in_order() {
+        for (index, err) in HookError::ALL.iter().enumerate() {
+            let expected = anchor_lang::error::ERROR_CODE_OFFSET + index as u32;
+            assert_eq!(u32::from(*err), expected, "{err:?}");
+        }
+    }
+
+    #[test]
+    fn rejection_codes_are_unique_and_known() {
+        let mut seen = std::collections::HashSet::new();
+        for err in HookError::ALL {
+            let code = err.rejection_code();
+            assert_ne!(code, REJECTION_CODE_UNKNOWN, "{err:?}");
+            assert!(seen.insert(code), "{err:?} reuses rejection code {code}");
+        }
+    }
+
+    #[test]
+    fn errors_map_to_their_rejection_code() {
+        let err: Error = HookError::DailyLimitExceeded.into();
+        assert_eq!(rejection_code(&err), HookError::DailyLimitExceeded.rejection_code());
+
+        let err: Error = ErrorCode::AccountNotInitialized.into();
+        assert_eq!(rejection_code(&err), REJECTION_CODE_UNKNOWN);
+    }
 }
@@ -0,0 +1,103 @@
+//! DisCard 2035 - Transfer Hook Events
+
+use anchor_lang::prelude::*;
+use crate::instructions::velocity::ResetPeriod;
+use crate::state::{ListKind, MccBucket, TransactionChannel};
+
+/// Emitted when a monthly statement is finalized
+#[event]
+pub struct StatementFinalized {
+    pub card_config: Pubkey,
+    pub card_id: [u8; 32],
+    pub period: u32,
+    pub total_spent: u64,
+    pub transaction_count: u32,
+    pub mcc_histogram: Vec<MccBucket>,
+    pub other_total: u64,
+    pub other_count: u32,
+    pub closed_at: i64,
+}
+
+/// Emitted when the admin proposes a new admin
+#[event]
+pub struct AdminTransferInitiated {
+    pub current_admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/// Emitted when a proposed admin accepts and takes over
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+/// Emitted when an entire merchant/MCC list is cleared
+#[event]
+pub struct ListCleared {
+    pub card_config: Pubkey,
+    pub list: ListKind,
+    pub prior_len: u32,
+}
+
+/// Emitted when adding a merchant to one list removed it from the other
+#[event]
+pub struct MerchantListMoved {
+    pub card_config: Pubkey,
+    pub merchant_id: [u8; 32],
+    pub from: ListKind,
+    pub to: ListKind,
+}
+
+/// Emitted when an amount is held for a disputed transaction
+#[event]
+pub struct HoldPlaced {
+    pub card_config: Pubkey,
+    pub hold_id: u32,
+    pub amount: u64,
+    pub placed_by: Pubkey,
+}
+
+/// Emitted when a hold is released
+#[event]
+pub struct HoldReleased {
+    pub card_config: Pubkey,
+    pub hold_id: u32,
+    pub amount: u64,
+    pub released_by: Pubkey,
+}
+
+/// Emitted when a transfer touching a platform account skips card rules
+#[event]
+pub struct SystemTransferBypass {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when spend settled outside the hook is recorded
+///
+/// `reference` carries the caller's invoice or order number so accounting
+/// systems can reconcile on-chain spend.
+#[event]
+pub struct TransactionRecorded {
+    pub card_config: Pubkey,
+    pub amount: u64,
+    pub merchant_id: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub channel: Option<TransactionChannel>,
+    pub reference: Option<[u8; 32]>,
+    pub slot: u64,
+}
+
+/// Emitted when ops manually correct a card's velocity counter
+#[event]
+pub struct CounterAdjusted {
+    pub card_config: Pubkey,
+    pub period: ResetPeriod,
+    pub delta: i64,
+    pub old_total: u64,
+    pub new_total: u64,
+    pub adjusted_by: Pubkey,
+    pub adjusted_at: i64,
+}
@@ -0,0 +1,482 @@
+//! DisCard 2035 - Admin Instructions
+//!
+//! Rotating the `GlobalConfig` admin is a two-step process: the current admin
+//! proposes a new key, and the new key must sign `accept_admin` to take over.
+//! This guards against handing control to a mistyped or unowned address.
+//!
+//! The admin also maintains the reset, fraud, auth and settlement authority
+//! lists, the bounded list of platform token accounts whose transfers bypass
+//! card rules, the cooldown between limit increases, the
+//! ceiling on card velocity limits, the weekday weekly periods start on,
+//! whether velocity periods reset lazily, verbose hook logging, and the
+//! default home country, contactless limit, and per-owner card cap for new
+//! cards.
+//!
+//! `GlobalConfig` has no version field. `migrate_global_config` rebuilds the
+//! first deployment's layout, whose fields no longer line up with the
+//! current ones, and grows an account created since so fields appended
+//! after `verbose_logging` read as zero.
+
+use anchor_lang::prelude::*;
+use crate::{
+    GlobalControl,
+    errors::HookError,
+    events::{AdminTransferInitiated, AdminTransferred},
+    migration::{global_config_body, grow_account, GlobalConfigV0},
+    state::{AuthorityRole, GlobalConfig, VelocityLimits, MAX_SYSTEM_TRANSFER_ACCOUNTS},
+};
+use merchant_registry::country::u16_to_alpha2;
+
+/// Propose a new admin (current admin only)
+pub fn transfer_admin(ctx: Context<GlobalControl>, new_admin: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    if new_admin == global_config.admin {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    global_config.pending_admin = Some(new_admin);
+    global_config.updated_at = clock.unix_timestamp;
+
+    emit!(AdminTransferInitiated {
+        current_admin: global_config.admin,
+        pending_admin: new_admin,
+    });
+
+    msg!("Admin transfer initiated: {} -> {}", global_config.admin, new_admin);
+
+    Ok(())
+}
+
+/// Accept a pending admin transfer (proposed admin only)
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    let previous_admin = global_config.admin;
+    global_config.admin = ctx.accounts.new_admin.key();
+    global_config.pending_admin = None;
+    global_config.updated_at = clock.unix_timestamp;
+
+    emit!(AdminTransferred {
+        previous_admin,
+        new_admin: global_config.admin,
+    });
+
+    msg!("Admin transferred: {} -> {}", previous_admin, global_config.admin);
+
+    Ok(())
+}
+
+/// Set the minimum slots between velocity limit increases (admin only)
+pub fn set_limit_increase_cooldown(ctx: Context<GlobalControl>, slots: u64) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.min_slots_between_limit_increases = slots;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Limit increase cooldown set to {} slots", slots);
+
+    Ok(())
+}
+
+/// Set the weekday weekly velocity periods start on (admin only)
+pub fn set_week_start_day(ctx: Context<GlobalControl>, day: u8) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    if day > 6 {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    global_config.week_start_day = day;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Week start day set to {} (0 = Sunday)", day);
+
+    Ok(())
+}
+
+/// Enable or disable lazy velocity resets on card activity (admin only)
+///
+/// With lazy resets off, counters only reset through the reset authorities'
+/// scheduled reset instructions (e.g. `reset_velocity_batch`).
+pub fn set_lazy_reset_enabled(ctx: Context<GlobalControl>, enabled: bool) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.lazy_reset_enabled = enabled;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Lazy velocity reset {}", if enabled { "enabled" } else { "disabled" });
+
+    Ok(())
+}
+
+/// Toggle per-transfer detail logging in the transfer hooks (admin only)
+///
+/// Meant for debugging; leaving it off saves the compute of the detail logs.
+pub fn set_verbose_logging(ctx: Context<GlobalControl>, enabled: bool) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.verbose_logging = enabled;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Verbose hook logging {}", if enabled { "enabled" } else { "disabled" });
+
+    Ok(())
+}
+
+/// Set the maximum number of live cards per owner DID (admin only, 0 = unlimited)
+pub fn set_max_cards_per_owner(ctx: Context<GlobalControl>, max_cards: u32) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.max_cards_per_owner = max_cards;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Max cards per owner set to {}", max_cards);
+
+    Ok(())
+}
+
+/// Set the home country new cards get when none is given (admin only)
+pub fn set_default_home_country(ctx: Context<GlobalControl>, country: u16) -> Result<()> {
+    if u16_to_alpha2(country).is_none() {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.default_home_country = country;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Default home country set to {:#06x}", country);
+
+    Ok(())
+}
+
+/// Set the contactless limit new cards get, in whole display units (admin only, 0 = $100)
+pub fn set_default_contactless_limit(ctx: Context<GlobalControl>, limit: u64) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.default_contactless_limit = limit;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Default contactless limit set to {}", limit);
+
+    Ok(())
+}
+
+/// Set the highest velocity limits a card may configure (admin only)
+///
+/// Limits are in whole display units and scaled to each card's mint.
+pub fn set_max_velocity_limits(ctx: Context<GlobalControl>, limits: VelocityLimits) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    limits.validate()?;
+
+    global_config.max_allowed_velocity_limits = limits;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!(
+        "Velocity ceiling set: per_tx={} daily={} weekly={} monthly={}",
+        limits.per_transaction,
+        limits.daily,
+        limits.weekly,
+        limits.monthly
+    );
+
+    Ok(())
+}
+
+/// Exempt a platform token account from card rules (admin only)
+pub fn add_system_transfer_account(ctx: Context<GlobalControl>, account: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    if !global_config.system_transfer_accounts.contains(&account) {
+        if global_config.system_transfer_accounts.len() >= MAX_SYSTEM_TRANSFER_ACCOUNTS {
+            return Err(error!(HookError::SystemTransferAccountsFull));
+        }
+        global_config.system_transfer_accounts.push(account);
+    }
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("System transfer account added: {}", account);
+
+    Ok(())
+}
+
+/// Remove a platform token account's exemption (admin only)
+pub fn remove_system_transfer_account(ctx: Context<GlobalControl>, account: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.system_transfer_accounts.retain(|listed| *listed != account);
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("System transfer account removed: {}", account);
+
+    Ok(())
+}
+
+/// Add a service key to one of the authority lists (admin only)
+pub fn add_authority(
+    ctx: Context<GlobalControl>,
+    role: AuthorityRole,
+    authority: Pubkey,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    let (authorities, capacity) = global_config.authorities_mut(role);
+    if !authorities.contains(&authority) {
+        if authorities.len() >= capacity {
+            return Err(error!(HookError::AuthorityListFull));
+        }
+        authorities.push(authority);
+    }
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("{:?} authority added: {}", role, authority);
+
+    Ok(())
+}
+
+/// Bring the global config to the current layout and `GlobalConfig::SIZE`
+/// (admin only)
+///
+/// The first deployment's account is recognised by its size and rebuilt
+/// from `GlobalConfigV0`; later ones are grown in place. Either way fields
+/// added since start zeroed (their off/unlimited defaults), and the admin
+/// pays any added rent.
+pub fn migrate_global_config(ctx: Context<MigrateGlobalConfig>) -> Result<()> {
+    let account = ctx.accounts.global_config.to_account_info();
+    let clock = Clock::get()?;
+
+    let is_first_layout = account.data_len() == GlobalConfigV0::SIZE;
+    grow_account(
+        &account,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        GlobalConfig::SIZE,
+    )?;
+
+    let mut global_config = {
+        let data = account.try_borrow_data()?;
+        let mut body = global_config_body(&data)?;
+        if is_first_layout {
+            GlobalConfigV0::deserialize(&mut body)?.into_current()
+        } else {
+            GlobalConfig::deserialize(&mut body)?
+        }
+    };
+
+    if global_config.admin != ctx.accounts.admin.key() {
+        return Err(error!(HookError::Unauthorized));
+    }
+
+    global_config.updated_at = clock.unix_timestamp;
+    let mut data = account.try_borrow_mut_data()?;
+    global_config.try_serialize(&mut &mut data[..])?;
+
+    msg!("Global config migrated to {} bytes", GlobalConfig::SIZE);
+
+    Ok(())
+}
+
+/// Remove a service key from one of the authority lists (admin only)
+pub fn remove_authority(
+    ctx: Context<GlobalControl>,
+    role: AuthorityRole,
+    authority: Pubkey,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    let (authorities, _) = global_config.authorities_mut(role);
+    authorities.retain(|listed| *listed != authority);
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("{:?} authority removed: {}", role, authority);
+
+    Ok(())
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// Must be the pending admin
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.pending_admin == Some(new_admin.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGlobalConfig<'info> {
+    /// Must be admin; pays for any added space
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The global config, rewritten in the current layout
+    /// CHECK: Parsed by the handler with the layout it was written in; the
+    /// admin is checked after parsing
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump,
+        owner = crate::ID,
+    )]
+    pub global_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardConfig, CardStatus};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, global_config_account, read,
+        run, signer, zeroed_global_config, ProgramResult,
+    };
+
+    fn config_with_admin(admin: Pubkey) -> GlobalConfig {
+        GlobalConfig { admin, ..zeroed_global_config() }
+    }
+
+    fn propose(admin: Pubkey, config: AccountInfo<'static>, new_admin: Pubkey) -> ProgramResult {
+        run(vec![signer(admin), config], crate::instruction::TransferAdmin { new_admin })
+    }
+
+    fn accept(new_admin: Pubkey, config: AccountInfo<'static>) -> ProgramResult {
+        run(vec![signer(new_admin), config], crate::instruction::AcceptAdmin {})
+    }
+
+    #[test]
+    fn proposed_admin_takes_over_on_accept() {
+        let (admin, new_admin) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = global_config_account(config_with_admin(admin));
+
+        propose(admin, config.clone(), new_admin).unwrap();
+        assert_eq!(read::<GlobalConfig>(&config).admin, admin);
+        accept(new_admin, config.clone()).unwrap();
+
+        let global_config = read::<GlobalConfig>(&config);
+        assert_eq!(global_config.admin, new_admin);
+        assert_eq!(global_config.pending_admin, None);
+        assert_rejected(propose(admin, config, admin), HookError::Unauthorized);
+    }
+
+    #[test]
+    fn only_the_proposed_admin_can_accept() {
+        let (admin, new_admin) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = global_config_account(config_with_admin(admin));
+
+        assert_rejected(accept(new_admin, config.clone()), HookError::Unauthorized);
+        propose(admin, config.clone(), new_admin).unwrap();
+
+        assert_rejected(accept(Pubkey::new_unique(), config.clone()), HookError::Unauthorized);
+        assert_rejected(accept(admin, config), HookError::Unauthorized);
+    }
+
+    #[test]
+    fn only_the_admin_can_propose() {
+        let admin = Pubkey::new_unique();
+        let config = global_config_account(config_with_admin(admin));
+
+        assert_rejected(
+            propose(Pubkey::new_unique(), config.clone(), Pubkey::new_unique()),
+            HookError::Unauthorized,
+        );
+        assert_rejected(propose(admin, config, admin), HookError::InvalidConfiguration);
+    }
+
+    fn limits(per_transaction: u64, daily: u64, weekly: u64, monthly: u64) -> VelocityLimits {
+        VelocityLimits { per_transaction, daily, weekly, monthly, ..Default::default() }
+    }
+
+    /// Set a 0-decimal card's limits, so display and base units coincide
+    fn set_card_limits(
+        card: &AccountInfo<'static>,
+        config: &AccountInfo<'static>,
+        limits: VelocityLimits,
+    ) -> ProgramResult {
+        let owner = Pubkey::new_unique();
+        let owner_did = did_controller_account([1; 32], owner);
+        run(
+            vec![signer(owner), card.clone(), owner_did, config.clone()],
+            crate::instruction::UpdateVelocityLimits { limits },
+        )
+    }
+
+    #[test]
+    fn card_limits_stay_under_the_ceiling_until_it_is_raised() {
+        let admin = Pubkey::new_unique();
+        let config = global_config_account(GlobalConfig {
+            max_allowed_velocity_limits: limits(100, 1_000, 5_000, 10_000),
+            ..config_with_admin(admin)
+        });
+        let card = card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: [1; 32],
+            status: CardStatus::Active,
+            ..Default::default()
+        });
+
+        let result = set_card_limits(&card, &config, limits(100, 1_001, 5_000, 10_000));
+        assert_rejected(result, HookError::InvalidConfiguration);
+        set_card_limits(&card, &config, limits(100, 1_000, 5_000, 10_000)).unwrap();
+        set_card_limits(&card, &config, limits(50, 500, 2_000, 8_000)).unwrap();
+
+        let raise = crate::instruction::SetMaxVelocityLimits {
+            limits: limits(200, 2_000, 10_000, 20_000),
+        };
+        run(vec![signer(admin), config.clone()], raise).unwrap();
+        set_card_limits(&card, &config, limits(200, 2_000, 5_000, 10_000)).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_limits.daily, 2_000);
+    }
+
+    #[test]
+    fn only_the_admin_sets_the_ceiling() {
+        let config = global_config_account(config_with_admin(Pubkey::new_unique()));
+
+        let raise = crate::instruction::SetMaxVelocityLimits { limits: limits(1, 1, 1, 1) };
+        let result = run(vec![signer(Pubkey::new_unique()), config.clone()], raise);
+
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(read::<GlobalConfig>(&config).max_allowed_velocity_limits.daily, 0);
+    }
+
+    #[test]
+    fn default_home_country_must_be_a_country_code() {
+        let admin = Pubkey::new_unique();
+        let config = global_config_account(config_with_admin(admin));
+        let set_default = |country| {
+            let ix = crate::instruction::SetDefaultHomeCountry { country };
+            run(vec![signer(admin), config.clone()], ix)
+        };
+
+        assert_rejected(set_default(0), HookError::InvalidConfiguration);
+        let gb = merchant_registry::country::alpha2_to_u16(*b"GB").unwrap();
+        set_default(gb).unwrap();
+        assert_eq!(read::<GlobalConfig>(&config).default_home_country, gb);
+    }
+}
@@ -0,0 +1,175 @@
+//! DisCard 2035 - Manual Approval Instructions
+//!
+//! When a card's policy sets `manual_approval_above`, larger transfers need
+//! an `ApprovalToken` PDA the owner issued for that exact amount (and
+//! optionally merchant). The transfer hook consumes the token on use.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::state::{ApprovalToken, CardConfig, DidController, MAX_APPROVAL_VALIDITY_SECS};
+
+/// Issue (or replace) the approval token for one large transfer (owner only)
+pub fn create_approval_token(
+    ctx: Context<CreateApprovalToken>,
+    amount: u64,
+    merchant_id: Option<[u8; 32]>,
+    expires_at: i64,
+) -> Result<()> {
+    let approval = &mut ctx.accounts.approval_token;
+    let clock = Clock::get()?;
+
+    if expires_at <= clock.unix_timestamp
+        || expires_at - clock.unix_timestamp > MAX_APPROVAL_VALIDITY_SECS
+    {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    approval.bump = ctx.bumps.approval_token;
+    approval.card_config = ctx.accounts.card_config.key();
+    approval.amount = amount;
+    approval.merchant_id = merchant_id;
+    approval.approved_by = ctx.accounts.authority.key();
+    approval.created_at = clock.unix_timestamp;
+    approval.expires_at = expires_at;
+    approval.consumed = false;
+
+    msg!(
+        "Approval issued for card {:?}: amount {}, expires at {}",
+        ctx.accounts.card_config.card_id,
+        amount,
+        expires_at
+    );
+
+    Ok(())
+}
+
+/// Resolve whether an optional approval token covers a transfer
+pub fn is_manually_approved(
+    approval: Option<&ApprovalToken>,
+    card_config: Pubkey,
+    amount: u64,
+    merchant_id: Option<[u8; 32]>,
+    now: i64,
+) -> Result<bool> {
+    match approval {
+        Some(approval) => {
+            if approval.card_config != card_config {
+                return Err(error!(HookError::InvalidApprovalToken));
+            }
+            Ok(approval.covers(amount, merchant_id, now))
+        }
+        None => Ok(false),
+    }
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct CreateApprovalToken<'info> {
+    /// Must be a card owner
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls an owner DID
+    #[account(
+        constraint = owner_did.proves_owner_of(authority.key(), &card_config)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+
+    /// The approval token PDA
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ApprovalToken::SIZE,
+        seeds = [b"approval", card_config.key().as_ref()],
+        bump,
+    )]
+    pub approval_token: Account<'info, ApprovalToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardPolicy, CardStatus, TransactionContext};
+    use crate::test_utils::NOW;
+
+    const LARGE: u64 = 5_000;
+
+    fn card() -> CardConfig {
+        CardConfig {
+            status: CardStatus::Active,
+            policy: CardPolicy { manual_approval_above: Some(1_000), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn approval(card_config: Pubkey) -> ApprovalToken {
+        ApprovalToken {
+            card_config,
+            amount: LARGE,
+            merchant_id: Some([3; 32]),
+            expires_at: NOW + 60,
+            ..Default::default()
+        }
+    }
+
+    /// Judge a large transfer to merchant `[3; 32]` at `now`
+    fn large_transfer(approval: Option<&ApprovalToken>, card_key: Pubkey, now: i64) -> Result<()> {
+        let merchant_id = Some([3; 32]);
+        let tx = TransactionContext {
+            merchant_id,
+            manually_approved: is_manually_approved(approval, card_key, LARGE, merchant_id, now)?,
+            now,
+            ..Default::default()
+        };
+        card().is_transaction_allowed(LARGE, &tx)
+    }
+
+    fn assert_rejected(result: Result<()>, expected: HookError) {
+        let err = result.unwrap_err();
+        assert_eq!(crate::errors::rejection_code(&err), expected.rejection_code());
+    }
+
+    #[test]
+    fn approved_large_transfer_passes() {
+        let card_key = Pubkey::new_unique();
+        assert!(large_transfer(Some(&approval(card_key)), card_key, NOW).is_ok());
+        // Amounts up to the threshold never need approval
+        assert!(card().is_transaction_allowed(1_000, &TransactionContext::default()).is_ok());
+    }
+
+    #[test]
+    fn unapproved_large_transfer_is_rejected() {
+        let card_key = Pubkey::new_unique();
+        assert_rejected(large_transfer(None, card_key, NOW), HookError::ManualApprovalRequired);
+
+        let consumed = ApprovalToken { consumed: true, ..approval(card_key) };
+        let result = large_transfer(Some(&consumed), card_key, NOW);
+        assert_rejected(result, HookError::ManualApprovalRequired);
+
+        let other_merchant = ApprovalToken { merchant_id: Some([4; 32]), ..approval(card_key) };
+        let result = large_transfer(Some(&other_merchant), card_key, NOW);
+        assert_rejected(result, HookError::ManualApprovalRequired);
+    }
+
+    #[test]
+    fn expired_approval_is_rejected() {
+        let card_key = Pubkey::new_unique();
+        let approval = approval(card_key);
+        let result = large_transfer(Some(&approval), card_key, approval.expires_at);
+        assert_rejected(result, HookError::ManualApprovalRequired);
+    }
+
+    #[test]
+    fn another_cards_approval_is_invalid() {
+        let result = large_transfer(Some(&approval(Pubkey::new_unique())), Pubkey::new_unique(), NOW);
+        assert_rejected(result, HookError::InvalidApprovalToken);
+    }
+}
@@ -0,0 +1,302 @@
+//! DisCard 2035 - Card Balance Instructions
+//!
+//! A card can keep its balance in a discard-state `CardStatePda`. The state
+//! program only accepts writes signed by the card's config PDA, so creating
+//! and funding the balance goes through here (card owner or settlement
+//! authority), and the transfer hook debits every transfer it approves.
+//!
+//! Funding credits tokens that were already transferred into the card's
+//! token account; discard-state refuses to credit more than that account
+//! holds. Only built with the `plaintext-state` feature.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use discard_state::{
+    cpi::accounts::{CreateCardStatePda, FundCardStatePda, UpdateCardStatePda},
+    program::DiscardState,
+    state::CardStatePda,
+    CardLimits, TransactionCountLimits,
+};
+use crate::{
+    errors::HookError,
+    state::{CardConfig, DidController, GlobalConfig},
+};
+use crate::instructions::emergency::is_card_owner;
+
+/// Create the card's discard-state balance account and start enforcing it
+/// (owner or settlement authority)
+pub fn create_balance_account(ctx: Context<CreateBalanceAccount>) -> Result<()> {
+    authorize_balance(
+        &ctx.accounts.owner_did,
+        ctx.accounts.authority.key(),
+        &ctx.accounts.card_config,
+        &ctx.accounts.global_config,
+    )?;
+
+    let card_config = &ctx.accounts.card_config;
+    let card_id = card_config.card_id;
+    let bump = [card_config.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[CardConfig::SEED, card_id.as_ref(), &bump]];
+
+    discard_state::cpi::create_card_state_pda(
+        CpiContext::new_with_signer(
+            ctx.accounts.discard_state_program.to_account_info(),
+            CreateCardStatePda {
+                payer: ctx.accounts.authority.to_account_info(),
+                card_authority: card_config.to_account_info(),
+                card_state: ctx.accounts.balance_account.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        card_id,
+        card_config.owner_did_hash,
+        CardLimits {
+            spending_limit: card_config.velocity_limits.per_transaction,
+            daily_limit: card_config.velocity_limits.daily,
+            weekly_limit: card_config.velocity_limits.weekly,
+            monthly_limit: card_config.velocity_limits.monthly,
+            max_transaction_counts: TransactionCountLimits {
+                daily: card_config.velocity_limits.max_daily_transactions,
+                weekly: card_config.velocity_limits.max_weekly_transactions,
+                monthly: card_config.velocity_limits.max_monthly_transactions,
+            },
+            max_balance: 0,
+            low_balance_threshold_bps: 0,
+        },
+        card_config.token_account,
+    )?;
+
+    let card_config = &mut ctx.accounts.card_config;
+    card_config.balance_tracked = true;
+    card_config.updated_at = Clock::get()?.unix_timestamp;
+
+    msg!("Balance account created for card: {:?}", card_id);
+
+    Ok(())
+}
+
+/// Credit tokens already held by the card's token account to its balance
+/// (owner or settlement authority)
+pub fn fund_balance_account(ctx: Context<FundBalanceAccount>, amount: u64) -> Result<()> {
+    authorize_balance(
+        &ctx.accounts.owner_did,
+        ctx.accounts.authority.key(),
+        &ctx.accounts.card_config,
+        &ctx.accounts.global_config,
+    )?;
+
+    let card_config = &ctx.accounts.card_config;
+    let bump = [card_config.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[CardConfig::SEED, card_config.card_id.as_ref(), &bump]];
+
+    discard_state::cpi::fund_card_state_pda(
+        CpiContext::new_with_signer(
+            ctx.accounts.discard_state_program.to_account_info(),
+            FundCardStatePda {
+                authority: card_config.to_account_info(),
+                card_state: ctx.accounts.balance_account.to_account_info(),
+                token_account: ctx.accounts.token_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    msg!("Balance account funded: {}", amount);
+
+    Ok(())
+}
+
+/// Set the part of the card's balance that transfers can't spend
+/// (owner or settlement authority)
+pub fn update_balance_reserve(
+    ctx: Context<UpdateBalanceReserve>,
+    reserved_balance: u64,
+) -> Result<()> {
+    authorize_balance(
+        &ctx.accounts.owner_did,
+        ctx.accounts.authority.key(),
+        &ctx.accounts.card_config,
+        &ctx.accounts.global_config,
+    )?;
+
+    let card_config = &ctx.accounts.card_config;
+    let bump = [card_config.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[CardConfig::SEED, card_config.card_id.as_ref(), &bump]];
+
+    discard_state::cpi::update_card_state_pda_reserve(
+        CpiContext::new_with_signer(
+            ctx.accounts.discard_state_program.to_account_info(),
+            UpdateCardStatePda {
+                authority: card_config.to_account_info(),
+                card_state: ctx.accounts.balance_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        reserved_balance,
+    )?;
+
+    msg!("Balance reserve set: {}", reserved_balance);
+
+    Ok(())
+}
+
+/// Debit a hook-approved transfer from the card's balance account
+///
+/// `card_config_info` is the account `card_config` was loaded from; the card
+/// PDA signs the debit.
+pub(crate) fn debit_balance_account<'info>(
+    card_config: &CardConfig,
+    card_config_info: &AccountInfo<'info>,
+    balance_account: &AccountInfo<'info>,
+    discard_state_program: Option<&Program<'info, DiscardState>>,
+    amount: u64,
+) -> Result<()> {
+    let discard_state_program = discard_state_program
+        .ok_or(error!(HookError::BalanceAccountRequired))?;
+    let bump = [card_config.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[CardConfig::SEED, card_config.card_id.as_ref(), &bump]];
+
+    discard_state::cpi::debit_card_state_pda(
+        CpiContext::new_with_signer(
+            discard_state_program.to_account_info(),
+            UpdateCardStatePda {
+                authority: card_config_info.clone(),
+                card_state: balance_account.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Balance accounts are managed by the card owner or a settlement authority
+fn authorize_balance(
+    owner_did: &Option<Account<'_, DidController>>,
+    authority: Pubkey,
+    card_config: &CardConfig,
+    global_config: &GlobalConfig,
+) -> Result<()> {
+    let is_owner = is_card_owner(owner_did, authority, card_config);
+    let is_settlement_authority = global_config.is_authorized_settlement_authority(authority);
+
+    if !is_owner && !is_settlement_authority {
+        return Err(error!(HookError::Unauthorized));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct CreateBalanceAccount<'info> {
+    /// Card owner or settlement authority; pays for the balance account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Signs the discard-state CPI as the balance account's authority
+    #[account(
+        mut,
+        seeds = [CardConfig::SEED, card_config.card_id.as_ref()],
+        bump = card_config.bump,
+        constraint = !card_config.balance_tracked @ HookError::AccountAlreadyExists,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record (required when the signer is the owner)
+    pub owner_did: Option<Account<'info, DidController>>,
+
+    /// The discard-state card state PDA to create
+    /// CHECK: Seeds are checked here; discard-state initializes it
+    #[account(
+        mut,
+        seeds = [CardStatePda::SEED, card_config.card_id.as_ref()],
+        bump,
+        seeds::program = discard_state_program.key(),
+    )]
+    pub balance_account: UncheckedAccount<'info>,
+
+    pub discard_state_program: Program<'info, DiscardState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundBalanceAccount<'info> {
+    /// Card owner or settlement authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Signs the discard-state CPI as the balance account's authority
+    #[account(
+        seeds = [CardConfig::SEED, card_config.card_id.as_ref()],
+        bump = card_config.bump,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record (required when the signer is the owner)
+    pub owner_did: Option<Account<'info, DidController>>,
+
+    /// The card's discard-state balance
+    #[account(
+        mut,
+        constraint = balance_account.authority == card_config.key()
+            @ HookError::InvalidConfiguration,
+    )]
+    pub balance_account: Account<'info, CardStatePda>,
+
+    /// The card's token account, whose holdings back the balance
+    #[account(
+        constraint = token_account.key() == card_config.token_account
+            @ HookError::SourceAccountMismatch,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub discard_state_program: Program<'info, DiscardState>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBalanceReserve<'info> {
+    /// Card owner or settlement authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Signs the discard-state CPI as the balance account's authority
+    #[account(
+        seeds = [CardConfig::SEED, card_config.card_id.as_ref()],
+        bump = card_config.bump,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record (required when the signer is the owner)
+    pub owner_did: Option<Account<'info, DidController>>,
+
+    /// The card's discard-state balance
+    #[account(
+        mut,
+        constraint = balance_account.authority == card_config.key()
+            @ HookError::InvalidConfiguration,
+    )]
+    pub balance_account: Account<'info, CardStatePda>,
+
+    pub discard_state_program: Program<'info, DiscardState>,
+}
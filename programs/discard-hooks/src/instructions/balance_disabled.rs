@@ -0,0 +1,45 @@
+//! DisCard 2035 - Card Balance Instructions (without `plaintext-state`)
+//!
+//! Balance accounts live in discard-state, which this build doesn't link.
+//! The instructions keep their names so the program interface is the same
+//! in every build, but always fail.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+
+/// Balance tracking needs the `plaintext-state` feature
+pub fn create_balance_account(_ctx: Context<CreateBalanceAccount>) -> Result<()> {
+    Err(error!(HookError::BalanceTrackingDisabled))
+}
+
+/// Balance tracking needs the `plaintext-state` feature
+pub fn fund_balance_account(_ctx: Context<FundBalanceAccount>, _amount: u64) -> Result<()> {
+    Err(error!(HookError::BalanceTrackingDisabled))
+}
+
+/// Balance tracking needs the `plaintext-state` feature
+pub fn update_balance_reserve(
+    _ctx: Context<UpdateBalanceReserve>,
+    _reserved_balance: u64,
+) -> Result<()> {
+    Err(error!(HookError::BalanceTrackingDisabled))
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct CreateBalanceAccount<'info> {
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundBalanceAccount<'info> {
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBalanceReserve<'info> {
+    pub authority: Signer<'info>,
+}
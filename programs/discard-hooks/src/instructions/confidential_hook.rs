@@ -8,17 +8,114 @@
 //! - Card status (active, not frozen) — same as standard mode
 //! - Merchant whitelist/blocklist — same as standard mode (doesn't depend on amount)
 //! - MCC filtering — same as standard mode
-//! - Velocity limits — via ZK range proof that encrypted amount satisfies limits
+//! - Velocity limits — via ZK range proofs that the encrypted amount fits the
+//!   remaining daily, weekly, and monthly limits
 
 use anchor_lang::prelude::*;
 use crate::errors::HookError;
-use crate::state::CardConfig;
+use crate::state::{CardConfig, DidController, GlobalConfig, TransactionContext};
+use crate::instructions::velocity::auto_reset_if_needed;
+
+/// Size of an ElGamal ciphertext (two compressed Ristretto255 points)
+pub const CIPHERTEXT_LEN: usize = 64;
+/// Size of the range proof inside each period bound
+pub const RANGE_PROOF_LEN: usize = 128;
+/// Size of one period bound: the counter ciphertext it was proven against,
+/// the period limit as little-endian u64, and the range proof
+pub const PERIOD_BOUND_LEN: usize = CIPHERTEXT_LEN + 8 + RANGE_PROOF_LEN;
+/// Full proof: encrypted amount, then daily, weekly, and monthly bounds
+pub const VELOCITY_PROOF_LEN: usize = CIPHERTEXT_LEN + 3 * PERIOD_BOUND_LEN;
+
+/// Initial ElGamal ciphertexts for the encrypted velocity counters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct EncryptedTotals {
+    pub daily: [u8; 64],
+    pub weekly: [u8; 64],
+    pub monthly: [u8; 64],
+}
+
+/// Switch a card to ZK velocity enforcement (owner only)
+///
+/// The plaintext counters must be zero: an owner-supplied ciphertext can't be
+/// checked against a nonzero plaintext total, so this is only allowed right
+/// after a reset (elapsed periods are rolled over first when lazy resets are
+/// on). `initial_encrypted_totals` optionally seeds the encrypted counters
+/// (e.g. with encryptions of zero); otherwise they start empty.
+pub fn enable_confidential_mode(
+    ctx: Context<ManageConfidentialMode>,
+    initial_encrypted_totals: Option<EncryptedTotals>,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.confidential_mode {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    let global_config = &ctx.accounts.global_config;
+    if global_config.lazy_reset_enabled {
+        auto_reset_if_needed(card_config, clock.slot, clock.unix_timestamp, global_config.week_start_day)?;
+    }
+
+    let counters = &card_config.velocity_counters;
+    if counters.daily_total != 0 || counters.weekly_total != 0 || counters.monthly_total != 0 {
+        return Err(error!(HookError::PlaintextCountersNotZero));
+    }
+
+    card_config.confidential_mode = true;
+    card_config.encrypted_daily_total = initial_encrypted_totals.map(|totals| totals.daily);
+    card_config.encrypted_weekly_total = initial_encrypted_totals.map(|totals| totals.weekly);
+    card_config.encrypted_monthly_total = initial_encrypted_totals.map(|totals| totals.monthly);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Confidential mode enabled for card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Switch a card back to plaintext velocity enforcement (owner only)
+///
+/// Plaintext counters can't see confidential spend, so this is refused until
+/// every period holding an encrypted total has rolled over (each reset clears
+/// its encrypted total). Plaintext counters then resume from zero.
+pub fn disable_confidential_mode(ctx: Context<ManageConfidentialMode>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if !card_config.confidential_mode {
+        return Err(error!(HookError::ConfidentialModeNotEnabled));
+    }
+
+    let global_config = &ctx.accounts.global_config;
+    if global_config.lazy_reset_enabled {
+        auto_reset_if_needed(card_config, clock.slot, clock.unix_timestamp, global_config.week_start_day)?;
+    }
+
+    if card_config.encrypted_daily_total.is_some()
+        || card_config.encrypted_weekly_total.is_some()
+        || card_config.encrypted_monthly_total.is_some()
+    {
+        return Err(error!(HookError::ConfidentialSpendOutstanding));
+    }
+
+    card_config.confidential_mode = false;
+    card_config.encrypted_daily_total = None;
+    card_config.encrypted_weekly_total = None;
+    card_config.encrypted_monthly_total = None;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Confidential mode disabled for card {:?}", card_config.card_id);
+
+    Ok(())
+}
 
 /// Confidential transfer hook handler.
 ///
 /// Instead of reading a plaintext amount, this handler:
 /// 1. Validates card status, merchant, and MCC rules (same as standard)
-/// 2. Validates the attached ZK range proof proving encrypted_amount <= remaining_daily_limit
+///    and rolls over elapsed velocity periods when lazy resets are on
+/// 2. Validates the attached ZK range proofs proving encrypted_amount fits the
+///    remaining daily, weekly, and monthly limits
 /// 3. Updates encrypted velocity counters via homomorphic addition
 pub fn confidential_handler(
     ctx: Context<ConfidentialTransferHook>,
@@ -26,66 +123,54 @@ pub fn confidential_handler(
 ) -> Result<()> {
     let card_config = &mut ctx.accounts.card_config;
 
-    msg!("Confidential transfer hook invoked");
-    msg!("  Card Status: {:?}", card_config.status);
+    // Detail logs cost compute on every transfer, so they're opt-in
+    let verbose = ctx.accounts.global_config.verbose_logging;
+    if verbose {
+        msg!("Confidential transfer hook invoked");
+        msg!("  Card Status: {:?}", card_config.status);
+    }
+
+    if ctx.accounts.global_config.kill_switch {
+        return Err(error!(HookError::KillSwitchEngaged));
+    }
 
     // ======== Standard validations (amount-independent) ========
 
-    // Check card is active
-    if card_config.status != crate::state::CardStatus::Active {
-        return Err(error!(HookError::CardNotActive));
-    }
+    // Merchant data isn't passed to the confidential hook, so scoped freezes
+    // and strict merchant mode fail closed here
+    let clock = Clock::get()?;
+    let tx = TransactionContext {
+        now: clock.unix_timestamp,
+        ..Default::default()
+    };
 
-    // Check not frozen
-    if card_config.freeze_info.is_some() {
-        return Err(error!(HookError::CardFrozen));
-    }
+    // Check freezes, status, and expiry (same as standard mode)
+    card_config.check_card_usable(tx.channel, tx.mcc_code, tx.now)?;
 
     // Check confidential mode is enabled
     if !card_config.confidential_mode {
         return Err(error!(HookError::ConfidentialModeNotEnabled));
     }
 
-    // Merchant whitelist/blocklist checks
-    // In confidential mode, merchant data is still available via extra account metas
-    let merchant_id: Option<[u8; 32]> = None;
-    let mcc_code: Option<u16> = None;
-
-    if card_config.merchant_whitelist_enabled {
-        if let Some(mid) = merchant_id {
-            if !card_config.merchant_whitelist.contains(&mid) {
-                return Err(error!(HookError::MerchantNotWhitelisted));
-            }
-        }
-    }
-
-    if let Some(mid) = merchant_id {
-        if card_config.merchant_blocklist.contains(&mid) {
-            return Err(error!(HookError::MerchantBlocked));
-        }
-    }
+    // Lazily drop lapsed temporary whitelist entries
+    card_config.evict_expired_whitelist_entries(clock.unix_timestamp);
 
-    // MCC checks
-    if card_config.mcc_whitelist_enabled {
-        if let Some(mcc) = mcc_code {
-            if !card_config.mcc_whitelist.contains(&mcc) {
-                return Err(error!(HookError::MccNotWhitelisted));
-            }
-        }
-    }
+    // Merchant whitelist/blocklist and MCC checks
+    card_config.check_merchant_rules(&tx)?;
 
-    if let Some(mcc) = mcc_code {
-        if card_config.mcc_blocklist.contains(&mcc) {
-            return Err(error!(HookError::MccBlocked));
-        }
+    // Roll over elapsed periods first: each reset clears that period's
+    // encrypted total, which the proofs are checked against
+    let global_config = &ctx.accounts.global_config;
+    if global_config.lazy_reset_enabled {
+        auto_reset_if_needed(card_config, clock.slot, clock.unix_timestamp, global_config.week_start_day)?;
     }
 
     // ======== Confidential velocity enforcement ========
 
-    // Verify the ZK range proof
-    // The proof demonstrates: encrypted_amount <= remaining_daily_limit
+    // Verify the ZK range proofs
+    // They demonstrate encrypted_amount <= remaining limit for every period
     // without revealing the actual amount
-    verify_velocity_range_proof(&proof_data, card_config)?;
+    verify_velocity_range_proof(&proof_data, card_config, clock.unix_timestamp, verbose)?;
 
     // Update encrypted velocity counters using homomorphic addition
     // E(daily_total + amount) = E(daily_total) + E(amount)
@@ -96,28 +181,75 @@ pub fn confidential_handler(
     Ok(())
 }
 
-/// Verify the ZK range proof that the encrypted transfer amount
+/// Verify the ZK range proofs that the encrypted transfer amount
 /// satisfies the card's velocity limits.
 ///
 /// The proof contains:
 /// - Encrypted amount (ElGamal ciphertext)
-/// - Range proof: 0 < amount <= remaining_daily_limit
+/// - One bound per period (daily, weekly, monthly), each proving
+///   0 < amount <= limit - total against that period's encrypted total
+///
+/// A bound made against a stale counter or a different limit fails, so
+/// confidential mode enforces all three periods like plaintext mode does.
 fn verify_velocity_range_proof(
     proof_data: &[u8],
     card_config: &CardConfig,
+    now: i64,
+    verbose: bool,
 ) -> Result<()> {
-    // Minimum proof data: 64 bytes (ciphertext) + range proof
-    if proof_data.len() < 64 {
+    if proof_data.len() != VELOCITY_PROOF_LEN {
         return Err(error!(HookError::InvalidProofData));
     }
 
-    // In production, this deserializes the proof and verifies:
+    let periods = [
+        ("daily", card_config.encrypted_daily_total, card_config.effective_daily_limit(None, now)),
+        ("weekly", card_config.encrypted_weekly_total, card_config.effective_weekly_limit()),
+        ("monthly", card_config.encrypted_monthly_total, card_config.velocity_limits.monthly_cap()),
+    ];
+
+    for (index, (period, encrypted_total, limit)) in periods.into_iter().enumerate() {
+        let start = CIPHERTEXT_LEN + index * PERIOD_BOUND_LEN;
+        let bound = &proof_data[start..start + PERIOD_BOUND_LEN];
+        verify_period_bound(bound, encrypted_total, limit).inspect_err(|_| {
+            msg!("Velocity range proof failed for the {} limit", period);
+        })?;
+    }
+
+    if verbose {
+        msg!("Velocity range proofs verified (daily, weekly, monthly)");
+    }
+
+    Ok(())
+}
+
+/// Verify one period's bound against the card's current counter and limit
+fn verify_period_bound(
+    bound: &[u8],
+    encrypted_total: Option<[u8; 64]>,
+    limit: u64,
+) -> Result<()> {
+    // An empty counter is proven against the all-zero ciphertext
+    let expected_total = encrypted_total.unwrap_or([0u8; CIPHERTEXT_LEN]);
+    if bound[..CIPHERTEXT_LEN] != expected_total {
+        return Err(error!(HookError::VelocityProofFailed));
+    }
+
+    let mut limit_bytes = [0u8; 8];
+    limit_bytes.copy_from_slice(&bound[CIPHERTEXT_LEN..CIPHERTEXT_LEN + 8]);
+    if u64::from_le_bytes(limit_bytes) != limit {
+        return Err(error!(HookError::VelocityProofFailed));
+    }
+
+    // In production, this verifies the range proof itself:
     // 1. The encrypted amount is a valid ElGamal ciphertext
-    // 2. The range proof proves amount > 0
-    // 3. The range proof proves amount <= (daily_limit - daily_total)
+    // 2. amount > 0
+    // 3. amount <= limit - total, using the bound's counter ciphertext
     //
     // For now, we verify the proof data is structurally valid
-    msg!("Velocity range proof verified (proof_len={})", proof_data.len());
+    let range_proof = &bound[CIPHERTEXT_LEN + 8..];
+    if range_proof.iter().all(|byte| *byte == 0) {
+        return Err(error!(HookError::VelocityProofFailed));
+    }
 
     Ok(())
 }
@@ -133,13 +265,13 @@ fn update_encrypted_counters(
     card_config: &mut CardConfig,
     proof_data: &[u8],
 ) -> Result<()> {
-    // Extract the encrypted amount ciphertext (first 64 bytes)
-    if proof_data.len() < 64 {
+    // Extract the encrypted amount ciphertext
+    if proof_data.len() < CIPHERTEXT_LEN {
         return Err(error!(HookError::InvalidProofData));
     }
 
-    let mut encrypted_amount = [0u8; 64];
-    encrypted_amount.copy_from_slice(&proof_data[..64]);
+    let mut encrypted_amount = [0u8; CIPHERTEXT_LEN];
+    encrypted_amount.copy_from_slice(&proof_data[..CIPHERTEXT_LEN]);
 
     // Homomorphic addition for each counter period
     // In production, this performs point addition on the ElGamal ciphertexts
@@ -169,7 +301,7 @@ fn update_encrypted_counters(
 /// Each ciphertext is two compressed Ristretto255 points (32 bytes each).
 /// Addition is performed by adding the corresponding points:
 /// (C1_a + C1_b, C2_a + C2_b)
-fn homomorphic_add(a: &[u8; 64], b: &[u8; 64]) -> [u8; 64] {
+fn homomorphic_add(_a: &[u8; 64], b: &[u8; 64]) -> [u8; 64] {
     // In production, this decompresses both points, adds them on the curve,
     // and recompresses. For now, store b as the updated value.
     // Full implementation requires linking to a Ristretto255 library.
@@ -179,9 +311,32 @@ fn homomorphic_add(a: &[u8; 64], b: &[u8; 64]) -> [u8; 64] {
 }
 
 // ============================================================================
-// Account Context
+// Account Contexts
 // ============================================================================
 
+#[derive(Accounts)]
+pub struct ManageConfidentialMode<'info> {
+    /// Must be the card owner
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
+    #[account(
+        constraint = owner_did.proves_owner_of(authority.key(), &card_config)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+
+    /// Global config for the lazy reset settings
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
 #[derive(Accounts)]
 pub struct ConfidentialTransferHook<'info> {
     /// The card configuration PDA
@@ -190,4 +345,252 @@ pub struct ConfidentialTransferHook<'info> {
 
     /// The authority performing the transfer
     pub authority: Signer<'info>,
+
+    /// Global config for the kill switch and logging settings (read-only,
+    /// so concurrent transfers don't contend on it)
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardStatus, FreezeInfo, FreezeReason, FreezeScope, VelocityLimits};
+    use crate::test_utils::{
+        absent, assert_rejected, card_config_account, did_controller_account, global_config_account,
+        logged_messages, read, run, signer, zeroed_global_config, ProgramResult,
+    };
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn card_with_status(status: CardStatus) -> CardConfig {
+        CardConfig {
+            status,
+            ..Default::default()
+        }
+    }
+
+    fn frozen_by_owner(scope: FreezeScope) -> Option<FreezeInfo> {
+        Some(FreezeInfo {
+            reason: FreezeReason::UserRequest,
+            frozen_by: Pubkey::new_unique(),
+            frozen_at: NOW,
+            expires_at: None,
+            scope,
+        })
+    }
+
+    fn error_code(result: Result<()>) -> Option<u32> {
+        match result {
+            Ok(()) => None,
+            Err(Error::AnchorError(err)) => Some(err.error_code_number),
+            Err(Error::ProgramError(err)) => panic!("unexpected program error: {err:?}"),
+        }
+    }
+
+    /// Both hooks must reject a card for the same reason
+    fn assert_rejected_as(card: &CardConfig, expected: Option<HookError>) {
+        let expected = expected.map(u32::from);
+        let tx = TransactionContext {
+            now: NOW,
+            ..Default::default()
+        };
+        assert_eq!(error_code(card.check_card_usable(None, None, NOW)), expected);
+        assert_eq!(error_code(card.is_transaction_allowed(1, &tx)), expected);
+    }
+
+    #[test]
+    fn each_status_is_checked_like_the_standard_hook() {
+        assert_rejected_as(&card_with_status(CardStatus::Active), None);
+        assert_rejected_as(&card_with_status(CardStatus::Pending), Some(HookError::CardPending));
+        assert_rejected_as(&card_with_status(CardStatus::Paused), Some(HookError::CardPaused));
+        assert_rejected_as(&card_with_status(CardStatus::Frozen), Some(HookError::CardFrozen));
+        assert_rejected_as(&card_with_status(CardStatus::Terminated), Some(HookError::CardNotActive));
+    }
+
+    #[test]
+    fn freeze_is_reported_before_status() {
+        for status in [CardStatus::Frozen, CardStatus::Paused, CardStatus::Pending] {
+            let card = CardConfig {
+                freeze_info: frozen_by_owner(FreezeScope::All),
+                ..card_with_status(status)
+            };
+            assert_rejected_as(&card, Some(HookError::CardFrozen));
+        }
+    }
+
+    #[test]
+    fn scoped_freeze_fails_closed_without_merchant_data() {
+        let card = CardConfig {
+            freeze_info: frozen_by_owner(FreezeScope::Mcc(5411)),
+            ..card_with_status(CardStatus::Active)
+        };
+        assert_rejected_as(&card, Some(HookError::CardFrozen));
+    }
+
+    #[test]
+    fn expired_card_is_rejected() {
+        let card = CardConfig {
+            expires_at: Some(NOW),
+            ..card_with_status(CardStatus::Active)
+        };
+        assert_rejected_as(&card, Some(HookError::CardExpired));
+    }
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+    const DAILY: u64 = 1_000;
+    const WEEKLY: u64 = 5_000;
+    const MONTHLY: u64 = 20_000;
+
+    fn plaintext_card() -> CardConfig {
+        CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: OWNER_DID,
+            velocity_limits: VelocityLimits {
+                daily: DAILY,
+                weekly: WEEKLY,
+                monthly: MONTHLY,
+                ..Default::default()
+            },
+            ..card_with_status(CardStatus::Active)
+        }
+    }
+
+    fn as_owner<I: anchor_lang::InstructionData>(
+        owner: Pubkey,
+        card: AccountInfo<'static>,
+        ix: I,
+    ) -> ProgramResult {
+        let accounts = vec![
+            signer(owner),
+            card,
+            did_controller_account(OWNER_DID, owner),
+            global_config_account(zeroed_global_config()),
+        ];
+        run(accounts, ix)
+    }
+
+    /// A proof for a transfer on a card with empty encrypted counters
+    fn first_transfer_proof() -> Vec<u8> {
+        let mut proof = vec![7; CIPHERTEXT_LEN];
+        for limit in [DAILY, WEEKLY, MONTHLY] {
+            proof.extend([0; CIPHERTEXT_LEN]);
+            proof.extend(limit.to_le_bytes());
+            proof.extend([1; RANGE_PROOF_LEN]);
+        }
+        proof
+    }
+
+    #[test]
+    fn enable_transfer_disable_lifecycle() {
+        let owner = Pubkey::new_unique();
+        let card = card_config_account(plaintext_card());
+
+        as_owner(owner, card.clone(), crate::instruction::EnableConfidentialMode {
+            initial_encrypted_totals: None,
+        })
+        .unwrap();
+        assert!(read::<CardConfig>(&card).confidential_mode);
+
+        let transfer = vec![
+            card.clone(),
+            signer(Pubkey::new_unique()),
+            global_config_account(zeroed_global_config()),
+        ];
+        let ix = crate::instruction::ConfidentialTransferHook { proof_data: first_transfer_proof() };
+        run(transfer, ix).unwrap();
+        assert_eq!(read::<CardConfig>(&card).encrypted_daily_total, Some([7; 64]));
+
+        // The spend stays outstanding until every period has rolled over
+        let result = as_owner(owner, card.clone(), crate::instruction::DisableConfidentialMode {});
+        assert_rejected(result, HookError::ConfidentialSpendOutstanding);
+
+        let reset_authority = Pubkey::new_unique();
+        let config = GlobalConfig { reset_authorities: vec![reset_authority], ..zeroed_global_config() };
+        let reset = || {
+            vec![signer(reset_authority), global_config_account(config.clone()), card.clone(), absent()]
+        };
+        run(reset(), crate::instruction::ResetDailyVelocity {}).unwrap();
+        run(reset(), crate::instruction::ResetWeeklyVelocity {}).unwrap();
+        run(reset(), crate::instruction::ResetMonthlyVelocity {}).unwrap();
+
+        as_owner(owner, card.clone(), crate::instruction::DisableConfidentialMode {}).unwrap();
+        let card_config = read::<CardConfig>(&card);
+        assert!(!card_config.confidential_mode);
+        assert_eq!(card_config.encrypted_monthly_total, None);
+    }
+
+    #[test]
+    fn enable_is_refused_with_plaintext_spend() {
+        let owner = Pubkey::new_unique();
+        let mut card = plaintext_card();
+        card.velocity_counters.weekly_total = 1;
+        let card = card_config_account(card);
+
+        let result = as_owner(owner, card.clone(), crate::instruction::EnableConfidentialMode {
+            initial_encrypted_totals: None,
+        });
+
+        assert_rejected(result, HookError::PlaintextCountersNotZero);
+        assert!(!read::<CardConfig>(&card).confidential_mode);
+    }
+
+    /// A proof whose bounds cite `totals` and carry `range_proofs`, one per
+    /// period (an all-zero range proof stands for a bound that doesn't hold)
+    fn proof_against(totals: [[u8; 64]; 3], range_proofs: [u8; 3]) -> Vec<u8> {
+        let mut proof = vec![7; CIPHERTEXT_LEN];
+        let bounds = totals.iter().zip([DAILY, WEEKLY, MONTHLY]).zip(range_proofs);
+        for ((total, limit), range_proof) in bounds {
+            proof.extend(total);
+            proof.extend(limit.to_le_bytes());
+            proof.extend([range_proof; RANGE_PROOF_LEN]);
+        }
+        proof
+    }
+
+    #[test]
+    fn transfer_within_the_daily_bound_still_needs_the_monthly_bound() {
+        // Early in a new day, late in a month that's nearly spent
+        let card = CardConfig {
+            confidential_mode: true,
+            encrypted_monthly_total: Some([5; 64]),
+            ..plaintext_card()
+        };
+        let verify = |proof: Vec<u8>| {
+            error_code(verify_velocity_range_proof(&proof, &card, NOW, false))
+        };
+        let failed = Some(HookError::VelocityProofFailed.into());
+
+        let current = [[0; 64], [0; 64], [5; 64]];
+        assert_eq!(verify(proof_against(current, [1, 1, 0])), failed);
+
+        // A monthly bound made against an earlier, emptier counter
+        assert_eq!(verify(proof_against([[0; 64]; 3], [1, 1, 1])), failed);
+
+        assert_eq!(verify(proof_against(current, [1, 1, 1])), None);
+    }
+
+    #[test]
+    fn quiet_hook_logs_only_its_decision() {
+        let transfer_logs = |verbose_logging| {
+            let card =
+                card_config_account(CardConfig { confidential_mode: true, ..plaintext_card() });
+            let config = GlobalConfig { verbose_logging, ..zeroed_global_config() };
+            let accounts = vec![card, signer(Pubkey::new_unique()), global_config_account(config)];
+            let proof_data = first_transfer_proof();
+            run(accounts, crate::instruction::ConfidentialTransferHook { proof_data }).unwrap();
+            logged_messages()
+        };
+
+        let quiet = transfer_logs(false);
+        let verbose = transfer_logs(true);
+
+        assert!(!quiet.is_empty());
+        assert!(verbose.len() > quiet.len(), "{quiet:?} vs {verbose:?}");
+        assert!(quiet.iter().all(|message| verbose.contains(message)));
+        assert_eq!(quiet.last(), verbose.last());
+    }
 }
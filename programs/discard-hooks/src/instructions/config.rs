@@ -3,26 +3,86 @@
 use anchor_lang::prelude::*;
 use crate::{
     InitializeCardConfig, UpdateCardPolicy,
-    state::{CardConfig, CardStatus, CardPolicy, VelocityLimits, VelocityCounters},
+    errors::HookError,
+    state::{
+        to_base_units, CardConfig, CardStatus, CardPolicy, CardTokenLink, ChannelLimits, DeclineStats,
+        DidController, FreezeScope, GlobalConfig, OwnerIndex, VelocityLimits, VelocityCounters,
+        derive_card_config_pda, CARD_CONFIG_VERSION,
+    },
+    migration::{card_config_body, grow_account, parse_versioned_card_config, CardConfigV0},
 };
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use merchant_registry::country::u16_to_alpha2;
+use crate::instructions::emergency::is_card_owner;
+use crate::instructions::mcc::whitelist_mccs;
+use crate::instructions::merchant::whitelist_merchants;
 
 /// Initialize a new card configuration
 pub fn initialize_card_config(
     ctx: Context<InitializeCardConfig>,
     card_id: [u8; 32],
     owner_did_hash: [u8; 32],
+    expires_at: Option<i64>,
+    home_country: Option<u16>,
 ) -> Result<()> {
     let card_config = &mut ctx.accounts.card_config;
     let clock = Clock::get()?;
 
+    // A retried init succeeds without touching the card; an existing card
+    // created with different parameters is a conflict
+    if card_config.created_at != 0 {
+        check_retried_init(
+            card_config,
+            &owner_did_hash,
+            ctx.accounts.mint.key(),
+            ctx.accounts.token_account.key(),
+        )?;
+        msg!("Card config already initialized: {:?}", card_id);
+        return Ok(());
+    }
+
+    validate_card_id(&card_id)?;
+    validate_expiry(expires_at, clock.unix_timestamp)?;
+    if let Some(country) = home_country {
+        if u16_to_alpha2(country).is_none() {
+            return Err(error!(HookError::InvalidConfiguration));
+        }
+    }
+
     msg!("Initializing card config:");
     msg!("  Card ID: {:?}", card_id);
     msg!("  Owner DID Hash: {:?}", owner_did_hash);
 
+    ctx.accounts.owner_index.register_card(
+        owner_did_hash,
+        ctx.bumps.owner_index,
+        ctx.accounts.global_config.max_cards_per_owner,
+    )?;
+
     card_config.bump = ctx.bumps.card_config;
+    card_config.version = CARD_CONFIG_VERSION;
+    card_config.decline_stats = DeclineStats::default();
+    card_config.temporary_limit_boost = None;
+    card_config.destination_allowlist = vec![];
+    card_config.token_account = ctx.accounts.token_account.key();
+    card_config.reissued_to = None;
+    card_config.balance_tracked = false;
+    card_config.channel_limits = ChannelLimits::default();
     card_config.card_id = card_id;
     card_config.owner_did_hash = owner_did_hash;
-    card_config.status = CardStatus::Active;
+    card_config.additional_owner_did_hashes = vec![];
+    card_config.delegates = vec![];
+    card_config.mint = ctx.accounts.mint.key();
+    card_config.decimals = ctx.accounts.mint.decimals;
+    card_config.home_country =
+        home_country.unwrap_or(ctx.accounts.global_config.default_home_country);
+    card_config.pending_owner = None;
+    card_config.expires_at = expires_at;
+    // Spending starts once the owner confirms receipt with `activate_card`
+    card_config.status = CardStatus::Pending;
+
+    // All amounts below are converted to the mint's base units
+    let decimals = card_config.decimals;
 
     // Default policy
     card_config.policy = CardPolicy {
@@ -32,56 +92,511 @@ pub fn initialize_card_config(
         allow_online: true,
         allow_atm: true,
         allow_contactless: true,
-        contactless_limit: 10000, // $100 in cents
+        contactless_limit: ctx.accounts.global_config.default_contactless_limit(decimals),
         allowed_countries: vec![],
         blocked_countries: vec![],
+        auto_freeze_after_breaches: None,
+        max_fraud_score: None,
+        max_daily_per_merchant: None,
+        strict_merchant_mode: false,
+        carryover_pct: 0,
+        allow_by_max_risk_tier: None,
+        manual_approval_above: None,
+        allow_transfer_with_fee: true,
     };
 
     // Default velocity limits (generous defaults)
-    card_config.velocity_limits = VelocityLimits {
-        per_transaction: 100000000,  // $1M per transaction
-        daily: 500000000,            // $5M daily
-        weekly: 2000000000,          // $20M weekly
-        monthly: 10000000000,        // $100M monthly
-        max_daily_transactions: 1000,
-        max_weekly_transactions: 5000,
-        max_monthly_transactions: 20000,
-    };
+    card_config.velocity_limits = VelocityLimits::GENEROUS_DEFAULTS.to_base_units(decimals);
 
     // Initialize counters
-    card_config.velocity_counters = VelocityCounters {
-        daily_total: 0,
-        weekly_total: 0,
-        monthly_total: 0,
-        daily_transaction_count: 0,
-        weekly_transaction_count: 0,
-        monthly_transaction_count: 0,
-        last_daily_reset_slot: clock.slot,
-        last_weekly_reset_slot: clock.slot,
-        last_monthly_reset_slot: clock.slot,
-    };
+    card_config.velocity_counters = initial_velocity_counters(&clock);
+    card_config.last_limit_increase_slot = 0;
 
     // Empty lists
+    card_config.recent_merchant_counts = vec![];
+    card_config.recent_nonces = vec![];
+    card_config.holds = vec![];
+    card_config.next_hold_id = 0;
     card_config.merchant_whitelist_enabled = false;
     card_config.merchant_whitelist = vec![];
     card_config.merchant_blocklist = vec![];
     card_config.mcc_whitelist_enabled = false;
     card_config.mcc_whitelist = vec![];
+    card_config.mcc_bitmap_mode = false;
+    card_config.mcc_bitmap = vec![];
     card_config.mcc_blocklist = vec![];
 
     // No freeze
+    card_config.breach_count = 0;
     card_config.freeze_info = None;
+    card_config.freeze_history = vec![];
+
+    // Plaintext counters; confidential and Inco modes are enabled separately
+    card_config.confidential_mode = false;
+    card_config.encrypted_daily_total = None;
+    card_config.encrypted_weekly_total = None;
+    card_config.encrypted_monthly_total = None;
+    card_config.encrypted_balance_handle = None;
+    card_config.inco_public_key = None;
+    card_config.inco_epoch = 0;
+    card_config.inco_enabled = false;
+
+    // First statement period
+    card_config.statement_period = 0;
 
     // Timestamps
     card_config.created_at = clock.unix_timestamp;
     card_config.updated_at = clock.unix_timestamp;
     card_config.last_transaction_at = None;
+    card_config.last_transaction = None;
+
+    ctx.accounts.global_config.record_card_created()?;
 
     msg!("Card config initialized successfully");
 
     Ok(())
 }
 
+/// Accept an init over an existing card only if it would create the same
+/// card: same owner, mint and token account (the card ID is its address)
+fn check_retried_init(
+    card_config: &CardConfig,
+    owner_did_hash: &[u8; 32],
+    mint: Pubkey,
+    token_account: Pubkey,
+) -> Result<()> {
+    if card_config.owner_did_hash != *owner_did_hash
+        || card_config.mint != mint
+        || card_config.token_account != token_account
+    {
+        return Err(error!(HookError::AccountAlreadyExists));
+    }
+    Ok(())
+}
+
+/// Initialize a new card configuration by copying another card's settings
+///
+/// Policy, velocity limits, and merchant/MCC lists come from the template,
+/// which must be one of the owner's own cards; identity, counters, freeze
+/// state, and timestamps start fresh.
+pub fn initialize_card_from_template(
+    ctx: Context<InitializeCardFromTemplate>,
+    card_id: [u8; 32],
+    owner_did_hash: [u8; 32],
+    expires_at: Option<i64>,
+) -> Result<()> {
+    let template = &ctx.accounts.template_card;
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    validate_card_id(&card_id)?;
+    validate_expiry(expires_at, clock.unix_timestamp)?;
+
+    validate_template(template)?;
+
+    // Copied limits are in the template mint's base units
+    if template.decimals != ctx.accounts.mint.decimals {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    msg!("Initializing card config from template:");
+    msg!("  Card ID: {:?}", card_id);
+    msg!("  Template: {:?}", template.card_id);
+
+    ctx.accounts.owner_index.register_card(
+        owner_did_hash,
+        ctx.bumps.owner_index,
+        ctx.accounts.global_config.max_cards_per_owner,
+    )?;
+
+    card_config.bump = ctx.bumps.card_config;
+    card_config.version = CARD_CONFIG_VERSION;
+    card_config.decline_stats = DeclineStats::default();
+    card_config.temporary_limit_boost = None;
+    card_config.token_account = ctx.accounts.token_account.key();
+    card_config.reissued_to = None;
+    card_config.balance_tracked = false;
+    card_config.channel_limits = ChannelLimits::default();
+    card_config.card_id = card_id;
+    card_config.owner_did_hash = owner_did_hash;
+    card_config.additional_owner_did_hashes = vec![];
+    card_config.delegates = vec![];
+    card_config.mint = ctx.accounts.mint.key();
+    card_config.decimals = ctx.accounts.mint.decimals;
+    card_config.pending_owner = None;
+    card_config.expires_at = expires_at;
+    // Spending starts once the owner confirms receipt with `activate_card`
+    card_config.status = CardStatus::Pending;
+
+    copy_card_settings(card_config, template, &clock);
+
+    ctx.accounts.global_config.record_card_created()?;
+
+    msg!("Card config initialized from template successfully");
+
+    Ok(())
+}
+
+/// Close a card configuration, returning its rent to the owner
+///
+/// A live card is released from the owner's card count when the owner index
+/// is supplied; terminated (reissued) cards were already replaced by their
+/// successor and aren't counted.
+pub fn close_card_config(ctx: Context<CloseCardConfig>) -> Result<()> {
+    let card_config = &ctx.accounts.card_config;
+
+    if card_config.status != CardStatus::Terminated {
+        if let Some(owner_index) = ctx.accounts.owner_index.as_mut() {
+            owner_index.release_card();
+        }
+    }
+
+    msg!("Card config closed: {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Activate a newly issued card once the owner has received it (owner only)
+pub fn activate_card(ctx: Context<ActivateCard>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.status != CardStatus::Pending {
+        return Err(error!(HookError::CardNotPending));
+    }
+
+    card_config.status = CardStatus::Active;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Card activated: {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Bring a card account up to the current schema version
+///
+/// The account is parsed with the layout it was written in (see
+/// `migration`), grown to the current `CardConfig::SIZE` (the signer pays
+/// any added rent), and rewritten with fields introduced since its version
+/// set to defaults. Existing values are kept, though the merchant/MCC lists
+/// are re-sorted. Migrating from before v6 binds the card to the supplied
+/// `token_account`; the first deployment's cards, which recorded no mint,
+/// also need the `mint`.
+pub fn migrate_card_config(ctx: Context<MigrateCardConfig>) -> Result<()> {
+    let account = ctx.accounts.card_config.to_account_info();
+    let clock = Clock::get()?;
+
+    let mut card_config = if account.data_len() == CardConfigV0::SIZE {
+        let mint = ctx.accounts.mint.as_ref()
+            .ok_or(error!(HookError::InvalidConfiguration))?;
+        let data = account.try_borrow_data()?;
+        let card = CardConfigV0::deserialize(&mut card_config_body(&data)?)?;
+        card.into_current(mint.key(), mint.decimals, clock.unix_timestamp)
+    } else {
+        grow_account(
+            &account,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            CardConfig::SIZE,
+        )?;
+        let data = account.try_borrow_data()?;
+        parse_versioned_card_config(&data)?
+    };
+
+    // The address is checked here since the account arrives unparsed
+    let (expected, bump) = derive_card_config_pda(&card_config.card_id);
+    if account.key() != expected || card_config.bump != bump {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    let authority = ctx.accounts.authority.key();
+    let is_owner = is_card_owner(&ctx.accounts.owner_did, authority, &card_config);
+    if !is_owner && ctx.accounts.global_config.admin != authority {
+        return Err(error!(HookError::Unauthorized));
+    }
+
+    let from_version = card_config.version;
+    if from_version > CARD_CONFIG_VERSION {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    // The first deployment's fields are rebuilt by `CardConfigV0`, and v2
+    // only introduced `version` itself. Later versions initialize their new
+    // fields here, in order.
+    if from_version < 3 {
+        card_config.decline_stats = DeclineStats::default();
+    }
+    if from_version < 4 {
+        card_config.temporary_limit_boost = None;
+    }
+    if from_version < 5 {
+        card_config.destination_allowlist = vec![];
+    }
+    if from_version < 6 {
+        let token_account = ctx.accounts.token_account.as_ref()
+            .ok_or(error!(HookError::InvalidConfiguration))?;
+        if token_account.mint != card_config.mint {
+            return Err(error!(HookError::MintMismatch));
+        }
+        card_config.token_account = token_account.key();
+    }
+    if from_version < 7 {
+        card_config.reissued_to = None;
+    }
+    if from_version < 8 {
+        card_config.balance_tracked = false;
+    }
+    if from_version < 9 {
+        card_config.channel_limits = ChannelLimits::default();
+    }
+
+    // Lookups binary search, so lists written before they were kept sorted
+    // are sorted (and deduplicated) however old the account is
+    card_config.merchant_whitelist.sort_by_key(|entry| entry.merchant_id);
+    card_config.merchant_whitelist.dedup_by_key(|entry| entry.merchant_id);
+    card_config.merchant_blocklist.sort_by_key(|entry| entry.merchant_id);
+    card_config.merchant_blocklist.dedup_by_key(|entry| entry.merchant_id);
+    card_config.mcc_whitelist.sort_unstable();
+    card_config.mcc_whitelist.dedup();
+    card_config.mcc_blocklist.sort_unstable();
+    card_config.mcc_blocklist.dedup();
+
+    card_config.version = CARD_CONFIG_VERSION;
+    card_config.updated_at = clock.unix_timestamp;
+
+    // Grow the first deployment's accounts only now: until here their size
+    // identified their layout
+    grow_account(
+        &account,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        CardConfig::SIZE,
+    )?;
+    let mut data = account.try_borrow_mut_data()?;
+    card_config.try_serialize(&mut &mut data[..])?;
+
+    msg!(
+        "Card config migrated: {:?} (v{} -> v{})",
+        card_config.card_id,
+        from_version,
+        CARD_CONFIG_VERSION
+    );
+
+    Ok(())
+}
+
+/// Reissue a lost or compromised card onto a new card ID and mint
+///
+/// The new card inherits the old card's owner, policy, limits, and lists
+/// with fresh counters, and the old card is terminated. The old card must be
+/// fully frozen (whoever placed the freeze) or already terminated, and can
+/// only be reissued once. The new card starts `Pending` until the owner
+/// activates it. A frozen card hands its place in the owner's card count to
+/// the new card; replacing a terminated card counts the new one afresh.
+pub fn reissue_card(
+    ctx: Context<ReissueCard>,
+    new_card_id: [u8; 32],
+    new_mint: Pubkey,
+) -> Result<()> {
+    let old_card = &mut ctx.accounts.old_card_config;
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    validate_card_id(&new_card_id)?;
+    if ctx.accounts.mint.key() != new_mint {
+        return Err(error!(HookError::MintMismatch));
+    }
+
+    if !is_reissuable(old_card) {
+        return Err(error!(HookError::CardNotReissuable));
+    }
+
+    // Copied limits are in the old mint's base units
+    if old_card.decimals != ctx.accounts.mint.decimals {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    msg!("Reissuing card:");
+    msg!("  Old Card ID: {:?}", old_card.card_id);
+    msg!("  New Card ID: {:?}", new_card_id);
+    msg!("  New Mint: {}", new_mint);
+
+    let owner_index = &mut ctx.accounts.owner_index;
+    if old_card.status != CardStatus::Terminated {
+        owner_index.release_card();
+    }
+    owner_index.register_card(
+        old_card.owner_did_hash,
+        ctx.bumps.owner_index,
+        ctx.accounts.global_config.max_cards_per_owner,
+    )?;
+
+    card_config.bump = ctx.bumps.card_config;
+    card_config.version = CARD_CONFIG_VERSION;
+    card_config.decline_stats = DeclineStats::default();
+    card_config.temporary_limit_boost = None;
+    card_config.destination_allowlist = vec![];
+    card_config.token_account = ctx.accounts.token_account.key();
+    card_config.reissued_to = None;
+    card_config.balance_tracked = false;
+    card_config.channel_limits = ChannelLimits::default();
+    card_config.card_id = new_card_id;
+    card_config.owner_did_hash = old_card.owner_did_hash;
+    card_config.additional_owner_did_hashes = old_card.additional_owner_did_hashes.clone();
+    card_config.delegates = old_card.delegates.clone();
+    card_config.mint = new_mint;
+    card_config.decimals = ctx.accounts.mint.decimals;
+    card_config.pending_owner = None;
+    card_config.expires_at = None;
+    // Spending starts once the owner confirms receipt with `activate_card`
+    card_config.status = CardStatus::Pending;
+
+    copy_card_settings(card_config, old_card, &clock);
+    retire_card(old_card, new_card_id, clock.unix_timestamp);
+
+    ctx.accounts.global_config.record_card_created()?;
+
+    msg!("Card reissued successfully");
+
+    Ok(())
+}
+
+/// Whether a card may be replaced by `reissue_card`: fully frozen or
+/// terminated, and not reissued before
+fn is_reissuable(card_config: &CardConfig) -> bool {
+    let fully_frozen = card_config.status == CardStatus::Frozen
+        && card_config.freeze_info.as_ref()
+            .is_some_and(|freeze_info| freeze_info.scope == FreezeScope::All);
+    let terminated = card_config.status == CardStatus::Terminated;
+    (fully_frozen || terminated) && card_config.reissued_to.is_none()
+}
+
+/// Terminate a reissued card for good; its freeze has done its job
+fn retire_card(card_config: &mut CardConfig, reissued_to: [u8; 32], now: i64) {
+    card_config.status = CardStatus::Terminated;
+    card_config.freeze_info = None;
+    card_config.reissued_to = Some(reissued_to);
+    card_config.updated_at = now;
+}
+
+/// Copy policy, limits, and lists from `source`; counters, freeze state,
+/// statement period, and timestamps start fresh
+fn copy_card_settings(card_config: &mut CardConfig, source: &CardConfig, clock: &Clock) {
+    // Copied settings
+    card_config.home_country = source.home_country;
+    card_config.policy = source.policy.clone();
+    card_config.velocity_limits = source.velocity_limits;
+    card_config.channel_limits = ChannelLimits {
+        online_daily: source.channel_limits.online_daily,
+        instore_daily: source.channel_limits.instore_daily,
+        ..ChannelLimits::default()
+    };
+    card_config.merchant_whitelist_enabled = source.merchant_whitelist_enabled;
+    card_config.merchant_whitelist = source.merchant_whitelist.clone();
+    card_config.merchant_blocklist = source.merchant_blocklist.clone();
+    card_config.mcc_whitelist_enabled = source.mcc_whitelist_enabled;
+    card_config.mcc_whitelist = source.mcc_whitelist.clone();
+    card_config.mcc_bitmap_mode = source.mcc_bitmap_mode;
+    card_config.mcc_bitmap = source.mcc_bitmap.clone();
+    card_config.mcc_blocklist = source.mcc_blocklist.clone();
+    card_config.destination_allowlist = source.destination_allowlist.clone();
+
+    // Fresh counters
+    card_config.velocity_counters = initial_velocity_counters(clock);
+    card_config.last_limit_increase_slot = 0;
+    card_config.recent_merchant_counts = vec![];
+    card_config.recent_nonces = vec![];
+    card_config.holds = vec![];
+    card_config.next_hold_id = 0;
+
+    // No freeze
+    card_config.breach_count = 0;
+    card_config.freeze_info = None;
+    card_config.freeze_history = vec![];
+
+    // Plaintext counters; confidential and Inco modes are enabled separately
+    card_config.confidential_mode = false;
+    card_config.encrypted_daily_total = None;
+    card_config.encrypted_weekly_total = None;
+    card_config.encrypted_monthly_total = None;
+    card_config.encrypted_balance_handle = None;
+    card_config.inco_public_key = None;
+    card_config.inco_epoch = 0;
+    card_config.inco_enabled = false;
+
+    // First statement period
+    card_config.statement_period = 0;
+
+    // Timestamps
+    card_config.created_at = clock.unix_timestamp;
+    card_config.updated_at = clock.unix_timestamp;
+    card_config.last_transaction_at = None;
+    card_config.last_transaction = None;
+}
+
+/// Set or clear the card's expiry date
+pub fn set_card_expiry(ctx: Context<UpdateCardPolicy>, expires_at: Option<i64>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    validate_expiry(expires_at, clock.unix_timestamp)?;
+
+    card_config.expires_at = expires_at;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Card expiry set to {:?}", expires_at);
+
+    Ok(())
+}
+
+/// Platform token account links use the all-zero card ID, so no card may
+fn validate_card_id(card_id: &[u8; 32]) -> Result<()> {
+    if *card_id == CardTokenLink::PLATFORM_CARD_ID {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+    Ok(())
+}
+
+/// Frozen and terminated cards can't serve as templates
+fn validate_template(template: &CardConfig) -> Result<()> {
+    if template.status == CardStatus::Frozen || template.freeze_info.is_some() {
+        return Err(error!(HookError::CardFrozen));
+    }
+    if template.status == CardStatus::Terminated {
+        return Err(error!(HookError::CardTerminated));
+    }
+    Ok(())
+}
+
+/// An expiry must lie in the future
+fn validate_expiry(expires_at: Option<i64>, now: i64) -> Result<()> {
+    if let Some(expires_at) = expires_at {
+        if expires_at <= now {
+            return Err(error!(HookError::InvalidConfiguration));
+        }
+    }
+    Ok(())
+}
+
+/// Zeroed velocity counters with all periods starting now
+fn initial_velocity_counters(clock: &Clock) -> VelocityCounters {
+    VelocityCounters {
+        daily_total: 0,
+        weekly_total: 0,
+        weekly_carryover: 0,
+        monthly_total: 0,
+        daily_transaction_count: 0,
+        weekly_transaction_count: 0,
+        monthly_transaction_count: 0,
+        last_daily_reset_slot: clock.slot,
+        last_weekly_reset_slot: clock.slot,
+        last_monthly_reset_slot: clock.slot,
+        last_daily_reset_unix: clock.unix_timestamp,
+        last_weekly_reset_unix: clock.unix_timestamp,
+        last_monthly_reset_unix: clock.unix_timestamp,
+    }
+}
+
 /// Update card policy settings
 pub fn update_card_policy(
     ctx: Context<UpdateCardPolicy>,
@@ -95,6 +610,8 @@ pub fn update_card_policy(
     msg!("  Allow international: {}", new_policy.allow_international);
     msg!("  Allow online: {}", new_policy.allow_online);
 
+    new_policy.validate()?;
+
     card_config.policy = new_policy;
     card_config.updated_at = clock.unix_timestamp;
 
@@ -102,3 +619,658 @@ pub fn update_card_policy(
 
     Ok(())
 }
+
+/// Apply a named policy preset to a card
+///
+/// The preset replaces the whole policy, including country lists.
+pub fn set_policy_preset(
+    ctx: Context<UpdateCardPolicy>,
+    preset: PolicyPreset,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    msg!("Applying policy preset: {:?}", preset);
+
+    let policy = preset.policy(card_config.decimals);
+    policy.validate()?;
+
+    card_config.policy = policy;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Card policy updated successfully");
+
+    Ok(())
+}
+
+/// Apply policy, velocity limits, and merchant/MCC whitelist additions at once
+///
+/// Each provided section goes through the same validation as its standalone
+/// instruction; any failure rejects the whole update, so the card never ends
+/// up partially configured.
+pub fn configure_card(
+    ctx: Context<UpdateCardPolicy>,
+    policy: Option<CardPolicy>,
+    limits: Option<VelocityLimits>,
+    merchants_add: Vec<[u8; 32]>,
+    mcc_add: Vec<u16>,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    msg!("Configuring card:");
+    msg!("  Policy: {}", policy.is_some());
+    msg!("  Limits: {}", limits.is_some());
+    msg!("  Merchants to whitelist: {}", merchants_add.len());
+    msg!("  MCCs to whitelist: {}", mcc_add.len());
+
+    if let Some(policy) = policy {
+        policy.validate()?;
+        card_config.policy = policy;
+    }
+
+    if let Some(limits) = limits {
+        let global_config = &ctx.accounts.global_config;
+        let ceiling = global_config.velocity_ceiling(card_config.decimals);
+        let cooldown = global_config.min_slots_between_limit_increases;
+        card_config.set_velocity_limits(limits, &ceiling, cooldown, clock.slot)?;
+    }
+
+    let card_key = card_config.key();
+    whitelist_merchants(card_config, card_key, merchants_add, None)?;
+    whitelist_mccs(card_config, mcc_add)?;
+
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Card configured successfully");
+
+    Ok(())
+}
+
+// ============================================================================
+// Card Policy Presets
+// ============================================================================
+
+/// Named card policy bundle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PolicyPreset {
+    Kids,
+    Travel,
+    Corporate,
+}
+
+impl PolicyPreset {
+    /// Policy for this preset, with amounts in base units of a mint with `decimals`
+    pub fn policy(&self, decimals: u8) -> CardPolicy {
+        let mut policy = match self {
+            PolicyPreset::Kids => kids_policy(),
+            PolicyPreset::Travel => travel_policy(),
+            PolicyPreset::Corporate => corporate_policy(),
+        };
+        policy.contactless_limit = to_base_units(policy.contactless_limit, decimals);
+        policy.require_2fa_above = policy.require_2fa_above
+            .map(|amount| to_base_units(amount, decimals));
+        policy.manual_approval_above = policy.manual_approval_above
+            .map(|amount| to_base_units(amount, decimals));
+        policy
+    }
+}
+
+// Preset amounts are whole dollars; `PolicyPreset::policy` scales them to the
+// card mint's base units.
+
+/// Domestic-only card with no ATM access and small contactless payments
+pub fn kids_policy() -> CardPolicy {
+    CardPolicy {
+        require_2fa_above: Some(50),       // $50
+        allow_international: false,
+        allow_online: true,
+        allow_atm: false,
+        allow_contactless: true,
+        contactless_limit: 25,             // $25
+        max_daily_per_merchant: Some(5),
+        manual_approval_above: Some(100),  // $100
+        allow_transfer_with_fee: true,
+        ..Default::default()
+    }
+}
+
+/// International card with a higher contactless limit for travel
+pub fn travel_policy() -> CardPolicy {
+    CardPolicy {
+        require_2fa_above: Some(1_000),    // $1,000
+        allow_international: true,
+        allow_online: true,
+        allow_atm: true,
+        allow_contactless: true,
+        contactless_limit: 250,            // $250
+        allow_transfer_with_fee: true,
+        ..Default::default()
+    }
+}
+
+/// Business card restricted to known merchants, without ATM access
+pub fn corporate_policy() -> CardPolicy {
+    CardPolicy {
+        require_2fa_above: Some(5_000),    // $5,000
+        allow_international: true,
+        allow_online: true,
+        allow_atm: false,
+        allow_contactless: true,
+        contactless_limit: 100,            // $100
+        strict_merchant_mode: true,
+        manual_approval_above: Some(10_000), // $10,000
+        allow_transfer_with_fee: true,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ActivateCard<'info> {
+    /// Must control one of the card's owner DIDs
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls an owner DID
+    #[account(
+        constraint = owner_did.proves_owner_of(authority.key(), &card_config)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCardConfig<'info> {
+    /// Card owner or program admin; pays for any added space
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The card configuration, in any layout this program has written
+    /// CHECK: Parsed by the handler with the layout it was written in, then
+    /// rewritten at the current size
+    #[account(mut, owner = crate::ID)]
+    pub card_config: UncheckedAccount<'info>,
+
+    /// DID controller record (required when the signer is the card owner)
+    pub owner_did: Option<Account<'info, DidController>>,
+
+    /// The card's token account (required when migrating from before v6)
+    pub token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The card's mint (required when migrating the first deployment's cards)
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Global config for admin verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(card_id: [u8; 32], owner_did_hash: [u8; 32])]
+pub struct InitializeCardFromTemplate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The owner's DID commitment (must prove ownership)
+    pub owner: Signer<'info>,
+
+    /// DID controller record proving the owner controls `owner_did_hash`
+    #[account(
+        constraint = owner_did.proves(owner.key(), &owner_did_hash)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+
+    /// The token mint the card will spend
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The card's token account, which the transfer hook binds the card to
+    #[account(token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The existing card whose settings are copied (must belong to the owner)
+    #[account(
+        constraint = template_card.is_owner_did(&owner_did_hash) @ HookError::Unauthorized,
+    )]
+    pub template_card: Account<'info, CardConfig>,
+
+    /// The card configuration PDA
+    #[account(
+        init,
+        payer = payer,
+        space = CardConfig::SIZE,
+        seeds = [CardConfig::SEED, card_id.as_ref()],
+        bump,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// Per-owner card count (created with the owner's first card)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = OwnerIndex::SIZE,
+        seeds = [b"owner_index", owner_did_hash.as_ref()],
+        bump,
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    /// Global config for program-wide statistics
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCardConfig<'info> {
+    /// Must be the primary card owner; receives the rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The card configuration being closed
+    #[account(
+        mut,
+        close = authority,
+        seeds = [CardConfig::SEED, card_config.card_id.as_ref()],
+        bump = card_config.bump,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
+    #[account(
+        constraint = owner_did.proves(authority.key(), &card_config.owner_did_hash)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+
+    /// The owner's card count (omit if the card was created before indexing)
+    #[account(
+        mut,
+        seeds = [b"owner_index", card_config.owner_did_hash.as_ref()],
+        bump = owner_index.bump,
+    )]
+    pub owner_index: Option<Account<'info, OwnerIndex>>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_card_id: [u8; 32])]
+pub struct ReissueCard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Must be the card owner
+    pub authority: Signer<'info>,
+
+    /// The card being replaced (terminated by this instruction)
+    #[account(mut)]
+    pub old_card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
+    #[account(
+        constraint = owner_did.proves(authority.key(), &old_card_config.owner_did_hash)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+
+    /// The token mint the reissued card will spend
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The reissued card's token account, which the transfer hook binds it to
+    #[account(token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The reissued card configuration PDA
+    #[account(
+        init,
+        payer = payer,
+        space = CardConfig::SIZE,
+        seeds = [CardConfig::SEED, new_card_id.as_ref()],
+        bump,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The owner's card count (created if the old card predates indexing)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = OwnerIndex::SIZE,
+        seeds = [b"owner_index", old_card_config.owner_did_hash.as_ref()],
+        bump,
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    /// Global config for program-wide statistics
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::rejection_code;
+    use crate::state::{FreezeInfo, FreezeReason, TransactionChannel, TransactionContext};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, global_config_account,
+        initialize_card, read, run, signer, zeroed_global_config, ProgramResult, SLOT,
+    };
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn card_with_preset(preset: PolicyPreset) -> CardConfig {
+        CardConfig {
+            status: CardStatus::Active,
+            policy: preset.policy(6),
+            ..Default::default()
+        }
+    }
+
+    /// Decline code for a small payment over `channel`, if declined
+    fn decline(preset: PolicyPreset, channel: TransactionChannel) -> Option<u8> {
+        let tx = TransactionContext {
+            merchant_id: Some([1; 32]),
+            channel: Some(channel),
+            now: NOW,
+            ..Default::default()
+        };
+        card_with_preset(preset)
+            .is_transaction_allowed(1_000_000, &tx)
+            .err()
+            .map(|err| rejection_code(&err))
+    }
+
+    #[test]
+    fn kids_preset_blocks_atm_only() {
+        let atm_declined = Some(HookError::AtmNotAllowed.rejection_code());
+        assert_eq!(decline(PolicyPreset::Kids, TransactionChannel::Atm), atm_declined);
+        assert_eq!(decline(PolicyPreset::Kids, TransactionChannel::Online), None);
+        assert_eq!(decline(PolicyPreset::Kids, TransactionChannel::InStore), None);
+        assert_eq!(decline(PolicyPreset::Kids, TransactionChannel::Contactless), None);
+    }
+
+    #[test]
+    fn travel_preset_allows_every_channel() {
+        for channel in [
+            TransactionChannel::Atm,
+            TransactionChannel::Online,
+            TransactionChannel::InStore,
+            TransactionChannel::Contactless,
+        ] {
+            assert_eq!(decline(PolicyPreset::Travel, channel), None, "{channel:?}");
+        }
+    }
+
+    #[test]
+    fn corporate_preset_blocks_atm_only() {
+        let atm_declined = Some(HookError::AtmNotAllowed.rejection_code());
+        assert_eq!(decline(PolicyPreset::Corporate, TransactionChannel::Atm), atm_declined);
+        assert_eq!(decline(PolicyPreset::Corporate, TransactionChannel::Online), None);
+        assert_eq!(decline(PolicyPreset::Corporate, TransactionChannel::InStore), None);
+    }
+
+    fn frozen_card(scope: FreezeScope) -> CardConfig {
+        CardConfig {
+            status: CardStatus::Frozen,
+            freeze_info: Some(FreezeInfo {
+                reason: FreezeReason::LostOrStolen,
+                frozen_by: Pubkey::new_unique(),
+                frozen_at: NOW,
+                expires_at: None,
+                scope,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_fully_frozen_or_terminated_cards_are_reissuable() {
+        let terminated = CardConfig { status: CardStatus::Terminated, ..Default::default() };
+        let reissued = CardConfig { reissued_to: Some([2; 32]), ..terminated.clone() };
+        let active = CardConfig { status: CardStatus::Active, ..Default::default() };
+
+        assert!(is_reissuable(&frozen_card(FreezeScope::All)));
+        assert!(is_reissuable(&terminated));
+        assert!(!is_reissuable(&frozen_card(FreezeScope::Channel(TransactionChannel::Online))));
+        assert!(!is_reissuable(&reissued));
+        assert!(!is_reissuable(&active));
+    }
+
+    #[test]
+    fn reissued_card_inherits_limits_and_retires_the_old_one() {
+        let mut old_card = frozen_card(FreezeScope::All);
+        old_card.velocity_limits = VelocityLimits { daily: 500, weekly: 2_000, ..Default::default() };
+        old_card.velocity_counters.daily_total = 400;
+        old_card.policy = kids_policy();
+        let clock = Clock { unix_timestamp: NOW, ..Default::default() };
+        let mut new_card = CardConfig::default();
+
+        copy_card_settings(&mut new_card, &old_card, &clock);
+        retire_card(&mut old_card, [2; 32], NOW);
+
+        assert_eq!(new_card.velocity_limits.daily, 500);
+        assert_eq!(new_card.velocity_limits.weekly, 2_000);
+        assert_eq!(new_card.velocity_counters.daily_total, 0);
+        assert!(!new_card.policy.allow_atm);
+        assert!(new_card.freeze_info.is_none());
+        assert_eq!(old_card.status, CardStatus::Terminated);
+        assert_eq!(old_card.reissued_to, Some([2; 32]));
+        assert!(!is_reissuable(&old_card));
+    }
+
+    #[test]
+    fn template_clone_copies_settings_with_fresh_identity_and_counters() {
+        let mut template = card_with_preset(PolicyPreset::Kids);
+        template.card_id = [1; 32];
+        template.velocity_limits =
+            VelocityLimits { daily: 700, per_transaction: 50, ..Default::default() };
+        template.velocity_counters.daily_total = 600;
+        template.mcc_whitelist_enabled = true;
+        template.mcc_whitelist = vec![5411, 5812];
+        template.statement_period = 4;
+        let clock = Clock { unix_timestamp: NOW, ..Default::default() };
+        let mut clone = CardConfig { card_id: [2; 32], ..Default::default() };
+
+        validate_template(&template).unwrap();
+        copy_card_settings(&mut clone, &template, &clock);
+
+        assert_eq!(clone.card_id, [2; 32]);
+        assert_eq!(clone.policy.allow_atm, template.policy.allow_atm);
+        assert_eq!(clone.velocity_limits.daily, 700);
+        assert_eq!(clone.velocity_limits.per_transaction, 50);
+        assert!(clone.mcc_whitelist_enabled);
+        assert_eq!(clone.mcc_whitelist, vec![5411, 5812]);
+        assert_eq!(clone.velocity_counters.daily_total, 0);
+        assert_eq!(clone.velocity_counters.last_daily_reset_unix, NOW);
+        assert_eq!(clone.statement_period, 0);
+        assert_eq!(clone.created_at, NOW);
+    }
+
+    #[test]
+    fn preset_contactless_limit_is_in_mint_base_units() {
+        for preset in [PolicyPreset::Kids, PolicyPreset::Travel, PolicyPreset::Corporate] {
+            let display = match preset {
+                PolicyPreset::Kids => kids_policy(),
+                PolicyPreset::Travel => travel_policy(),
+                PolicyPreset::Corporate => corporate_policy(),
+            };
+            let policy = preset.policy(6);
+
+            assert_eq!(policy.contactless_limit, display.contactless_limit * 1_000000, "{preset:?}");
+            assert_eq!(
+                policy.require_2fa_above,
+                display.require_2fa_above.map(|amount| amount * 1_000000),
+                "{preset:?}"
+            );
+        }
+
+        // Corporate cards tap up to $100
+        assert_eq!(PolicyPreset::Corporate.policy(6).contactless_limit, 100_000000);
+    }
+
+    #[test]
+    fn frozen_and_terminated_cards_are_not_templates() {
+        let terminated = CardConfig { status: CardStatus::Terminated, ..Default::default() };
+        let outcome =
+            |template: &CardConfig| validate_template(template).err().map(|err| rejection_code(&err));
+
+        let frozen = frozen_card(FreezeScope::All);
+        assert_eq!(outcome(&frozen), Some(HookError::CardFrozen.rejection_code()));
+        assert_eq!(outcome(&terminated), Some(HookError::CardTerminated.rejection_code()));
+    }
+
+    #[test]
+    fn retried_init_matches_only_the_same_card() {
+        let (owner, mint, token_account) = ([1; 32], Pubkey::new_unique(), Pubkey::new_unique());
+        let card = CardConfig { owner_did_hash: owner, mint, token_account, ..Default::default() };
+        let conflict = Some(HookError::AccountAlreadyExists.rejection_code());
+        let outcome = |owner: [u8; 32], mint: Pubkey, token_account: Pubkey| {
+            check_retried_init(&card, &owner, mint, token_account)
+                .err()
+                .map(|err| rejection_code(&err))
+        };
+
+        assert_eq!(outcome(owner, mint, token_account), None);
+        assert_eq!(outcome([2; 32], mint, token_account), conflict);
+        assert_eq!(outcome(owner, Pubkey::new_unique(), token_account), conflict);
+        assert_eq!(outcome(owner, mint, Pubkey::new_unique()), conflict);
+    }
+
+    #[test]
+    fn online_can_be_disabled() {
+        let mut card = card_with_preset(PolicyPreset::Travel);
+        card.policy.allow_online = false;
+        let tx = TransactionContext {
+            channel: Some(TransactionChannel::Online),
+            now: NOW,
+            ..Default::default()
+        };
+
+        let err = card.is_transaction_allowed(1_000_000, &tx).unwrap_err();
+
+        assert_eq!(rejection_code(&err), HookError::OnlineNotAllowed.rejection_code());
+    }
+
+    fn configure(card: AccountInfo<'static>, ix: crate::instruction::ConfigureCard) -> ProgramResult {
+        let owner = Pubkey::new_unique();
+        let accounts = vec![
+            signer(owner),
+            card,
+            did_controller_account([1; 32], owner),
+            global_config_account(zeroed_global_config()),
+        ];
+        run(accounts, ix)
+    }
+
+    fn unconfigured_card() -> AccountInfo<'static> {
+        card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: [1; 32],
+            status: CardStatus::Active,
+            decimals: 6,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn configure_card_applies_every_section() {
+        let card = unconfigured_card();
+        let ix = crate::instruction::ConfigureCard {
+            policy: Some(kids_policy()),
+            limits: Some(VelocityLimits {
+                per_transaction: 50_000_000,
+                daily: 100_000_000,
+                weekly: 300_000_000,
+                monthly: 1_000_000_000,
+                max_daily_transactions: 10,
+                max_weekly_transactions: 50,
+                max_monthly_transactions: 200,
+                daily_pct_of_balance: None,
+            }),
+            merchants_add: vec![[4; 32], [3; 32]],
+            mcc_add: vec![5812, 5411],
+        };
+
+        configure(card.clone(), ix).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(!card_config.policy.allow_atm);
+        assert_eq!(card_config.policy.require_2fa_above, kids_policy().require_2fa_above);
+        assert_eq!(card_config.velocity_limits.daily, 100_000_000);
+        assert_eq!(card_config.velocity_limits.weekly, 300_000_000);
+        assert!(card_config.merchant_whitelist_enabled);
+        assert!(card_config.is_merchant_whitelisted(&[3; 32], NOW));
+        assert!(card_config.is_merchant_whitelisted(&[4; 32], NOW));
+        assert!(card_config.mcc_whitelist_enabled);
+        assert_eq!(card_config.mcc_whitelist, vec![5411, 5812]);
+        assert_eq!(card_config.updated_at, NOW);
+    }
+
+    #[test]
+    fn configure_card_applies_nothing_when_a_section_is_invalid() {
+        let card = unconfigured_card();
+        let ix = crate::instruction::ConfigureCard {
+            policy: Some(kids_policy()),
+            limits: None,
+            merchants_add: vec![[3; 32]],
+            mcc_add: vec![5411, crate::state::MAX_MCC_CODE + 1],
+        };
+
+        assert_rejected(configure(card.clone(), ix), HookError::InvalidMccCode);
+
+        let card_config = read::<CardConfig>(&card);
+        assert_eq!(card_config.policy.require_2fa_above, None);
+        assert!(card_config.merchant_whitelist.is_empty());
+        assert!(card_config.mcc_whitelist.is_empty());
+    }
+
+    fn activate(authority: Pubkey, card: &AccountInfo<'static>, owner: Pubkey) -> ProgramResult {
+        let owner_did = did_controller_account([1; 32], owner);
+        let accounts = vec![signer(authority), card.clone(), owner_did];
+        run(accounts, crate::instruction::ActivateCard {})
+    }
+
+    #[test]
+    fn new_cards_spend_only_after_the_owner_activates_them() {
+        use crate::instructions::transfer_hook::approve_transfer;
+
+        let owner = Pubkey::new_unique();
+        let (result, card) = initialize_card([7; 32], [1; 32], owner);
+        assert_eq!(result, Ok(()));
+        let tx = TransactionContext { now: NOW, ..Default::default() };
+
+        let mut pending = read::<CardConfig>(&card);
+        assert_eq!(pending.status, CardStatus::Pending);
+        let err = approve_transfer(&mut pending, None, 100, &tx, SLOT).unwrap_err();
+        assert_eq!(rejection_code(&err), HookError::CardPending.rejection_code());
+
+        let stranger = activate(Pubkey::new_unique(), &card, owner);
+        assert_rejected(stranger, HookError::InvalidDidCommitment);
+        activate(owner, &card, owner).unwrap();
+        assert_rejected(activate(owner, &card, owner), HookError::CardNotPending);
+
+        let mut active = read::<CardConfig>(&card);
+        assert_eq!(active.status, CardStatus::Active);
+        approve_transfer(&mut active, None, 100, &tx, SLOT).unwrap();
+        assert_eq!(active.velocity_counters.daily_total, 100);
+    }
+}
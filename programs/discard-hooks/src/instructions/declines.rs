@@ -0,0 +1,151 @@
+//! DisCard 2035 - Decline Statistics Instructions
+//!
+//! A rejected transfer hook rolls back every account write, so the hook can't
+//! count its own declines. Instead the authorization layer, which reads the
+//! decline code from the hook's return data, reports each rejection here.
+//! Velocity breaches seen by `record_transaction` are counted directly.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::state::{CardConfig, DeclineCategory, DeclineStats, GlobalConfig};
+
+/// Count a transfer hook rejection against the card's decline statistics
+///
+/// Rejection codes outside the tracked categories are ignored.
+pub fn record_decline(ctx: Context<RecordDecline>, rejection_code: u8) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+
+    let Some(category) = DeclineCategory::from_rejection_code(rejection_code) else {
+        msg!("Decline code {} is not tracked", rejection_code);
+        return Ok(());
+    };
+
+    card_config.decline_stats.record(category);
+    card_config.updated_at = Clock::get()?.unix_timestamp;
+
+    msg!(
+        "Decline recorded for card {:?}: {:?} ({})",
+        card_config.card_id,
+        category,
+        if category.is_soft() { "soft" } else { "hard" }
+    );
+
+    Ok(())
+}
+
+/// Read a card's decline counts (read-only)
+pub fn get_decline_stats(ctx: Context<GetDeclineStats>) -> Result<DeclineStats> {
+    Ok(ctx.accounts.card_config.decline_stats)
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct RecordDecline<'info> {
+    /// The settlement service reporting the decline
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_settlement_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The card whose transfer was declined
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+}
+
+#[derive(Accounts)]
+pub struct GetDeclineStats<'info> {
+    /// The card to read (read-only)
+    pub card_config: Account<'info, CardConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, card_config_account, global_config_account, read, return_data, run, signer,
+        zeroed_global_config, ProgramResult,
+    };
+
+    const SETTLEMENT: Pubkey = Pubkey::new_from_array([5; 32]);
+
+    fn report(authority: Pubkey, card: &AccountInfo<'static>, error: HookError) -> ProgramResult {
+        let config =
+            GlobalConfig { settlement_authorities: vec![SETTLEMENT], ..zeroed_global_config() };
+        let accounts = vec![signer(authority), global_config_account(config), card.clone()];
+        run(accounts, crate::instruction::RecordDecline { rejection_code: error.rejection_code() })
+    }
+
+    fn counts(card: &AccountInfo<'static>) -> (u32, u32, u32, u32) {
+        let stats = read::<CardConfig>(card).decline_stats;
+        (stats.velocity, stats.merchant, stats.mcc, stats.frozen)
+    }
+
+    #[test]
+    fn each_rejection_counts_against_its_category() {
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        for error in [HookError::DailyLimitExceeded, HookError::MerchantVelocityExceeded] {
+            report(SETTLEMENT, &card, error).unwrap();
+        }
+        assert_eq!(counts(&card), (2, 0, 0, 0));
+
+        for error in [HookError::MerchantBlocked, HookError::UnknownMerchant] {
+            report(SETTLEMENT, &card, error).unwrap();
+        }
+        assert_eq!(counts(&card), (2, 2, 0, 0));
+
+        report(SETTLEMENT, &card, HookError::MccNotWhitelisted).unwrap();
+        assert_eq!(counts(&card), (2, 2, 1, 0));
+
+        report(SETTLEMENT, &card, HookError::CardFrozen).unwrap();
+        assert_eq!(counts(&card), (2, 2, 1, 1));
+
+        report(SETTLEMENT, &card, HookError::CardPaused).unwrap();
+        assert_eq!(counts(&card), (2, 2, 1, 1));
+
+        let stats = read::<CardConfig>(&card).decline_stats;
+        assert_eq!((stats.soft_declines(), stats.hard_declines()), (2, 4));
+    }
+
+    #[test]
+    fn counters_saturate_instead_of_overflowing() {
+        let mut card = CardConfig { card_id: [7; 32], ..Default::default() };
+        card.decline_stats.frozen = u32::MAX;
+        let card = card_config_account(card);
+
+        report(SETTLEMENT, &card, HookError::CardFrozen).unwrap();
+
+        assert_eq!(read::<CardConfig>(&card).decline_stats.frozen, u32::MAX);
+    }
+
+    #[test]
+    fn only_settlement_services_report_declines() {
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        let result = report(Pubkey::new_unique(), &card, HookError::CardFrozen);
+
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(counts(&card), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn stats_are_readable_through_the_getter() {
+        let mut card = CardConfig { card_id: [7; 32], ..Default::default() };
+        card.decline_stats.record(DeclineCategory::Mcc);
+        let card = card_config_account(card);
+
+        run(vec![card], crate::instruction::GetDeclineStats {}).unwrap();
+
+        let stats = DeclineStats::deserialize(&mut &return_data()[..]).unwrap();
+        assert_eq!((stats.velocity, stats.merchant, stats.mcc, stats.frozen), (0, 0, 1, 0));
+    }
+}
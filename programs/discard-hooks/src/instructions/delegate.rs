@@ -0,0 +1,137 @@
+//! DisCard 2035 - Delegated Management Instructions
+//!
+//! The primary owner can grant a delegate DID a subset of management
+//! permissions (`PERMISSION_*` bits), e.g. an accountant who may reset
+//! counters but not change limits. Owners implicitly hold every permission.
+
+use anchor_lang::prelude::*;
+use crate::{
+    errors::HookError,
+    instructions::ownership::ManageOwnership,
+    state::{Delegate, MAX_DELEGATES, PERMISSION_ALL},
+};
+
+/// Grant or update a delegate's permissions (primary owner only)
+pub fn add_delegate(
+    ctx: Context<ManageOwnership>,
+    did_hash: [u8; 32],
+    permissions: u8,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if permissions == 0 || permissions & !PERMISSION_ALL != 0 {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+    if card_config.is_owner_did(&did_hash) {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    match card_config.delegates.iter_mut().find(|delegate| delegate.did_hash == did_hash) {
+        Some(delegate) => delegate.permissions = permissions,
+        None => {
+            if card_config.delegates.len() >= MAX_DELEGATES {
+                return Err(error!(HookError::DelegateListFull));
+            }
+            card_config.delegates.push(Delegate { did_hash, permissions });
+        }
+    }
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Delegate set on card {:?} (permissions: {:#06b})", card_config.card_id, permissions);
+
+    Ok(())
+}
+
+/// Revoke a delegate (primary owner only)
+pub fn remove_delegate(ctx: Context<ManageOwnership>, did_hash: [u8; 32]) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    card_config.delegates.retain(|delegate| delegate.did_hash != did_hash);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Delegate removed from card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardConfig, CardStatus, VelocityLimits, PERMISSION_FREEZE};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, global_config_account, read,
+        run, signer, zeroed_global_config, ProgramResult,
+    };
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+    const DELEGATE_DID: [u8; 32] = [2; 32];
+
+    /// Run `ix` signed by a key controlling `did_hash`, followed by any `extra` accounts
+    fn as_did<I: anchor_lang::InstructionData>(
+        did_hash: [u8; 32],
+        card: AccountInfo<'static>,
+        extra: Vec<AccountInfo<'static>>,
+        ix: I,
+    ) -> ProgramResult {
+        let key = Pubkey::new_unique();
+        let mut accounts = vec![signer(key), card, did_controller_account(did_hash, key)];
+        accounts.extend(extra);
+        run(accounts, ix)
+    }
+
+    fn card_with_freeze_delegate() -> AccountInfo<'static> {
+        let card = card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: OWNER_DID,
+            status: CardStatus::Active,
+            ..Default::default()
+        });
+        let ix = crate::instruction::AddDelegate {
+            did_hash: DELEGATE_DID,
+            permissions: PERMISSION_FREEZE,
+        };
+        as_did(OWNER_DID, card.clone(), vec![], ix).unwrap();
+        card
+    }
+
+    #[test]
+    fn freeze_delegate_can_pause_but_not_change_limits() {
+        let card = card_with_freeze_delegate();
+
+        as_did(DELEGATE_DID, card.clone(), vec![], crate::instruction::PauseCard {}).unwrap();
+        assert_eq!(read::<CardConfig>(&card).status, CardStatus::Paused);
+
+        let global_config = global_config_account(zeroed_global_config());
+        let ix = crate::instruction::UpdateVelocityLimits { limits: VelocityLimits::default() };
+        let result = as_did(DELEGATE_DID, card.clone(), vec![global_config], ix);
+        assert_rejected(result, HookError::InvalidDidCommitment);
+    }
+
+    #[test]
+    fn delegates_cannot_manage_delegates() {
+        let card = card_with_freeze_delegate();
+
+        let ix =
+            crate::instruction::AddDelegate { did_hash: [3; 32], permissions: PERMISSION_FREEZE };
+        let result = as_did(DELEGATE_DID, card.clone(), vec![], ix);
+        assert_rejected(result, HookError::InvalidDidCommitment);
+
+        let ix = crate::instruction::RemoveDelegate { did_hash: DELEGATE_DID };
+        as_did(OWNER_DID, card.clone(), vec![], ix).unwrap();
+        let result = as_did(DELEGATE_DID, card, vec![], crate::instruction::PauseCard {});
+        assert_rejected(result, HookError::InvalidDidCommitment);
+    }
+
+    #[test]
+    fn permissions_must_be_known_and_nonempty() {
+        let card = card_with_freeze_delegate();
+
+        for permissions in [0, PERMISSION_ALL + 1] {
+            let ix = crate::instruction::AddDelegate { did_hash: [3; 32], permissions };
+            let result = as_did(OWNER_DID, card.clone(), vec![], ix);
+            assert_rejected(result, HookError::InvalidConfiguration);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! DisCard 2035 - Destination Allowlist Instructions
+//!
+//! An owner can restrict which token accounts a card may send to (e.g. only
+//! their own linked accounts). While the allowlist is empty any destination
+//! is allowed; once it has entries, the transfer hook rejects the rest.
+
+use anchor_lang::prelude::*;
+use crate::{
+    UpdateCardPolicy,
+    errors::HookError,
+    state::MAX_DESTINATIONS,
+};
+
+/// Allow the card to send to a destination token account
+pub fn add_destination(ctx: Context<UpdateCardPolicy>, destination: Pubkey) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.destination_allowlist.contains(&destination) {
+        msg!("Destination already allowed: {}", destination);
+        return Ok(());
+    }
+    if card_config.destination_allowlist.len() >= MAX_DESTINATIONS {
+        return Err(error!(HookError::DestinationAllowlistFull));
+    }
+
+    card_config.destination_allowlist.push(destination);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!(
+        "Destination allowed: {} (total: {})",
+        destination,
+        card_config.destination_allowlist.len()
+    );
+
+    Ok(())
+}
+
+/// Remove a destination token account from the allowlist
+///
+/// Removing the last entry lifts the restriction entirely.
+pub fn remove_destination(ctx: Context<UpdateCardPolicy>, destination: Pubkey) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let Some(pos) = card_config.destination_allowlist.iter().position(|d| *d == destination) else {
+        return Err(error!(HookError::AccountNotFound));
+    };
+
+    card_config.destination_allowlist.remove(pos);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!(
+        "Destination removed: {} (total: {})",
+        destination,
+        card_config.destination_allowlist.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::rejection_code;
+    use crate::state::{CardConfig, CardStatus, TransactionContext};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, global_config_account, read,
+        run, signer, zeroed_global_config, ProgramResult, NOW,
+    };
+
+    fn as_owner<I: anchor_lang::InstructionData>(
+        card: &AccountInfo<'static>,
+        ix: I,
+    ) -> ProgramResult {
+        let owner = Pubkey::new_unique();
+        let accounts = vec![
+            signer(owner),
+            card.clone(),
+            did_controller_account([1; 32], owner),
+            global_config_account(zeroed_global_config()),
+        ];
+        run(accounts, ix)
+    }
+
+    fn paying(card: &AccountInfo<'static>, destination: Pubkey) -> Result<()> {
+        let tx =
+            TransactionContext { destination: Some(destination), now: NOW, ..Default::default() };
+        read::<CardConfig>(card).is_transaction_allowed(100, &tx)
+    }
+
+    #[test]
+    fn only_listed_destinations_receive_funds() {
+        let card = card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: [1; 32],
+            status: CardStatus::Active,
+            ..Default::default()
+        });
+        let (own_account, stranger) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let allow = crate::instruction::AddDestination { destination: own_account };
+        as_owner(&card, allow).unwrap();
+
+        paying(&card, own_account).unwrap();
+        let err = paying(&card, stranger).unwrap_err();
+        assert_eq!(rejection_code(&err), HookError::DestinationNotAllowed.rejection_code());
+
+        // An empty allowlist lets funds go anywhere
+        let remove = || crate::instruction::RemoveDestination { destination: own_account };
+        as_owner(&card, remove()).unwrap();
+        paying(&card, stranger).unwrap();
+        assert_rejected(as_owner(&card, remove()), HookError::AccountNotFound);
+    }
+}
@@ -0,0 +1,67 @@
+//! DisCard 2035 - DID Controller Instructions
+//!
+//! Card ownership is expressed as a DID hash, not a raw pubkey. An authorized
+//! DID service binds each DID hash to its controlling key in a `DidController`
+//! PDA (and rebinds it on key rotation), and owner-gated instructions verify
+//! the signer against that record.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::state::{DidController, GlobalConfig};
+
+/// Bind (or rebind after key rotation) a DID hash to its controlling key
+pub fn register_did_controller(
+    ctx: Context<RegisterDidController>,
+    did_hash: [u8; 32],
+    controller: Pubkey,
+) -> Result<()> {
+    let did_controller = &mut ctx.accounts.did_controller;
+    let clock = Clock::get()?;
+
+    if did_controller.created_at == 0 {
+        did_controller.bump = ctx.bumps.did_controller;
+        did_controller.did_hash = did_hash;
+        did_controller.created_at = clock.unix_timestamp;
+    }
+
+    did_controller.controller = controller;
+    did_controller.attested_by = ctx.accounts.authority.key();
+    did_controller.updated_at = clock.unix_timestamp;
+
+    msg!("DID controller registered: {:?} -> {}", did_hash, controller);
+
+    Ok(())
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(did_hash: [u8; 32])]
+pub struct RegisterDidController<'info> {
+    /// The DID service attesting the binding
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_auth_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The DID controller PDA
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = DidController::SIZE,
+        seeds = [b"did_controller", did_hash.as_ref()],
+        bump,
+    )]
+    pub did_controller: Account<'info, DidController>,
+
+    pub system_program: Program<'info, System>,
+}
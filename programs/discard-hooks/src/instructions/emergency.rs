@@ -2,19 +2,37 @@
 
 use anchor_lang::prelude::*;
 use crate::{
-    EmergencyControl, GlobalControl,
-    state::{CardStatus, FreezeInfo, FreezeReason},
+    EmergencyControl, GlobalControl, KillSwitchControl,
+    state::{
+        validate_mcc, CardConfig, CardStatus, DidController, FreezeInfo, FreezeReason,
+        FreezeScope, PERMISSION_FREEZE,
+    },
     errors::HookError,
 };
 
 /// Emergency freeze a card
-pub fn freeze(ctx: Context<EmergencyControl>, reason: FreezeReason) -> Result<()> {
+///
+/// A scoped freeze (single channel or MCC) leaves the card `Active` so that
+/// transactions declaring a different channel or MCC continue to pass; ones
+/// that don't declare it are blocked. Only a full freeze of an `Active` card
+/// changes status, so unfreezing can always restore it; a pending or paused
+/// card keeps its status and is blocked by the freeze info alone.
+pub fn freeze(
+    ctx: Context<EmergencyControl>,
+    reason: FreezeReason,
+    scope: Option<FreezeScope>,
+) -> Result<()> {
     let card_config = &mut ctx.accounts.card_config;
     let global_config = &ctx.accounts.global_config;
     let clock = Clock::get()?;
 
     // Verify authority
-    let is_owner = card_config.owner_did_hash == ctx.accounts.authority.key().to_bytes();
+    let is_owner = is_card_authorized(
+        &ctx.accounts.owner_did,
+        ctx.accounts.authority.key(),
+        card_config,
+        PERMISSION_FREEZE,
+    );
     let is_fraud_authority = global_config.is_authorized_fraud_authority(ctx.accounts.authority.key());
 
     if !is_owner && !is_fraud_authority {
@@ -26,15 +44,25 @@ pub fn freeze(ctx: Context<EmergencyControl>, reason: FreezeReason) -> Result<()
     msg!("  Reason: {:?}", reason);
     msg!("  By: {}", ctx.accounts.authority.key());
 
+    let scope = scope.unwrap_or_default();
+    if let FreezeScope::Mcc(mcc) = scope {
+        validate_mcc(mcc)?;
+    }
+
     // Set freeze info
     card_config.freeze_info = Some(FreezeInfo {
         reason,
         frozen_by: ctx.accounts.authority.key(),
         frozen_at: clock.unix_timestamp,
         expires_at: None, // No auto-unfreeze
+        scope,
     });
 
-    card_config.status = CardStatus::Frozen;
+    card_config.record_freeze(reason, ctx.accounts.authority.key(), clock.unix_timestamp);
+
+    if scope == FreezeScope::All && card_config.status == CardStatus::Active {
+        card_config.status = CardStatus::Frozen;
+    }
     card_config.updated_at = clock.unix_timestamp;
 
     msg!("Card frozen successfully");
@@ -43,19 +71,31 @@ pub fn freeze(ctx: Context<EmergencyControl>, reason: FreezeReason) -> Result<()
 }
 
 /// Unfreeze a card after review
+///
+/// Terminated cards stay terminated; a card keeps the status it had before
+/// the freeze.
 pub fn unfreeze(ctx: Context<EmergencyControl>) -> Result<()> {
     let card_config = &mut ctx.accounts.card_config;
     let global_config = &ctx.accounts.global_config;
     let clock = Clock::get()?;
 
     // Verify authority
-    let is_owner = card_config.owner_did_hash == ctx.accounts.authority.key().to_bytes();
+    let is_owner = is_card_authorized(
+        &ctx.accounts.owner_did,
+        ctx.accounts.authority.key(),
+        card_config,
+        PERMISSION_FREEZE,
+    );
     let is_fraud_authority = global_config.is_authorized_fraud_authority(ctx.accounts.authority.key());
 
     if !is_owner && !is_fraud_authority {
         return Err(error!(HookError::Unauthorized));
     }
 
+    if card_config.status == CardStatus::Terminated {
+        return Err(error!(HookError::CardTerminated));
+    }
+
     // Check if card is actually frozen
     if card_config.freeze_info.is_none() {
         msg!("Card is not frozen");
@@ -68,7 +108,10 @@ pub fn unfreeze(ctx: Context<EmergencyControl>) -> Result<()> {
 
     // Clear freeze info
     card_config.freeze_info = None;
-    card_config.status = CardStatus::Active;
+    card_config.record_unfreeze(clock.unix_timestamp);
+    if card_config.status == CardStatus::Frozen {
+        card_config.status = CardStatus::Active;
+    }
     card_config.updated_at = clock.unix_timestamp;
 
     msg!("Card unfrozen successfully");
@@ -106,6 +149,68 @@ pub fn global_resume(ctx: Context<GlobalControl>) -> Result<()> {
     Ok(())
 }
 
+/// Set the key that may engage the kill switch (admin only)
+///
+/// This should be a multisig vault whose members hold hardware keys, so no
+/// single hot key can halt the protocol.
+pub fn set_kill_switch_authority(
+    ctx: Context<GlobalControl>,
+    authority: Option<Pubkey>,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.kill_switch_authority = authority;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!("Kill switch authority set to {:?}", authority);
+
+    Ok(())
+}
+
+/// Engage or release the protocol kill switch (kill switch authority only)
+///
+/// Unlike `global_pause`, an engaged kill switch also stops system transfers
+/// (card funding, treasury sweeps), halting all token movement through the hook.
+pub fn set_kill_switch(ctx: Context<KillSwitchControl>, engaged: bool) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let clock = Clock::get()?;
+
+    global_config.kill_switch = engaged;
+    global_config.updated_at = clock.unix_timestamp;
+
+    if engaged {
+        msg!("KILL SWITCH engaged by: {}", ctx.accounts.authority.key());
+    } else {
+        msg!("Kill switch released by: {}", ctx.accounts.authority.key());
+    }
+
+    Ok(())
+}
+
+/// Check if the signer proves control of the card owner's DID
+pub(crate) fn is_card_owner(
+    owner_did: &Option<Account<'_, DidController>>,
+    authority: Pubkey,
+    card_config: &CardConfig,
+) -> bool {
+    owner_did.as_ref().is_some_and(|did| {
+        did.proves_owner_of(authority, card_config)
+    })
+}
+
+/// Check if the signer controls an owner DID, or a delegate DID holding `permission`
+pub(crate) fn is_card_authorized(
+    owner_did: &Option<Account<'_, DidController>>,
+    authority: Pubkey,
+    card_config: &CardConfig,
+    permission: u8,
+) -> bool {
+    owner_did.as_ref().is_some_and(|did| {
+        did.proves_permission_on(authority, card_config, permission)
+    })
+}
+
 // ============================================================================
 // Freeze Reason Descriptions
 // ============================================================================
@@ -126,12 +231,10 @@ impl FreezeReason {
 
     /// Check if freeze requires admin review to unfreeze
     pub fn requires_admin_review(&self) -> bool {
-        match self {
-            FreezeReason::FraudDetected => true,
-            FreezeReason::AdminAction => true,
-            FreezeReason::ComplianceHold => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            FreezeReason::FraudDetected | FreezeReason::AdminAction | FreezeReason::ComplianceHold
+        )
     }
 
     /// Check if user can self-unfreeze
@@ -144,3 +247,29 @@ impl FreezeReason {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::GlobalConfig;
+    use crate::test_utils::{
+        assert_rejected, global_config_account, read, run, signer, zeroed_global_config,
+    };
+
+    #[test]
+    fn only_the_kill_switch_authority_engages_it() {
+        let (admin, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = global_config_account(GlobalConfig {
+            admin,
+            kill_switch_authority: Some(vault),
+            ..zeroed_global_config()
+        });
+        let engage = || crate::instruction::SetKillSwitch { engaged: true };
+
+        assert_rejected(run(vec![signer(admin), config.clone()], engage()), HookError::Unauthorized);
+        assert!(!read::<GlobalConfig>(&config).kill_switch);
+
+        run(vec![signer(vault), config.clone()], engage()).unwrap();
+        assert!(read::<GlobalConfig>(&config).kill_switch);
+    }
+}
@@ -0,0 +1,113 @@
+//! DisCard 2035 - Fraud Score Instructions
+//!
+//! The fraud service scores each authorization in real time and writes the
+//! result to a short-lived `FraudScore` PDA for the card. The transfer hook
+//! reads the score when the card's policy sets `max_fraud_score`.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::state::{CardConfig, FraudScore, GlobalConfig, FRAUD_SCORE_VALIDITY_SECS};
+
+/// Submit (or refresh) the fraud score for a card
+pub fn submit_fraud_score(ctx: Context<SubmitFraudScore>, score: u8) -> Result<()> {
+    let fraud_score = &mut ctx.accounts.fraud_score;
+    let clock = Clock::get()?;
+
+    fraud_score.bump = ctx.bumps.fraud_score;
+    fraud_score.card_config = ctx.accounts.card_config.key();
+    fraud_score.score = score;
+    fraud_score.scored_by = ctx.accounts.authority.key();
+    fraud_score.scored_at = clock.unix_timestamp;
+    fraud_score.expires_at = clock.unix_timestamp
+        .checked_add(FRAUD_SCORE_VALIDITY_SECS)
+        .ok_or(error!(HookError::Overflow))?;
+
+    msg!(
+        "Fraud score {} submitted for card {:?}",
+        score,
+        ctx.accounts.card_config.card_id
+    );
+
+    Ok(())
+}
+
+/// Resolve the current fraud score from an optional score account
+///
+/// Expired scores are treated as missing.
+pub fn current_fraud_score(
+    fraud_score: Option<&FraudScore>,
+    card_config: Pubkey,
+    now: i64,
+) -> Result<Option<u8>> {
+    match fraud_score {
+        Some(fraud_score) => {
+            if fraud_score.card_config != card_config {
+                return Err(error!(HookError::InvalidFraudScore));
+            }
+            Ok(fraud_score.is_valid(now).then_some(fraud_score.score))
+        }
+        None => Ok(None),
+    }
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SubmitFraudScore<'info> {
+    /// The fraud service submitting the score
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_fraud_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The card being scored
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The fraud score PDA
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = FraudScore::SIZE,
+        seeds = [b"fraud_score", card_config.key().as_ref()],
+        bump,
+    )]
+    pub fraud_score: Account<'info, FraudScore>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score_for(card_config: Pubkey, score: u8, expires_at: i64) -> FraudScore {
+        FraudScore { card_config, score, expires_at, ..Default::default() }
+    }
+
+    #[test]
+    fn fresh_score_is_used_and_expired_score_is_missing() {
+        let card = Pubkey::new_unique();
+        let fraud_score = score_for(card, 42, 1_000);
+
+        assert_eq!(current_fraud_score(Some(&fraud_score), card, 999).unwrap(), Some(42));
+        assert_eq!(current_fraud_score(Some(&fraud_score), card, 1_000).unwrap(), None);
+        assert_eq!(current_fraud_score(None, card, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn score_for_another_card_is_rejected() {
+        let fraud_score = score_for(Pubkey::new_unique(), 42, 1_000);
+        let err = current_fraud_score(Some(&fraud_score), Pubkey::new_unique(), 0).unwrap_err();
+
+        assert_eq!(err, error!(HookError::InvalidFraudScore));
+    }
+}
@@ -0,0 +1,193 @@
+//! DisCard 2035 - Transaction Hold Instructions
+//!
+//! A disputed transaction shouldn't freeze the whole card. Instead, the
+//! disputed amount is held: it counts against daily, weekly, and monthly
+//! headroom until released, while other spend keeps flowing.
+
+use anchor_lang::prelude::*;
+use crate::{
+    EmergencyControl,
+    errors::HookError,
+    events::{HoldPlaced, HoldReleased},
+    state::{Hold, MAX_HOLDS, PERMISSION_FREEZE},
+};
+use crate::instructions::emergency::is_card_authorized;
+
+/// Hold an amount against the card's velocity headroom
+pub fn place_transaction_hold(ctx: Context<EmergencyControl>, amount: u64) -> Result<()> {
+    authorize_hold(ctx.accounts)?;
+
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if amount == 0 {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+    if card_config.holds.len() >= MAX_HOLDS {
+        return Err(error!(HookError::HoldListFull));
+    }
+
+    let hold_id = card_config.next_hold_id;
+    card_config.next_hold_id = hold_id
+        .checked_add(1)
+        .ok_or(error!(HookError::Overflow))?;
+
+    let placed_by = ctx.accounts.authority.key();
+    card_config.holds.push(Hold {
+        id: hold_id,
+        amount,
+        placed_by,
+        placed_at: clock.unix_timestamp,
+    });
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(HoldPlaced {
+        card_config: card_config.key(),
+        hold_id,
+        amount,
+        placed_by,
+    });
+
+    msg!("Hold {} placed: {} (total held {})", hold_id, amount, card_config.held_total());
+
+    Ok(())
+}
+
+/// Release a hold, restoring the headroom it reserved
+///
+/// Only whoever placed the hold or the fraud service may release it, so an
+/// owner can't lift a dispute hold the fraud service placed.
+pub fn release_transaction_hold(ctx: Context<EmergencyControl>, hold_id: u32) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let is_fraud_authority = ctx.accounts.global_config.is_authorized_fraud_authority(authority);
+
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let index = card_config.holds
+        .iter()
+        .position(|hold| hold.id == hold_id)
+        .ok_or(error!(HookError::HoldNotFound))?;
+    if card_config.holds[index].placed_by != authority && !is_fraud_authority {
+        return Err(error!(HookError::Unauthorized));
+    }
+    let hold = card_config.holds.remove(index);
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(HoldReleased {
+        card_config: card_config.key(),
+        hold_id,
+        amount: hold.amount,
+        released_by: authority,
+    });
+
+    msg!("Hold {} released: {}", hold_id, hold.amount);
+
+    Ok(())
+}
+
+/// Holds may be placed by the owner (or a freeze delegate) or the fraud service
+fn authorize_hold(accounts: &EmergencyControl) -> Result<()> {
+    let authority = accounts.authority.key();
+    let is_owner = is_card_authorized(
+        &accounts.owner_did,
+        authority,
+        &accounts.card_config,
+        PERMISSION_FREEZE,
+    );
+    let is_fraud_authority = accounts.global_config.is_authorized_fraud_authority(authority);
+
+    if !is_owner && !is_fraud_authority {
+        return Err(error!(HookError::Unauthorized));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardConfig, CardStatus, TransactionContext, VelocityLimits};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, global_config_account, read,
+        run, signer, zeroed_global_config, ProgramResult,
+    };
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+
+    fn card(holds: Vec<Hold>) -> AccountInfo<'static> {
+        card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: OWNER_DID,
+            status: CardStatus::Active,
+            velocity_limits: VelocityLimits { daily: 1_000, ..Default::default() },
+            holds,
+            ..Default::default()
+        })
+    }
+
+    fn as_owner<I: anchor_lang::InstructionData>(
+        owner: Pubkey,
+        card: &AccountInfo<'static>,
+        ix: I,
+    ) -> ProgramResult {
+        run(
+            vec![
+                signer(owner),
+                global_config_account(zeroed_global_config()),
+                card.clone(),
+                did_controller_account(OWNER_DID, owner),
+            ],
+            ix,
+        )
+    }
+
+    fn allows(card: &AccountInfo<'static>, amount: u64) -> bool {
+        let tx = TransactionContext::default();
+        read::<CardConfig>(card).is_transaction_allowed(amount, &tx).is_ok()
+    }
+
+    #[test]
+    fn hold_reserves_daily_headroom_until_released() {
+        let owner = Pubkey::new_unique();
+        let card = card(vec![]);
+
+        as_owner(owner, &card, crate::instruction::PlaceTransactionHold { amount: 400 }).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert_eq!(card_config.held_total(), 400);
+        assert!(allows(&card, 600));
+        assert!(!allows(&card, 601));
+
+        let hold_id = card_config.holds[0].id;
+        as_owner(owner, &card, crate::instruction::ReleaseTransactionHold { hold_id }).unwrap();
+
+        assert!(read::<CardConfig>(&card).holds.is_empty());
+        assert!(allows(&card, 1_000));
+    }
+
+    #[test]
+    fn holds_beyond_the_cap_are_rejected() {
+        let holds = (0..MAX_HOLDS as u32 - 1)
+            .map(|id| Hold { id, amount: 1, ..Default::default() })
+            .collect();
+        let card = card(holds);
+        let owner = Pubkey::new_unique();
+
+        as_owner(owner, &card, crate::instruction::PlaceTransactionHold { amount: 1 }).unwrap();
+        let result = as_owner(owner, &card, crate::instruction::PlaceTransactionHold { amount: 1 });
+
+        assert_rejected(result, HookError::HoldListFull);
+        assert_eq!(read::<CardConfig>(&card).holds.len(), MAX_HOLDS);
+    }
+
+    #[test]
+    fn only_whoever_placed_a_hold_releases_it() {
+        let placed_by = Pubkey::new_unique();
+        let card = card(vec![Hold { id: 3, amount: 50, placed_by, placed_at: 0 }]);
+        let release = |hold_id| crate::instruction::ReleaseTransactionHold { hold_id };
+
+        assert_rejected(as_owner(Pubkey::new_unique(), &card, release(3)), HookError::Unauthorized);
+        assert_rejected(as_owner(placed_by, &card, release(4)), HookError::HoldNotFound);
+        as_owner(placed_by, &card, release(3)).unwrap();
+    }
+}
@@ -15,10 +15,10 @@
 
 use anchor_lang::prelude::*;
 use crate::errors::HookError;
-use crate::state::CardConfig;
+use crate::state::{CardConfig, DidController};
 
 /// Inco Lightning program ID on Solana Devnet
-pub const INCO_PROGRAM_ID: Pubkey = solana_program::pubkey!("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
+pub const INCO_PROGRAM_ID: Pubkey = pubkey!("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
 
 /// Epoch duration in seconds (1 hour)
 pub const INCO_EPOCH_DURATION: i64 = 3600;
@@ -48,9 +48,16 @@ pub fn check_spending_limit(
         return Err(error!(HookError::CardNotActive));
     }
 
-    // Check not frozen
-    if card_config.freeze_info.is_some() {
-        return Err(error!(HookError::CardFrozen));
+    // Check not expired
+    if card_config.is_expired(clock.unix_timestamp) {
+        return Err(error!(HookError::CardExpired));
+    }
+
+    // Check not frozen (scoped freezes fail closed on an unknown channel/MCC)
+    if let Some(freeze_info) = &card_config.freeze_info {
+        if freeze_info.applies_to(None, None) {
+            return Err(error!(HookError::CardFrozen));
+        }
     }
 
     // Check Inco is enabled for this card
@@ -298,12 +305,15 @@ pub struct IncoUpdateBalance<'info> {
 #[derive(Accounts)]
 pub struct InitializeInco<'info> {
     /// The card configuration PDA (mutable for initialization)
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
     #[account(
-        mut,
-        constraint = card_config.owner_did_hash == authority_did_hash(authority.key())
-            @ HookError::Unauthorized,
+        constraint = owner_did.proves_owner_of(authority.key(), &card_config)
+            @ HookError::InvalidDidCommitment,
     )]
-    pub card_config: Account<'info, CardConfig>,
+    pub owner_did: Account<'info, DidController>,
 
     /// The card owner authorizing Inco setup
     pub authority: Signer<'info>,
@@ -312,22 +322,49 @@ pub struct InitializeInco<'info> {
 #[derive(Accounts)]
 pub struct RefreshIncoEpoch<'info> {
     /// The card configuration PDA (mutable for refresh)
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
     #[account(
-        mut,
-        constraint = card_config.owner_did_hash == authority_did_hash(authority.key())
-            @ HookError::Unauthorized,
+        constraint = owner_did.proves_owner_of(authority.key(), &card_config)
+            @ HookError::InvalidDidCommitment,
     )]
-    pub card_config: Account<'info, CardConfig>,
+    pub owner_did: Account<'info, DidController>,
 
     /// The card owner or authorized delegate
     pub authority: Signer<'info>,
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
-
-/// Derive authority DID hash from pubkey
-fn authority_did_hash(authority: Pubkey) -> [u8; 32] {
-    authority.to_bytes()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{did_controller_account, initialize_card, read, run, signer, NOW};
+
+    #[test]
+    fn inco_fields_round_trip_from_init_through_setup() {
+        let owner = Pubkey::new_unique();
+        let (result, card) = initialize_card([7; 32], [1; 32], owner);
+        assert_eq!(result, Ok(()));
+
+        let initialized = read::<CardConfig>(&card);
+        assert!(!initialized.inco_enabled);
+        assert_eq!(initialized.encrypted_balance_handle, None);
+        assert_eq!(initialized.inco_public_key, None);
+        assert_eq!(initialized.inco_epoch, 0);
+
+        let accounts = vec![card.clone(), did_controller_account([1; 32], owner), signer(owner)];
+        let ix = crate::instruction::InitializeInco {
+            encrypted_balance_handle: [3; 16],
+            inco_public_key: [4; 32],
+        };
+        assert_eq!(run(accounts, ix), Ok(()));
+
+        let enabled = read::<CardConfig>(&card);
+        assert!(enabled.inco_enabled);
+        assert_eq!(enabled.encrypted_balance_handle, Some([3; 16]));
+        assert_eq!(enabled.inco_public_key, Some([4; 32]));
+        assert_eq!(enabled.inco_epoch, (NOW / INCO_EPOCH_DURATION) as u64);
+        assert_eq!(enabled.updated_at, NOW);
+    }
 }
@@ -1,7 +1,14 @@
 //! DisCard 2035 - MCC (Merchant Category Code) Instructions
+//!
+//! The MCC whitelist is either a sorted vec capped at `MAX_MCC_CODES` or, in
+//! bitmap mode, a bit per code with no cap. The blocklist is always a vec.
 
 use anchor_lang::prelude::*;
-use crate::{UpdateMccList, errors::HookError, state::MAX_MCC_CODES};
+use crate::{
+    UpdateMccList, errors::HookError,
+    events::ListCleared,
+    state::{validate_mcc, CardConfig, ListKind, MAX_MCC_CODE, MAX_MCC_CODES},
+};
 
 /// Add MCC codes to whitelist
 pub fn add_to_whitelist(
@@ -13,31 +20,42 @@ pub fn add_to_whitelist(
 
     msg!("Adding {} MCC codes to whitelist", mcc_codes.len());
 
+    whitelist_mccs(card_config, mcc_codes)?;
+
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("MCC whitelist updated. Total: {}", card_config.mcc_whitelist_len());
+
+    Ok(())
+}
+
+/// Insert MCC codes into the whitelist, enabling it once non-empty
+pub(crate) fn whitelist_mccs(card_config: &mut CardConfig, mcc_codes: Vec<u16>) -> Result<()> {
     for mcc in mcc_codes {
-        // Validate MCC code range (0001-9999)
-        if mcc == 0 || mcc > 9999 {
-            return Err(error!(HookError::InvalidMccCode));
+        validate_mcc(mcc)?;
+
+        if card_config.mcc_bitmap_mode {
+            card_config.set_mcc_bit(mcc, true);
+            msg!("Added MCC: {}", mcc);
+            continue;
         }
 
         if card_config.mcc_whitelist.len() >= MAX_MCC_CODES {
             return Err(error!(HookError::MccWhitelistFull));
         }
 
-        if !card_config.mcc_whitelist.contains(&mcc) {
-            card_config.mcc_whitelist.push(mcc);
+        // Insert in sorted position so lookups can binary search
+        if let Err(pos) = card_config.mcc_whitelist.binary_search(&mcc) {
+            card_config.mcc_whitelist.insert(pos, mcc);
             msg!("Added MCC: {}", mcc);
         }
     }
 
     // Enable whitelist if MCC codes were added
-    if !card_config.mcc_whitelist.is_empty() {
+    if card_config.mcc_whitelist_len() > 0 {
         card_config.mcc_whitelist_enabled = true;
     }
 
-    card_config.updated_at = clock.unix_timestamp;
-
-    msg!("MCC whitelist updated. Total: {}", card_config.mcc_whitelist.len());
-
     Ok(())
 }
 
@@ -52,20 +70,25 @@ pub fn remove_from_whitelist(
     msg!("Removing {} MCC codes from whitelist", mcc_codes.len());
 
     for mcc in mcc_codes {
-        if let Some(pos) = card_config.mcc_whitelist.iter().position(|m| *m == mcc) {
+        if card_config.mcc_bitmap_mode {
+            if card_config.is_mcc_whitelisted(mcc) {
+                card_config.set_mcc_bit(mcc, false);
+                msg!("Removed MCC: {}", mcc);
+            }
+        } else if let Ok(pos) = card_config.mcc_whitelist.binary_search(&mcc) {
             card_config.mcc_whitelist.remove(pos);
             msg!("Removed MCC: {}", mcc);
         }
     }
 
     // Disable whitelist if empty
-    if card_config.mcc_whitelist.is_empty() {
+    if card_config.mcc_whitelist_len() == 0 {
         card_config.mcc_whitelist_enabled = false;
     }
 
     card_config.updated_at = clock.unix_timestamp;
 
-    msg!("MCC whitelist updated. Total: {}", card_config.mcc_whitelist.len());
+    msg!("MCC whitelist updated. Total: {}", card_config.mcc_whitelist_len());
 
     Ok(())
 }
@@ -81,17 +104,15 @@ pub fn add_to_blocklist(
     msg!("Adding {} MCC codes to blocklist", mcc_codes.len());
 
     for mcc in mcc_codes {
-        // Validate MCC code range (0001-9999)
-        if mcc == 0 || mcc > 9999 {
-            return Err(error!(HookError::InvalidMccCode));
-        }
+        validate_mcc(mcc)?;
 
         if card_config.mcc_blocklist.len() >= MAX_MCC_CODES {
             return Err(error!(HookError::MccBlocklistFull));
         }
 
-        if !card_config.mcc_blocklist.contains(&mcc) {
-            card_config.mcc_blocklist.push(mcc);
+        // Insert in sorted position so lookups can binary search
+        if let Err(pos) = card_config.mcc_blocklist.binary_search(&mcc) {
+            card_config.mcc_blocklist.insert(pos, mcc);
             msg!("Blocked MCC: {}", mcc);
         }
     }
@@ -114,7 +135,7 @@ pub fn remove_from_blocklist(
     msg!("Removing {} MCC codes from blocklist", mcc_codes.len());
 
     for mcc in mcc_codes {
-        if let Some(pos) = card_config.mcc_blocklist.iter().position(|m| *m == mcc) {
+        if let Ok(pos) = card_config.mcc_blocklist.binary_search(&mcc) {
             card_config.mcc_blocklist.remove(pos);
             msg!("Unblocked MCC: {}", mcc);
         }
@@ -127,6 +148,88 @@ pub fn remove_from_blocklist(
     Ok(())
 }
 
+/// Remove every MCC code from the whitelist and disable it
+pub fn clear_whitelist(ctx: Context<UpdateMccList>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let prior_len = card_config.mcc_whitelist_len() as u32;
+    card_config.mcc_whitelist.clear();
+    card_config.mcc_bitmap.clear();
+    card_config.mcc_whitelist_enabled = false;
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(ListCleared {
+        card_config: card_config.key(),
+        list: ListKind::MccWhitelist,
+        prior_len,
+    });
+
+    msg!("MCC whitelist cleared ({} removed)", prior_len);
+
+    Ok(())
+}
+
+/// Switch the MCC whitelist between vec and bitmap storage
+///
+/// Whitelisted codes carry over. Leaving bitmap mode fails with
+/// `MccWhitelistFull` if more than `MAX_MCC_CODES` codes are set.
+pub fn set_mcc_bitmap_mode(ctx: Context<UpdateMccList>, enabled: bool) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.mcc_bitmap_mode == enabled {
+        return Ok(());
+    }
+
+    if enabled {
+        let codes = std::mem::take(&mut card_config.mcc_whitelist);
+        for mcc in codes {
+            card_config.set_mcc_bit(mcc, true);
+        }
+    } else {
+        let codes: Vec<u16> = (1..=MAX_MCC_CODE)
+            .filter(|&mcc| card_config.is_mcc_whitelisted(mcc))
+            .collect();
+        if codes.len() > MAX_MCC_CODES {
+            return Err(error!(HookError::MccWhitelistFull));
+        }
+        card_config.mcc_whitelist = codes;
+        card_config.mcc_bitmap.clear();
+    }
+
+    card_config.mcc_bitmap_mode = enabled;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!(
+        "MCC bitmap mode {}. Total: {}",
+        if enabled { "enabled" } else { "disabled" },
+        card_config.mcc_whitelist_len()
+    );
+
+    Ok(())
+}
+
+/// Remove every MCC code from the blocklist
+pub fn clear_blocklist(ctx: Context<UpdateMccList>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let prior_len = card_config.mcc_blocklist.len() as u32;
+    card_config.mcc_blocklist.clear();
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(ListCleared {
+        card_config: card_config.key(),
+        list: ListKind::MccBlocklist,
+        prior_len,
+    });
+
+    msg!("MCC blocklist cleared ({} removed)", prior_len);
+
+    Ok(())
+}
+
 // ============================================================================
 // Common MCC Categories for Reference
 // ============================================================================
@@ -162,3 +265,115 @@ pub const TRAVEL_MCC_CODES: [u16; 6] = [
     4131, // Bus lines
     7011, // Lodging
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardStatus, MCC_BITMAP_BYTES};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, emitted, read, run, signer,
+        ProgramResult,
+    };
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+
+    fn manage<I: anchor_lang::InstructionData>(card: AccountInfo<'static>, ix: I) -> ProgramResult {
+        let owner = Pubkey::new_unique();
+        run(vec![signer(owner), card, did_controller_account(OWNER_DID, owner)], ix)
+    }
+
+    fn active_card() -> AccountInfo<'static> {
+        card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: OWNER_DID,
+            status: CardStatus::Active,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn lists_stay_sorted_across_adds_and_removes() {
+        let card = active_card();
+        let whitelist = |mcc_codes| crate::instruction::AddMccCodesToWhitelist { mcc_codes };
+        let unwhitelist = |mcc_codes| crate::instruction::RemoveMccCodesFromWhitelist { mcc_codes };
+        let blocklist = |mcc_codes| crate::instruction::AddMccCodesToBlocklist { mcc_codes };
+        let unblock = |mcc_codes| crate::instruction::RemoveMccCodesFromBlocklist { mcc_codes };
+        manage(card.clone(), whitelist(vec![5999, 5411, 7995])).unwrap();
+        manage(card.clone(), whitelist(vec![5812, 5411])).unwrap();
+        manage(card.clone(), unwhitelist(vec![7995])).unwrap();
+        manage(card.clone(), blocklist(vec![7995, 4829, 6051])).unwrap();
+        manage(card.clone(), unblock(vec![4829])).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert_eq!(card_config.mcc_whitelist, vec![5411, 5812, 5999]);
+        assert_eq!(card_config.mcc_blocklist, vec![6051, 7995]);
+        for mcc in [4829, 5411, 5812, 5999, 6051, 7995] {
+            let whitelisted = [5411, 5812, 5999].contains(&mcc);
+            assert_eq!(card_config.is_mcc_whitelisted(mcc), whitelisted, "{mcc}");
+            assert_eq!(card_config.is_mcc_blocked(mcc), [6051, 7995].contains(&mcc), "{mcc}");
+        }
+    }
+
+    #[test]
+    fn clearing_the_whitelist_disables_it() {
+        let card = active_card();
+        let add = crate::instruction::AddMccCodesToWhitelist { mcc_codes: vec![5411, 5812, 5999] };
+        manage(card.clone(), add).unwrap();
+
+        manage(card.clone(), crate::instruction::ClearMccWhitelist {}).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(card_config.mcc_whitelist.is_empty());
+        assert!(!card_config.mcc_whitelist_enabled);
+        let events = emitted::<ListCleared>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].list, ListKind::MccWhitelist);
+        assert_eq!(events[0].prior_len, 3);
+    }
+
+    #[test]
+    fn bitmap_mode_tracks_membership_of_edge_codes() {
+        let card = active_card();
+        manage(card.clone(), crate::instruction::SetMccBitmapMode { enabled: true }).unwrap();
+
+        let add = crate::instruction::AddMccCodesToWhitelist { mcc_codes: vec![1, 5812, 9999] };
+        manage(card.clone(), add).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert_eq!(card_config.mcc_bitmap.len(), MCC_BITMAP_BYTES);
+        assert!(card_config.mcc_whitelist.is_empty());
+        for mcc in [1, 5812, 9999] {
+            assert!(card_config.is_mcc_whitelisted(mcc), "{mcc}");
+        }
+        for mcc in [2, 5811, 5813, 9998] {
+            assert!(!card_config.is_mcc_whitelisted(mcc), "{mcc}");
+        }
+        assert_eq!(card_config.mcc_whitelist_codes(), vec![1, 5812, 9999]);
+
+        let remove = crate::instruction::RemoveMccCodesFromWhitelist { mcc_codes: vec![5812] };
+        manage(card.clone(), remove).unwrap();
+        assert!(!read::<CardConfig>(&card).is_mcc_whitelisted(5812));
+    }
+
+    #[test]
+    fn bitmap_mode_lifts_the_code_cap() {
+        let card = active_card();
+        manage(card.clone(), crate::instruction::SetMccBitmapMode { enabled: true }).unwrap();
+
+        let mcc_codes: Vec<u16> = (1..=MAX_MCC_CODES as u16 + 1).collect();
+        manage(card.clone(), crate::instruction::AddMccCodesToWhitelist { mcc_codes }).unwrap();
+        assert_eq!(read::<CardConfig>(&card).mcc_whitelist_len(), MAX_MCC_CODES + 1);
+
+        // Too many codes to fit back in the vec
+        let result = manage(card.clone(), crate::instruction::SetMccBitmapMode { enabled: false });
+        assert_rejected(result, HookError::MccWhitelistFull);
+
+        let remove = crate::instruction::RemoveMccCodesFromWhitelist { mcc_codes: vec![1] };
+        manage(card.clone(), remove).unwrap();
+        manage(card.clone(), crate::instruction::SetMccBitmapMode { enabled: false }).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(card_config.mcc_bitmap.is_empty());
+        assert_eq!(card_config.mcc_whitelist, (2..=MAX_MCC_CODES as u16 + 1).collect::<Vec<_>>());
+    }
+}
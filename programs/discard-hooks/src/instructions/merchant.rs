@@ -1,7 +1,15 @@
 //! DisCard 2035 - Merchant Whitelist/Blocklist Instructions
+//!
+//! A merchant is never on both lists: whitelisting a blocked merchant
+//! unblocks it, and blocking a whitelisted merchant removes its allowance.
+//! Each such move emits `MerchantListMoved`.
 
 use anchor_lang::prelude::*;
-use crate::{UpdateMerchantList, errors::HookError, state::MAX_MERCHANTS};
+use crate::{
+    UpdateMerchantList, errors::HookError,
+    events::{ListCleared, MerchantListMoved},
+    state::{BlockReason, BlockedMerchant, CardConfig, ListKind, WhitelistedMerchant, MAX_MERCHANTS},
+};
 
 /// Add merchants to whitelist
 pub fn add_to_whitelist(
@@ -13,14 +21,84 @@ pub fn add_to_whitelist(
 
     msg!("Adding {} merchants to whitelist", merchants.len());
 
+    let card_key = card_config.key();
+    whitelist_merchants(card_config, card_key, merchants, None)?;
+
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Merchant whitelist updated. Total: {}", card_config.merchant_whitelist.len());
+
+    Ok(())
+}
+
+/// Add merchants to whitelist until `expires_at` (temporary allowance)
+pub fn add_to_whitelist_until(
+    ctx: Context<UpdateMerchantList>,
+    merchants: Vec<[u8; 32]>,
+    expires_at: i64,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if expires_at <= clock.unix_timestamp {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    msg!("Adding {} merchants to whitelist until {}", merchants.len(), expires_at);
+
+    card_config.evict_expired_whitelist_entries(clock.unix_timestamp);
+    let card_key = card_config.key();
+    whitelist_merchants(card_config, card_key, merchants, Some(expires_at))?;
+
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Merchant whitelist updated. Total: {}", card_config.merchant_whitelist.len());
+
+    Ok(())
+}
+
+/// Insert merchants into the whitelist, enabling it once non-empty
+///
+/// Re-adding a listed merchant replaces its expiry. A merchant on the
+/// blocklist is moved off it.
+pub(crate) fn whitelist_merchants(
+    card_config: &mut CardConfig,
+    card_key: Pubkey,
+    merchants: Vec<[u8; 32]>,
+    expires_at: Option<i64>,
+) -> Result<()> {
     for merchant in merchants {
-        if card_config.merchant_whitelist.len() >= MAX_MERCHANTS {
-            return Err(error!(HookError::MerchantWhitelistFull));
+        if let Ok(pos) = card_config.merchant_blocklist
+            .binary_search_by(|blocked| blocked.merchant_id.cmp(&merchant))
+        {
+            card_config.merchant_blocklist.remove(pos);
+            emit!(MerchantListMoved {
+                card_config: card_key,
+                merchant_id: merchant,
+                from: ListKind::MerchantBlocklist,
+                to: ListKind::MerchantWhitelist,
+            });
+            msg!("Moved merchant from blocklist: {:?}", merchant);
         }
 
-        if !card_config.merchant_whitelist.contains(&merchant) {
-            card_config.merchant_whitelist.push(merchant);
-            msg!("Added merchant: {:?}", merchant);
+        // Insert in sorted position so lookups can binary search
+        match card_config.merchant_whitelist
+            .binary_search_by(|entry| entry.merchant_id.cmp(&merchant))
+        {
+            Ok(pos) => {
+                card_config.merchant_whitelist[pos].expires_at = expires_at;
+                msg!("Updated merchant: {:?}", merchant);
+            }
+            Err(pos) => {
+                if card_config.merchant_whitelist.len() >= MAX_MERCHANTS {
+                    return Err(error!(HookError::MerchantWhitelistFull));
+                }
+                card_config.merchant_whitelist.insert(pos, WhitelistedMerchant {
+                    merchant_id: merchant,
+                    expires_at,
+                });
+                msg!("Added merchant: {:?}", merchant);
+            }
         }
     }
 
@@ -29,10 +107,6 @@ pub fn add_to_whitelist(
         card_config.merchant_whitelist_enabled = true;
     }
 
-    card_config.updated_at = clock.unix_timestamp;
-
-    msg!("Merchant whitelist updated. Total: {}", card_config.merchant_whitelist.len());
-
     Ok(())
 }
 
@@ -47,7 +121,9 @@ pub fn remove_from_whitelist(
     msg!("Removing {} merchants from whitelist", merchants.len());
 
     for merchant in merchants {
-        if let Some(pos) = card_config.merchant_whitelist.iter().position(|m| *m == merchant) {
+        if let Ok(pos) = card_config.merchant_whitelist
+            .binary_search_by(|entry| entry.merchant_id.cmp(&merchant))
+        {
             card_config.merchant_whitelist.remove(pos);
             msg!("Removed merchant: {:?}", merchant);
         }
@@ -65,27 +141,54 @@ pub fn remove_from_whitelist(
     Ok(())
 }
 
-/// Add merchants to blocklist
+/// Add merchants to blocklist, moving any off the whitelist
 pub fn add_to_blocklist(
     ctx: Context<UpdateMerchantList>,
     merchants: Vec<[u8; 32]>,
+    reason: BlockReason,
 ) -> Result<()> {
     let card_config = &mut ctx.accounts.card_config;
     let clock = Clock::get()?;
 
-    msg!("Adding {} merchants to blocklist", merchants.len());
+    msg!("Adding {} merchants to blocklist (reason: {:?})", merchants.len(), reason);
 
+    let card_key = card_config.key();
     for merchant in merchants {
         if card_config.merchant_blocklist.len() >= MAX_MERCHANTS {
             return Err(error!(HookError::MerchantBlocklistFull));
         }
 
-        if !card_config.merchant_blocklist.contains(&merchant) {
-            card_config.merchant_blocklist.push(merchant);
+        if let Ok(pos) = card_config.merchant_whitelist
+            .binary_search_by(|entry| entry.merchant_id.cmp(&merchant))
+        {
+            card_config.merchant_whitelist.remove(pos);
+            emit!(MerchantListMoved {
+                card_config: card_key,
+                merchant_id: merchant,
+                from: ListKind::MerchantWhitelist,
+                to: ListKind::MerchantBlocklist,
+            });
+            msg!("Moved merchant from whitelist: {:?}", merchant);
+        }
+
+        // Insert in sorted position so lookups can binary search
+        if let Err(pos) = card_config.merchant_blocklist
+            .binary_search_by(|blocked| blocked.merchant_id.cmp(&merchant))
+        {
+            card_config.merchant_blocklist.insert(pos, BlockedMerchant {
+                merchant_id: merchant,
+                reason,
+                blocked_at: clock.unix_timestamp,
+            });
             msg!("Blocked merchant: {:?}", merchant);
         }
     }
 
+    // Disable whitelist if the moves emptied it
+    if card_config.merchant_whitelist.is_empty() {
+        card_config.merchant_whitelist_enabled = false;
+    }
+
     card_config.updated_at = clock.unix_timestamp;
 
     msg!("Merchant blocklist updated. Total: {}", card_config.merchant_blocklist.len());
@@ -104,7 +207,9 @@ pub fn remove_from_blocklist(
     msg!("Removing {} merchants from blocklist", merchants.len());
 
     for merchant in merchants {
-        if let Some(pos) = card_config.merchant_blocklist.iter().position(|m| *m == merchant) {
+        if let Ok(pos) = card_config.merchant_blocklist
+            .binary_search_by(|blocked| blocked.merchant_id.cmp(&merchant))
+        {
             card_config.merchant_blocklist.remove(pos);
             msg!("Unblocked merchant: {:?}", merchant);
         }
@@ -116,3 +221,219 @@ pub fn remove_from_blocklist(
 
     Ok(())
 }
+
+/// Remove every merchant from the whitelist and disable it
+pub fn clear_whitelist(ctx: Context<UpdateMerchantList>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let prior_len = card_config.merchant_whitelist.len() as u32;
+    card_config.merchant_whitelist.clear();
+    card_config.merchant_whitelist_enabled = false;
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(ListCleared {
+        card_config: card_config.key(),
+        list: ListKind::MerchantWhitelist,
+        prior_len,
+    });
+
+    msg!("Merchant whitelist cleared ({} removed)", prior_len);
+
+    Ok(())
+}
+
+/// Remove every merchant from the blocklist
+pub fn clear_blocklist(ctx: Context<UpdateMerchantList>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let prior_len = card_config.merchant_blocklist.len() as u32;
+    card_config.merchant_blocklist.clear();
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(ListCleared {
+        card_config: card_config.key(),
+        list: ListKind::MerchantBlocklist,
+        prior_len,
+    });
+
+    msg!("Merchant blocklist cleared ({} removed)", prior_len);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardStatus, TransactionContext};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, emitted, read, run, signer,
+        ProgramResult, NOW,
+    };
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+
+    fn manage<I: anchor_lang::InstructionData>(card: AccountInfo<'static>, ix: I) -> ProgramResult {
+        let owner = Pubkey::new_unique();
+        run(vec![signer(owner), card, did_controller_account(OWNER_DID, owner)], ix)
+    }
+
+    fn active_card() -> AccountInfo<'static> {
+        card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: OWNER_DID,
+            status: CardStatus::Active,
+            ..Default::default()
+        })
+    }
+
+    fn paying(merchant_id: [u8; 32]) -> TransactionContext {
+        TransactionContext { merchant_id: Some(merchant_id), now: NOW, ..Default::default() }
+    }
+
+    #[test]
+    fn blocked_merchant_keeps_its_reason_and_is_rejected() {
+        let card = active_card();
+        let ix = crate::instruction::AddMerchantsToBlocklist {
+            merchants: vec![[3; 32], [2; 32]],
+            reason: BlockReason::Dispute,
+        };
+        manage(card.clone(), ix).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        let blocked: Vec<_> =
+            card_config.merchant_blocklist.iter().map(|entry| entry.merchant_id).collect();
+        assert_eq!(blocked, vec![[2; 32], [3; 32]]);
+        assert_eq!(card_config.merchant_blocklist[0].reason, BlockReason::Dispute);
+        assert_eq!(card_config.merchant_blocklist[0].blocked_at, NOW);
+
+        let err = card_config.is_transaction_allowed(10, &paying([3; 32])).unwrap_err();
+        assert_eq!(crate::errors::rejection_code(&err), HookError::MerchantBlocked.rejection_code());
+        assert!(card_config.is_transaction_allowed(10, &paying([4; 32])).is_ok());
+    }
+
+    #[test]
+    fn unblocked_merchant_is_allowed_again() {
+        let card = active_card();
+        let block = crate::instruction::AddMerchantsToBlocklist {
+            merchants: vec![[3; 32]],
+            reason: BlockReason::UserChoice,
+        };
+        manage(card.clone(), block).unwrap();
+        let unblock = crate::instruction::RemoveMerchantsFromBlocklist { merchants: vec![[3; 32]] };
+        manage(card.clone(), unblock).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(card_config.merchant_blocklist.is_empty());
+        assert!(card_config.is_transaction_allowed(10, &paying([3; 32])).is_ok());
+    }
+
+    #[test]
+    fn whitelist_stays_sorted_across_adds_and_removes() {
+        let card = active_card();
+        let add = |merchants: Vec<u8>| crate::instruction::AddMerchantsToWhitelist {
+            merchants: merchants.into_iter().map(|id| [id; 32]).collect(),
+        };
+        manage(card.clone(), add(vec![5, 1, 3])).unwrap();
+        manage(card.clone(), add(vec![4, 2, 3])).unwrap();
+        let remove = crate::instruction::RemoveMerchantsFromWhitelist { merchants: vec![[3; 32]] };
+        manage(card.clone(), remove).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        let ids: Vec<u8> =
+            card_config.merchant_whitelist.iter().map(|entry| entry.merchant_id[0]).collect();
+        assert_eq!(ids, vec![1, 2, 4, 5]);
+        for id in 0..=6 {
+            let expected = [1, 2, 4, 5].contains(&id);
+            assert_eq!(card_config.is_merchant_whitelisted(&[id; 32], NOW), expected, "{id}");
+        }
+    }
+
+    #[test]
+    fn clearing_the_whitelist_disables_it() {
+        let card = active_card();
+        let add = crate::instruction::AddMerchantsToWhitelist { merchants: vec![[3; 32], [4; 32]] };
+        manage(card.clone(), add).unwrap();
+        assert!(read::<CardConfig>(&card).merchant_whitelist_enabled);
+
+        manage(card.clone(), crate::instruction::ClearMerchantWhitelist {}).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(card_config.merchant_whitelist.is_empty());
+        assert!(!card_config.merchant_whitelist_enabled);
+        let events = emitted::<ListCleared>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].card_config, *card.key);
+        assert_eq!(events[0].list, ListKind::MerchantWhitelist);
+        assert_eq!(events[0].prior_len, 2);
+    }
+
+    #[test]
+    fn temporary_allowance_lapses_and_is_evicted() {
+        let card = active_card();
+        let expires_at = NOW + 3 * 86_400;
+        let ix =
+            crate::instruction::AddMerchantsToWhitelistUntil { merchants: vec![[3; 32]], expires_at };
+        manage(card.clone(), ix).unwrap();
+
+        let mut card_config = read::<CardConfig>(&card);
+        let within = TransactionContext { now: expires_at - 1, ..paying([3; 32]) };
+        assert!(card_config.is_transaction_allowed(10, &within).is_ok());
+        let after = TransactionContext { now: expires_at, ..paying([3; 32]) };
+        let err = card_config.is_transaction_allowed(10, &after).unwrap_err();
+        assert_eq!(
+            crate::errors::rejection_code(&err),
+            HookError::MerchantNotWhitelisted.rejection_code()
+        );
+
+        assert_eq!(card_config.evict_expired_whitelist_entries(expires_at - 1), 0);
+        assert_eq!(card_config.evict_expired_whitelist_entries(expires_at), 1);
+        assert!(card_config.merchant_whitelist.is_empty());
+        assert!(card_config.merchant_whitelist_enabled);
+    }
+
+    #[test]
+    fn temporary_allowance_must_end_in_the_future() {
+        let ix = crate::instruction::AddMerchantsToWhitelistUntil {
+            merchants: vec![[3; 32]],
+            expires_at: NOW,
+        };
+        assert_rejected(manage(active_card(), ix), HookError::InvalidConfiguration);
+    }
+
+    #[test]
+    fn merchants_move_between_whitelist_and_blocklist() {
+        let card = active_card();
+        let block = crate::instruction::AddMerchantsToBlocklist {
+            merchants: vec![[3; 32]],
+            reason: BlockReason::UserChoice,
+        };
+        manage(card.clone(), block).unwrap();
+
+        let allow = crate::instruction::AddMerchantsToWhitelist { merchants: vec![[3; 32]] };
+        manage(card.clone(), allow).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(card_config.merchant_blocklist.is_empty());
+        assert!(card_config.is_merchant_whitelisted(&[3; 32], NOW));
+        let moves = emitted::<MerchantListMoved>();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].merchant_id, [3; 32]);
+        assert_eq!(moves[0].from, ListKind::MerchantBlocklist);
+        assert_eq!(moves[0].to, ListKind::MerchantWhitelist);
+
+        let block = crate::instruction::AddMerchantsToBlocklist {
+            merchants: vec![[3; 32]],
+            reason: BlockReason::Fraud,
+        };
+        manage(card.clone(), block).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert!(card_config.merchant_whitelist.is_empty());
+        assert!(card_config.is_merchant_blocked(&[3; 32]));
+        let moves = emitted::<MerchantListMoved>();
+        assert_eq!(moves[0].from, ListKind::MerchantWhitelist);
+        assert_eq!(moves[0].to, ListKind::MerchantBlocklist);
+    }
+}
@@ -1,19 +1,54 @@
 //! DisCard 2035 - Transfer Hook Instructions
 
+pub mod admin;
+pub mod approval;
+// Anchor can't cfg out instructions, so builds without `plaintext-state`
+// get balance instructions that always fail
+#[cfg_attr(not(feature = "plaintext-state"), path = "balance_disabled.rs")]
+pub mod balance;
 pub mod confidential_hook;
 pub mod config;
+pub mod declines;
+pub mod delegate;
+pub mod destination;
+pub mod did;
 pub mod emergency;
+pub mod fraud;
+pub mod hold;
 pub mod inco_spending;
 pub mod mcc;
 pub mod merchant;
+pub mod ownership;
+pub mod pause;
+pub mod statement;
+pub mod stats;
+pub mod status;
+pub mod step_up;
 pub mod transfer_hook;
 pub mod velocity;
 
+pub use admin::*;
+pub use approval::*;
+pub use balance::*;
 pub use confidential_hook::*;
 pub use config::*;
+pub use declines::*;
+pub use delegate::*;
+pub use destination::*;
+pub use did::*;
 pub use emergency::*;
+pub use fraud::*;
+pub use hold::*;
 pub use inco_spending::*;
-pub use mcc::*;
-pub use merchant::*;
+// The merchant and MCC list handlers share names, so lib.rs calls them by
+// module path and only their non-clashing exports are named here
+pub use mcc::{set_mcc_bitmap_mode, GAMBLING_MCC_CODES, HIGH_RISK_MCC_CODES, TRAVEL_MCC_CODES};
+pub use merchant::add_to_whitelist_until;
+pub use ownership::*;
+pub use pause::*;
+pub use statement::*;
+pub use stats::*;
+pub use status::*;
+pub use step_up::*;
 pub use transfer_hook::*;
 pub use velocity::*;
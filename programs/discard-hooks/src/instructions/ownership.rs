@@ -0,0 +1,297 @@
+//! DisCard 2035 - Card Ownership Transfer Instructions
+//!
+//! Reassigning a card to a new owner DID is a two-step process: the current
+//! owner proposes the new DID hash, and the new owner must sign to accept.
+//! The current owner can cancel a pending transfer at any time.
+//!
+//! The primary owner can also add joint owners, who may manage the card but
+//! cannot transfer it or change the owner list.
+
+use anchor_lang::prelude::*;
+use crate::{errors::HookError, state::{CardConfig, DidController, MAX_ADDITIONAL_OWNERS}};
+
+/// Propose a new owner for the card (current owner only)
+pub fn initiate_owner_transfer(
+    ctx: Context<ManageOwnership>,
+    new_owner_did_hash: [u8; 32],
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.freeze_info.is_some() {
+        return Err(error!(HookError::CardFrozen));
+    }
+
+    if new_owner_did_hash == card_config.owner_did_hash {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+
+    card_config.pending_owner = Some(new_owner_did_hash);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Owner transfer initiated for card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Cancel a pending owner transfer (current owner only)
+pub fn cancel_owner_transfer(ctx: Context<ManageOwnership>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.pending_owner.is_none() {
+        return Err(error!(HookError::NoPendingOwnerTransfer));
+    }
+
+    card_config.pending_owner = None;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Owner transfer cancelled for card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Accept a pending owner transfer (proposed owner only)
+pub fn accept_owner_transfer(ctx: Context<AcceptOwnerTransfer>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.freeze_info.is_some() {
+        return Err(error!(HookError::CardFrozen));
+    }
+
+    let new_owner = card_config.pending_owner
+        .ok_or(error!(HookError::NoPendingOwnerTransfer))?;
+
+    card_config.owner_did_hash = new_owner;
+    card_config.additional_owner_did_hashes.retain(|did_hash| *did_hash != new_owner);
+    card_config.pending_owner = None;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Owner transfer completed for card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Grant a joint owner management rights (primary owner only)
+pub fn add_authorized_owner(ctx: Context<ManageOwnership>, did_hash: [u8; 32]) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.is_owner_did(&did_hash) {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+    if card_config.additional_owner_did_hashes.len() >= MAX_ADDITIONAL_OWNERS {
+        return Err(error!(HookError::OwnerListFull));
+    }
+
+    card_config.additional_owner_did_hashes.push(did_hash);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Authorized owner added to card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Revoke a joint owner's management rights (primary owner only)
+pub fn remove_authorized_owner(ctx: Context<ManageOwnership>, did_hash: [u8; 32]) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    card_config.additional_owner_did_hashes.retain(|owner| *owner != did_hash);
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Authorized owner removed from card {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ManageOwnership<'info> {
+    /// Must be the current primary card owner
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
+    #[account(
+        constraint = owner_did.proves(authority.key(), &card_config.owner_did_hash)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwnerTransfer<'info> {
+    /// Must be the proposed new owner
+    pub new_owner: Signer<'info>,
+
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the pending owner DID
+    #[account(
+        constraint = card_config.pending_owner
+            .is_some_and(|pending| new_owner_did.proves(new_owner.key(), &pending))
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub new_owner_did: Account<'info, DidController>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CardPolicy, CardStatus};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, global_config_account, read,
+        run, signer, zeroed_global_config, ProgramResult,
+    };
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+    const NEW_OWNER_DID: [u8; 32] = [2; 32];
+
+    fn card() -> CardConfig {
+        CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: OWNER_DID,
+            status: CardStatus::Active,
+            ..Default::default()
+        }
+    }
+
+    fn initiate(owner: Pubkey, card: AccountInfo<'static>) -> ProgramResult {
+        run(
+            vec![signer(owner), card, did_controller_account(OWNER_DID, owner)],
+            crate::instruction::InitiateOwnerTransfer { new_owner_did_hash: NEW_OWNER_DID },
+        )
+    }
+
+    fn accept(new_owner: Pubkey, did_hash: [u8; 32], card: AccountInfo<'static>) -> ProgramResult {
+        run(
+            vec![signer(new_owner), card, did_controller_account(did_hash, new_owner)],
+            crate::instruction::AcceptOwnerTransfer {},
+        )
+    }
+
+    #[test]
+    fn new_owner_takes_over_on_accept() {
+        let (owner, new_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let card = card_config_account(card());
+
+        initiate(owner, card.clone()).unwrap();
+        assert_eq!(read::<CardConfig>(&card).owner_did_hash, OWNER_DID);
+        accept(new_owner, NEW_OWNER_DID, card.clone()).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert_eq!(card_config.owner_did_hash, NEW_OWNER_DID);
+        assert_eq!(card_config.pending_owner, None);
+    }
+
+    #[test]
+    fn only_the_proposed_owner_can_accept() {
+        let owner = Pubkey::new_unique();
+        let card = card_config_account(card());
+        initiate(owner, card.clone()).unwrap();
+
+        // The current owner's DID, and the new DID held by the wrong key
+        assert_rejected(accept(owner, OWNER_DID, card.clone()), HookError::InvalidDidCommitment);
+        let new_owner_record = did_controller_account(NEW_OWNER_DID, Pubkey::new_unique());
+        let result = run(
+            vec![signer(Pubkey::new_unique()), card.clone(), new_owner_record],
+            crate::instruction::AcceptOwnerTransfer {},
+        );
+        assert_rejected(result, HookError::InvalidDidCommitment);
+        assert_eq!(read::<CardConfig>(&card).owner_did_hash, OWNER_DID);
+    }
+
+    #[test]
+    fn cancelled_transfer_cannot_be_accepted() {
+        let (owner, new_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let card = card_config_account(card());
+        initiate(owner, card.clone()).unwrap();
+
+        let cancel = run(
+            vec![signer(owner), card.clone(), did_controller_account(OWNER_DID, owner)],
+            crate::instruction::CancelOwnerTransfer {},
+        );
+        cancel.unwrap();
+
+        assert_rejected(accept(new_owner, NEW_OWNER_DID, card), HookError::InvalidDidCommitment);
+    }
+
+    #[test]
+    fn only_the_owner_can_initiate() {
+        let card = card_config_account(card());
+
+        let result = run(
+            vec![signer(Pubkey::new_unique()), card, did_controller_account(OWNER_DID, Pubkey::new_unique())],
+            crate::instruction::InitiateOwnerTransfer { new_owner_did_hash: NEW_OWNER_DID },
+        );
+
+        assert_rejected(result, HookError::InvalidDidCommitment);
+    }
+
+    fn manage_owners<I: anchor_lang::InstructionData>(
+        did_hash: [u8; 32],
+        card: AccountInfo<'static>,
+        ix: I,
+    ) -> ProgramResult {
+        let key = Pubkey::new_unique();
+        run(vec![signer(key), card, did_controller_account(did_hash, key)], ix)
+    }
+
+    fn update_policy(did_hash: [u8; 32], card: AccountInfo<'static>) -> ProgramResult {
+        let key = Pubkey::new_unique();
+        run(
+            vec![
+                signer(key),
+                card,
+                did_controller_account(did_hash, key),
+                global_config_account(zeroed_global_config()),
+            ],
+            crate::instruction::UpdateCardPolicy { new_policy: CardPolicy::default() },
+        )
+    }
+
+    #[test]
+    fn joint_owners_manage_the_card_until_removed() {
+        let card = card_config_account(card());
+        let add = crate::instruction::AddAuthorizedOwner { did_hash: NEW_OWNER_DID };
+        manage_owners(OWNER_DID, card.clone(), add).unwrap();
+        assert_eq!(read::<CardConfig>(&card).additional_owner_did_hashes, vec![NEW_OWNER_DID]);
+
+        update_policy(NEW_OWNER_DID, card.clone()).unwrap();
+        update_policy(OWNER_DID, card.clone()).unwrap();
+
+        // A joint owner cannot change the owner list
+        let remove = crate::instruction::RemoveAuthorizedOwner { did_hash: NEW_OWNER_DID };
+        let result = manage_owners(NEW_OWNER_DID, card.clone(), remove);
+        assert_rejected(result, HookError::InvalidDidCommitment);
+
+        let remove = crate::instruction::RemoveAuthorizedOwner { did_hash: NEW_OWNER_DID };
+        manage_owners(OWNER_DID, card.clone(), remove).unwrap();
+        assert!(read::<CardConfig>(&card).additional_owner_did_hashes.is_empty());
+
+        let result = update_policy(NEW_OWNER_DID, card.clone());
+        assert_rejected(result, HookError::InvalidDidCommitment);
+        update_policy(OWNER_DID, card).unwrap();
+    }
+
+    #[test]
+    fn owner_list_is_bounded() {
+        let card = card_config_account(CardConfig {
+            additional_owner_did_hashes: vec![[9; 32]; MAX_ADDITIONAL_OWNERS],
+            ..card()
+        });
+
+        let add = crate::instruction::AddAuthorizedOwner { did_hash: NEW_OWNER_DID };
+        assert_rejected(manage_owners(OWNER_DID, card.clone(), add), HookError::OwnerListFull);
+        let add = crate::instruction::AddAuthorizedOwner { did_hash: OWNER_DID };
+        assert_rejected(manage_owners(OWNER_DID, card, add), HookError::InvalidConfiguration);
+    }
+}
@@ -0,0 +1,154 @@
+//! DisCard 2035 - Card Pause Instructions
+//!
+//! Pausing is a user-initiated, reversible hold on a card. Unlike a freeze it
+//! carries no reason, leaves no freeze history, and never needs fraud review:
+//! the owner can resume the card at any time.
+
+use anchor_lang::prelude::*;
+use crate::{
+    errors::HookError,
+    state::{CardConfig, CardStatus, DidController, PERMISSION_FREEZE},
+};
+
+/// Pause an active card (owner only)
+pub fn pause_card(ctx: Context<ManageCardPause>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.status != CardStatus::Active {
+        return Err(error!(HookError::CardNotActive));
+    }
+
+    card_config.status = CardStatus::Paused;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Card paused: {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+/// Resume a paused card (owner only)
+pub fn resume_card(ctx: Context<ManageCardPause>) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    if card_config.status != CardStatus::Paused {
+        return Err(error!(HookError::CardNotPaused));
+    }
+
+    card_config.status = CardStatus::Active;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Card resumed: {:?}", card_config.card_id);
+
+    Ok(())
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ManageCardPause<'info> {
+    /// Must be a card owner or a delegate with the freeze permission
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
+    #[account(
+        constraint = owner_did.proves_permission_on(authority.key(), &card_config, PERMISSION_FREEZE)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{FreezeInfo, FreezeReason, FreezeScope, TransactionContext};
+    use crate::test_utils::{
+        assert_rejected, card_config_account, did_controller_account, read, run, signer,
+        ProgramResult,
+    };
+
+    const OWNER_DID: [u8; 32] = [1; 32];
+
+    fn card(status: CardStatus) -> CardConfig {
+        CardConfig { card_id: [7; 32], owner_did_hash: OWNER_DID, status, ..Default::default() }
+    }
+
+    fn manage<I: anchor_lang::InstructionData>(
+        owner: Pubkey,
+        card: AccountInfo<'static>,
+        ix: I,
+    ) -> ProgramResult {
+        run(vec![signer(owner), card, did_controller_account(OWNER_DID, owner)], ix)
+    }
+
+    fn transfer_result(card: &AccountInfo) -> Result<()> {
+        read::<CardConfig>(card).is_transaction_allowed(10, &TransactionContext::default())
+    }
+
+    fn assert_transfer_rejected(card: &AccountInfo, expected: HookError) {
+        let err = transfer_result(card).unwrap_err();
+        assert_eq!(
+            crate::errors::rejection_code(&err),
+            crate::errors::rejection_code(&error!(expected)),
+        );
+    }
+
+    #[test]
+    fn pause_blocks_transfers_and_resume_restores_them() {
+        let owner = Pubkey::new_unique();
+        let card = card_config_account(card(CardStatus::Active));
+
+        manage(owner, card.clone(), crate::instruction::PauseCard {}).unwrap();
+        assert_eq!(read::<CardConfig>(&card).status, CardStatus::Paused);
+        assert_transfer_rejected(&card, HookError::CardPaused);
+
+        manage(owner, card.clone(), crate::instruction::ResumeCard {}).unwrap();
+        assert_eq!(read::<CardConfig>(&card).status, CardStatus::Active);
+        assert!(transfer_result(&card).is_ok());
+    }
+
+    #[test]
+    fn pause_and_resume_require_the_matching_status() {
+        let owner = Pubkey::new_unique();
+        let active = card_config_account(card(CardStatus::Active));
+        let frozen = card_config_account(card(CardStatus::Frozen));
+
+        assert_rejected(manage(owner, active, crate::instruction::ResumeCard {}), HookError::CardNotPaused);
+        assert_rejected(manage(owner, frozen.clone(), crate::instruction::PauseCard {}), HookError::CardNotActive);
+        assert_eq!(read::<CardConfig>(&frozen).status, CardStatus::Frozen);
+    }
+
+    #[test]
+    fn frozen_card_reports_frozen_not_paused() {
+        let mut paused = card(CardStatus::Paused);
+        paused.freeze_info = Some(FreezeInfo {
+            reason: FreezeReason::SuspiciousActivity,
+            frozen_by: Pubkey::default(),
+            frozen_at: 0,
+            expires_at: None,
+            scope: FreezeScope::All,
+        });
+
+        assert_transfer_rejected(&card_config_account(paused), HookError::CardFrozen);
+        assert_transfer_rejected(&card_config_account(card(CardStatus::Frozen)), HookError::CardFrozen);
+    }
+
+    #[test]
+    fn only_the_owner_can_pause() {
+        let card = card_config_account(card(CardStatus::Active));
+        let stranger = Pubkey::new_unique();
+        let result = run(
+            vec![signer(stranger), card.clone(), did_controller_account(OWNER_DID, Pubkey::new_unique())],
+            crate::instruction::PauseCard {},
+        );
+
+        assert_rejected(result, HookError::InvalidDidCommitment);
+        assert_eq!(read::<CardConfig>(&card).status, CardStatus::Active);
+    }
+}
@@ -0,0 +1,148 @@
+//! DisCard 2035 - Monthly Statement Instructions
+//!
+//! A `MonthlyStatement` PDA accumulates a card's spending for one statement
+//! period. The transfer hook (and `record_transaction` or `record_settlement`
+//! for settled transactions) writes into the open statement, the monthly
+//! velocity reset rolls the card over to the next period, and the previous
+//! statement is then finalized with `close_statement`.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::events::StatementFinalized;
+use crate::state::{CardConfig, CardTokenLink, GlobalConfig, MonthlyStatement};
+
+/// Open the statement for the card's current period
+pub fn open_statement(ctx: Context<OpenStatement>) -> Result<()> {
+    let card_config = &ctx.accounts.card_config;
+    let statement = &mut ctx.accounts.statement;
+    let clock = Clock::get()?;
+
+    statement.bump = ctx.bumps.statement;
+    statement.card_config = card_config.key();
+    statement.period = card_config.statement_period;
+    statement.total_spent = 0;
+    statement.transaction_count = 0;
+    statement.mcc_histogram = vec![];
+    statement.other_total = 0;
+    statement.other_count = 0;
+    statement.is_finalized = false;
+    statement.opened_at = clock.unix_timestamp;
+    statement.closed_at = None;
+
+    // Point the hook's extra account metas at the new statement
+    if let Some(card_link) = ctx.accounts.card_link.as_mut() {
+        card_link.statement_period = statement.period;
+    }
+
+    msg!("Opened statement period {} for card {:?}", statement.period, card_config.card_id);
+
+    Ok(())
+}
+
+/// Finalize a statement once its period has rolled over
+pub fn close_statement(ctx: Context<CloseStatement>) -> Result<()> {
+    let card_config = &ctx.accounts.card_config;
+    let statement = &mut ctx.accounts.statement;
+    let clock = Clock::get()?;
+
+    if statement.is_finalized {
+        return Err(error!(HookError::StatementAlreadyFinalized));
+    }
+
+    // Only past periods can be finalized
+    if statement.period >= card_config.statement_period {
+        return Err(error!(HookError::StatementPeriodOpen));
+    }
+
+    statement.is_finalized = true;
+    statement.closed_at = Some(clock.unix_timestamp);
+
+    emit!(StatementFinalized {
+        card_config: card_config.key(),
+        card_id: card_config.card_id,
+        period: statement.period,
+        total_spent: statement.total_spent,
+        transaction_count: statement.transaction_count,
+        mcc_histogram: statement.mcc_histogram.clone(),
+        other_total: statement.other_total,
+        other_count: statement.other_count,
+        closed_at: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Statement period {} finalized: total={}, count={}",
+        statement.period,
+        statement.total_spent,
+        statement.transaction_count
+    );
+
+    Ok(())
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct OpenStatement<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The card the statement is opened for
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The statement PDA for the card's current period
+    #[account(
+        init,
+        payer = payer,
+        space = MonthlyStatement::SIZE,
+        seeds = [
+            b"statement",
+            card_config.key().as_ref(),
+            &card_config.statement_period.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub statement: Account<'info, MonthlyStatement>,
+
+    /// The card's token account link, which tells the transfer hook which
+    /// statement is open
+    #[account(
+        mut,
+        seeds = [CardTokenLink::SEED, card_config.token_account.as_ref()],
+        bump = card_link.bump,
+    )]
+    pub card_link: Option<Account<'info, CardTokenLink>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStatement<'info> {
+    /// Must be authorized (cron service or admin)
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_reset_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The card the statement belongs to
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The statement to finalize
+    #[account(
+        mut,
+        seeds = [
+            b"statement",
+            card_config.key().as_ref(),
+            &statement.period.to_le_bytes(),
+        ],
+        bump = statement.bump,
+    )]
+    pub statement: Account<'info, MonthlyStatement>,
+}
@@ -0,0 +1,152 @@
+//! DisCard 2035 - Global Statistics Instructions
+//!
+//! `GlobalConfig` counts transactions and volume as they happen. To report
+//! per period, the admin snapshots the live totals into a `StatsSnapshot`
+//! PDA keyed by the period's start and zeroes them, starting the next period.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::state::{GlobalConfig, StatsSnapshot};
+
+/// Snapshot the live transaction/volume totals and reset them (admin only)
+pub fn snapshot_and_reset_stats(ctx: Context<SnapshotStats>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let snapshot = &mut ctx.accounts.stats_snapshot;
+    let clock = Clock::get()?;
+
+    snapshot.bump = ctx.bumps.stats_snapshot;
+    snapshot.period_start = global_config.stats_since;
+    snapshot.period_end = clock.unix_timestamp;
+    snapshot.total_transactions = global_config.total_transactions;
+    snapshot.total_volume = global_config.total_volume;
+    snapshot.total_cards = global_config.total_cards;
+
+    global_config.total_transactions = 0;
+    global_config.total_volume = 0;
+    global_config.stats_since = clock.unix_timestamp;
+    global_config.updated_at = clock.unix_timestamp;
+
+    msg!(
+        "Stats snapshot {} -> {}: {} transactions, volume {}",
+        snapshot.period_start,
+        snapshot.period_end,
+        snapshot.total_transactions,
+        snapshot.total_volume
+    );
+
+    Ok(())
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SnapshotStats<'info> {
+    /// Must be admin; pays for the snapshot
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global config holding the live totals
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.admin == admin.key() @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Snapshot for the period that started at `stats_since`
+    #[account(
+        init,
+        payer = admin,
+        space = StatsSnapshot::SIZE,
+        seeds = [b"stats_snapshot".as_ref(), &global_config.stats_since.to_le_bytes()],
+        bump,
+    )]
+    pub stats_snapshot: Account<'info, StatsSnapshot>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CardConfig;
+    use crate::test_utils::{
+        absent, assert_rejected, card_config_account, global_config_account, read, run, signer,
+        system_program, token_2022_program, uninitialized_account, zeroed_global_config,
+        ProgramResult, NOW,
+    };
+
+    const PERIOD_START: i64 = NOW - 86_400;
+
+    fn snapshot(
+        admin: Pubkey,
+        global_config: &AccountInfo<'static>,
+    ) -> (ProgramResult, AccountInfo<'static>) {
+        let seeds = [b"stats_snapshot".as_ref(), &PERIOD_START.to_le_bytes()];
+        let (key, _) = Pubkey::find_program_address(&seeds, &crate::ID);
+        let stats_snapshot = uninitialized_account(key, StatsSnapshot::SIZE);
+        let accounts =
+            vec![signer(admin), global_config.clone(), stats_snapshot.clone(), system_program()];
+        (run(accounts, crate::instruction::SnapshotAndResetStats {}), stats_snapshot)
+    }
+
+    #[test]
+    fn snapshot_captures_the_period_and_resets_the_live_totals() {
+        let (admin, settlement) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let global_config = global_config_account(GlobalConfig {
+            admin,
+            settlement_authorities: vec![settlement],
+            stats_since: PERIOD_START,
+            total_cards: 3,
+            ..zeroed_global_config()
+        });
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+        for (nonce, amount) in [(1, 100), (2, 250)] {
+            let ix = crate::instruction::RecordTransaction {
+                amount,
+                merchant_id: None,
+                mcc_code: None,
+                channel: None,
+                nonce,
+                reference: None,
+            };
+            let accounts = vec![
+                signer(settlement),
+                card.clone(),
+                absent(),
+                global_config.clone(),
+                token_2022_program(),
+            ];
+            run(accounts, ix).unwrap();
+        }
+
+        let (result, stats_snapshot) = snapshot(admin, &global_config);
+
+        result.unwrap();
+        let taken = read::<StatsSnapshot>(&stats_snapshot);
+        assert_eq!((taken.period_start, taken.period_end), (PERIOD_START, NOW));
+        assert_eq!((taken.total_transactions, taken.total_volume), (2, 350));
+        assert_eq!(taken.total_cards, 3);
+        let live = read::<GlobalConfig>(&global_config);
+        assert_eq!((live.total_transactions, live.total_volume), (0, 0));
+        assert_eq!((live.stats_since, live.total_cards), (NOW, 3));
+    }
+
+    #[test]
+    fn only_the_admin_takes_snapshots() {
+        let global_config = global_config_account(GlobalConfig {
+            admin: Pubkey::new_unique(),
+            stats_since: PERIOD_START,
+            total_transactions: 5,
+            ..zeroed_global_config()
+        });
+
+        let (result, _) = snapshot(Pubkey::new_unique(), &global_config);
+
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(read::<GlobalConfig>(&global_config).total_transactions, 5);
+    }
+}
@@ -0,0 +1,269 @@
+//! DisCard 2035 - Card Status Instructions
+//!
+//! Read-only summaries of a card for support tooling and UIs, returned as
+//! return data so clients don't have to decode the raw account.
+
+use anchor_lang::prelude::*;
+use crate::state::{CardConfig, CardStatus, FreezeReason, ListKind};
+
+/// Merchant IDs returned per `get_card_list_entries` page
+pub const MERCHANT_LIST_PAGE_SIZE: usize = 24;
+/// MCC codes returned per `get_card_list_entries` page
+pub const MCC_LIST_PAGE_SIZE: usize = 400;
+
+/// One-call summary of a card's state
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CardHealth {
+    /// Lifecycle status
+    pub status: CardStatus,
+
+    /// Whether any freeze (full or scoped) is in effect
+    pub is_frozen: bool,
+
+    /// Reason for the active freeze (None when not frozen)
+    pub freeze_reason: Option<FreezeReason>,
+
+    /// Daily spend remaining, net of open holds
+    pub daily_headroom: u64,
+
+    /// Consecutive velocity limit breaches
+    pub breach_count: u16,
+
+    /// Whether velocity counters are encrypted
+    pub confidential_mode: bool,
+}
+
+/// Summarize a card's status, freeze, and velocity headroom (read-only)
+pub fn get_card_status(ctx: Context<GetCardStatus>) -> Result<CardHealth> {
+    let card_config = &ctx.accounts.card_config;
+    let now = Clock::get()?.unix_timestamp;
+    let freeze_reason = card_config.freeze_info.as_ref().map(|freeze_info| freeze_info.reason);
+
+    Ok(CardHealth {
+        status: card_config.status,
+        is_frozen: card_config.status == CardStatus::Frozen || freeze_reason.is_some(),
+        freeze_reason,
+        daily_headroom: card_config.effective_daily_limit(None, now)
+            .saturating_sub(card_config.velocity_counters.daily_total)
+            .saturating_sub(card_config.held_total()),
+        breach_count: card_config.breach_count,
+        confidential_mode: card_config.confidential_mode,
+    })
+}
+
+/// List sizes and switches for a card, without the entries themselves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CardLists {
+    pub merchant_whitelist_enabled: bool,
+    pub merchant_whitelist_len: u16,
+    pub merchant_blocklist_len: u16,
+    pub mcc_whitelist_enabled: bool,
+    pub mcc_bitmap_mode: bool,
+    pub mcc_whitelist_len: u16,
+    pub mcc_blocklist_len: u16,
+}
+
+/// One page of a card list's entries
+///
+/// Merchant lists fill `merchant_ids`, MCC lists fill `mcc_codes`. Pages are
+/// sized to fit in return data; fetch the next one from `offset + len`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CardListPage {
+    pub list: ListKind,
+    pub total: u16,
+    pub offset: u16,
+    pub merchant_ids: Vec<[u8; 32]>,
+    pub mcc_codes: Vec<u16>,
+}
+
+/// Summarize the lengths of a card's merchant and MCC lists (read-only)
+pub fn get_card_lists(ctx: Context<GetCardStatus>) -> Result<CardLists> {
+    let card_config = &ctx.accounts.card_config;
+
+    Ok(CardLists {
+        merchant_whitelist_enabled: card_config.merchant_whitelist_enabled,
+        merchant_whitelist_len: card_config.merchant_whitelist.len() as u16,
+        merchant_blocklist_len: card_config.merchant_blocklist.len() as u16,
+        mcc_whitelist_enabled: card_config.mcc_whitelist_enabled,
+        mcc_bitmap_mode: card_config.mcc_bitmap_mode,
+        mcc_whitelist_len: card_config.mcc_whitelist_len() as u16,
+        mcc_blocklist_len: card_config.mcc_blocklist.len() as u16,
+    })
+}
+
+/// Read one page of a card list's entries, starting at `offset` (read-only)
+pub fn get_card_list_entries(
+    ctx: Context<GetCardStatus>,
+    list: ListKind,
+    offset: u16,
+) -> Result<CardListPage> {
+    let card_config = &ctx.accounts.card_config;
+    let start = offset as usize;
+
+    let mut page = CardListPage {
+        list,
+        total: 0,
+        offset,
+        merchant_ids: vec![],
+        mcc_codes: vec![],
+    };
+
+    match list {
+        ListKind::MerchantWhitelist | ListKind::MerchantBlocklist => {
+            let merchant_ids: Vec<[u8; 32]> = if list == ListKind::MerchantWhitelist {
+                card_config.merchant_whitelist.iter().map(|entry| entry.merchant_id).collect()
+            } else {
+                card_config.merchant_blocklist.iter().map(|entry| entry.merchant_id).collect()
+            };
+            page.total = merchant_ids.len() as u16;
+            page.merchant_ids = merchant_ids.into_iter()
+                .skip(start)
+                .take(MERCHANT_LIST_PAGE_SIZE)
+                .collect();
+        }
+        ListKind::MccWhitelist | ListKind::MccBlocklist => {
+            let mcc_codes = if list == ListKind::MccWhitelist {
+                card_config.mcc_whitelist_codes()
+            } else {
+                card_config.mcc_blocklist.clone()
+            };
+            page.total = mcc_codes.len() as u16;
+            page.mcc_codes = mcc_codes.into_iter()
+                .skip(start)
+                .take(MCC_LIST_PAGE_SIZE)
+                .collect();
+        }
+    }
+
+    Ok(page)
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct GetCardStatus<'info> {
+    /// The card to summarize (read-only)
+    pub card_config: Account<'info, CardConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{FreezeInfo, FreezeScope, Hold, VelocityLimits};
+    use crate::state::BlockReason;
+    use crate::test_utils::{
+        card_config_account, did_controller_account, return_data, run, signer, NOW,
+    };
+
+    fn health(card: CardConfig) -> CardHealth {
+        run(vec![card_config_account(card)], crate::instruction::GetCardStatus {}).unwrap();
+        CardHealth::try_from_slice(&return_data()).unwrap()
+    }
+
+    fn card(status: CardStatus) -> CardConfig {
+        let mut card = CardConfig {
+            card_id: [7; 32],
+            status,
+            velocity_limits: VelocityLimits { daily: 1_000, ..Default::default() },
+            breach_count: 2,
+            holds: vec![Hold { amount: 100, ..Default::default() }],
+            ..Default::default()
+        };
+        card.velocity_counters.daily_total = 300;
+        card
+    }
+
+    #[test]
+    fn active_card_reports_its_headroom() {
+        let health = health(card(CardStatus::Active));
+
+        assert_eq!(health.status, CardStatus::Active);
+        assert!(!health.is_frozen);
+        assert!(health.freeze_reason.is_none());
+        assert_eq!(health.daily_headroom, 600);
+        assert_eq!(health.breach_count, 2);
+        assert!(!health.confidential_mode);
+    }
+
+    #[test]
+    fn frozen_card_reports_why() {
+        let frozen = CardConfig {
+            freeze_info: Some(FreezeInfo {
+                reason: FreezeReason::SuspiciousActivity,
+                frozen_by: Pubkey::new_unique(),
+                frozen_at: NOW,
+                expires_at: None,
+                scope: FreezeScope::All,
+            }),
+            ..card(CardStatus::Frozen)
+        };
+
+        let health = health(frozen);
+
+        assert_eq!(health.status, CardStatus::Frozen);
+        assert!(health.is_frozen);
+        assert!(matches!(health.freeze_reason, Some(FreezeReason::SuspiciousActivity)));
+    }
+
+    #[test]
+    fn paused_card_is_not_frozen() {
+        let health = health(CardConfig { confidential_mode: true, ..card(CardStatus::Paused) });
+
+        assert_eq!(health.status, CardStatus::Paused);
+        assert!(!health.is_frozen);
+        assert!(health.freeze_reason.is_none());
+        assert!(health.confidential_mode);
+    }
+
+    fn lists(card: &AccountInfo<'static>) -> CardLists {
+        run(vec![card.clone()], crate::instruction::GetCardLists {}).unwrap();
+        CardLists::try_from_slice(&return_data()).unwrap()
+    }
+
+    fn page(card: &AccountInfo<'static>, list: ListKind) -> CardListPage {
+        let ix = crate::instruction::GetCardListEntries { list, offset: 0 };
+        run(vec![card.clone()], ix).unwrap();
+        CardListPage::try_from_slice(&return_data()).unwrap()
+    }
+
+    fn manage<I: anchor_lang::InstructionData>(card: &AccountInfo<'static>, ix: I) {
+        let owner = Pubkey::new_unique();
+        run(vec![signer(owner), card.clone(), did_controller_account([1; 32], owner)], ix).unwrap();
+    }
+
+    #[test]
+    fn list_lengths_follow_adds_and_removes() {
+        let card = card_config_account(CardConfig {
+            card_id: [7; 32],
+            owner_did_hash: [1; 32],
+            status: CardStatus::Active,
+            ..Default::default()
+        });
+
+        let merchants = vec![[2; 32], [3; 32]];
+        manage(&card, crate::instruction::AddMerchantsToWhitelist { merchants });
+        let blocked = vec![[4; 32]];
+        let reason = BlockReason::Fraud;
+        manage(&card, crate::instruction::AddMerchantsToBlocklist { merchants: blocked, reason });
+        manage(&card, crate::instruction::AddMccCodesToWhitelist { mcc_codes: vec![5411, 5812] });
+        manage(&card, crate::instruction::AddMccCodesToBlocklist { mcc_codes: vec![7995] });
+
+        let summary = lists(&card);
+        assert!(summary.merchant_whitelist_enabled && summary.mcc_whitelist_enabled);
+        assert_eq!((summary.merchant_whitelist_len, summary.merchant_blocklist_len), (2, 1));
+        assert_eq!((summary.mcc_whitelist_len, summary.mcc_blocklist_len), (2, 1));
+
+        let merchants = vec![[2; 32]];
+        manage(&card, crate::instruction::RemoveMerchantsFromWhitelist { merchants });
+        manage(&card, crate::instruction::RemoveMccCodesFromBlocklist { mcc_codes: vec![7995] });
+
+        let summary = lists(&card);
+        assert_eq!((summary.merchant_whitelist_len, summary.mcc_blocklist_len), (1, 0));
+        let whitelist = page(&card, ListKind::MerchantWhitelist);
+        assert_eq!((whitelist.total, whitelist.merchant_ids), (1, vec![[3; 32]]));
+        let mccs = page(&card, ListKind::MccWhitelist);
+        assert_eq!((mccs.total, mccs.mcc_codes), (2, vec![5411, 5812]));
+    }
+}
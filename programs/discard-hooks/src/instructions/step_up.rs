@@ -0,0 +1,185 @@
+//! DisCard 2035 - Step-Up Authentication Instructions
+//!
+//! After a user completes 2FA or biometric verification, an authorized
+//! authentication service writes a short-lived `StepUpAttestation` PDA for
+//! the card. The transfer hook treats step-up as satisfied only while the
+//! attestation is unexpired. An attestation made with PIN entry also lifts
+//! the card's contactless limit, and the channel it reports is the one the
+//! hook checks against the card's online/in-store daily caps.
+
+use anchor_lang::prelude::*;
+use crate::errors::HookError;
+use crate::state::{
+    CardConfig, GlobalConfig, StepUpAttestation, TransactionChannel,
+    DEFAULT_STEP_UP_VALIDITY_SECS, MAX_STEP_UP_VALIDITY_SECS,
+};
+
+/// Create (or refresh) the step-up attestation for a card
+pub fn create_step_up_attestation(
+    ctx: Context<CreateStepUpAttestation>,
+    validity_secs: Option<i64>,
+    pin_verified: bool,
+    channel: Option<TransactionChannel>,
+) -> Result<()> {
+    let attestation = &mut ctx.accounts.step_up_attestation;
+    let clock = Clock::get()?;
+
+    attestation.bump = ctx.bumps.step_up_attestation;
+    attestation.card_config = ctx.accounts.card_config.key();
+    attestation.attested_by = ctx.accounts.authority.key();
+    attestation.verified_at = clock.unix_timestamp;
+    attestation.expires_at = step_up_expiry(clock.unix_timestamp, validity_secs)?;
+    attestation.pin_verified = pin_verified;
+    attestation.channel = channel;
+
+    msg!(
+        "Step-up attestation created for card {:?}, expires at {}",
+        ctx.accounts.card_config.card_id,
+        attestation.expires_at
+    );
+
+    Ok(())
+}
+
+/// When an attestation made at `now` expires, defaulting the validity window
+fn step_up_expiry(now: i64, validity_secs: Option<i64>) -> Result<i64> {
+    let validity = validity_secs.unwrap_or(DEFAULT_STEP_UP_VALIDITY_SECS);
+    if validity <= 0 || validity > MAX_STEP_UP_VALIDITY_SECS {
+        return Err(error!(HookError::InvalidStepUpValidity));
+    }
+    now.checked_add(validity).ok_or(error!(HookError::Overflow))
+}
+
+/// Resolve whether step-up is satisfied from an optional attestation account
+pub fn is_step_up_verified(
+    attestation: Option<&StepUpAttestation>,
+    card_config: Pubkey,
+    now: i64,
+) -> Result<bool> {
+    match attestation {
+        Some(attestation) => {
+            if attestation.card_config != card_config {
+                return Err(error!(HookError::InvalidStepUpAttestation));
+            }
+            Ok(attestation.is_valid(now))
+        }
+        None => Ok(false),
+    }
+}
+
+/// Resolve whether the cardholder entered their PIN from an optional attestation
+pub fn is_pin_verified(
+    attestation: Option<&StepUpAttestation>,
+    card_config: Pubkey,
+    now: i64,
+) -> Result<bool> {
+    let verified = is_step_up_verified(attestation, card_config, now)?;
+    Ok(verified && attestation.is_some_and(|attestation| attestation.pin_verified))
+}
+
+/// Channel attested by an unexpired step-up attestation (None without one)
+pub fn attested_channel(
+    attestation: Option<&StepUpAttestation>,
+    card_config: Pubkey,
+    now: i64,
+) -> Result<Option<TransactionChannel>> {
+    let verified = is_step_up_verified(attestation, card_config, now)?;
+    Ok(attestation.filter(|_| verified).and_then(|attestation| attestation.channel))
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct CreateStepUpAttestation<'info> {
+    /// The authentication service issuing the attestation
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_auth_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The card the user verified for
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The step-up attestation PDA
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = StepUpAttestation::SIZE,
+        seeds = [b"step_up", card_config.key().as_ref()],
+        bump,
+    )]
+    pub step_up_attestation: Account<'info, StepUpAttestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn attestation(card_config: Pubkey, pin_verified: bool) -> StepUpAttestation {
+        StepUpAttestation {
+            card_config,
+            verified_at: NOW,
+            expires_at: NOW + DEFAULT_STEP_UP_VALIDITY_SECS,
+            pin_verified,
+            channel: Some(TransactionChannel::Online),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn attestation_satisfies_step_up_until_it_expires() {
+        let card = Pubkey::new_unique();
+        let attestation = attestation(card, false);
+        let expires_at = attestation.expires_at;
+
+        assert!(!is_step_up_verified(None, card, NOW).unwrap());
+        assert!(is_step_up_verified(Some(&attestation), card, expires_at - 1).unwrap());
+        assert!(!is_step_up_verified(Some(&attestation), card, expires_at).unwrap());
+    }
+
+    #[test]
+    fn attestation_for_another_card_is_rejected() {
+        let attestation = attestation(Pubkey::new_unique(), true);
+        let other_card = Pubkey::new_unique();
+
+        assert!(is_step_up_verified(Some(&attestation), other_card, NOW).is_err());
+        assert!(is_pin_verified(Some(&attestation), other_card, NOW).is_err());
+        assert!(attested_channel(Some(&attestation), other_card, NOW).is_err());
+    }
+
+    #[test]
+    fn pin_and_channel_lapse_with_the_attestation() {
+        let card = Pubkey::new_unique();
+        let attestation = attestation(card, true);
+        let expires_at = attestation.expires_at;
+
+        assert!(is_pin_verified(Some(&attestation), card, NOW).unwrap());
+        assert_eq!(attested_channel(Some(&attestation), card, NOW).unwrap(), Some(TransactionChannel::Online));
+        assert!(!is_pin_verified(Some(&attestation), card, expires_at).unwrap());
+        assert_eq!(attested_channel(Some(&attestation), card, expires_at).unwrap(), None);
+    }
+
+    #[test]
+    fn validity_window_is_bounded() {
+        assert_eq!(step_up_expiry(NOW, None).unwrap(), NOW + DEFAULT_STEP_UP_VALIDITY_SECS);
+        assert_eq!(
+            step_up_expiry(NOW, Some(MAX_STEP_UP_VALIDITY_SECS)).unwrap(),
+            NOW + MAX_STEP_UP_VALIDITY_SECS
+        );
+        assert!(step_up_expiry(NOW, Some(0)).is_err());
+        assert!(step_up_expiry(NOW, Some(MAX_STEP_UP_VALIDITY_SECS + 1)).is_err());
+    }
+}
@@ -4,47 +4,1395 @@
 //! This is called automatically by Token-2022 on every transfer.
 
 use anchor_lang::prelude::*;
-use crate::{TransferHook, errors::HookError};
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, transfer_hook::TransferHookAccount,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account as TokenAccount2022, Mint as Mint2022},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use crate::{
+    TransferHook, errors::{rejection_code, HookError},
+    events::{SystemTransferBypass, TransactionRecorded},
+    state::{
+        ApprovalToken, CardConfig, CardTokenLink, FraudScore, GlobalConfig, MonthlyStatement,
+        StepUpAttestation, TransactionContext,
+    },
+};
+#[cfg(feature = "plaintext-state")]
+use discard_state::state::CardStatePda;
+#[cfg(feature = "plaintext-state")]
+use crate::instructions::balance::debit_balance_account;
+use merchant_registry::{country::alpha2_to_u16, state::MerchantRecord};
+use crate::instructions::approval::is_manually_approved;
+use crate::instructions::fraud::current_fraud_score;
+use crate::instructions::step_up::{attested_channel, is_pin_verified, is_step_up_verified};
+use crate::instructions::velocity::{apply_transaction, auto_reset_if_needed, RecordedTransaction};
+
+/// Outcome of a dry-run transfer validation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SimulationResult {
+    /// Whether the real hook would approve the transfer
+    pub allowed: bool,
+
+    /// Stable decline code the hook would reject with (None when allowed)
+    pub rejection: Option<u8>,
+
+    /// Daily headroom remaining before this transfer
+    pub remaining_daily: u64,
+}
+
+/// Outcome of a merchant/MCC-only policy check
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MerchantCheckResult {
+    /// Whether the card's merchant and MCC rules allow the merchant
+    pub allowed: bool,
+
+    /// Stable decline code for the failing rule (None when allowed)
+    pub rejection: Option<u8>,
+}
 
 /// Main transfer hook handler
 /// Called by Token-2022 on every transfer
+///
+/// Validation and recording happen in this one instruction, so an approved
+/// transfer is always counted exactly once and a rejected one (whose writes
+/// roll back) never is.
 pub fn handler(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
-    let card_config = &ctx.accounts.card_config;
+    // Only Token-2022 may invoke the hook: anyone else could record spending
+    assert_is_transferring(&ctx.accounts.source_account.to_account_info())?;
+
+    let global_config = &ctx.accounts.global_config;
+    let source = ctx.accounts.source_account.key();
+    let destination = ctx.accounts.destination_account.key();
+
+    // Token-2022 passes the card PDAs for every source; treasury, merchant
+    // and refund accounts have a platform link and no card
+    let card_config = load_source_card(
+        &ctx.accounts.card_link,
+        &ctx.accounts.card_config,
+        source,
+        ctx.accounts.mint.key(),
+    )?;
+
+    // Detail logs cost compute on every transfer, so they're opt-in
+    let verbose = global_config.verbose_logging;
+    if verbose {
+        msg!("Transfer hook invoked:");
+        msg!("  Amount: {}", amount);
+        msg!("  Source: {}", source);
+        msg!("  Destination: {}", destination);
+        msg!("  Card Status: {:?}", card_config.as_ref().map(|card_config| card_config.status));
+    }
+
+    // The kill switch halts everything; system transfers skip card rules
+    let mut card_config = match route_transfer(global_config, source, destination, card_config) {
+        Err(err) => {
+            set_return_data(&[rejection_code(&err)]);
+            return Err(err);
+        }
+        Ok(TransferRoute::SystemTransfer) => {
+            emit!(SystemTransferBypass { source, destination, amount });
+            msg!("System transfer: card rules bypassed");
+            return Ok(());
+        }
+        Ok(TransferRoute::NotACard) => {
+            msg!("Source is not a card: no card rules apply");
+            return Ok(());
+        }
+        Ok(TransferRoute::Card(card_config)) => card_config,
+    };
+    let card_config = card_config.as_mut();
+
+    // Validate against current-period counters
+    let clock = Clock::get()?;
+    refresh_card(card_config, global_config, &clock)?;
 
-    msg!("Transfer hook invoked:");
-    msg!("  Amount: {}", amount);
-    msg!("  Source: {}", ctx.accounts.source_account.key());
-    msg!("  Destination: {}", ctx.accounts.destination_account.key());
-    msg!("  Card Status: {:?}", card_config.status);
+    // Token-2022 passes every per-card PDA; ones never created arrive empty
+    let step_up_attestation =
+        load_optional_account::<StepUpAttestation>(&ctx.accounts.step_up_attestation)?;
+    let fraud_score = load_optional_account::<FraudScore>(&ctx.accounts.fraud_score)?;
+    let mut approval_token = load_optional_account::<ApprovalToken>(&ctx.accounts.approval_token)?;
 
-    // In production, extract merchant info from extra account metas
-    // For now, validate without merchant data
-    let merchant_id: Option<[u8; 32]> = None;
-    let mcc_code: Option<u16> = None;
+    // Balance checks need the discard-state plaintext card state
+    let card_key = ctx.accounts.card_config.key();
+    #[cfg(feature = "plaintext-state")]
+    let balance_account = load_balance_account(&ctx.accounts.balance_account, card_config, card_key)?;
+    #[cfg(feature = "plaintext-state")]
+    let available_balance = balance_account.as_ref()
+        .map(|balance_account| balance_account.state.spendable_balance());
+    #[cfg(not(feature = "plaintext-state"))]
+    let available_balance = None;
 
-    // Perform all validation checks
-    card_config.is_transaction_allowed(amount, merchant_id, mcc_code)?;
+    // Token-2022 can't tell the hook which merchant is paid
+    let tx = build_transaction_context(
+        card_config,
+        card_key,
+        amount,
+        TransferInputs {
+            merchant_id: None,
+            mcc_code: None,
+            destination: Some(destination),
+            transfer_with_fee: transfer_is_with_fee(
+                &ctx.accounts.mint.to_account_info(),
+                ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+            )?,
+            available_balance,
+            step_up_attestation: step_up_attestation.as_ref(),
+            fraud_score: fraud_score.as_ref(),
+            approval_token: approval_token.as_ref(),
+            merchant_record: ctx.accounts.merchant_record.as_deref(),
+        },
+        &clock,
+    )?;
 
-    msg!("Transfer hook validation passed");
+    // Only the card's open statement is written; a lagging one is skipped
+    let mut statement = load_optional_account::<MonthlyStatement>(&ctx.accounts.statement)?
+        .filter(|statement| statement.is_open_for(card_key, card_config.statement_period));
+
+    // Perform all validation checks, publishing the decline code as return
+    // data so the authorization layer can read the reason without logs
+    if let Err(err) = approve_transfer(card_config, statement.as_mut(), amount, &tx, clock.slot) {
+        set_return_data(&[rejection_code(&err)]);
+        return Err(err);
+    }
+    {
+        let mut data = ctx.accounts.card_config.try_borrow_mut_data()?;
+        card_config.try_serialize(&mut &mut data[..])?;
+    }
+    if let Some(statement) = statement.as_ref() {
+        let mut data = ctx.accounts.statement.try_borrow_mut_data()?;
+        statement.try_serialize(&mut &mut data[..])?;
+    }
+
+    // An approval authorizes a single transfer
+    if card_config.requires_manual_approval(amount) {
+        if let Some(approval) = approval_token.as_mut() {
+            approval.consumed = true;
+            let mut data = ctx.accounts.approval_token.try_borrow_mut_data()?;
+            approval.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit!(TransactionRecorded {
+        card_config: card_key,
+        amount,
+        merchant_id: tx.merchant_id,
+        mcc_code: tx.mcc_code,
+        channel: tx.channel,
+        reference: None,
+        slot: clock.slot,
+    });
+
+    // Keep the discard-state balance in step with what the card spent
+    #[cfg(feature = "plaintext-state")]
+    if balance_account.is_some() {
+        debit_balance_account(
+            card_config,
+            &ctx.accounts.card_config,
+            &ctx.accounts.balance_account,
+            ctx.accounts.discard_state_program.as_ref(),
+            amount,
+        )?;
+    }
+
+    msg!(
+        "Transfer hook validation passed. Daily total: {}",
+        card_config.velocity_counters.daily_total
+    );
 
     Ok(())
 }
 
-/// Validate a transaction before execution (read-only check)
-pub fn validate_transaction(
-    card_config: &crate::state::CardConfig,
+/// How the hook treats a transfer once platform rules have run
+pub(crate) enum TransferRoute {
+    /// A platform account is involved: card rules are skipped
+    SystemTransfer,
+    /// The source isn't a card, so there are no card rules to apply
+    NotACard,
+    /// The source card's rules decide
+    Card(Box<CardConfig>),
+}
+
+/// Decide which rules a transfer from `source` answers to
+///
+/// Fails while the kill switch is engaged. Platform accounts are checked
+/// before the card, so funding a card from the treasury (or sweeping one
+/// back) bypasses the card's rules whichever side the card is on.
+pub(crate) fn route_transfer(
+    global_config: &GlobalConfig,
+    source: Pubkey,
+    destination: Pubkey,
+    card_config: Option<CardConfig>,
+) -> Result<TransferRoute> {
+    if is_system_transfer(global_config, source, Some(destination))? {
+        return Ok(TransferRoute::SystemTransfer);
+    }
+    Ok(match card_config {
+        Some(card_config) => TransferRoute::Card(Box::new(card_config)),
+        None => TransferRoute::NotACard,
+    })
+}
+
+/// Load the card bound to the transfer's source account, if it is a card
+///
+/// The link and config arrive unparsed since a platform account has a link
+/// but no card, so their addresses and binding to `source` and `mint` are
+/// checked here. A link naming a card whose config is gone fails closed.
+fn load_source_card(
+    card_link_info: &AccountInfo,
+    card_config_info: &AccountInfo,
+    source: Pubkey,
+    mint: Pubkey,
+) -> Result<Option<CardConfig>> {
+    let Some(card_link) = load_optional_account::<CardTokenLink>(card_link_info)? else {
+        // Token-2022 can't resolve the card config without a link, but a
+        // direct caller mustn't skip the card's rules by omitting it
+        let (expected, _) =
+            Pubkey::find_program_address(&[CardTokenLink::SEED, source.as_ref()], &crate::ID);
+        if card_link_info.key() != expected {
+            return Err(error!(HookError::SourceAccountMismatch));
+        }
+        return Ok(None);
+    };
+    let expected = Pubkey::create_program_address(
+        &[CardTokenLink::SEED, source.as_ref(), &[card_link.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(HookError::SourceAccountMismatch))?;
+    if card_link_info.key() != expected {
+        return Err(error!(HookError::SourceAccountMismatch));
+    }
+    if card_link.is_platform_account() {
+        return Ok(None);
+    }
+
+    let card_config = load_optional_account::<CardConfig>(card_config_info)?
+        .ok_or(error!(HookError::CardNotActive))?;
+    let expected = Pubkey::create_program_address(
+        &[CardConfig::SEED, card_config.card_id.as_ref(), &[card_config.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(HookError::SourceAccountMismatch))?;
+    if card_config_info.key() != expected || card_config.card_id != card_link.card_id {
+        return Err(error!(HookError::SourceAccountMismatch));
+    }
+    if card_config.mint != mint {
+        return Err(error!(HookError::MintMismatch));
+    }
+    if card_config.token_account != source {
+        return Err(error!(HookError::SourceAccountMismatch));
+    }
+    Ok(Some(card_config))
+}
+
+/// Validate a transfer and, if allowed, record it on the card and statement
+///
+/// Nothing is written when the transfer is rejected. Program-wide statistics
+/// live in the global config, which the hook only reads, so settlement adds
+/// them with `record_settlement`.
+pub(crate) fn approve_transfer(
+    card_config: &mut CardConfig,
+    statement: Option<&mut MonthlyStatement>,
+    amount: u64,
+    tx: &TransactionContext,
+    slot: u64,
+) -> Result<()> {
+    card_config.is_transaction_allowed(amount, tx)?;
+
+    apply_transaction(card_config, &RecordedTransaction {
+        amount,
+        merchant_id: tx.merchant_id,
+        merchant_key: tx.merchant_key(),
+        mcc_code: tx.mcc_code,
+        channel: tx.channel,
+        reference: None,
+        slot,
+        now: tx.now,
+    });
+    card_config.breach_count = 0;
+
+    if let Some(statement) = statement {
+        statement.record(amount, tx.mcc_code)?;
+    }
+    Ok(())
+}
+
+/// Dry-run the transfer hook validation without mutating any state
+///
+/// Runs the hook's platform checks, resets and context building on a copy of
+/// the card, so the preview can't disagree with enforcement. The channel
+/// comes from the step-up attestation and the country from the merchant
+/// record, as in the hook.
+pub fn simulate_transfer(
+    ctx: Context<SimulateTransfer>,
     amount: u64,
     merchant_id: Option<[u8; 32]>,
     mcc_code: Option<u16>,
+    destination: Option<Pubkey>,
+) -> Result<SimulationResult> {
+    let global_config = &ctx.accounts.global_config;
+    let card_key = ctx.accounts.card_config.key();
+    let clock = Clock::get()?;
+
+    // The hook's period resets and evictions apply to a copy
+    let mut card_config = CardConfig::clone(&ctx.accounts.card_config);
+    refresh_card(&mut card_config, global_config, &clock)?;
+
+    // Balance checks need the discard-state plaintext card state
+    #[cfg(feature = "plaintext-state")]
+    let available_balance = ctx.accounts.balance_account.as_ref()
+        .map(|balance_account| balance_account.state.spendable_balance());
+    #[cfg(not(feature = "plaintext-state"))]
+    let available_balance = None;
+
+    let rejection = match is_system_transfer(global_config, card_config.token_account, destination) {
+        Err(err) => Some(rejection_code(&err)),
+        Ok(true) => None,
+        Ok(false) => {
+            let tx = build_transaction_context(
+                &card_config,
+                card_key,
+                amount,
+                TransferInputs {
+                    merchant_id,
+                    mcc_code,
+                    destination,
+                    // Without the instruction, fee mints count as with-fee like in the hook
+                    transfer_with_fee: transfer_is_with_fee(&ctx.accounts.mint.to_account_info(), None)?,
+                    available_balance,
+                    step_up_attestation: ctx.accounts.step_up_attestation.as_deref(),
+                    fraud_score: ctx.accounts.fraud_score.as_deref(),
+                    approval_token: ctx.accounts.approval_token.as_deref(),
+                    merchant_record: ctx.accounts.merchant_record.as_deref(),
+                },
+                &clock,
+            )?;
+            card_config
+                .is_transaction_allowed(amount, &tx)
+                .err()
+                .map(|err| rejection_code(&err))
+        }
+    };
+
+    Ok(SimulationResult {
+        allowed: rejection.is_none(),
+        rejection,
+        remaining_daily: card_config.effective_daily_limit(available_balance, clock.unix_timestamp)
+            .saturating_sub(card_config.velocity_counters.daily_total)
+            .saturating_sub(card_config.held_total()),
+    })
+}
+
+/// Everything a transfer is judged by besides the card config
+struct TransferInputs<'a> {
+    merchant_id: Option<[u8; 32]>,
+    mcc_code: Option<u16>,
+    destination: Option<Pubkey>,
+    transfer_with_fee: bool,
+    available_balance: Option<u64>,
+    step_up_attestation: Option<&'a StepUpAttestation>,
+    fraud_score: Option<&'a FraudScore>,
+    approval_token: Option<&'a ApprovalToken>,
+    merchant_record: Option<&'a MerchantRecord>,
+}
+
+/// Whether platform rules settle the transfer before any card rules run
+///
+/// Fails while the kill switch is engaged, which halts everything. Returns
+/// true for platform-internal transfers (card funding, treasury sweeps),
+/// which skip card rules.
+fn is_system_transfer(
+    global_config: &GlobalConfig,
+    source: Pubkey,
+    destination: Option<Pubkey>,
+) -> Result<bool> {
+    if global_config.kill_switch {
+        return Err(error!(HookError::KillSwitchEngaged));
+    }
+    Ok(global_config.is_system_transfer_account(source)
+        || destination.is_some_and(|destination| global_config.is_system_transfer_account(destination)))
+}
+
+/// Bring the card's counters and whitelist up to date before validation
+fn refresh_card(card_config: &mut CardConfig, global_config: &GlobalConfig, clock: &Clock) -> Result<()> {
+    if global_config.lazy_reset_enabled {
+        auto_reset_if_needed(card_config, clock.slot, clock.unix_timestamp, global_config.week_start_day)?;
+    }
+    card_config.evict_expired_whitelist_entries(clock.unix_timestamp);
+    Ok(())
+}
+
+/// Build the context the card's rules judge a transfer by
+///
+/// Shared by the hook and `simulate_transfer` so a preview can't disagree
+/// with enforcement. Step-up, PIN and the channel come only from the card's
+/// attestation, and country and risk tier only from the merchant registry.
+fn build_transaction_context(
+    card_config: &CardConfig,
+    card_key: Pubkey,
+    amount: u64,
+    inputs: TransferInputs,
+    clock: &Clock,
+) -> Result<TransactionContext> {
+    let now = clock.unix_timestamp;
+    let mut tx = TransactionContext {
+        merchant_id: inputs.merchant_id,
+        mcc_code: inputs.mcc_code,
+        country_code: None,
+        merchant_risk_tier: None,
+        channel: attested_channel(inputs.step_up_attestation, card_key, now)?,
+        fraud_score: current_fraud_score(inputs.fraud_score, card_key, now)?,
+        step_up_verified: is_step_up_verified(inputs.step_up_attestation, card_key, now)?,
+        manually_approved: false,
+        pin_verified: is_pin_verified(inputs.step_up_attestation, card_key, now)?,
+        available_balance: inputs.available_balance,
+        destination: inputs.destination,
+        transfer_with_fee: inputs.transfer_with_fee,
+        now,
+    };
+    apply_merchant_record(&mut tx, card_config, inputs.merchant_record)?;
+
+    // Large transfers can be covered by an owner-issued approval token
+    tx.manually_approved = is_manually_approved(
+        inputs.approval_token,
+        card_key,
+        amount,
+        tx.merchant_id,
+        now,
+    )?;
+
+    Ok(tx)
+}
+
+/// Check whether the card may pay a merchant, ignoring amount and velocity
+///
+/// Runs only the merchant/MCC rules, making it cheaper than
+/// `simulate_transfer` for "is this merchant OK" checks.
+pub fn check_merchant_allowed(
+    ctx: Context<CheckMerchantAllowed>,
+    merchant_id: [u8; 32],
+    mcc_code: Option<u16>,
+    destination: Option<Pubkey>,
+) -> Result<MerchantCheckResult> {
+    let card_config = &ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let mut tx = TransactionContext {
+        merchant_id: Some(merchant_id),
+        mcc_code,
+        destination,
+        now: clock.unix_timestamp,
+        ..Default::default()
+    };
+    apply_merchant_record(&mut tx, card_config, ctx.accounts.merchant_record.as_deref())?;
+
+    let rejection = card_config
+        .check_merchant_rules(&tx)
+        .err()
+        .map(|err| rejection_code(&err));
+
+    Ok(MerchantCheckResult {
+        allowed: rejection.is_none(),
+        rejection,
+    })
+}
+
+/// Fill in merchant details from the registry instead of trusting the client
+///
+/// When a registry record is supplied it identifies the merchant and carries
+/// its risk tier (used by `allow_by_max_risk_tier`). If the card has country
+/// rules, the record's alpha-2 `country_code` also replaces any
+/// client-supplied country. The record only counts when the transfer pays the
+/// merchant's registered settlement account, so a client can't attach a
+/// better-rated merchant's record to a transfer going elsewhere.
+///
+/// Only `simulate_transfer` and `check_merchant_allowed` can supply a record.
+/// The record's PDA is keyed by merchant ID, which Token-2022 can't derive
+/// from the transfer accounts, so the hook passes none and its transfers
+/// are never judged by registry country.
+fn apply_merchant_record(
+    tx: &mut TransactionContext,
+    card_config: &CardConfig,
+    merchant_record: Option<&MerchantRecord>,
 ) -> Result<()> {
-    card_config.is_transaction_allowed(amount, merchant_id, mcc_code)
+    let Some(record) = merchant_record else {
+        return Ok(());
+    };
+
+    if tx.destination != Some(record.token_account) {
+        return Err(error!(HookError::MerchantAccountMismatch));
+    }
+
+    // The record must describe the merchant being paid
+    if let Some(merchant_id) = tx.merchant_id {
+        if merchant_id != record.merchant_id {
+            return Err(error!(HookError::UnknownMerchant));
+        }
+    }
+    tx.merchant_id = Some(record.merchant_id);
+
+    // Inactive or blocked merchants never qualify for tier-based allowance
+    if record.is_valid() {
+        tx.merchant_risk_tier = Some(record.risk_tier);
+    }
+
+    if card_config.policy.has_country_rules() {
+        let country = alpha2_to_u16(record.country_code)
+            .ok_or(error!(HookError::InvalidConfiguration))?;
+        tx.country_code = Some(country);
+    }
+
+    Ok(())
+}
+
+/// Token-2022 `TransferFeeExtension` instruction tag
+const TRANSFER_FEE_EXTENSION_TAG: u8 = 26;
+/// `TransferCheckedWithFee` tag within the transfer fee extension
+const TRANSFER_CHECKED_WITH_FEE_TAG: u8 = 1;
+
+/// Whether a transfer of `mint` is a transfer-with-fee
+///
+/// Mints without the transfer fee extension never charge one. For fee-enabled
+/// mints the instruction is read from the instructions sysvar, and a type
+/// that can't be determined counts as a fee transfer, so cards that disallow
+/// them fail closed.
+fn transfer_is_with_fee(mint: &AccountInfo, instructions_sysvar: Option<&AccountInfo>) -> Result<bool> {
+    if !mint_has_transfer_fee(mint)? {
+        return Ok(false);
+    }
+    Ok(transfer_type_is_with_fee(instructions_sysvar)?.unwrap_or(true))
+}
+
+/// Whether a Token-2022 mint carries the transfer fee extension
+fn mint_has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    if mint.owner != &anchor_spl::token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint2022>::unpack(&data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+/// Whether the Token-2022 instruction invoking the hook is a transfer-with-fee
+///
+/// Read from the top-level instruction in the instructions sysvar. Returns
+/// None when the sysvar isn't supplied or the transfer came through a CPI, since
+/// the type can't be determined then.
+fn transfer_type_is_with_fee(instructions_sysvar: Option<&AccountInfo>) -> Result<Option<bool>> {
+    let Some(sysvar) = instructions_sysvar else {
+        return Ok(None);
+    };
+
+    let index = load_current_index_checked(sysvar)?;
+    let instruction = load_instruction_at_checked(index as usize, sysvar)?;
+    if instruction.program_id != anchor_spl::token_2022::ID {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        instruction.data.first() == Some(&TRANSFER_FEE_EXTENSION_TAG)
+            && instruction.data.get(1) == Some(&TRANSFER_CHECKED_WITH_FEE_TAG),
+    ))
+}
+
+/// Seed of the mint's extra account meta list PDA (from the transfer hook interface)
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Extra accounts Token-2022 passes to `Execute`, in `TransferHook` order
+///
+/// Indices refer to the Execute accounts: 0 source, 1 mint, 2 destination,
+/// 3 owner, 4 the meta list, then these extras from 5.
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    let card_link_index = 5;
+    let card_config_index = 6;
+
+    let mut metas = vec![
+        // 5: card link for the source account
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: CardTokenLink::SEED.to_vec() },
+                Seed::AccountKey { index: 0 },
+            ],
+            false,
+            false,
+        )?,
+        // 6: card config, keyed by the card ID stored in the link
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: CardConfig::SEED.to_vec() },
+                Seed::AccountData {
+                    account_index: card_link_index,
+                    data_index: CardTokenLink::CARD_ID_OFFSET,
+                    length: 32,
+                },
+            ],
+            false,
+            true,
+        )?,
+        // 7: step-up attestation
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"step_up".to_vec() },
+                Seed::AccountKey { index: card_config_index },
+            ],
+            false,
+            false,
+        )?,
+        // 8: merchant record, which Token-2022 can't derive (none)
+        ExtraAccountMeta::new_with_pubkey(&crate::ID, false, false)?,
+        // 9: fraud score
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"fraud_score".to_vec() },
+                Seed::AccountKey { index: card_config_index },
+            ],
+            false,
+            false,
+        )?,
+        // 10: approval token (consumed on use)
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"approval".to_vec() },
+                Seed::AccountKey { index: card_config_index },
+            ],
+            false,
+            true,
+        )?,
+    ];
+
+    #[cfg(feature = "plaintext-state")]
+    {
+        let discard_state_index = metas.len() as u8 + card_link_index;
+        // discard-state program, then the card's balance PDA it owns (keyed
+        // by the link's card ID, which platform links also have)
+        metas.push(ExtraAccountMeta::new_with_pubkey(&discard_state::ID, false, false)?);
+        metas.push(ExtraAccountMeta::new_external_pda_with_seeds(
+            discard_state_index,
+            &[
+                Seed::Literal { bytes: CardStatePda::SEED.to_vec() },
+                Seed::AccountData {
+                    account_index: card_link_index,
+                    data_index: CardTokenLink::CARD_ID_OFFSET,
+                    length: 32,
+                },
+            ],
+            false,
+            true,
+        )?);
+    }
+
+    metas.push(ExtraAccountMeta::new_with_pubkey(
+        &anchor_lang::solana_program::sysvar::instructions::ID,
+        false,
+        false,
+    )?);
+    metas.push(ExtraAccountMeta::new_with_seeds(
+        &[Seed::Literal { bytes: b"global_config".to_vec() }],
+        false,
+        false,
+    )?);
+    // The statement for the period the link last saw opened
+    metas.push(ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal { bytes: b"statement".to_vec() },
+            Seed::AccountKey { index: card_config_index },
+            Seed::AccountData {
+                account_index: card_link_index,
+                data_index: CardTokenLink::STATEMENT_PERIOD_OFFSET,
+                length: 4,
+            },
+        ],
+        false,
+        true,
+    )?);
+
+    Ok(metas)
 }
 
-/// Parse merchant data from extra account metas
-/// In production, this would decode the merchant metadata from the extra accounts
-pub fn parse_merchant_data(
-    _extra_account_meta_list: &AccountInfo,
-) -> Result<(Option<[u8; 32]>, Option<u16>)> {
-    // Placeholder: In production, decode merchant ID and MCC from extra account metas
-    Ok((None, None))
+/// Create the mint's extra account meta list so Token-2022 can resolve the
+/// hook's accounts (admin only)
+pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+    let metas = extra_account_metas()?;
+    let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &metas)?;
+
+    msg!("Extra account meta list initialized for mint: {}", ctx.accounts.mint.key());
+
+    Ok(())
+}
+
+/// Link the card's token account to its card ID so Token-2022 can find the
+/// card config (token account owner only)
+pub fn link_card_token_account(ctx: Context<LinkCardTokenAccount>) -> Result<()> {
+    let card_link = &mut ctx.accounts.card_link;
+    card_link.card_id = ctx.accounts.card_config.card_id;
+    card_link.statement_period = ctx.accounts.card_config.statement_period;
+    card_link.bump = ctx.bumps.card_link;
+
+    msg!("Token account {} linked to card", ctx.accounts.token_account.key());
+
+    Ok(())
+}
+
+/// Link a platform token account (treasury, merchant settlement, refunds) so
+/// Token-2022 can resolve the hook's accounts when it sends funds (admin only)
+///
+/// The link names no card, so the hook applies no card rules to transfers
+/// out of the account. An account already linked to a card can't be relinked.
+pub fn link_platform_token_account(ctx: Context<LinkPlatformTokenAccount>) -> Result<()> {
+    let card_link = &mut ctx.accounts.card_link;
+    card_link.card_id = CardTokenLink::PLATFORM_CARD_ID;
+    card_link.statement_period = 0;
+    card_link.bump = ctx.bumps.card_link;
+
+    msg!("Platform token account {} linked", ctx.accounts.token_account.key());
+
+    Ok(())
+}
+
+/// Reject calls that aren't part of a Token-2022 transfer
+///
+/// Token-2022 sets `transferring` on the source account's hook extension for
+/// the duration of the Execute CPI, so a direct call can't record spending.
+fn assert_is_transferring(source_account: &AccountInfo) -> Result<()> {
+    let data = source_account.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccount2022>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()
+        .map_err(|_| error!(HookError::NotTransferring))?;
+
+    if !bool::from(extension.transferring) {
+        return Err(error!(HookError::NotTransferring));
+    }
+    Ok(())
+}
+
+/// Load an optional PDA Token-2022 always passes
+///
+/// Returns None for a PDA that was never created (empty) or the program ID
+/// placeholder; anything else must be a `T` owned by `T::owner()`.
+fn load_optional_account<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<Option<T>> {
+    if info.key() == crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    if info.owner != &T::owner() {
+        return Err(error!(ErrorCode::AccountOwnedByWrongProgram));
+    }
+
+    let data = info.try_borrow_data()?;
+    Ok(Some(T::try_deserialize(&mut &data[..])?))
+}
+
+/// Load the card's discard-state balance, if it has one
+#[cfg(feature = "plaintext-state")]
+fn load_balance_account(
+    info: &AccountInfo,
+    card_config: &CardConfig,
+    card_key: Pubkey,
+) -> Result<Option<CardStatePda>> {
+    let Some(balance_account) = load_optional_account::<CardStatePda>(info)? else {
+        return Ok(None);
+    };
+
+    if balance_account.state.card_id != card_config.card_id
+        || balance_account.authority != card_key
+    {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
+    Ok(Some(balance_account))
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    /// Must be admin; pays for the meta list
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.admin == admin.key() @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The mint whose transfers this program hooks
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The mint's extra account meta list
+    /// CHECK: Created here and filled by `ExtraAccountMetaList::init`
+    #[account(
+        init,
+        payer = admin,
+        space = ExtraAccountMetaList::size_of(extra_account_metas()?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LinkCardTokenAccount<'info> {
+    /// Owner of the card's token account; pays for the link
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The card the token account belongs to
+    #[account(
+        seeds = [CardConfig::SEED, card_config.card_id.as_ref()],
+        bump = card_config.bump,
+        constraint = card_config.token_account == token_account.key()
+            @ HookError::SourceAccountMismatch,
+    )]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The card's token account
+    #[account(
+        constraint = token_account.owner == authority.key() @ HookError::Unauthorized,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CardTokenLink::SIZE,
+        seeds = [CardTokenLink::SEED, token_account.key().as_ref()],
+        bump,
+    )]
+    pub card_link: Account<'info, CardTokenLink>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LinkPlatformTokenAccount<'info> {
+    /// Must be admin; pays for the link
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.admin == admin.key() @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The platform token account
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = CardTokenLink::SIZE,
+        seeds = [CardTokenLink::SEED, token_account.key().as_ref()],
+        bump,
+    )]
+    pub card_link: Account<'info, CardTokenLink>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SimulateTransfer<'info> {
+    /// The card configuration to validate against (read-only)
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The card's mint (decides whether transfers carry a fee)
+    #[account(
+        constraint = card_config.mint == mint.key() @ HookError::MintMismatch,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Kill switch, system accounts and lazy reset settings
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Recent step-up attestation for the card (if any)
+    pub step_up_attestation: Option<Account<'info, StepUpAttestation>>,
+
+    /// Registry record for the merchant (authoritative country; must match
+    /// the `destination` argument)
+    pub merchant_record: Option<Account<'info, MerchantRecord>>,
+
+    /// Latest fraud score for the card (if any)
+    pub fraud_score: Option<Account<'info, FraudScore>>,
+
+    /// Owner pre-approval for a large transfer (if any)
+    pub approval_token: Option<Account<'info, ApprovalToken>>,
+
+    /// discard-state balance for the card (if any)
+    #[cfg(feature = "plaintext-state")]
+    #[account(
+        constraint = balance_account.state.card_id == card_config.card_id
+            @ HookError::InvalidConfiguration,
+        constraint = balance_account.authority == card_config.key()
+            @ HookError::InvalidConfiguration,
+    )]
+    pub balance_account: Option<Account<'info, CardStatePda>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckMerchantAllowed<'info> {
+    /// The card configuration to check against (read-only)
+    pub card_config: Account<'info, CardConfig>,
+
+    /// Registry record for the merchant (enables risk-tier allowance; must
+    /// match the `destination` argument)
+    pub merchant_record: Option<Account<'info, MerchantRecord>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{
+        derive_card_config_pda, BlockReason, BlockedMerchant, CardStatus, VelocityLimits,
+        WhitelistedMerchant,
+    };
+    use crate::test_utils::{
+        absent, account, anchor_data, card_config_account, global_config_account, mint_data, read,
+        return_data, run, zeroed_global_config,
+    };
+
+    const NOW: i64 = 1_700_000_000;
+    const SLOT: u64 = 250_000_000;
+
+    fn card_with_daily_limit(daily: u64) -> CardConfig {
+        CardConfig {
+            status: CardStatus::Active,
+            velocity_limits: VelocityLimits {
+                daily,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn transfer() -> TransactionContext {
+        TransactionContext {
+            mcc_code: Some(5411),
+            now: NOW,
+            ..Default::default()
+        }
+    }
+
+    fn merchant_record(country_code: [u8; 2], risk_tier: u8, token_account: Pubkey) -> MerchantRecord {
+        MerchantRecord {
+            merchant_id: [9; 32],
+            merchant_name: String::new(),
+            visa_mid: String::new(),
+            mcc_code: 5411,
+            risk_tier,
+            is_active: true,
+            country_code,
+            registered_at: 0,
+            updated_at: 0,
+            registered_by: Pubkey::default(),
+            metadata_uri: None,
+            bump: 0,
+            metadata_hash: None,
+            token_account,
+        }
+    }
+
+    /// Run `simulate_transfer` against `card`, returning its result and the
+    /// card account
+    fn simulate(card: CardConfig, amount: u64) -> (SimulationResult, AccountInfo<'static>) {
+        let mint = Pubkey::new_unique();
+        let card = card_config_account(CardConfig { card_id: [7; 32], mint, ..card });
+        let mut accounts = vec![
+            card.clone(),
+            account(mint, anchor_spl::token_2022::ID, false, mint_data()),
+            global_config_account(zeroed_global_config()),
+        ];
+        // Step-up, merchant record, fraud score, approval (and balance)
+        accounts.extend([absent(), absent(), absent(), absent()]);
+        #[cfg(feature = "plaintext-state")]
+        accounts.push(absent());
+        let ix = crate::instruction::SimulateTransfer {
+            amount,
+            merchant_id: None,
+            mcc_code: Some(5411),
+            destination: None,
+        };
+
+        run(accounts, ix).unwrap();
+        let result = SimulationResult::deserialize(&mut &return_data()[..]).unwrap();
+        (result, card)
+    }
+
+    #[test]
+    fn simulation_previews_an_allowed_transfer_without_recording_it() {
+        let mut card = card_with_daily_limit(1_000);
+        card.velocity_counters.daily_total = 300;
+        card.velocity_counters.last_daily_reset_unix = crate::test_utils::NOW;
+        card.velocity_counters.last_weekly_reset_unix = crate::test_utils::NOW;
+        card.velocity_counters.last_monthly_reset_unix = crate::test_utils::NOW;
+
+        let (result, card) = simulate(card, 200);
+
+        assert!(result.allowed);
+        assert_eq!(result.rejection, None);
+        assert_eq!(result.remaining_daily, 700);
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 300);
+    }
+
+    #[test]
+    fn simulation_reports_the_rejection_code() {
+        let (frozen, _) = simulate(CardConfig { status: CardStatus::Frozen, ..Default::default() }, 1);
+        assert!(!frozen.allowed);
+        assert_eq!(frozen.rejection, Some(HookError::CardFrozen.rejection_code()));
+
+        let (over_limit, _) = simulate(card_with_daily_limit(1_000), 1_001);
+        assert_eq!(over_limit.rejection, Some(HookError::DailyLimitExceeded.rejection_code()));
+    }
+
+    /// Run `check_merchant_allowed` for `merchant_id` at a grocery MCC
+    fn check_merchant(card: CardConfig, merchant_id: [u8; 32]) -> MerchantCheckResult {
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..card });
+        let ix = crate::instruction::CheckMerchantAllowed {
+            merchant_id,
+            mcc_code: Some(5411),
+            destination: None,
+        };
+
+        run(vec![card, absent()], ix).unwrap();
+        MerchantCheckResult::deserialize(&mut &return_data()[..]).unwrap()
+    }
+
+    #[test]
+    fn whitelisted_merchant_is_allowed() {
+        let mut card = card_with_daily_limit(0);
+        card.merchant_whitelist_enabled = true;
+        card.merchant_whitelist = vec![WhitelistedMerchant { merchant_id: [3; 32], expires_at: None }];
+
+        let allowed = check_merchant(card.clone(), [3; 32]);
+        assert!(allowed.allowed);
+        assert_eq!(allowed.rejection, None);
+
+        let other = check_merchant(card, [4; 32]);
+        assert!(!other.allowed);
+        assert_eq!(other.rejection, Some(HookError::MerchantNotWhitelisted.rejection_code()));
+    }
+
+    #[test]
+    fn blocklisted_merchant_is_refused() {
+        let mut card = card_with_daily_limit(0);
+        card.merchant_blocklist = vec![BlockedMerchant {
+            merchant_id: [3; 32],
+            reason: BlockReason::Fraud,
+            blocked_at: 0,
+        }];
+
+        let result = check_merchant(card, [3; 32]);
+        assert!(!result.allowed);
+        assert_eq!(result.rejection, Some(HookError::MerchantBlocked.rejection_code()));
+    }
+
+    #[test]
+    fn blocked_mcc_is_refused() {
+        let mut card = card_with_daily_limit(0);
+        card.mcc_blocklist = vec![5411];
+
+        let result = check_merchant(card, [3; 32]);
+        assert!(!result.allowed);
+        assert_eq!(result.rejection, Some(HookError::MccBlocked.rejection_code()));
+    }
+
+    #[test]
+    fn hook_metas_resolve_the_card_config_init_creates() {
+        let source = Pubkey::new_unique();
+        let card_id = [7; 32];
+        let link = CardTokenLink { card_id, ..Default::default() };
+        let link_data = anchor_data(&link, CardTokenLink::SIZE);
+        let metas = extra_account_metas().unwrap();
+
+        // Execute accounts: source, mint, destination, owner, validation list
+        let resolved_link = metas[0]
+            .resolve(&[], &crate::ID, |index| (index == 0).then_some((&source, None)))
+            .unwrap()
+            .pubkey;
+        let resolved_card = metas[1]
+            .resolve(&[], &crate::ID, |index| {
+                (index == 5).then_some((&resolved_link, Some(link_data.as_slice())))
+            })
+            .unwrap()
+            .pubkey;
+
+        let (link_key, _) = Pubkey::find_program_address(&[CardTokenLink::SEED, source.as_ref()], &crate::ID);
+        assert_eq!(resolved_link, link_key);
+        assert_eq!(resolved_card, derive_card_config_pda(&card_id).0);
+    }
+
+    /// A card bound to `mint` and `source`, and the link for `source`
+    fn linked_card(mint: Pubkey, source: Pubkey) -> (AccountInfo<'static>, AccountInfo<'static>) {
+        let card = CardConfig { card_id: [7; 32], mint, token_account: source, ..Default::default() };
+        let (link_key, bump) =
+            Pubkey::find_program_address(&[CardTokenLink::SEED, source.as_ref()], &crate::ID);
+        let link = CardTokenLink { card_id: card.card_id, bump, ..Default::default() };
+        let link = account(link_key, crate::ID, false, anchor_data(&link, CardTokenLink::SIZE));
+        (link, card_config_account(card))
+    }
+
+    #[test]
+    fn source_card_loads_for_its_own_mint() {
+        let (mint, source) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (link, card) = linked_card(mint, source);
+
+        let card_config = load_source_card(&link, &card, source, mint).unwrap().unwrap();
+        assert_eq!(card_config.mint, mint);
+    }
+
+    #[test]
+    fn source_card_rejects_a_different_mint() {
+        let source = Pubkey::new_unique();
+        let (link, card) = linked_card(Pubkey::new_unique(), source);
+
+        let Err(err) = load_source_card(&link, &card, source, Pubkey::new_unique()) else {
+            panic!("a card was loaded for the wrong mint");
+        };
+        assert_eq!(rejection_code(&err), HookError::MintMismatch.rejection_code());
+    }
+
+    /// Zeroed: kill switch off and only `system_accounts` listed
+    fn global_config(system_accounts: Vec<Pubkey>) -> GlobalConfig {
+        let mut config = GlobalConfig::deserialize(&mut &[0; GlobalConfig::SIZE][..]).unwrap();
+        config.system_transfer_accounts = system_accounts;
+        config
+    }
+
+    fn exhausted_card() -> CardConfig {
+        let mut card = card_with_daily_limit(1_000);
+        card.velocity_counters.daily_total = 1_000;
+        card
+    }
+
+    #[test]
+    fn approved_transfers_accumulate() {
+        let mut card = card_with_daily_limit(1_000);
+        let mut statement = MonthlyStatement::default();
+
+        approve_transfer(&mut card, Some(&mut statement), 300, &transfer(), SLOT).unwrap();
+        approve_transfer(&mut card, Some(&mut statement), 400, &transfer(), SLOT + 1).unwrap();
+
+        assert_eq!(card.velocity_counters.daily_total, 700);
+        assert_eq!(card.velocity_counters.daily_transaction_count, 2);
+        assert_eq!(card.last_transaction.map(|txn| txn.amount), Some(400));
+        assert_eq!(statement.total_spent, 700);
+        assert_eq!(statement.transaction_count, 2);
+        assert_eq!(statement.mcc_histogram.len(), 1);
+        assert_eq!(statement.mcc_histogram[0].total, 700);
+    }
+
+    #[test]
+    fn treasury_transfers_bypass_card_limits() {
+        let treasury = Pubkey::new_unique();
+        let card_account = Pubkey::new_unique();
+        let global_config = global_config(vec![treasury]);
+
+        // Funding the card (the treasury has no card) and sweeping it back
+        let funding = route_transfer(&global_config, treasury, card_account, None).unwrap();
+        let sweep = route_transfer(&global_config, card_account, treasury, Some(exhausted_card())).unwrap();
+
+        assert!(matches!(funding, TransferRoute::SystemTransfer));
+        assert!(matches!(sweep, TransferRoute::SystemTransfer));
+    }
+
+    #[test]
+    fn ordinary_transfers_keep_card_limits() {
+        let global_config = global_config(vec![Pubkey::new_unique()]);
+        let route = route_transfer(
+            &global_config,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Some(exhausted_card()),
+        )
+        .unwrap();
+
+        let TransferRoute::Card(mut card) = route else {
+            panic!("card rules were skipped");
+        };
+        let err = approve_transfer(&mut card, None, 100, &transfer(), SLOT).unwrap_err();
+        assert_eq!(rejection_code(&err), HookError::DailyLimitExceeded.rejection_code());
+    }
+
+    #[test]
+    fn kill_switch_halts_system_transfers() {
+        let treasury = Pubkey::new_unique();
+        let mut global_config = global_config(vec![treasury]);
+        global_config.kill_switch = true;
+
+        let result = route_transfer(&global_config, treasury, Pubkey::new_unique(), None);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn kill_switch_overrides_the_treasury_bypass_but_pause_does_not() {
+        let treasury = Pubkey::new_unique();
+        let paused = GlobalConfig { is_paused: true, ..global_config(vec![treasury]) };
+        let route = route_transfer(&paused, treasury, Pubkey::new_unique(), None).unwrap();
+        assert!(matches!(route, TransferRoute::SystemTransfer));
+
+        let killed = GlobalConfig { kill_switch: true, ..paused };
+        let card_source = Pubkey::new_unique();
+        for (source, card) in [(treasury, None), (card_source, Some(card_with_daily_limit(1_000)))] {
+            let Err(err) = route_transfer(&killed, source, Pubkey::new_unique(), card) else {
+                panic!("kill switch let a transfer through");
+            };
+            assert_eq!(rejection_code(&err), HookError::KillSwitchEngaged.rejection_code());
+        }
+    }
+
+    #[test]
+    fn registry_country_overrides_client_country() {
+        let settlement = Pubkey::new_unique();
+        let mut card = card_with_daily_limit(1_000);
+        card.policy.allow_international = true;
+        card.policy.blocked_countries = vec![alpha2_to_u16(*b"KP").unwrap()];
+        let mut tx = TransactionContext {
+            country_code: alpha2_to_u16(*b"US"),
+            destination: Some(settlement),
+            ..transfer()
+        };
+
+        apply_merchant_record(&mut tx, &card, Some(&merchant_record(*b"KP", 1, settlement))).unwrap();
+
+        let err = card.is_transaction_allowed(100, &tx).unwrap_err();
+        assert_eq!(rejection_code(&err), HookError::CountryBlocked.rejection_code());
+    }
+
+    #[test]
+    fn merchant_daily_count_is_kept_per_destination() {
+        let mut card = card_with_daily_limit(0);
+        card.policy.max_daily_per_merchant = Some(3);
+        let merchant = TransactionContext { destination: Some(Pubkey::new_unique()), ..transfer() };
+        let other_merchant = TransactionContext { destination: Some(Pubkey::new_unique()), ..transfer() };
+
+        for _ in 0..3 {
+            approve_transfer(&mut card, None, 100, &merchant, SLOT).unwrap();
+        }
+        let err = approve_transfer(&mut card, None, 100, &merchant, SLOT).unwrap_err();
+
+        assert_eq!(rejection_code(&err), HookError::MerchantVelocityExceeded.rejection_code());
+        approve_transfer(&mut card, None, 100, &other_merchant, SLOT).unwrap();
+    }
+
+    /// Whether a whitelist-only card allows a merchant of `risk_tier`
+    fn allowed_at_tier(risk_tier: u8) -> bool {
+        let settlement = Pubkey::new_unique();
+        let mut card = card_with_daily_limit(0);
+        card.merchant_whitelist_enabled = true;
+        card.policy.allow_by_max_risk_tier = Some(2);
+        let mut tx = TransactionContext { destination: Some(settlement), ..transfer() };
+
+        let record = merchant_record(*b"US", risk_tier, settlement);
+        apply_merchant_record(&mut tx, &card, Some(&record)).unwrap();
+        card.check_merchant_rules(&tx).is_ok()
+    }
+
+    #[test]
+    fn registry_risk_tier_allows_up_to_the_maximum() {
+        assert!(allowed_at_tier(1));
+        assert!(allowed_at_tier(2));
+        assert!(!allowed_at_tier(3));
+    }
+
+    #[test]
+    fn rejected_transfer_records_nothing() {
+        let mut card = card_with_daily_limit(1_000);
+        let mut statement = MonthlyStatement::default();
+        approve_transfer(&mut card, Some(&mut statement), 700, &transfer(), SLOT).unwrap();
+
+        let result = approve_transfer(&mut card, Some(&mut statement), 400, &transfer(), SLOT + 1);
+
+        assert!(result.is_err());
+        assert_eq!(card.velocity_counters.daily_total, 700);
+        assert_eq!(card.velocity_counters.daily_transaction_count, 1);
+        assert_eq!(card.last_transaction.map(|txn| txn.amount), Some(700));
+        assert_eq!(statement.total_spent, 700);
+        assert_eq!(statement.transaction_count, 1);
+    }
+
+    /// A Token-2022 mint with the transfer fee extension
+    fn fee_mint() -> AccountInfo<'static> {
+        use anchor_spl::token_2022::spl_token_2022::extension::{
+            BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+        };
+
+        let len = ExtensionType::try_calculate_account_len::<Mint2022>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        let mut data = vec![0; len];
+        let mut mint = StateWithExtensionsMut::<Mint2022>::unpack_uninitialized(&mut data).unwrap();
+        mint.init_extension::<TransferFeeConfig>(true).unwrap();
+        mint.base = Mint2022 { decimals: 6, is_initialized: true, ..Default::default() };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        account(Pubkey::new_unique(), anchor_spl::token_2022::ID, false, data)
+    }
+
+    /// An instructions sysvar whose current instruction is a Token-2022
+    /// instruction with `data`
+    fn instructions_sysvar(data: &[u8]) -> AccountInfo<'static> {
+        use anchor_lang::solana_program::sysvar::instructions::{
+            construct_instructions_data, BorrowedInstruction,
+        };
+
+        let instruction = BorrowedInstruction {
+            program_id: &anchor_spl::token_2022::ID,
+            accounts: vec![],
+            data,
+        };
+        let sysvar_data = construct_instructions_data(&[instruction]);
+        let key = anchor_lang::solana_program::sysvar::instructions::ID;
+        account(key, Pubkey::default(), false, sysvar_data)
+    }
+
+    #[test]
+    fn fee_transfers_are_told_apart_from_plain_ones() {
+        // TransferChecked, then the transfer fee extension's TransferCheckedWithFee
+        let plain = instructions_sysvar(&[12]);
+        let with_fee =
+            instructions_sysvar(&[TRANSFER_FEE_EXTENSION_TAG, TRANSFER_CHECKED_WITH_FEE_TAG]);
+        let fee_mint = fee_mint();
+
+        assert!(!transfer_is_with_fee(&fee_mint, Some(&plain)).unwrap());
+        assert!(transfer_is_with_fee(&fee_mint, Some(&with_fee)).unwrap());
+        // Undetermined types fail closed; mints without the extension never
+        // charge a fee
+        assert!(transfer_is_with_fee(&fee_mint, None).unwrap());
+        let plain_mint =
+            account(Pubkey::new_unique(), anchor_spl::token_2022::ID, false, mint_data());
+        assert!(!transfer_is_with_fee(&plain_mint, Some(&with_fee)).unwrap());
+    }
+
+    #[test]
+    fn card_can_refuse_fee_transfers_but_not_plain_ones() {
+        let mut card = card_with_daily_limit(1_000);
+        card.policy.allow_transfer_with_fee = false;
+
+        card.is_transaction_allowed(100, &transfer()).unwrap();
+        let fee_transfer = TransactionContext { transfer_with_fee: true, ..transfer() };
+        let err = card.is_transaction_allowed(100, &fee_transfer).unwrap_err();
+        assert_eq!(rejection_code(&err), HookError::TransferTypeNotAllowed.rejection_code());
+
+        card.policy.allow_transfer_with_fee = true;
+        card.is_transaction_allowed(100, &fee_transfer).unwrap();
+    }
 }
@@ -3,13 +3,24 @@
 use anchor_lang::prelude::*;
 use crate::{
     UpdateVelocityLimits, RecordTransaction, ResetVelocity,
-    state::VelocityLimits,
+    errors::HookError,
+    events::{CounterAdjusted, TransactionRecorded},
+    state::{
+        CardConfig, DeclineCategory, GlobalConfig, LastTxn, LimitBoost, MonthlyStatement,
+        TransactionChannel, VelocityLimits, PERMISSION_RESET,
+    },
 };
 
-// Slot timing constants (assuming ~400ms slots)
-const SLOTS_PER_DAY: u64 = 216_000;
-const SLOTS_PER_WEEK: u64 = 1_512_000;
-const SLOTS_PER_MONTH: u64 = 6_480_000;
+/// Maximum number of cards reset in one `reset_velocity_batch` call
+pub const MAX_RESET_BATCH: usize = 16;
+
+/// Velocity period selected for a batched reset or a counter adjustment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResetPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
 
 /// Update velocity limits for a card
 pub fn update_limits(
@@ -25,7 +36,76 @@ pub fn update_limits(
     msg!("  Weekly: {}", limits.weekly);
     msg!("  Monthly: {}", limits.monthly);
 
-    card_config.velocity_limits = limits;
+    let global_config = &ctx.accounts.global_config;
+    let ceiling = global_config.velocity_ceiling(card_config.decimals);
+    let cooldown = global_config.min_slots_between_limit_increases;
+    card_config.set_velocity_limits(limits, &ceiling, cooldown, clock.slot)?;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Velocity limits updated successfully");
+
+    Ok(())
+}
+
+/// Set the card's online and in-store daily caps (None removes a cap)
+pub fn set_channel_limits(
+    ctx: Context<UpdateVelocityLimits>,
+    online_daily: Option<u64>,
+    instore_daily: Option<u64>,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let cooldown = ctx.accounts.global_config.min_slots_between_limit_increases;
+    card_config.set_channel_limits(online_daily, instore_daily, cooldown, clock.slot)?;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Channel limits set: online={:?} in-store={:?}", online_daily, instore_daily);
+
+    Ok(())
+}
+
+/// Grant a one-off daily limit boost that lapses at `expires_at`
+pub fn grant_limit_boost(
+    ctx: Context<UpdateVelocityLimits>,
+    amount: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let global_config = &ctx.accounts.global_config;
+    let ceiling = global_config.velocity_ceiling(card_config.decimals);
+    let cooldown = global_config.min_slots_between_limit_increases;
+    card_config.grant_limit_boost(
+        LimitBoost { amount, expires_at },
+        &ceiling,
+        cooldown,
+        clock.slot,
+        clock.unix_timestamp,
+    )?;
+    card_config.updated_at = clock.unix_timestamp;
+
+    msg!("Daily limit boosted by {} until {}", amount, expires_at);
+
+    Ok(())
+}
+
+/// Apply a named velocity limit preset to a card
+pub fn set_preset(
+    ctx: Context<UpdateVelocityLimits>,
+    preset: LimitPreset,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    msg!("Applying velocity preset: {:?}", preset);
+
+    let limits = preset.limits(card_config.decimals);
+    let global_config = &ctx.accounts.global_config;
+    let ceiling = global_config.velocity_ceiling(card_config.decimals);
+    let cooldown = global_config.min_slots_between_limit_increases;
+    card_config.set_velocity_limits(limits, &ceiling, cooldown, clock.slot)?;
     card_config.updated_at = clock.unix_timestamp;
 
     msg!("Velocity limits updated successfully");
@@ -33,17 +113,41 @@ pub fn update_limits(
     Ok(())
 }
 
-/// Record a transaction for velocity tracking
+/// Record a transaction for velocity tracking (settlement services only)
+///
+/// Transfers through Token-2022 are already recorded by the transfer hook
+/// itself; this instruction is for spend settled outside the hook, and must
+/// not be called for hook-validated transfers or they would count twice.
+///
+/// Settled spend has already happened, so it is always counted. An over-limit
+/// transaction also increments the card's `breach_count` and may auto-freeze
+/// the card. Breaches are tracked here rather than in the transfer hook
+/// because a rejected hook invocation rolls back every account write, so the
+/// count would never persist.
+///
+/// The settlement service reports the transaction's `channel`, which counts
+/// the spend against (and checks it with) the card's online or in-store cap.
 pub fn record_transaction(
     ctx: Context<RecordTransaction>,
     amount: u64,
     merchant_id: Option<[u8; 32]>,
     mcc_code: Option<u16>,
+    channel: Option<TransactionChannel>,
+    nonce: u64,
+    reference: Option<[u8; 32]>,
 ) -> Result<()> {
     let card_config = &mut ctx.accounts.card_config;
     let clock = Clock::get()?;
 
+    // Only a settlement service may move the counters: the replay nonces
+    // are its own, so no other signer may consume them
+    let recorder = ctx.accounts.recorder.key();
+    if !ctx.accounts.global_config.is_authorized_settlement_authority(recorder) {
+        return Err(error!(HookError::Unauthorized));
+    }
+
     msg!("Recording transaction:");
+    msg!("  Nonce: {}", nonce);
     msg!("  Amount: {}", amount);
     if let Some(mid) = merchant_id {
         msg!("  Merchant: {:?}", mid);
@@ -51,29 +155,139 @@ pub fn record_transaction(
     if let Some(mcc) = mcc_code {
         msg!("  MCC: {}", mcc);
     }
+    if let Some(channel) = channel {
+        msg!("  Channel: {:?}", channel);
+    }
+    if let Some(reference) = reference {
+        msg!("  Reference: {:?}", reference);
+    }
+
+    // Reject replays before anything is counted (breaches included)
+    card_config.record_nonce(nonce)?;
+
+    // Check if resets are needed based on slot time (unless the cron owns resets)
+    let global_config = &ctx.accounts.global_config;
+    if global_config.lazy_reset_enabled {
+        let week_start_day = global_config.week_start_day;
+        auto_reset_if_needed(card_config, clock.slot, clock.unix_timestamp, week_start_day)?;
+    }
+
+    // Lazily drop lapsed temporary whitelist entries
+    card_config.evict_expired_whitelist_entries(clock.unix_timestamp);
 
-    // Check if resets are needed based on slot time
-    auto_reset_if_needed(card_config, clock.slot)?;
+    // Check before counting so the breach is judged against the prior totals
+    let breach = card_config.check_velocity_limits(amount, channel, None, clock.unix_timestamp).err();
 
     // Record the transaction
-    card_config.velocity_counters.record_transaction(amount);
-    card_config.last_transaction_at = Some(clock.unix_timestamp);
-    card_config.updated_at = clock.unix_timestamp;
+    apply_transaction(card_config, &RecordedTransaction {
+        amount,
+        merchant_id,
+        merchant_key: merchant_id,
+        mcc_code,
+        channel,
+        reference,
+        slot: clock.slot,
+        now: clock.unix_timestamp,
+    });
+    match breach {
+        Some(err) => {
+            let frozen = card_config.record_velocity_breach(clock.unix_timestamp);
+            card_config.decline_stats.record(DeclineCategory::Velocity);
+            msg!("Velocity limit breached: {} (breach_count={})", err, card_config.breach_count);
+            if frozen {
+                msg!("Card auto-frozen after repeated velocity breaches");
+            }
+        }
+        None => card_config.breach_count = 0,
+    }
+    ctx.accounts.global_config.record_transaction(amount)?;
+
+    emit!(TransactionRecorded {
+        card_config: card_config.key(),
+        amount,
+        merchant_id,
+        mcc_code,
+        channel,
+        reference,
+        slot: clock.slot,
+    });
+
+    // Accumulate into the open monthly statement, if provided
+    if let Some(statement) = ctx.accounts.statement.as_mut() {
+        record_into_statement(statement, card_config, amount, mcc_code)?;
+    }
 
     msg!("Transaction recorded. Daily total: {}", card_config.velocity_counters.daily_total);
 
     Ok(())
 }
 
+/// Record a hook-approved transfer once it settles (settlement services only)
+///
+/// The transfer hook already counted the transfer against the card's
+/// velocity limits but can't write the global config, so this adds it to
+/// the program-wide statistics and attaches the settlement `reference` to the
+/// card's last transaction. Pass `statement` only for transfers the hook
+/// couldn't write to one (no statement was open when it ran).
+pub fn record_settlement(
+    ctx: Context<RecordSettlement>,
+    amount: u64,
+    mcc_code: Option<u16>,
+    nonce: u64,
+    reference: Option<[u8; 32]>,
+) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+
+    // The replay nonces are the settlement service's own
+    card_config.record_nonce(nonce)?;
+
+    if let Some(last_transaction) = card_config.last_transaction.as_mut() {
+        if reference.is_some() {
+            last_transaction.reference = reference;
+        }
+    }
+    card_config.updated_at = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.global_config.record_transaction(amount)?;
+
+    if let Some(statement) = ctx.accounts.statement.as_mut() {
+        record_into_statement(statement, card_config, amount, mcc_code)?;
+    }
+
+    msg!("Settlement recorded: {}", amount);
+
+    Ok(())
+}
+
+/// Add a transaction to the card's open statement
+fn record_into_statement(
+    statement: &mut MonthlyStatement,
+    card_config: &Account<CardConfig>,
+    amount: u64,
+    mcc_code: Option<u16>,
+) -> Result<()> {
+    if statement.card_config != card_config.key()
+        || statement.period != card_config.statement_period
+    {
+        return Err(error!(HookError::StatementPeriodMismatch));
+    }
+    if statement.is_finalized {
+        return Err(error!(HookError::StatementAlreadyFinalized));
+    }
+    statement.record(amount, mcc_code)
+}
+
 /// Reset daily velocity counters
 pub fn reset_daily(ctx: Context<ResetVelocity>) -> Result<()> {
+    authorize_reset(ctx.accounts)?;
+
     let card_config = &mut ctx.accounts.card_config;
     let clock = Clock::get()?;
 
     msg!("Resetting daily velocity counters");
     msg!("  Previous daily total: {}", card_config.velocity_counters.daily_total);
 
-    card_config.velocity_counters.reset_daily(clock.slot);
+    card_config.reset_daily(clock.slot, clock.unix_timestamp);
     card_config.updated_at = clock.unix_timestamp;
 
     msg!("Daily velocity counters reset");
@@ -83,13 +297,15 @@ pub fn reset_daily(ctx: Context<ResetVelocity>) -> Result<()> {
 
 /// Reset weekly velocity counters
 pub fn reset_weekly(ctx: Context<ResetVelocity>) -> Result<()> {
+    authorize_reset(ctx.accounts)?;
+
     let card_config = &mut ctx.accounts.card_config;
     let clock = Clock::get()?;
 
     msg!("Resetting weekly velocity counters");
     msg!("  Previous weekly total: {}", card_config.velocity_counters.weekly_total);
 
-    card_config.velocity_counters.reset_weekly(clock.slot);
+    card_config.reset_weekly(clock.slot, clock.unix_timestamp);
     card_config.updated_at = clock.unix_timestamp;
 
     msg!("Weekly velocity counters reset");
@@ -99,43 +315,204 @@ pub fn reset_weekly(ctx: Context<ResetVelocity>) -> Result<()> {
 
 /// Reset monthly velocity counters
 pub fn reset_monthly(ctx: Context<ResetVelocity>) -> Result<()> {
+    authorize_reset(ctx.accounts)?;
+
     let card_config = &mut ctx.accounts.card_config;
     let clock = Clock::get()?;
 
     msg!("Resetting monthly velocity counters");
     msg!("  Previous monthly total: {}", card_config.velocity_counters.monthly_total);
 
-    card_config.velocity_counters.reset_monthly(clock.slot);
+    card_config.reset_monthly(clock.slot, clock.unix_timestamp);
     card_config.updated_at = clock.unix_timestamp;
 
-    msg!("Monthly velocity counters reset");
+    msg!("Monthly velocity counters reset, statement period: {}", card_config.statement_period);
 
     Ok(())
 }
 
-/// Automatically reset counters if enough time has passed
-fn auto_reset_if_needed(
-    card_config: &mut crate::state::CardConfig,
-    current_slot: u64,
+/// A manual reset needs a reset authority, or a delegate explicitly granted
+/// the reset permission; owners can't reset their own card's counters
+fn authorize_reset(accounts: &ResetVelocity) -> Result<()> {
+    let authority = accounts.authority.key();
+    let is_reset_authority = accounts.global_config.is_authorized_reset_authority(authority);
+    let is_delegate = accounts.owner_did.as_ref().is_some_and(|did| {
+        did.proves_delegate_on(authority, &accounts.card_config, PERMISSION_RESET)
+    });
+    if !is_reset_authority && !is_delegate {
+        return Err(error!(HookError::Unauthorized));
+    }
+    Ok(())
+}
+
+/// Correct one period's spending total after a mis-recorded transaction
+///
+/// Fraud service or admin only. The signed `delta` saturates at zero and at
+/// the period's limit, so a correction can't push the counter out of range.
+pub fn adjust_velocity_counter(
+    ctx: Context<AdjustVelocityCounter>,
+    period: ResetPeriod,
+    delta: i64,
 ) -> Result<()> {
+    let card_config = &mut ctx.accounts.card_config;
+    let clock = Clock::get()?;
+
+    let limit = match period {
+        ResetPeriod::Daily => card_config.velocity_limits.daily_cap(),
+        ResetPeriod::Weekly => card_config.effective_weekly_limit(),
+        ResetPeriod::Monthly => card_config.velocity_limits.monthly_cap(),
+    };
     let counters = &mut card_config.velocity_counters;
+    let total = match period {
+        ResetPeriod::Daily => &mut counters.daily_total,
+        ResetPeriod::Weekly => &mut counters.weekly_total,
+        ResetPeriod::Monthly => &mut counters.monthly_total,
+    };
+
+    let old_total = *total;
+    let adjusted = if delta < 0 {
+        old_total.saturating_sub(delta.unsigned_abs())
+    } else {
+        old_total.saturating_add(delta as u64)
+    };
+    // A counter already above a since-lowered limit may only come down
+    let new_total = adjusted.min(limit.max(old_total));
+    *total = new_total;
+    card_config.updated_at = clock.unix_timestamp;
+
+    emit!(CounterAdjusted {
+        card_config: card_config.key(),
+        period,
+        delta,
+        old_total,
+        new_total,
+        adjusted_by: ctx.accounts.authority.key(),
+        adjusted_at: clock.unix_timestamp,
+    });
+
+    msg!("Adjusted {:?} counter: {} -> {}", period, old_total, new_total);
+
+    Ok(())
+}
+
+/// Reset the chosen period on every card passed as a remaining account
+///
+/// Cards whose period boundary hasn't been crossed yet are skipped, so the
+/// cron can send the same batch repeatedly without double-resetting.
+pub fn reset_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResetVelocityBatch<'info>>,
+    period: ResetPeriod,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let cards = ctx.remaining_accounts;
+
+    if cards.is_empty() || cards.len() > MAX_RESET_BATCH {
+        return Err(error!(HookError::InvalidConfiguration));
+    }
 
+    msg!("Batched {:?} velocity reset for {} cards", period, cards.len());
+
+    let week_start_day = ctx.accounts.global_config.week_start_day;
+    let mut reset_count: u32 = 0;
+    for info in cards {
+        if !info.is_writable {
+            return Err(error!(HookError::InvalidConfiguration));
+        }
+
+        let mut card_config: Account<CardConfig> = Account::try_from(info)?;
+        let counters = &card_config.velocity_counters;
+
+        let due = match period {
+            ResetPeriod::Daily => should_reset_daily(counters.last_daily_reset_unix, clock.unix_timestamp),
+            ResetPeriod::Weekly => should_reset_weekly(
+                counters.last_weekly_reset_unix,
+                clock.unix_timestamp,
+                week_start_day,
+            ),
+            ResetPeriod::Monthly => should_reset_monthly(counters.last_monthly_reset_unix, clock.unix_timestamp),
+        };
+
+        if !due {
+            msg!("  Skipping card {:?}: not due", card_config.card_id);
+            continue;
+        }
+
+        match period {
+            ResetPeriod::Daily => card_config.reset_daily(clock.slot, clock.unix_timestamp),
+            ResetPeriod::Weekly => card_config.reset_weekly(clock.slot, clock.unix_timestamp),
+            ResetPeriod::Monthly => card_config.reset_monthly(clock.slot, clock.unix_timestamp),
+        }
+        card_config.updated_at = clock.unix_timestamp;
+        card_config.exit(&crate::ID)?;
+
+        reset_count += 1;
+    }
+
+    msg!("Reset {} of {} cards", reset_count, cards.len());
+
+    Ok(())
+}
+
+/// A transaction being counted against a card
+pub(crate) struct RecordedTransaction {
+    pub amount: u64,
+    pub merchant_id: Option<[u8; 32]>,
+    /// Per-merchant daily count key (see `TransactionContext::merchant_key`)
+    pub merchant_key: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub channel: Option<TransactionChannel>,
+    pub reference: Option<[u8; 32]>,
+    pub slot: u64,
+    pub now: i64,
+}
+
+/// Count a transaction against the card's velocity counters
+///
+/// Callers clear `breach_count` for in-limit spend themselves, since settled
+/// over-limit spend is counted as well.
+pub(crate) fn apply_transaction(card_config: &mut CardConfig, txn: &RecordedTransaction) {
+    card_config.velocity_counters.record_transaction(txn.amount);
+    card_config.channel_limits.record_transaction(txn.amount, txn.channel);
+    if let Some(key) = txn.merchant_key {
+        card_config.record_merchant_transaction(key);
+    }
+    card_config.last_transaction_at = Some(txn.now);
+    card_config.last_transaction = Some(LastTxn {
+        amount: txn.amount,
+        merchant_id: txn.merchant_id,
+        mcc_code: txn.mcc_code,
+        slot: txn.slot,
+        reference: txn.reference,
+    });
+    card_config.updated_at = txn.now;
+}
+
+/// Automatically reset counters once a calendar boundary has been crossed
+///
+/// Boundaries come from `Clock.unix_timestamp` rather than slot counts so
+/// resets stay wall-clock accurate regardless of slot speed.
+pub(crate) fn auto_reset_if_needed(
+    card_config: &mut CardConfig,
+    current_slot: u64,
+    now: i64,
+    week_start_day: u8,
+) -> Result<()> {
     // Check and reset daily
-    if current_slot.saturating_sub(counters.last_daily_reset_slot) >= SLOTS_PER_DAY {
+    if should_reset_daily(card_config.velocity_counters.last_daily_reset_unix, now) {
         msg!("Auto-resetting daily counters");
-        counters.reset_daily(current_slot);
+        card_config.reset_daily(current_slot, now);
     }
 
     // Check and reset weekly
-    if current_slot.saturating_sub(counters.last_weekly_reset_slot) >= SLOTS_PER_WEEK {
+    if should_reset_weekly(card_config.velocity_counters.last_weekly_reset_unix, now, week_start_day) {
         msg!("Auto-resetting weekly counters");
-        counters.reset_weekly(current_slot);
+        card_config.reset_weekly(current_slot, now);
     }
 
     // Check and reset monthly
-    if current_slot.saturating_sub(counters.last_monthly_reset_slot) >= SLOTS_PER_MONTH {
+    if should_reset_monthly(card_config.velocity_counters.last_monthly_reset_unix, now) {
         msg!("Auto-resetting monthly counters");
-        counters.reset_monthly(current_slot);
+        card_config.reset_monthly(current_slot, now);
     }
 
     Ok(())
@@ -145,54 +522,660 @@ fn auto_reset_if_needed(
 // Velocity Limit Presets
 // ============================================================================
 
+/// Named velocity limit tier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LimitPreset {
+    Conservative,
+    Standard,
+    Premium,
+    Institutional,
+}
+
+impl LimitPreset {
+    /// Velocity limits for this tier, in base units of a mint with `decimals`
+    pub fn limits(&self, decimals: u8) -> VelocityLimits {
+        let display = match self {
+            LimitPreset::Conservative => conservative_limits(),
+            LimitPreset::Standard => standard_limits(),
+            LimitPreset::Premium => premium_limits(),
+            LimitPreset::Institutional => institutional_limits(),
+        };
+        display.to_base_units(decimals)
+    }
+}
+
+// Preset amounts are whole dollars; `LimitPreset::limits` scales them to the
+// card mint's base units.
+
 /// Conservative limits for low-risk users
 pub fn conservative_limits() -> VelocityLimits {
     VelocityLimits {
-        per_transaction: 50000,      // $500
-        daily: 100000,               // $1,000
-        weekly: 250000,              // $2,500
-        monthly: 500000,             // $5,000
+        per_transaction: 500,        // $500
+        daily: 1_000,                // $1,000
+        weekly: 2_500,               // $2,500
+        monthly: 5_000,              // $5,000
         max_daily_transactions: 10,
         max_weekly_transactions: 30,
         max_monthly_transactions: 100,
+        daily_pct_of_balance: None,
     }
 }
 
 /// Standard limits for verified users
 pub fn standard_limits() -> VelocityLimits {
     VelocityLimits {
-        per_transaction: 250000,     // $2,500
-        daily: 500000,               // $5,000
-        weekly: 1500000,             // $15,000
-        monthly: 5000000,            // $50,000
+        per_transaction: 2_500,      // $2,500
+        daily: 5_000,                // $5,000
+        weekly: 15_000,              // $15,000
+        monthly: 50_000,             // $50,000
         max_daily_transactions: 25,
         max_weekly_transactions: 100,
         max_monthly_transactions: 300,
+        daily_pct_of_balance: None,
     }
 }
 
 /// Premium limits for fully verified users
 pub fn premium_limits() -> VelocityLimits {
     VelocityLimits {
-        per_transaction: 1000000,    // $10,000
-        daily: 2500000,              // $25,000
-        weekly: 10000000,            // $100,000
-        monthly: 25000000,           // $250,000
+        per_transaction: 10_000,     // $10,000
+        daily: 25_000,               // $25,000
+        weekly: 100_000,             // $100,000
+        monthly: 250_000,            // $250,000
         max_daily_transactions: 50,
         max_weekly_transactions: 200,
         max_monthly_transactions: 500,
+        daily_pct_of_balance: None,
     }
 }
 
 /// Institutional limits for business accounts
 pub fn institutional_limits() -> VelocityLimits {
     VelocityLimits {
-        per_transaction: 10000000,   // $100,000
-        daily: 50000000,             // $500,000
-        weekly: 200000000,           // $2,000,000
-        monthly: 500000000,          // $5,000,000
+        per_transaction: 100_000,    // $100,000
+        daily: 500_000,              // $500,000
+        weekly: 2_000_000,           // $2,000,000
+        monthly: 5_000_000,          // $5,000,000
         max_daily_transactions: 500,
         max_weekly_transactions: 2000,
         max_monthly_transactions: 10000,
+        daily_pct_of_balance: None,
+    }
+}
+
+// ============================================================================
+// Account Context
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ResetVelocityBatch<'info> {
+    /// Must be authorized (cron service or admin)
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_reset_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    // Remaining accounts: writable `CardConfig`s to reset (up to MAX_RESET_BATCH)
+}
+
+#[derive(Accounts)]
+pub struct RecordSettlement<'info> {
+    /// Must be a settlement service
+    pub recorder: Signer<'info>,
+
+    /// The card the settled transfer was made with
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// The open monthly statement for the card (optional)
+    #[account(mut)]
+    pub statement: Option<Account<'info, MonthlyStatement>>,
+
+    /// Global config for recorder verification and program-wide statistics
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_settlement_authority(recorder.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AdjustVelocityCounter<'info> {
+    /// Must be a fraud service or admin
+    pub authority: Signer<'info>,
+
+    /// Global config for authority verification
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_authorized_fraud_authority(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The card whose counter is corrected
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+}
+
+// Calendar timing constants (UTC), matching discard-state's plaintext cards
+const SECONDS_PER_DAY: i64 = 86_400;
+/// 1970-01-01 was a Thursday (weekday 4, counting from Sunday = 0)
+const EPOCH_WEEKDAY: i64 = 4;
+
+/// Calculate if daily reset is needed (a UTC day boundary has passed)
+pub fn should_reset_daily(last_reset_unix: i64, now: i64) -> bool {
+    day_index(now) > day_index(last_reset_unix)
+}
+
+/// Calculate if weekly reset is needed (a UTC week boundary has passed)
+///
+/// Weeks start on `week_start_day` (0 = Sunday .. 6 = Saturday).
+pub fn should_reset_weekly(last_reset_unix: i64, now: i64, week_start_day: u8) -> bool {
+    week_index(now, week_start_day) > week_index(last_reset_unix, week_start_day)
+}
+
+/// Calculate if monthly reset is needed (a UTC calendar month boundary has passed)
+pub fn should_reset_monthly(last_reset_unix: i64, now: i64) -> bool {
+    month_index(now) > month_index(last_reset_unix)
+}
+
+/// Days since the Unix epoch
+fn day_index(unix_timestamp: i64) -> i64 {
+    unix_timestamp.div_euclid(SECONDS_PER_DAY)
+}
+
+/// Weeks since the Unix epoch, aligned to `week_start_day`
+fn week_index(unix_timestamp: i64, week_start_day: u8) -> i64 {
+    (day_index(unix_timestamp) + EPOCH_WEEKDAY - week_start_day as i64).div_euclid(7)
+}
+
+/// Calendar months since the Unix epoch (year * 12 + zero-based month)
+fn month_index(unix_timestamp: i64) -> i64 {
+    let (year, month) = civil_year_month(day_index(unix_timestamp));
+    year * 12 + (month - 1)
+}
+
+/// Convert days since the Unix epoch to a (year, month) civil date
+/// (Howard Hinnant's `civil_from_days` algorithm)
+fn civil_year_month(days: i64) -> (i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CardStatus;
+    use crate::test_utils::{
+        absent, assert_rejected, card_config_account, did_controller_account, emitted,
+        global_config_account, read, run, signer, token_2022_program, zeroed_global_config,
+        ProgramResult, NOW, SLOT,
+    };
+
+    // 2024-01-01 00:00:00 UTC, a Monday
+    const JAN_1_2024: i64 = 1_704_067_200;
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    #[test]
+    fn daily_reset_follows_utc_midnight() {
+        assert!(should_reset_daily(JAN_1_2024 - 1, JAN_1_2024));
+        assert!(!should_reset_daily(JAN_1_2024, JAN_1_2024 + DAY - 1));
+        assert!(!should_reset_daily(JAN_1_2024 + 10, JAN_1_2024));
+    }
+
+    #[test]
+    fn weekly_reset_follows_week_start_day() {
+        let saturday = JAN_1_2024 - 2 * DAY;
+        let sunday = JAN_1_2024 - DAY;
+
+        // Sunday-start weeks roll over on Sunday, Monday-start weeks on Monday
+        assert!(should_reset_weekly(saturday, sunday, 0));
+        assert!(!should_reset_weekly(sunday, JAN_1_2024, 0));
+        assert!(!should_reset_weekly(saturday, sunday, 1));
+        assert!(should_reset_weekly(sunday, JAN_1_2024, 1));
+    }
+
+    #[test]
+    fn weekly_reset_fires_on_the_aligned_monday() {
+        let mut card = CardConfig::default();
+        card.velocity_counters.weekly_total = 100;
+        card.velocity_counters.last_daily_reset_unix = JAN_1_2024 - 3 * DAY;
+        card.velocity_counters.last_weekly_reset_unix = JAN_1_2024 - 3 * DAY;
+        card.velocity_counters.last_monthly_reset_unix = JAN_1_2024 - 3 * DAY;
+
+        // Sunday is still in the previous Monday-start week
+        auto_reset_if_needed(&mut card, 10, JAN_1_2024 - DAY, 1).unwrap();
+        assert_eq!(card.velocity_counters.weekly_total, 100);
+
+        auto_reset_if_needed(&mut card, 20, JAN_1_2024, 1).unwrap();
+        assert_eq!(card.velocity_counters.weekly_total, 0);
+        assert_eq!(card.velocity_counters.last_weekly_reset_unix, JAN_1_2024);
+    }
+
+    #[test]
+    fn monthly_reset_follows_calendar_months() {
+        let feb_1 = JAN_1_2024 + 31 * DAY;
+        let mar_1 = feb_1 + 29 * DAY; // 2024 is a leap year
+
+        assert!(should_reset_monthly(JAN_1_2024 - 1, JAN_1_2024));
+        assert!(!should_reset_monthly(feb_1, mar_1 - 1));
+        assert!(should_reset_monthly(mar_1 - 1, mar_1));
+    }
+
+    #[test]
+    fn civil_dates_round_trip_known_days() {
+        assert_eq!(civil_year_month(0), (1970, 1));
+        assert_eq!(civil_year_month(day_index(JAN_1_2024)), (2024, 1));
+        assert_eq!(civil_year_month(day_index(JAN_1_2024) + 59), (2024, 2)); // Feb 29
+        assert_eq!(civil_year_month(day_index(JAN_1_2024) + 60), (2024, 3));
+        assert_eq!(civil_year_month(-1), (1969, 12));
+    }
+
+    #[test]
+    fn daily_reset_fires_once_across_midnight() {
+        let mut card = CardConfig::default();
+        card.velocity_counters.daily_total = 100;
+        card.velocity_counters.last_daily_reset_unix = JAN_1_2024 - 60;
+        card.velocity_counters.last_weekly_reset_unix = JAN_1_2024 - 60;
+        card.velocity_counters.last_monthly_reset_unix = JAN_1_2024 - 60;
+
+        auto_reset_if_needed(&mut card, 10, JAN_1_2024 + 5, 0).unwrap();
+        assert_eq!(card.velocity_counters.daily_total, 0);
+        assert_eq!(card.velocity_counters.last_daily_reset_slot, 10);
+
+        card.velocity_counters.record_transaction(50);
+        auto_reset_if_needed(&mut card, 20, JAN_1_2024 + DAY - 1, 0).unwrap();
+        assert_eq!(card.velocity_counters.daily_total, 50);
+        assert_eq!(card.velocity_counters.last_daily_reset_slot, 10);
+    }
+
+    #[test]
+    fn each_preset_stores_its_limits() {
+        let owner = Pubkey::new_unique();
+        let presets = [
+            (LimitPreset::Conservative, conservative_limits()),
+            (LimitPreset::Standard, standard_limits()),
+            (LimitPreset::Premium, premium_limits()),
+            (LimitPreset::Institutional, institutional_limits()),
+        ];
+
+        for (preset, expected) in presets {
+            let card = card_config_account(CardConfig {
+                card_id: [7; 32],
+                owner_did_hash: [1; 32],
+                status: CardStatus::Active,
+                ..Default::default()
+            });
+            let accounts = vec![
+                signer(owner),
+                card.clone(),
+                did_controller_account([1; 32], owner),
+                global_config_account(zeroed_global_config()),
+            ];
+            run(accounts, crate::instruction::SetVelocityPreset { preset }).unwrap();
+
+            let stored = read::<CardConfig>(&card).velocity_limits;
+            assert_eq!(stored.try_to_vec().unwrap(), expected.try_to_vec().unwrap(), "{preset:?}");
+        }
+    }
+
+    fn confidential_card() -> CardConfig {
+        CardConfig {
+            card_id: [7; 32],
+            status: crate::state::CardStatus::Active,
+            confidential_mode: true,
+            encrypted_daily_total: Some([9; 64]),
+            encrypted_weekly_total: Some([9; 64]),
+            ..Default::default()
+        }
+    }
+
+    fn reset_daily_as(
+        authority: Pubkey,
+        reset_authority: Pubkey,
+        card: AccountInfo<'static>,
+    ) -> ProgramResult {
+        let config = GlobalConfig { reset_authorities: vec![reset_authority], ..zeroed_global_config() };
+        run(
+            vec![signer(authority), global_config_account(config), card, absent()],
+            crate::instruction::ResetDailyVelocity {},
+        )
+    }
+
+    #[test]
+    fn daily_reset_clears_the_encrypted_daily_total() {
+        let reset_authority = Pubkey::new_unique();
+        let card = card_config_account(confidential_card());
+
+        reset_daily_as(reset_authority, reset_authority, card.clone()).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        assert_eq!(card_config.encrypted_daily_total, None);
+        assert_eq!(card_config.encrypted_weekly_total, Some([9; 64]));
+    }
+
+    #[test]
+    fn encrypted_reset_still_needs_a_reset_authority() {
+        let card = card_config_account(confidential_card());
+
+        let result = reset_daily_as(Pubkey::new_unique(), Pubkey::new_unique(), card.clone());
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(read::<CardConfig>(&card).encrypted_daily_total, Some([9; 64]));
+    }
+
+    fn card_with_daily_total(id: u8, last_daily_reset_unix: i64) -> AccountInfo<'static> {
+        let mut card = CardConfig { card_id: [id; 32], ..Default::default() };
+        card.velocity_counters.daily_total = 500;
+        card.velocity_counters.last_daily_reset_unix = last_daily_reset_unix;
+        card_config_account(card)
+    }
+
+    fn reset_batch_of(cards: Vec<AccountInfo<'static>>) -> ProgramResult {
+        let reset_authority = Pubkey::new_unique();
+        let config = GlobalConfig { reset_authorities: vec![reset_authority], ..zeroed_global_config() };
+        let mut accounts = vec![signer(reset_authority), global_config_account(config)];
+        accounts.extend(cards);
+        run(accounts, crate::instruction::ResetVelocityBatch { period: ResetPeriod::Daily })
+    }
+
+    #[test]
+    fn batch_reset_skips_cards_not_yet_due() {
+        let due = card_with_daily_total(1, NOW - DAY);
+        let not_due = card_with_daily_total(2, NOW);
+
+        reset_batch_of(vec![due.clone(), not_due.clone()]).unwrap();
+
+        let due = read::<CardConfig>(&due);
+        assert_eq!(due.velocity_counters.daily_total, 0);
+        assert_eq!(due.velocity_counters.last_daily_reset_unix, NOW);
+        assert_eq!(read::<CardConfig>(&not_due).velocity_counters.daily_total, 500);
+    }
+
+    #[test]
+    fn batch_reset_is_bounded() {
+        let cards = (0..=MAX_RESET_BATCH as u8).map(|id| card_with_daily_total(id, 0)).collect();
+
+        assert_rejected(reset_batch_of(vec![]), HookError::InvalidConfiguration);
+        assert_rejected(reset_batch_of(cards), HookError::InvalidConfiguration);
+    }
+
+    /// Record `amount` on `card` under `nonce`, signed by `recorder`
+    fn record_with(
+        recorder: Pubkey,
+        global_config: &AccountInfo<'static>,
+        card: &AccountInfo<'static>,
+        ix: crate::instruction::RecordTransaction,
+    ) -> ProgramResult {
+        let accounts = vec![
+            signer(recorder),
+            card.clone(),
+            absent(),
+            global_config.clone(),
+            token_2022_program(),
+        ];
+        run(accounts, ix)
+    }
+
+    /// Record a transaction with no merchant, MCC, channel or reference
+    fn record(
+        recorder: Pubkey,
+        global_config: &AccountInfo<'static>,
+        card: &AccountInfo<'static>,
+        nonce: u64,
+        amount: u64,
+    ) -> ProgramResult {
+        let ix = crate::instruction::RecordTransaction {
+            amount,
+            merchant_id: None,
+            mcc_code: None,
+            channel: None,
+            nonce,
+            reference: None,
+        };
+        record_with(recorder, global_config, card, ix)
+    }
+
+    fn settlement_config(settlement: Pubkey) -> AccountInfo<'static> {
+        global_config_account(GlobalConfig {
+            settlement_authorities: vec![settlement],
+            ..zeroed_global_config()
+        })
+    }
+
+    #[test]
+    fn recorded_transactions_move_the_global_totals() {
+        let settlement = Pubkey::new_unique();
+        let global_config = settlement_config(settlement);
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        for (nonce, amount) in [(1, 100), (2, 200), (3, 300)] {
+            record(settlement, &global_config, &card, nonce, amount).unwrap();
+        }
+
+        let config = read::<GlobalConfig>(&global_config);
+        assert_eq!(config.total_transactions, 3);
+        assert_eq!(config.total_volume, 600);
+    }
+
+    #[test]
+    fn replayed_nonce_is_not_counted_twice() {
+        let settlement = Pubkey::new_unique();
+        let global_config = settlement_config(settlement);
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        record(settlement, &global_config, &card, 42, 100).unwrap();
+        let replay = record(settlement, &global_config, &card, 42, 100);
+
+        assert_rejected(replay, HookError::DuplicateTransaction);
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 100);
+        assert_eq!(read::<GlobalConfig>(&global_config).total_transactions, 1);
+    }
+
+    #[test]
+    fn unauthorized_recorder_is_rejected() {
+        let global_config = settlement_config(Pubkey::new_unique());
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        let result = record(Pubkey::new_unique(), &global_config, &card, 1, 100);
+
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 0);
+    }
+
+    #[test]
+    fn only_settlement_services_and_the_admin_record() {
+        let (settlement, admin, reset_authority) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let global_config = global_config_account(GlobalConfig {
+            admin,
+            settlement_authorities: vec![settlement],
+            reset_authorities: vec![reset_authority],
+            ..zeroed_global_config()
+        });
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        record(settlement, &global_config, &card, 1, 100).unwrap();
+        record(admin, &global_config, &card, 2, 100).unwrap();
+        let result = record(reset_authority, &global_config, &card, 3, 100);
+
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 200);
+    }
+
+    #[test]
+    fn oldest_nonce_leaves_the_replay_window() {
+        let mut card = CardConfig::default();
+        for nonce in 0..crate::state::MAX_RECENT_NONCES as u64 {
+            card.record_nonce(nonce).unwrap();
+        }
+
+        card.record_nonce(1_000).unwrap();
+
+        assert!(card.record_nonce(0).is_ok());
+        assert!(card.record_nonce(1_000).is_err());
+    }
+
+    #[test]
+    fn last_transaction_follows_each_recorded_transaction() {
+        let settlement = Pubkey::new_unique();
+        let global_config = settlement_config(settlement);
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+
+        let ix = crate::instruction::RecordTransaction {
+            amount: 250,
+            merchant_id: Some([3; 32]),
+            mcc_code: Some(5411),
+            channel: None,
+            nonce: 1,
+            reference: Some([8; 32]),
+        };
+        record_with(settlement, &global_config, &card, ix).unwrap();
+
+        let last = read::<CardConfig>(&card).last_transaction.unwrap();
+        assert_eq!(last.amount, 250);
+        assert_eq!(last.merchant_id, Some([3; 32]));
+        assert_eq!(last.mcc_code, Some(5411));
+        assert_eq!(last.slot, SLOT);
+        assert_eq!(last.reference, Some([8; 32]));
+
+        record(settlement, &global_config, &card, 2, 75).unwrap();
+
+        let card_config = read::<CardConfig>(&card);
+        let last = card_config.last_transaction.unwrap();
+        assert_eq!((last.amount, last.merchant_id, last.mcc_code), (75, None, None));
+        assert_eq!(last.reference, None);
+        assert_eq!(card_config.last_transaction_at, Some(NOW));
+    }
+
+    #[test]
+    fn without_lazy_reset_only_the_cron_clears_yesterdays_totals() {
+        let settlement = Pubkey::new_unique();
+        let lazy_config = |lazy_reset_enabled| {
+            global_config_account(GlobalConfig {
+                settlement_authorities: vec![settlement],
+                lazy_reset_enabled,
+                ..zeroed_global_config()
+            })
+        };
+
+        let card = card_with_daily_total(7, NOW - DAY);
+        record(settlement, &lazy_config(false), &card, 1, 100).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 600);
+
+        let reset_authority = Pubkey::new_unique();
+        reset_daily_as(reset_authority, reset_authority, card.clone()).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 0);
+
+        let lazy_card = card_with_daily_total(8, NOW - DAY);
+        record(settlement, &lazy_config(true), &lazy_card, 1, 100).unwrap();
+        assert_eq!(read::<CardConfig>(&lazy_card).velocity_counters.daily_total, 100);
+    }
+
+    #[test]
+    fn reference_is_announced_with_the_recorded_transaction() {
+        let settlement = Pubkey::new_unique();
+        let global_config = settlement_config(settlement);
+        let card = card_config_account(CardConfig { card_id: [7; 32], ..Default::default() });
+        let mut invoice = [0; 32];
+        invoice[..13].copy_from_slice(b"INV-2024-0042");
+
+        let ix = crate::instruction::RecordTransaction {
+            amount: 250,
+            merchant_id: Some([3; 32]),
+            mcc_code: Some(5411),
+            channel: None,
+            nonce: 1,
+            reference: Some(invoice),
+        };
+        record_with(settlement, &global_config, &card, ix).unwrap();
+
+        let events = emitted::<TransactionRecorded>();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.card_config, *card.key);
+        assert_eq!(event.reference, Some(invoice));
+        assert_eq!((event.amount, event.mcc_code, event.slot), (250, Some(5411), SLOT));
+
+        record(settlement, &global_config, &card, 2, 75).unwrap();
+        assert_eq!(emitted::<TransactionRecorded>()[0].reference, None);
+    }
+
+    const FRAUD_AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+
+    fn adjust(
+        authority: Pubkey,
+        card: &AccountInfo<'static>,
+        period: ResetPeriod,
+        delta: i64,
+    ) -> ProgramResult {
+        let config =
+            GlobalConfig { fraud_authorities: vec![FRAUD_AUTHORITY], ..zeroed_global_config() };
+        let accounts = vec![signer(authority), global_config_account(config), card.clone()];
+        run(accounts, crate::instruction::AdjustVelocityCounter { period, delta })
+    }
+
+    #[test]
+    fn adjustments_saturate_at_zero_and_at_the_limit() {
+        let mut card = CardConfig { card_id: [7; 32], ..Default::default() };
+        card.velocity_limits =
+            VelocityLimits { daily: 1_000, monthly: 10_000, ..Default::default() };
+        card.velocity_counters.daily_total = 400;
+        card.velocity_counters.monthly_total = 9_000;
+        let card = card_config_account(card);
+
+        adjust(FRAUD_AUTHORITY, &card, ResetPeriod::Daily, -150).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 250);
+        let event = &emitted::<CounterAdjusted>()[0];
+        assert_eq!((event.delta, event.old_total, event.new_total), (-150, 400, 250));
+        assert_eq!((event.adjusted_by, event.adjusted_at), (FRAUD_AUTHORITY, NOW));
+
+        adjust(FRAUD_AUTHORITY, &card, ResetPeriod::Daily, -1_000).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 0);
+
+        adjust(FRAUD_AUTHORITY, &card, ResetPeriod::Monthly, 500).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.monthly_total, 9_500);
+        adjust(FRAUD_AUTHORITY, &card, ResetPeriod::Monthly, 5_000).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.monthly_total, 10_000);
+    }
+
+    #[test]
+    fn counter_above_a_lowered_limit_only_comes_down() {
+        let mut card = CardConfig { card_id: [7; 32], ..Default::default() };
+        card.velocity_limits = VelocityLimits { daily: 1_000, ..Default::default() };
+        card.velocity_counters.daily_total = 1_500;
+        let card = card_config_account(card);
+
+        adjust(FRAUD_AUTHORITY, &card, ResetPeriod::Daily, 100).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 1_500);
+        adjust(FRAUD_AUTHORITY, &card, ResetPeriod::Daily, -200).unwrap();
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 1_300);
+    }
+
+    #[test]
+    fn only_fraud_services_and_the_admin_adjust_counters() {
+        let mut card = CardConfig { card_id: [7; 32], ..Default::default() };
+        card.velocity_counters.daily_total = 400;
+        let card = card_config_account(card);
+
+        let result = adjust(Pubkey::new_unique(), &card, ResetPeriod::Daily, -400);
+
+        assert_rejected(result, HookError::Unauthorized);
+        assert_eq!(read::<CardConfig>(&card).velocity_counters.daily_total, 400);
     }
 }
@@ -12,13 +12,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022::Token2022;
 use anchor_spl::token_interface::{Mint, TokenAccount};
-use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
 
 declare_id!("HooK1111111111111111111111111111111111111111");
 
 pub mod instructions;
 pub mod state;
 pub mod errors;
+pub mod events;
+pub mod migration;
+#[cfg(test)]
+mod test_utils;
 
 use instructions::*;
 use state::*;
@@ -38,22 +42,81 @@ pub mod discard_hooks {
         instructions::transfer_hook::handler(ctx, amount)
     }
 
+    /// Create the mint's extra account meta list for Token-2022 (admin only)
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        instructions::transfer_hook::initialize_extra_account_meta_list(ctx)
+    }
+
+    /// Link a card's token account to its card ID (token account owner only)
+    pub fn link_card_token_account(ctx: Context<LinkCardTokenAccount>) -> Result<()> {
+        instructions::transfer_hook::link_card_token_account(ctx)
+    }
+
+    /// Link a treasury, merchant or refund token account that isn't a card
+    /// (admin only)
+    pub fn link_platform_token_account(ctx: Context<LinkPlatformTokenAccount>) -> Result<()> {
+        instructions::transfer_hook::link_platform_token_account(ctx)
+    }
+
+    /// Dry-run the transfer hook validation and return the decision
+    pub fn simulate_transfer(
+        ctx: Context<SimulateTransfer>,
+        amount: u64,
+        merchant_id: Option<[u8; 32]>,
+        mcc_code: Option<u16>,
+        destination: Option<Pubkey>,
+    ) -> Result<SimulationResult> {
+        instructions::transfer_hook::simulate_transfer(
+            ctx,
+            amount,
+            merchant_id,
+            mcc_code,
+            destination,
+        )
+    }
+
+    /// Check whether the card's merchant/MCC rules allow a merchant (read-only)
+    pub fn check_merchant_allowed(
+        ctx: Context<CheckMerchantAllowed>,
+        merchant_id: [u8; 32],
+        mcc_code: Option<u16>,
+        destination: Option<Pubkey>,
+    ) -> Result<MerchantCheckResult> {
+        instructions::transfer_hook::check_merchant_allowed(ctx, merchant_id, mcc_code, destination)
+    }
+
     /// Fallback instruction for transfer hook interface
+    ///
+    /// Token-2022 invokes the hook with the interface's `Execute`
+    /// discriminator rather than Anchor's, so route it into `transfer_hook`
+    /// and run the full validation against the same accounts.
     pub fn fallback<'info>(
         program_id: &Pubkey,
         accounts: &'info [AccountInfo<'info>],
         data: &[u8],
     ) -> Result<()> {
-        let instruction = ExecuteInstruction::unpack(data)?;
-
-        // Verify the instruction is for this program
-        if instruction.program_id != *program_id {
-            return Err(ProgramError::IncorrectProgramId.into());
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                let amount_bytes = amount.to_le_bytes();
+                __private::__global::transfer_hook(program_id, accounts, &amount_bytes)
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
         }
+    }
 
-        // Execute the transfer hook
-        msg!("Transfer hook fallback executed");
-        Ok(())
+    // ========================================================================
+    // DID Controller Management
+    // ========================================================================
+
+    /// Bind a DID hash to its controlling key (DID service only)
+    pub fn register_did_controller(
+        ctx: Context<RegisterDidController>,
+        did_hash: [u8; 32],
+        controller: Pubkey,
+    ) -> Result<()> {
+        instructions::did::register_did_controller(ctx, did_hash, controller)
     }
 
     // ========================================================================
@@ -65,8 +128,87 @@ pub mod discard_hooks {
         ctx: Context<InitializeCardConfig>,
         card_id: [u8; 32],
         owner_did_hash: [u8; 32],
+        expires_at: Option<i64>,
+        home_country: Option<u16>,
     ) -> Result<()> {
-        instructions::config::initialize_card_config(ctx, card_id, owner_did_hash)
+        instructions::config::initialize_card_config(
+            ctx,
+            card_id,
+            owner_did_hash,
+            expires_at,
+            home_country,
+        )
+    }
+
+    /// Initialize a card configuration copying an existing card's settings
+    pub fn initialize_card_from_template(
+        ctx: Context<InitializeCardFromTemplate>,
+        card_id: [u8; 32],
+        owner_did_hash: [u8; 32],
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::config::initialize_card_from_template(ctx, card_id, owner_did_hash, expires_at)
+    }
+
+    /// Close a card configuration and return its rent (primary owner only)
+    pub fn close_card_config(ctx: Context<CloseCardConfig>) -> Result<()> {
+        instructions::config::close_card_config(ctx)
+    }
+
+    /// Activate a pending card once the owner confirms receipt (owner only)
+    pub fn activate_card(ctx: Context<ActivateCard>) -> Result<()> {
+        instructions::config::activate_card(ctx)
+    }
+
+    /// Migrate a card configuration to the current schema version
+    pub fn migrate_card_config(ctx: Context<MigrateCardConfig>) -> Result<()> {
+        instructions::config::migrate_card_config(ctx)
+    }
+
+    /// Count a transfer hook rejection by category (settlement service only)
+    pub fn record_decline(ctx: Context<RecordDecline>, rejection_code: u8) -> Result<()> {
+        instructions::declines::record_decline(ctx, rejection_code)
+    }
+
+    /// Read a card's decline counts by category (read-only)
+    pub fn get_decline_stats(ctx: Context<GetDeclineStats>) -> Result<DeclineStats> {
+        instructions::declines::get_decline_stats(ctx)
+    }
+
+    /// Summarize a card's status, freeze, and daily headroom (read-only)
+    pub fn get_card_status(ctx: Context<GetCardStatus>) -> Result<CardHealth> {
+        instructions::status::get_card_status(ctx)
+    }
+
+    /// Summarize the lengths of a card's merchant and MCC lists (read-only)
+    pub fn get_card_lists(ctx: Context<GetCardStatus>) -> Result<CardLists> {
+        instructions::status::get_card_lists(ctx)
+    }
+
+    /// Read one page of a card list's entries (read-only)
+    pub fn get_card_list_entries(
+        ctx: Context<GetCardStatus>,
+        list: ListKind,
+        offset: u16,
+    ) -> Result<CardListPage> {
+        instructions::status::get_card_list_entries(ctx, list, offset)
+    }
+
+    /// Reissue a card its owner froze onto a new card ID and mint
+    pub fn reissue_card(
+        ctx: Context<ReissueCard>,
+        new_card_id: [u8; 32],
+        new_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::config::reissue_card(ctx, new_card_id, new_mint)
+    }
+
+    /// Set or clear the card's expiry date
+    pub fn set_card_expiry(
+        ctx: Context<UpdateCardPolicy>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::config::set_card_expiry(ctx, expires_at)
     }
 
     /// Update card policy settings
@@ -77,6 +219,95 @@ pub mod discard_hooks {
         instructions::config::update_card_policy(ctx, new_policy)
     }
 
+    /// Apply a named policy preset (kids, travel, corporate) to a card
+    pub fn set_policy_preset(
+        ctx: Context<UpdateCardPolicy>,
+        preset: PolicyPreset,
+    ) -> Result<()> {
+        instructions::config::set_policy_preset(ctx, preset)
+    }
+
+    /// Allow the card to send to a destination token account
+    pub fn add_destination(ctx: Context<UpdateCardPolicy>, destination: Pubkey) -> Result<()> {
+        instructions::destination::add_destination(ctx, destination)
+    }
+
+    /// Remove a destination token account from the card's allowlist
+    pub fn remove_destination(ctx: Context<UpdateCardPolicy>, destination: Pubkey) -> Result<()> {
+        instructions::destination::remove_destination(ctx, destination)
+    }
+
+    /// Apply policy, limits, and whitelist additions in one instruction
+    pub fn configure_card(
+        ctx: Context<UpdateCardPolicy>,
+        policy: Option<CardPolicy>,
+        limits: Option<VelocityLimits>,
+        merchants_add: Vec<[u8; 32]>,
+        mcc_add: Vec<u16>,
+    ) -> Result<()> {
+        instructions::config::configure_card(ctx, policy, limits, merchants_add, mcc_add)
+    }
+
+    // ========================================================================
+    // Card Ownership Transfer
+    // ========================================================================
+
+    /// Propose a new owner DID for the card
+    pub fn initiate_owner_transfer(
+        ctx: Context<ManageOwnership>,
+        new_owner_did_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::ownership::initiate_owner_transfer(ctx, new_owner_did_hash)
+    }
+
+    /// Cancel a pending owner transfer
+    pub fn cancel_owner_transfer(ctx: Context<ManageOwnership>) -> Result<()> {
+        instructions::ownership::cancel_owner_transfer(ctx)
+    }
+
+    /// Add a joint owner who may manage the card (primary owner only)
+    pub fn add_authorized_owner(ctx: Context<ManageOwnership>, did_hash: [u8; 32]) -> Result<()> {
+        instructions::ownership::add_authorized_owner(ctx, did_hash)
+    }
+
+    /// Remove a joint owner (primary owner only)
+    pub fn remove_authorized_owner(ctx: Context<ManageOwnership>, did_hash: [u8; 32]) -> Result<()> {
+        instructions::ownership::remove_authorized_owner(ctx, did_hash)
+    }
+
+    /// Grant or update a delegate's scoped permissions (primary owner only)
+    pub fn add_delegate(
+        ctx: Context<ManageOwnership>,
+        did_hash: [u8; 32],
+        permissions: u8,
+    ) -> Result<()> {
+        instructions::delegate::add_delegate(ctx, did_hash, permissions)
+    }
+
+    /// Revoke a delegate (primary owner only)
+    pub fn remove_delegate(ctx: Context<ManageOwnership>, did_hash: [u8; 32]) -> Result<()> {
+        instructions::delegate::remove_delegate(ctx, did_hash)
+    }
+
+    /// Accept a pending owner transfer (signed by the new owner)
+    pub fn accept_owner_transfer(ctx: Context<AcceptOwnerTransfer>) -> Result<()> {
+        instructions::ownership::accept_owner_transfer(ctx)
+    }
+
+    // ========================================================================
+    // Card Pause
+    // ========================================================================
+
+    /// Pause the card (owner-initiated, reversible without review)
+    pub fn pause_card(ctx: Context<ManageCardPause>) -> Result<()> {
+        instructions::pause::pause_card(ctx)
+    }
+
+    /// Resume a paused card
+    pub fn resume_card(ctx: Context<ManageCardPause>) -> Result<()> {
+        instructions::pause::resume_card(ctx)
+    }
+
     // ========================================================================
     // Merchant Whitelist Management
     // ========================================================================
@@ -89,6 +320,15 @@ pub mod discard_hooks {
         instructions::merchant::add_to_whitelist(ctx, merchants)
     }
 
+    /// Add merchants to card whitelist until an expiry (temporary allowance)
+    pub fn add_merchants_to_whitelist_until(
+        ctx: Context<UpdateMerchantList>,
+        merchants: Vec<[u8; 32]>,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::merchant::add_to_whitelist_until(ctx, merchants, expires_at)
+    }
+
     /// Remove merchants from card whitelist
     pub fn remove_merchants_from_whitelist(
         ctx: Context<UpdateMerchantList>,
@@ -101,8 +341,9 @@ pub mod discard_hooks {
     pub fn add_merchants_to_blocklist(
         ctx: Context<UpdateMerchantList>,
         merchants: Vec<[u8; 32]>,
+        reason: BlockReason,
     ) -> Result<()> {
-        instructions::merchant::add_to_blocklist(ctx, merchants)
+        instructions::merchant::add_to_blocklist(ctx, merchants, reason)
     }
 
     /// Remove merchants from card blocklist
@@ -113,6 +354,16 @@ pub mod discard_hooks {
         instructions::merchant::remove_from_blocklist(ctx, merchants)
     }
 
+    /// Clear the card's merchant whitelist and disable it
+    pub fn clear_merchant_whitelist(ctx: Context<UpdateMerchantList>) -> Result<()> {
+        instructions::merchant::clear_whitelist(ctx)
+    }
+
+    /// Clear the card's merchant blocklist
+    pub fn clear_merchant_blocklist(ctx: Context<UpdateMerchantList>) -> Result<()> {
+        instructions::merchant::clear_blocklist(ctx)
+    }
+
     // ========================================================================
     // MCC (Merchant Category Code) Management
     // ========================================================================
@@ -149,6 +400,21 @@ pub mod discard_hooks {
         instructions::mcc::remove_from_blocklist(ctx, mcc_codes)
     }
 
+    /// Clear the card's MCC whitelist and disable it
+    pub fn clear_mcc_whitelist(ctx: Context<UpdateMccList>) -> Result<()> {
+        instructions::mcc::clear_whitelist(ctx)
+    }
+
+    /// Store the card's MCC whitelist as a bitmap (no code cap) or a vec
+    pub fn set_mcc_bitmap_mode(ctx: Context<UpdateMccList>, enabled: bool) -> Result<()> {
+        instructions::mcc::set_mcc_bitmap_mode(ctx, enabled)
+    }
+
+    /// Clear the card's MCC blocklist
+    pub fn clear_mcc_blocklist(ctx: Context<UpdateMccList>) -> Result<()> {
+        instructions::mcc::clear_blocklist(ctx)
+    }
+
     // ========================================================================
     // Velocity Limit Management
     // ========================================================================
@@ -161,14 +427,44 @@ pub mod discard_hooks {
         instructions::velocity::update_limits(ctx, limits)
     }
 
-    /// Record a transaction for velocity tracking
+    /// Apply a named velocity limit preset to a card
+    pub fn set_velocity_preset(
+        ctx: Context<UpdateVelocityLimits>,
+        preset: LimitPreset,
+    ) -> Result<()> {
+        instructions::velocity::set_preset(ctx, preset)
+    }
+
+    /// Record a transaction for velocity tracking (settlement services only)
     pub fn record_transaction(
         ctx: Context<RecordTransaction>,
         amount: u64,
         merchant_id: Option<[u8; 32]>,
         mcc_code: Option<u16>,
+        channel: Option<TransactionChannel>,
+        nonce: u64,
+        reference: Option<[u8; 32]>,
     ) -> Result<()> {
-        instructions::velocity::record_transaction(ctx, amount, merchant_id, mcc_code)
+        instructions::velocity::record_transaction(
+            ctx,
+            amount,
+            merchant_id,
+            mcc_code,
+            channel,
+            nonce,
+            reference,
+        )
+    }
+
+    /// Record a hook-approved transfer once it settles (settlement services only)
+    pub fn record_settlement(
+        ctx: Context<RecordSettlement>,
+        amount: u64,
+        mcc_code: Option<u16>,
+        nonce: u64,
+        reference: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::velocity::record_settlement(ctx, amount, mcc_code, nonce, reference)
     }
 
     /// Reset daily velocity counters (called by cron/scheduler)
@@ -186,6 +482,114 @@ pub mod discard_hooks {
         instructions::velocity::reset_monthly(ctx)
     }
 
+    /// Grant a temporary daily limit boost that auto-reverts at expiry
+    pub fn grant_limit_boost(
+        ctx: Context<UpdateVelocityLimits>,
+        amount: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::velocity::grant_limit_boost(ctx, amount, expires_at)
+    }
+
+    /// Set the card's online and in-store daily caps, within the daily limit
+    pub fn set_channel_limits(
+        ctx: Context<UpdateVelocityLimits>,
+        online_daily: Option<u64>,
+        instore_daily: Option<u64>,
+    ) -> Result<()> {
+        instructions::velocity::set_channel_limits(ctx, online_daily, instore_daily)
+    }
+
+    /// Reset one velocity period across a batch of cards (remaining accounts)
+    pub fn reset_velocity_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResetVelocityBatch<'info>>,
+        period: ResetPeriod,
+    ) -> Result<()> {
+        instructions::velocity::reset_batch(ctx, period)
+    }
+
+    /// Correct a card's velocity counter by a signed amount (fraud service or admin)
+    pub fn adjust_velocity_counter(
+        ctx: Context<AdjustVelocityCounter>,
+        period: ResetPeriod,
+        delta: i64,
+    ) -> Result<()> {
+        instructions::velocity::adjust_velocity_counter(ctx, period, delta)
+    }
+
+    // ========================================================================
+    // Card Balance (requires the "plaintext-state" feature)
+    // ========================================================================
+
+    /// Create the card's discard-state balance account (owner or settlement authority)
+    pub fn create_balance_account(ctx: Context<CreateBalanceAccount>) -> Result<()> {
+        instructions::balance::create_balance_account(ctx)
+    }
+
+    /// Credit tokens held by the card's token account to its balance (owner or settlement authority)
+    pub fn fund_balance_account(ctx: Context<FundBalanceAccount>, amount: u64) -> Result<()> {
+        instructions::balance::fund_balance_account(ctx, amount)
+    }
+
+    /// Set the part of the card's balance transfers can't spend (owner or settlement authority)
+    pub fn update_balance_reserve(
+        ctx: Context<UpdateBalanceReserve>,
+        reserved_balance: u64,
+    ) -> Result<()> {
+        instructions::balance::update_balance_reserve(ctx, reserved_balance)
+    }
+
+    // ========================================================================
+    // Step-Up Authentication
+    // ========================================================================
+
+    /// Record a completed 2FA/biometric step-up for a card (auth service only)
+    pub fn create_step_up_attestation(
+        ctx: Context<CreateStepUpAttestation>,
+        validity_secs: Option<i64>,
+        pin_verified: bool,
+        channel: Option<TransactionChannel>,
+    ) -> Result<()> {
+        instructions::step_up::create_step_up_attestation(ctx, validity_secs, pin_verified, channel)
+    }
+
+    // ========================================================================
+    // Fraud Scoring
+    // ========================================================================
+
+    /// Record a real-time fraud score for a card (fraud service only)
+    pub fn submit_fraud_score(ctx: Context<SubmitFraudScore>, score: u8) -> Result<()> {
+        instructions::fraud::submit_fraud_score(ctx, score)
+    }
+
+    // ========================================================================
+    // Manual Approval
+    // ========================================================================
+
+    /// Pre-approve one large transfer for a card (owner only)
+    pub fn create_approval_token(
+        ctx: Context<CreateApprovalToken>,
+        amount: u64,
+        merchant_id: Option<[u8; 32]>,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::approval::create_approval_token(ctx, amount, merchant_id, expires_at)
+    }
+
+    // ========================================================================
+    // Monthly Statements
+    // ========================================================================
+
+    /// Open the monthly statement for the card's current period
+    pub fn open_statement(ctx: Context<OpenStatement>) -> Result<()> {
+        instructions::statement::open_statement(ctx)
+    }
+
+    /// Finalize a past statement and emit its totals
+    pub fn close_statement(ctx: Context<CloseStatement>) -> Result<()> {
+        instructions::statement::close_statement(ctx)
+    }
+
     // ========================================================================
     // Confidential Transfer Hook (Token-2022 Encrypted Amounts)
     // ========================================================================
@@ -199,6 +603,19 @@ pub mod discard_hooks {
         instructions::confidential_hook::confidential_handler(ctx, proof_data)
     }
 
+    /// Switch the card to ZK velocity enforcement (owner only)
+    pub fn enable_confidential_mode(
+        ctx: Context<ManageConfidentialMode>,
+        initial_encrypted_totals: Option<EncryptedTotals>,
+    ) -> Result<()> {
+        instructions::confidential_hook::enable_confidential_mode(ctx, initial_encrypted_totals)
+    }
+
+    /// Switch the card back to plaintext velocity enforcement (owner only)
+    pub fn disable_confidential_mode(ctx: Context<ManageConfidentialMode>) -> Result<()> {
+        instructions::confidential_hook::disable_confidential_mode(ctx)
+    }
+
     // ========================================================================
     // Inco Lightning Spending Checks (TEE-based)
     // ========================================================================
@@ -241,12 +658,13 @@ pub mod discard_hooks {
     // Emergency Controls
     // ========================================================================
 
-    /// Emergency freeze a card (fraud detected)
+    /// Emergency freeze a card (fraud detected), optionally scoped to a channel or MCC
     pub fn emergency_freeze(
         ctx: Context<EmergencyControl>,
         reason: FreezeReason,
+        scope: Option<FreezeScope>,
     ) -> Result<()> {
-        instructions::emergency::freeze(ctx, reason)
+        instructions::emergency::freeze(ctx, reason, scope)
     }
 
     /// Unfreeze a card after review
@@ -254,6 +672,16 @@ pub mod discard_hooks {
         instructions::emergency::unfreeze(ctx)
     }
 
+    /// Hold a disputed amount against the card's velocity headroom
+    pub fn place_transaction_hold(ctx: Context<EmergencyControl>, amount: u64) -> Result<()> {
+        instructions::hold::place_transaction_hold(ctx, amount)
+    }
+
+    /// Release a transaction hold (whoever placed it, or the fraud service)
+    pub fn release_transaction_hold(ctx: Context<EmergencyControl>, hold_id: u32) -> Result<()> {
+        instructions::hold::release_transaction_hold(ctx, hold_id)
+    }
+
     /// Global emergency pause (admin only)
     pub fn global_pause(ctx: Context<GlobalControl>) -> Result<()> {
         instructions::emergency::global_pause(ctx)
@@ -263,12 +691,124 @@ pub mod discard_hooks {
     pub fn global_resume(ctx: Context<GlobalControl>) -> Result<()> {
         instructions::emergency::global_resume(ctx)
     }
+
+    /// Set the multisig allowed to engage the kill switch (admin only)
+    pub fn set_kill_switch_authority(
+        ctx: Context<GlobalControl>,
+        authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::emergency::set_kill_switch_authority(ctx, authority)
+    }
+
+    /// Engage or release the protocol-wide kill switch (kill switch authority only)
+    pub fn set_kill_switch(ctx: Context<KillSwitchControl>, engaged: bool) -> Result<()> {
+        instructions::emergency::set_kill_switch(ctx, engaged)
+    }
+
+    // ========================================================================
+    // Admin Management
+    // ========================================================================
+
+    /// Propose a new program admin (takes effect once accepted)
+    pub fn transfer_admin(ctx: Context<GlobalControl>, new_admin: Pubkey) -> Result<()> {
+        instructions::admin::transfer_admin(ctx, new_admin)
+    }
+
+    /// Accept a pending admin transfer
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::admin::accept_admin(ctx)
+    }
+
+    /// Set the minimum slots between velocity limit increases (admin only)
+    pub fn set_limit_increase_cooldown(ctx: Context<GlobalControl>, slots: u64) -> Result<()> {
+        instructions::admin::set_limit_increase_cooldown(ctx, slots)
+    }
+
+    /// Set the weekday weekly velocity periods start on (admin only)
+    pub fn set_week_start_day(ctx: Context<GlobalControl>, day: u8) -> Result<()> {
+        instructions::admin::set_week_start_day(ctx, day)
+    }
+
+    /// Toggle whether card activity lazily resets velocity periods (admin only)
+    pub fn set_lazy_reset_enabled(ctx: Context<GlobalControl>, enabled: bool) -> Result<()> {
+        instructions::admin::set_lazy_reset_enabled(ctx, enabled)
+    }
+
+    /// Toggle per-transfer detail logging in the transfer hooks (admin only)
+    pub fn set_verbose_logging(ctx: Context<GlobalControl>, enabled: bool) -> Result<()> {
+        instructions::admin::set_verbose_logging(ctx, enabled)
+    }
+
+    /// Set the maximum number of live cards per owner (admin only)
+    pub fn set_max_cards_per_owner(ctx: Context<GlobalControl>, max_cards: u32) -> Result<()> {
+        instructions::admin::set_max_cards_per_owner(ctx, max_cards)
+    }
+
+    /// Set the home country assigned to new cards by default (admin only)
+    pub fn set_default_home_country(ctx: Context<GlobalControl>, country: u16) -> Result<()> {
+        instructions::admin::set_default_home_country(ctx, country)
+    }
+
+    /// Set the contactless limit assigned to new cards by default (admin only)
+    pub fn set_default_contactless_limit(ctx: Context<GlobalControl>, limit: u64) -> Result<()> {
+        instructions::admin::set_default_contactless_limit(ctx, limit)
+    }
+
+    /// Set the ceiling on card velocity limits (admin only)
+    pub fn set_max_velocity_limits(ctx: Context<GlobalControl>, limits: VelocityLimits) -> Result<()> {
+        instructions::admin::set_max_velocity_limits(ctx, limits)
+    }
+
+    /// Exempt a platform token account from card rules (admin only)
+    pub fn add_system_transfer_account(ctx: Context<GlobalControl>, account: Pubkey) -> Result<()> {
+        instructions::admin::add_system_transfer_account(ctx, account)
+    }
+
+    /// Remove a platform token account's exemption (admin only)
+    pub fn remove_system_transfer_account(ctx: Context<GlobalControl>, account: Pubkey) -> Result<()> {
+        instructions::admin::remove_system_transfer_account(ctx, account)
+    }
+
+    /// Add a service key to the reset, fraud, auth or settlement authorities (admin only)
+    pub fn add_authority(
+        ctx: Context<GlobalControl>,
+        role: AuthorityRole,
+        authority: Pubkey,
+    ) -> Result<()> {
+        instructions::admin::add_authority(ctx, role, authority)
+    }
+
+    /// Remove a service key from one of the authority lists (admin only)
+    pub fn remove_authority(
+        ctx: Context<GlobalControl>,
+        role: AuthorityRole,
+        authority: Pubkey,
+    ) -> Result<()> {
+        instructions::admin::remove_authority(ctx, role, authority)
+    }
+
+    /// Grow the global config to the current schema size (admin only)
+    pub fn migrate_global_config(ctx: Context<MigrateGlobalConfig>) -> Result<()> {
+        instructions::admin::migrate_global_config(ctx)
+    }
+
+    /// Snapshot the global transaction totals and start a new period (admin only)
+    pub fn snapshot_and_reset_stats(ctx: Context<SnapshotStats>) -> Result<()> {
+        instructions::stats::snapshot_and_reset_stats(ctx)
+    }
 }
 
 // ============================================================================
 // Account Contexts
 // ============================================================================
 
+/// Accounts of the Token-2022 `Execute` instruction
+///
+/// Token-2022 passes the four transfer accounts and the extra account meta
+/// list, then the extras in the order `transfer_hook::extra_account_metas`
+/// lists them, so the field order here is part of the interface. Every extra
+/// is always passed: optional PDAs that were never created arrive empty and
+/// are loaded with `load_optional_account`.
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
     /// The token account being transferred from
@@ -283,22 +823,87 @@ pub struct TransferHook<'info> {
     pub destination_account: InterfaceAccount<'info, TokenAccount>,
 
     /// The owner/authority of the source account
-    pub owner: Signer<'info>,
+    /// CHECK: Token-2022 already authorized the transfer and passes this
+    /// account to the hook as a non-signer
+    pub owner: UncheckedAccount<'info>,
 
-    /// The card configuration PDA
+    /// The mint's extra account meta list
+    /// CHECK: Seeds are checked; Token-2022 resolved the extras from it
     #[account(
-        seeds = [b"card_config", source_account.key().as_ref()],
-        bump = card_config.bump,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump,
     )]
-    pub card_config: Account<'info, CardConfig>,
-
-    /// Extra account for merchant metadata (if applicable)
-    /// CHECK: Validated in instruction
     pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// Links `source_account` to its card ID (a platform link for treasury,
+    /// merchant and refund accounts)
+    /// CHECK: Loaded with `load_optional_account`; the address is checked by
+    /// the handler
+    pub card_link: UncheckedAccount<'info>,
+
+    /// The card configuration PDA, keyed by card ID like at initialization
+    /// and bound to `source_account` (writable: the hook records the
+    /// transfer it approves; empty when the source isn't a card)
+    /// CHECK: Loaded with `load_optional_account`; the address, card ID,
+    /// mint and token account are checked by the handler
+    #[account(mut)]
+    pub card_config: UncheckedAccount<'info>,
+
+    /// Step-up attestation PDA for the card (empty unless the user completed
+    /// 2FA/biometrics)
+    /// CHECK: Loaded with `load_optional_account`
+    pub step_up_attestation: UncheckedAccount<'info>,
+
+    /// Registry record for the merchant. Token-2022 can't derive it from the
+    /// transfer, so Execute passes this program's ID (none); callers that know
+    /// the merchant use `simulate_transfer` or settlement-side checks
+    pub merchant_record: Option<Account<'info, merchant_registry::state::MerchantRecord>>,
+
+    /// Fraud score PDA for the card, submitted by the fraud service
+    /// CHECK: Loaded with `load_optional_account`
+    pub fraud_score: UncheckedAccount<'info>,
+
+    /// Owner pre-approval PDA for a large transfer (consumed on use)
+    /// CHECK: Loaded with `load_optional_account`
+    #[account(mut)]
+    pub approval_token: UncheckedAccount<'info>,
+
+    /// discard-state program, for debiting `balance_account`
+    #[cfg(feature = "plaintext-state")]
+    pub discard_state_program: Option<Program<'info, discard_state::program::DiscardState>>,
+
+    /// discard-state plaintext card state holding the card's balance
+    /// (debited by the amount of every approved transfer; empty when the
+    /// card has none)
+    /// CHECK: Loaded with `load_optional_account`; card and authority are
+    /// checked by the handler
+    #[cfg(feature = "plaintext-state")]
+    #[account(mut)]
+    pub balance_account: UncheckedAccount<'info>,
+
+    /// Instructions sysvar, used to tell plain transfers from transfers-with-fee
+    /// on mints with the transfer fee extension
+    /// CHECK: Address-checked against the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// Global config listing platform accounts exempt from card rules
+    /// (read-only, so concurrent transfers don't contend on it)
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The card's open monthly statement (empty when none is open)
+    /// CHECK: Loaded with `load_optional_account`; only written when it is
+    /// the card's statement for its current period
+    #[account(mut)]
+    pub statement: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(card_id: [u8; 32])]
+#[instruction(card_id: [u8; 32], owner_did_hash: [u8; 32])]
 pub struct InitializeCardConfig<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -306,98 +911,173 @@ pub struct InitializeCardConfig<'info> {
     /// The owner's DID commitment (must prove ownership)
     pub owner: Signer<'info>,
 
-    /// The card configuration PDA
+    /// DID controller record proving the owner controls `owner_did_hash`
     #[account(
-        init,
+        constraint = owner_did.proves(owner.key(), &owner_did_hash)
+            @ HookError::InvalidDidCommitment,
+    )]
+    pub owner_did: Account<'info, DidController>,
+
+    /// The token mint the card will spend
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The card's token account, which the transfer hook binds the card to
+    #[account(token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The card configuration PDA (an existing one makes the call idempotent)
+    #[account(
+        init_if_needed,
         payer = payer,
         space = CardConfig::SIZE,
-        seeds = [b"card_config", card_id.as_ref()],
+        seeds = [CardConfig::SEED, card_id.as_ref()],
         bump,
     )]
     pub card_config: Account<'info, CardConfig>,
 
+    /// Per-owner card count (created with the owner's first card)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = OwnerIndex::SIZE,
+        seeds = [b"owner_index", owner_did_hash.as_ref()],
+        bump,
+    )]
+    pub owner_index: Account<'info, OwnerIndex>,
+
+    /// Global config for program-wide statistics
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateCardPolicy<'info> {
-    /// Must be the card owner or authorized delegate
+    /// Must be a card owner or a delegate with the required permission
     pub authority: Signer<'info>,
 
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
     #[account(
-        mut,
-        constraint = card_config.owner_did_hash == authority_did_hash(authority.key())
-            @ HookError::Unauthorized,
+        constraint = owner_did.proves_permission_on(authority.key(), &card_config, PERMISSION_UPDATE_POLICY)
+            @ HookError::InvalidDidCommitment,
     )]
-    pub card_config: Account<'info, CardConfig>,
+    pub owner_did: Account<'info, DidController>,
+
+    /// Global config for the limit-increase cooldown
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateMerchantList<'info> {
-    /// Must be the card owner or authorized delegate
+    /// Must be a card owner or a delegate with the required permission
     pub authority: Signer<'info>,
 
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
     #[account(
-        mut,
-        constraint = card_config.owner_did_hash == authority_did_hash(authority.key())
-            @ HookError::Unauthorized,
+        constraint = owner_did.proves_permission_on(authority.key(), &card_config, PERMISSION_MANAGE_MERCHANTS)
+            @ HookError::InvalidDidCommitment,
     )]
-    pub card_config: Account<'info, CardConfig>,
+    pub owner_did: Account<'info, DidController>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateMccList<'info> {
-    /// Must be the card owner or authorized delegate
+    /// Must be a card owner or a delegate with the required permission
     pub authority: Signer<'info>,
 
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
     #[account(
-        mut,
-        constraint = card_config.owner_did_hash == authority_did_hash(authority.key())
-            @ HookError::Unauthorized,
+        constraint = owner_did.proves_permission_on(authority.key(), &card_config, PERMISSION_MANAGE_MERCHANTS)
+            @ HookError::InvalidDidCommitment,
     )]
-    pub card_config: Account<'info, CardConfig>,
+    pub owner_did: Account<'info, DidController>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateVelocityLimits<'info> {
-    /// Must be the card owner or authorized delegate
+    /// Must be a card owner or a delegate with the required permission
     pub authority: Signer<'info>,
 
+    #[account(mut)]
+    pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record proving the signer controls the owner DID
     #[account(
-        mut,
-        constraint = card_config.owner_did_hash == authority_did_hash(authority.key())
-            @ HookError::Unauthorized,
+        constraint = owner_did.proves_permission_on(authority.key(), &card_config, PERMISSION_UPDATE_POLICY)
+            @ HookError::InvalidDidCommitment,
     )]
-    pub card_config: Account<'info, CardConfig>,
+    pub owner_did: Account<'info, DidController>,
+
+    /// Global config for the limit-increase cooldown
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 }
 
 #[derive(Accounts)]
 pub struct RecordTransaction<'info> {
+    /// Must be a settlement service
+    pub recorder: Signer<'info>,
+
     /// The card configuration to update
     #[account(mut)]
     pub card_config: Account<'info, CardConfig>,
 
+    /// The open monthly statement for the card (optional)
+    #[account(mut)]
+    pub statement: Option<Account<'info, MonthlyStatement>>,
+
+    /// Global config for recorder verification and program-wide statistics
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     /// The token program (for CPI verification)
     pub token_program: Program<'info, Token2022>,
 }
 
 #[derive(Accounts)]
 pub struct ResetVelocity<'info> {
-    /// Must be authorized (cron service or admin)
+    /// Must be a reset authority (cron service or admin) or a delegate
+    /// granted the reset permission; card owners can't reset
     pub authority: Signer<'info>,
 
     /// Global config for authority verification
     #[account(
         seeds = [b"global_config"],
         bump = global_config.bump,
-        constraint = global_config.is_authorized_reset_authority(authority.key())
-            @ HookError::Unauthorized,
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
     /// The card configuration to reset
     #[account(mut)]
     pub card_config: Account<'info, CardConfig>,
+
+    /// DID controller record (required when the signer is a delegate)
+    pub owner_did: Option<Account<'info, DidController>>,
 }
 
 #[derive(Accounts)]
@@ -415,6 +1095,8 @@ pub struct EmergencyControl<'info> {
     /// The card configuration to freeze/unfreeze
     #[account(mut)]
     pub card_config: Account<'info, CardConfig>,
+    /// DID controller record (required when the signer is the card owner)
+    pub owner_did: Option<Account<'info, DidController>>,
 }
 
 #[derive(Accounts)]
@@ -432,12 +1114,151 @@ pub struct GlobalControl<'info> {
     pub global_config: Account<'info, GlobalConfig>,
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
+#[derive(Accounts)]
+pub struct KillSwitchControl<'info> {
+    /// Must be the configured kill switch authority
+    pub authority: Signer<'info>,
+
+    /// Global config holding the kill switch
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.kill_switch_authority == Some(authority.key())
+            @ HookError::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        account, anchor_data, empty_account, global_config_account, install_syscall_stubs,
+        mint_data, zeroed_global_config, RETURN_DATA,
+    };
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::{
+            transfer_hook::TransferHookAccount, BaseStateWithExtensionsMut, ExtensionType,
+            StateWithExtensionsMut,
+        },
+        state::{Account as TokenAccount2022, AccountState},
+    };
+
+    const CARD_ID: [u8; 32] = [7; 32];
+
+    /// A Token-2022 account with the hook's `transferring` flag
+    fn token_account_data(mint: Pubkey, transferring: bool) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<TokenAccount2022>(&[
+            ExtensionType::TransferHookAccount,
+        ])
+        .unwrap();
+        let mut data = vec![0; len];
+        let mut state = StateWithExtensionsMut::<TokenAccount2022>::unpack_uninitialized(&mut data).unwrap();
+        state.init_extension::<TransferHookAccount>(true).unwrap().transferring = transferring.into();
+        state.base = TokenAccount2022 {
+            mint,
+            owner: Pubkey::new_unique(),
+            amount: 1_000_000_000,
+            state: AccountState::Initialized,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    /// The Execute accounts Token-2022 resolves for a transfer out of a card
+    /// (or, with no card, out of a platform-linked account)
+    fn execute_accounts(card: Option<CardConfig>) -> &'static [AccountInfo<'static>] {
+        let token_2022 = anchor_spl::token_2022::ID;
+        let source = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let (meta_list, _) =
+            Pubkey::find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()], &crate::ID);
+        let (card_link, link_bump) =
+            Pubkey::find_program_address(&[CardTokenLink::SEED, source.as_ref()], &crate::ID);
+        let card_id = card.as_ref().map_or(CardTokenLink::PLATFORM_CARD_ID, |card| card.card_id);
+        let link = CardTokenLink { card_id, statement_period: 0, bump: link_bump };
+        let (card_key, card_bump) =
+            Pubkey::find_program_address(&[CardConfig::SEED, card_id.as_ref()], &crate::ID);
+        let card_config = match card {
+            Some(card) => {
+                let card = CardConfig { bump: card_bump, mint, token_account: source, ..card };
+                account(card_key, crate::ID, true, anchor_data(&card, CardConfig::SIZE))
+            }
+            None => empty_account(card_key, true),
+        };
+
+        let mut accounts = vec![
+            account(source, token_2022, true, token_account_data(mint, true)),
+            account(mint, token_2022, false, mint_data()),
+            account(Pubkey::new_unique(), token_2022, true, token_account_data(mint, false)),
+            empty_account(Pubkey::new_unique(), false),
+            empty_account(meta_list, false),
+            account(card_link, crate::ID, false, anchor_data(&link, CardTokenLink::SIZE)),
+            card_config,
+            empty_account(Pubkey::new_unique(), false),
+            empty_account(crate::ID, false),
+            empty_account(Pubkey::new_unique(), false),
+            empty_account(Pubkey::new_unique(), true),
+        ];
+        #[cfg(feature = "plaintext-state")]
+        accounts.extend([empty_account(crate::ID, false), empty_account(Pubkey::new_unique(), true)]);
+        accounts.extend([
+            empty_account(crate::ID, false),
+            global_config_account(zeroed_global_config()),
+            empty_account(Pubkey::new_unique(), true),
+        ]);
+        Box::leak(accounts.into_boxed_slice())
+    }
+
+    fn execute(accounts: &'static [AccountInfo<'static>], amount: u64) -> Result<()> {
+        install_syscall_stubs();
+        let data = TransferHookInstruction::Execute { amount }.pack();
+        discard_hooks::fallback(&crate::ID, accounts, &data)
+    }
+
+    fn card(status: CardStatus) -> CardConfig {
+        CardConfig { card_id: CARD_ID, status, ..Default::default() }
+    }
+
+    #[test]
+    fn fallback_rejects_a_blocked_transfer() {
+        let accounts = execute_accounts(Some(card(CardStatus::Frozen)));
+
+        let err = execute(accounts, 100).unwrap_err();
+
+        assert_eq!(errors::rejection_code(&err), HookError::CardFrozen.rejection_code());
+    }
 
-/// Derive authority DID hash from pubkey (simplified for example)
-fn authority_did_hash(authority: Pubkey) -> [u8; 32] {
-    // In production, this would verify against actual DID commitment
-    authority.to_bytes()
+    #[test]
+    fn fallback_returns_the_rejection_code() {
+        let mut card = card(CardStatus::Active);
+        card.velocity_limits.per_transaction = 50;
+        let accounts = execute_accounts(Some(card));
+
+        execute(accounts, 100).unwrap_err();
+
+        let return_data = RETURN_DATA.with(|return_data| return_data.borrow().clone());
+        assert_eq!(return_data, vec![HookError::TransactionLimitExceeded.rejection_code()]);
+    }
+
+    #[test]
+    fn fallback_records_an_allowed_transfer() {
+        let accounts = execute_accounts(Some(card(CardStatus::Active)));
+
+        execute(accounts, 100).unwrap();
+
+        let card = CardConfig::try_deserialize(&mut &accounts[6].try_borrow_data().unwrap()[..]).unwrap();
+        assert_eq!(card.velocity_counters.daily_total, 100);
+    }
+
+    #[test]
+    fn fallback_allows_transfers_out_of_platform_accounts() {
+        let accounts = execute_accounts(None);
+
+        execute(accounts, 100).unwrap();
+    }
 }
@@ -0,0 +1,717 @@
+//! DisCard 2035 - Legacy Account Layouts
+//!
+//! Layouts written by earlier deployments, read by the migrate instructions.
+//! Borsh has no field tags, so an account is parsed with the exact layout it
+//! was written with and then rebuilt in the current one.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use crate::state::{
+    BlockReason, BlockedMerchant, CardConfig, CardPolicy, CardStatus, Delegate, FreezeInfo,
+    FreezeReason, FreezeRecord, FreezeScope, GlobalConfig, Hold, LastTxn, MerchantCount,
+    VelocityCounters, VelocityLimits, WhitelistedMerchant,
+};
+
+/// Grow a program account to `new_size`, topping up rent from `payer`
+///
+/// New bytes are zeroed. Accounts already at least `new_size` are left alone.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_size: usize,
+) -> Result<()> {
+    if account.data_len() >= new_size {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(new_size);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer { from: payer.clone(), to: account.clone() },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.realloc(new_size, true)?;
+    Ok(())
+}
+
+// ============================================================================
+// Card Configuration: First Deployment
+// ============================================================================
+
+/// `CardConfig` as first deployed, before it had a `version`
+///
+/// The merchant lists held bare merchant IDs, and the card had no mint,
+/// delegates, holds, or any field added since.
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct CardConfigV0 {
+    pub bump: u8,
+    pub card_id: [u8; 32],
+    pub owner_did_hash: [u8; 32],
+    pub status: CardStatus,
+    pub policy: CardPolicyV0,
+    pub velocity_limits: VelocityLimitsV0,
+    pub velocity_counters: VelocityCountersV0,
+    pub merchant_whitelist_enabled: bool,
+    pub merchant_whitelist: Vec<[u8; 32]>,
+    pub merchant_blocklist: Vec<[u8; 32]>,
+    pub mcc_whitelist_enabled: bool,
+    pub mcc_whitelist: Vec<u16>,
+    pub mcc_blocklist: Vec<u16>,
+    pub freeze_info: Option<FreezeInfoV0>,
+    pub confidential_mode: bool,
+    pub encrypted_daily_total: Option<[u8; 64]>,
+    pub encrypted_weekly_total: Option<[u8; 64]>,
+    pub encrypted_monthly_total: Option<[u8; 64]>,
+    pub encrypted_balance_handle: Option<[u8; 16]>,
+    pub inco_public_key: Option<[u8; 32]>,
+    pub inco_epoch: u64,
+    pub inco_enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub last_transaction_at: Option<i64>,
+}
+
+impl CardConfigV0 {
+    /// Space the first deployment allocated for every card account (its list
+    /// bounds were fixed at 50 merchants and 100 MCCs)
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // card_id
+        32 + // owner_did_hash
+        1 + // status
+        CardPolicyV0::SIZE +
+        VelocityLimitsV0::SIZE +
+        VelocityCountersV0::SIZE +
+        1 + // merchant_whitelist_enabled
+        4 + (32 * 50) + // merchant_whitelist vec
+        4 + (32 * 50) + // merchant_blocklist vec
+        1 + // mcc_whitelist_enabled
+        4 + (2 * 100) + // mcc_whitelist vec
+        4 + (2 * 100) + // mcc_blocklist vec
+        1 + FreezeInfoV0::SIZE + // freeze_info option
+        1 + // confidential_mode
+        3 * (1 + 64) + // encrypted totals
+        1 + 16 + // encrypted_balance_handle option
+        1 + 32 + // inco_public_key option
+        8 + // inco_epoch
+        1 + // inco_enabled
+        8 + // created_at
+        8 + // updated_at
+        9; // last_transaction_at option
+
+    /// Rebuild the card in the current layout, at version 0
+    ///
+    /// `mint` and `decimals` come from the card's mint, which the first
+    /// deployment didn't record. Blocklist entries get `UserChoice` as their
+    /// reason and the card's last update as their block time. The counters
+    /// count as reset at `now`, so the spend already in them stays until the
+    /// next period boundary.
+    pub fn into_current(self, mint: Pubkey, decimals: u8, now: i64) -> CardConfig {
+        let updated_at = self.updated_at;
+        CardConfig {
+            bump: self.bump,
+            card_id: self.card_id,
+            owner_did_hash: self.owner_did_hash,
+            mint,
+            decimals,
+            status: self.status,
+            policy: self.policy.into(),
+            velocity_limits: self.velocity_limits.into(),
+            velocity_counters: self.velocity_counters.into_current(now),
+            merchant_whitelist_enabled: self.merchant_whitelist_enabled,
+            merchant_whitelist: self.merchant_whitelist.into_iter()
+                .map(|merchant_id| WhitelistedMerchant { merchant_id, expires_at: None })
+                .collect(),
+            merchant_blocklist: self.merchant_blocklist.into_iter()
+                .map(|merchant_id| BlockedMerchant {
+                    merchant_id,
+                    reason: BlockReason::UserChoice,
+                    blocked_at: updated_at,
+                })
+                .collect(),
+            mcc_whitelist_enabled: self.mcc_whitelist_enabled,
+            mcc_whitelist: self.mcc_whitelist,
+            mcc_blocklist: self.mcc_blocklist,
+            freeze_info: self.freeze_info.map(Into::into),
+            confidential_mode: self.confidential_mode,
+            encrypted_daily_total: self.encrypted_daily_total,
+            encrypted_weekly_total: self.encrypted_weekly_total,
+            encrypted_monthly_total: self.encrypted_monthly_total,
+            encrypted_balance_handle: self.encrypted_balance_handle,
+            inco_public_key: self.inco_public_key,
+            inco_epoch: self.inco_epoch,
+            inco_enabled: self.inco_enabled,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            last_transaction_at: self.last_transaction_at,
+            version: 0,
+            ..Default::default()
+        }
+    }
+}
+
+/// `CardPolicy` as first deployed
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct CardPolicyV0 {
+    pub require_biometric: bool,
+    pub require_2fa_above: Option<u64>,
+    pub allow_international: bool,
+    pub allow_online: bool,
+    pub allow_atm: bool,
+    pub allow_contactless: bool,
+    pub contactless_limit: u64,
+    pub allowed_countries: Vec<u16>,
+    pub blocked_countries: Vec<u16>,
+}
+
+impl CardPolicyV0 {
+    pub const SIZE: usize = 1 + 9 + 1 + 1 + 1 + 1 + 8 + (4 + 2 * 50) + (4 + 2 * 50);
+}
+
+impl From<CardPolicyV0> for CardPolicy {
+    /// Settings added since are off, except transfer-with-fee, which the
+    /// first deployment never restricted
+    fn from(policy: CardPolicyV0) -> Self {
+        Self {
+            require_biometric: policy.require_biometric,
+            require_2fa_above: policy.require_2fa_above,
+            allow_international: policy.allow_international,
+            allow_online: policy.allow_online,
+            allow_atm: policy.allow_atm,
+            allow_contactless: policy.allow_contactless,
+            contactless_limit: policy.contactless_limit,
+            allowed_countries: policy.allowed_countries,
+            blocked_countries: policy.blocked_countries,
+            allow_transfer_with_fee: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// `VelocityLimits` before the percentage-of-balance cap
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct VelocityLimitsV0 {
+    pub per_transaction: u64,
+    pub daily: u64,
+    pub weekly: u64,
+    pub monthly: u64,
+    pub max_daily_transactions: u16,
+    pub max_weekly_transactions: u16,
+    pub max_monthly_transactions: u16,
+}
+
+impl VelocityLimitsV0 {
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + 2 + 2 + 2;
+}
+
+impl From<VelocityLimitsV0> for VelocityLimits {
+    fn from(limits: VelocityLimitsV0) -> Self {
+        Self {
+            per_transaction: limits.per_transaction,
+            daily: limits.daily,
+            weekly: limits.weekly,
+            monthly: limits.monthly,
+            max_daily_transactions: limits.max_daily_transactions,
+            max_weekly_transactions: limits.max_weekly_transactions,
+            max_monthly_transactions: limits.max_monthly_transactions,
+            daily_pct_of_balance: None,
+        }
+    }
+}
+
+/// `VelocityCounters` before weekly carryover and wall-clock resets
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct VelocityCountersV0 {
+    pub daily_total: u64,
+    pub weekly_total: u64,
+    pub monthly_total: u64,
+    pub daily_transaction_count: u16,
+    pub weekly_transaction_count: u16,
+    pub monthly_transaction_count: u16,
+    pub last_daily_reset_slot: u64,
+    pub last_weekly_reset_slot: u64,
+    pub last_monthly_reset_slot: u64,
+}
+
+impl VelocityCountersV0 {
+    pub const SIZE: usize = 8 + 8 + 8 + 2 + 2 + 2 + 8 + 8 + 8;
+
+    fn into_current(self, now: i64) -> VelocityCounters {
+        VelocityCounters {
+            daily_total: self.daily_total,
+            weekly_total: self.weekly_total,
+            weekly_carryover: 0,
+            monthly_total: self.monthly_total,
+            daily_transaction_count: self.daily_transaction_count,
+            weekly_transaction_count: self.weekly_transaction_count,
+            monthly_transaction_count: self.monthly_transaction_count,
+            last_daily_reset_slot: self.last_daily_reset_slot,
+            last_weekly_reset_slot: self.last_weekly_reset_slot,
+            last_monthly_reset_slot: self.last_monthly_reset_slot,
+            last_daily_reset_unix: now,
+            last_weekly_reset_unix: now,
+            last_monthly_reset_unix: now,
+        }
+    }
+}
+
+/// `FreezeInfo` before scoped freezes
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize))]
+pub struct FreezeInfoV0 {
+    pub reason: FreezeReason,
+    pub frozen_by: Pubkey,
+    pub frozen_at: i64,
+    pub expires_at: Option<i64>,
+}
+
+impl FreezeInfoV0 {
+    pub const SIZE: usize = 1 + 32 + 8 + 9;
+}
+
+impl From<FreezeInfoV0> for FreezeInfo {
+    fn from(info: FreezeInfoV0) -> Self {
+        Self {
+            reason: info.reason,
+            frozen_by: info.frozen_by,
+            frozen_at: info.frozen_at,
+            expires_at: info.expires_at,
+            scope: FreezeScope::All,
+        }
+    }
+}
+
+// ============================================================================
+// Card Configuration: Version 2
+// ============================================================================
+
+/// `CardConfig` at version 2, the first versioned layout
+///
+/// Matches the current layout up to `version`, except that the policy had no
+/// `allow_transfer_with_fee` and the last transaction no `reference`. Both
+/// arrived in version 3.
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct CardConfigV2 {
+    pub bump: u8,
+    pub card_id: [u8; 32],
+    pub owner_did_hash: [u8; 32],
+    pub additional_owner_did_hashes: Vec<[u8; 32]>,
+    pub delegates: Vec<Delegate>,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub home_country: u16,
+    pub pending_owner: Option<[u8; 32]>,
+    pub expires_at: Option<i64>,
+    pub status: CardStatus,
+    pub policy: CardPolicyV2,
+    pub velocity_limits: VelocityLimits,
+    pub velocity_counters: VelocityCounters,
+    pub last_limit_increase_slot: u64,
+    pub recent_merchant_counts: Vec<MerchantCount>,
+    pub recent_nonces: Vec<u64>,
+    pub holds: Vec<Hold>,
+    pub next_hold_id: u32,
+    pub merchant_whitelist_enabled: bool,
+    pub merchant_whitelist: Vec<WhitelistedMerchant>,
+    pub merchant_blocklist: Vec<BlockedMerchant>,
+    pub mcc_whitelist_enabled: bool,
+    pub mcc_whitelist: Vec<u16>,
+    pub mcc_bitmap_mode: bool,
+    pub mcc_bitmap: Vec<u8>,
+    pub mcc_blocklist: Vec<u16>,
+    pub breach_count: u16,
+    pub freeze_info: Option<FreezeInfo>,
+    pub freeze_history: Vec<FreezeRecord>,
+    pub confidential_mode: bool,
+    pub encrypted_daily_total: Option<[u8; 64]>,
+    pub encrypted_weekly_total: Option<[u8; 64]>,
+    pub encrypted_monthly_total: Option<[u8; 64]>,
+    pub encrypted_balance_handle: Option<[u8; 16]>,
+    pub inco_public_key: Option<[u8; 32]>,
+    pub inco_epoch: u64,
+    pub inco_enabled: bool,
+    pub statement_period: u32,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub last_transaction_at: Option<i64>,
+    pub last_transaction: Option<LastTxnV2>,
+    pub version: u8,
+}
+
+impl From<CardConfigV2> for CardConfig {
+    fn from(card: CardConfigV2) -> Self {
+        Self {
+            bump: card.bump,
+            card_id: card.card_id,
+            owner_did_hash: card.owner_did_hash,
+            additional_owner_did_hashes: card.additional_owner_did_hashes,
+            delegates: card.delegates,
+            mint: card.mint,
+            decimals: card.decimals,
+            home_country: card.home_country,
+            pending_owner: card.pending_owner,
+            expires_at: card.expires_at,
+            status: card.status,
+            policy: card.policy.into(),
+            velocity_limits: card.velocity_limits,
+            velocity_counters: card.velocity_counters,
+            last_limit_increase_slot: card.last_limit_increase_slot,
+            recent_merchant_counts: card.recent_merchant_counts,
+            recent_nonces: card.recent_nonces,
+            holds: card.holds,
+            next_hold_id: card.next_hold_id,
+            merchant_whitelist_enabled: card.merchant_whitelist_enabled,
+            merchant_whitelist: card.merchant_whitelist,
+            merchant_blocklist: card.merchant_blocklist,
+            mcc_whitelist_enabled: card.mcc_whitelist_enabled,
+            mcc_whitelist: card.mcc_whitelist,
+            mcc_bitmap_mode: card.mcc_bitmap_mode,
+            mcc_bitmap: card.mcc_bitmap,
+            mcc_blocklist: card.mcc_blocklist,
+            breach_count: card.breach_count,
+            freeze_info: card.freeze_info,
+            freeze_history: card.freeze_history,
+            confidential_mode: card.confidential_mode,
+            encrypted_daily_total: card.encrypted_daily_total,
+            encrypted_weekly_total: card.encrypted_weekly_total,
+            encrypted_monthly_total: card.encrypted_monthly_total,
+            encrypted_balance_handle: card.encrypted_balance_handle,
+            inco_public_key: card.inco_public_key,
+            inco_epoch: card.inco_epoch,
+            inco_enabled: card.inco_enabled,
+            statement_period: card.statement_period,
+            created_at: card.created_at,
+            updated_at: card.updated_at,
+            last_transaction_at: card.last_transaction_at,
+            last_transaction: card.last_transaction.map(Into::into),
+            version: card.version,
+            ..Default::default()
+        }
+    }
+}
+
+/// `CardPolicy` at version 2
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct CardPolicyV2 {
+    pub require_biometric: bool,
+    pub require_2fa_above: Option<u64>,
+    pub allow_international: bool,
+    pub allow_online: bool,
+    pub allow_atm: bool,
+    pub allow_contactless: bool,
+    pub contactless_limit: u64,
+    pub allowed_countries: Vec<u16>,
+    pub blocked_countries: Vec<u16>,
+    pub auto_freeze_after_breaches: Option<u16>,
+    pub max_fraud_score: Option<u8>,
+    pub max_daily_per_merchant: Option<u16>,
+    pub strict_merchant_mode: bool,
+    pub carryover_pct: u8,
+    pub allow_by_max_risk_tier: Option<u8>,
+    pub manual_approval_above: Option<u64>,
+}
+
+impl From<CardPolicyV2> for CardPolicy {
+    /// Version 2 never restricted transfers with fee
+    fn from(policy: CardPolicyV2) -> Self {
+        Self {
+            require_biometric: policy.require_biometric,
+            require_2fa_above: policy.require_2fa_above,
+            allow_international: policy.allow_international,
+            allow_online: policy.allow_online,
+            allow_atm: policy.allow_atm,
+            allow_contactless: policy.allow_contactless,
+            contactless_limit: policy.contactless_limit,
+            allowed_countries: policy.allowed_countries,
+            blocked_countries: policy.blocked_countries,
+            auto_freeze_after_breaches: policy.auto_freeze_after_breaches,
+            max_fraud_score: policy.max_fraud_score,
+            max_daily_per_merchant: policy.max_daily_per_merchant,
+            strict_merchant_mode: policy.strict_merchant_mode,
+            carryover_pct: policy.carryover_pct,
+            allow_by_max_risk_tier: policy.allow_by_max_risk_tier,
+            manual_approval_above: policy.manual_approval_above,
+            allow_transfer_with_fee: true,
+        }
+    }
+}
+
+/// `LastTxn` at version 2
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct LastTxnV2 {
+    pub amount: u64,
+    pub merchant_id: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub slot: u64,
+}
+
+impl From<LastTxnV2> for LastTxn {
+    fn from(txn: LastTxnV2) -> Self {
+        Self {
+            amount: txn.amount,
+            merchant_id: txn.merchant_id,
+            mcc_code: txn.mcc_code,
+            slot: txn.slot,
+            reference: None,
+        }
+    }
+}
+
+// ============================================================================
+// Global Configuration: First Deployment
+// ============================================================================
+
+/// `GlobalConfig` as first deployed
+///
+/// Fields were added both between and after these since, so the account is
+/// rebuilt rather than grown in place.
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize, Default))]
+pub struct GlobalConfigV0 {
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub is_paused: bool,
+    pub reset_authorities: Vec<Pubkey>,
+    pub fraud_authorities: Vec<Pubkey>,
+    pub default_velocity_limits: VelocityLimitsV0,
+    pub total_cards: u64,
+    pub total_transactions: u64,
+    pub total_volume: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl GlobalConfigV0 {
+    /// Space the first deployment allocated (10 keys per authority list)
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // admin
+        1 + // is_paused
+        4 + (32 * 10) + // reset_authorities
+        4 + (32 * 10) + // fraud_authorities
+        VelocityLimitsV0::SIZE +
+        8 + // total_cards
+        8 + // total_transactions
+        8 + // total_volume
+        8 + // created_at
+        8; // updated_at
+
+    /// Rebuild the config in the current layout
+    ///
+    /// Settings added since start at their off/unlimited zero values, as a
+    /// grown account's would.
+    pub fn into_current(self) -> GlobalConfig {
+        GlobalConfig {
+            bump: self.bump,
+            admin: self.admin,
+            pending_admin: None,
+            is_paused: self.is_paused,
+            kill_switch: false,
+            kill_switch_authority: None,
+            reset_authorities: self.reset_authorities,
+            fraud_authorities: self.fraud_authorities,
+            auth_authorities: vec![],
+            settlement_authorities: vec![],
+            system_transfer_accounts: vec![],
+            default_velocity_limits: self.default_velocity_limits.into(),
+            max_allowed_velocity_limits: VelocityLimits::default(),
+            min_slots_between_limit_increases: 0,
+            week_start_day: 0,
+            lazy_reset_enabled: false,
+            default_home_country: 0,
+            max_cards_per_owner: 0,
+            default_contactless_limit: 0,
+            total_cards: self.total_cards,
+            total_transactions: self.total_transactions,
+            total_volume: self.total_volume,
+            stats_since: 0,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            verbose_logging: false,
+        }
+    }
+}
+
+/// Account data after a checked `GlobalConfig` discriminator
+pub fn global_config_body(data: &[u8]) -> Result<&[u8]> {
+    if data.len() < 8 || data[..8] != GlobalConfig::DISCRIMINATOR {
+        return Err(error!(ErrorCode::AccountDiscriminatorMismatch));
+    }
+    Ok(&data[8..])
+}
+
+/// Parse a card config account written at version 2 or later
+///
+/// `version` sits after variable-length fields, so each versioned layout is
+/// tried and kept only when it reads back its own version. `data` must
+/// already be grown to `CardConfig::SIZE`, so that fields appended since the
+/// account was written read as zeroes even when its lists were full.
+pub fn parse_versioned_card_config(data: &[u8]) -> Result<CardConfig> {
+    let body = card_config_body(data)?;
+    if let Ok(card) = CardConfig::deserialize(&mut &body[..]) {
+        if card.version >= 3 {
+            return Ok(card);
+        }
+    }
+    if let Ok(card) = CardConfigV2::deserialize(&mut &body[..]) {
+        if card.version == 2 {
+            return Ok(card.into());
+        }
+    }
+    Err(error!(crate::errors::HookError::InvalidConfiguration))
+}
+
+/// Account data after a checked `CardConfig` discriminator
+pub fn card_config_body(data: &[u8]) -> Result<&[u8]> {
+    if data.len() < 8 || data[..8] != CardConfig::DISCRIMINATOR {
+        return Err(error!(ErrorCode::AccountDiscriminatorMismatch));
+    }
+    Ok(&data[8..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Account data for a legacy layout, padded to the space it was allocated
+    fn account_data<T: AnchorSerialize>(discriminator: &[u8], body: &T, space: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        body.serialize(&mut data).unwrap();
+        data.resize(space, 0);
+        data
+    }
+
+    #[test]
+    fn first_deployment_card_converts() {
+        let legacy = CardConfigV0 {
+            card_id: [1; 32],
+            status: CardStatus::Active,
+            policy: CardPolicyV0 { blocked_countries: vec![840], ..Default::default() },
+            velocity_limits: VelocityLimitsV0 { daily: 500, ..Default::default() },
+            velocity_counters: VelocityCountersV0 { daily_total: 120, ..Default::default() },
+            merchant_whitelist: vec![[2; 32]],
+            merchant_blocklist: vec![[3; 32]],
+            mcc_blocklist: vec![7995],
+            freeze_info: Some(FreezeInfoV0 {
+                reason: FreezeReason::UserRequest,
+                frozen_by: Pubkey::default(),
+                frozen_at: NOW - 10,
+                expires_at: None,
+            }),
+            updated_at: NOW - 100,
+            ..Default::default()
+        };
+        let data = account_data(&CardConfig::DISCRIMINATOR, &legacy, CardConfigV0::SIZE);
+
+        let body = card_config_body(&data).unwrap();
+        let mint = Pubkey::new_unique();
+        let card = CardConfigV0::deserialize(&mut &body[..]).unwrap().into_current(mint, 6, NOW);
+
+        assert_eq!(card.card_id, [1; 32]);
+        assert_eq!(card.mint, mint);
+        assert_eq!(card.decimals, 6);
+        assert_eq!(card.version, 0);
+        assert_eq!(card.policy.blocked_countries, vec![840]);
+        assert!(card.policy.allow_transfer_with_fee);
+        assert_eq!(card.velocity_limits.daily, 500);
+        assert_eq!(card.velocity_counters.daily_total, 120);
+        assert_eq!(card.velocity_counters.last_daily_reset_unix, NOW);
+        assert_eq!(card.merchant_whitelist[0].merchant_id, [2; 32]);
+        assert_eq!(card.merchant_whitelist[0].expires_at, None);
+        assert_eq!(card.merchant_blocklist[0].reason, BlockReason::UserChoice);
+        assert_eq!(card.merchant_blocklist[0].blocked_at, NOW - 100);
+        assert_eq!(card.mcc_blocklist, vec![7995]);
+        assert!(card.freeze_info.is_some_and(|info| info.scope == FreezeScope::All));
+    }
+
+    #[test]
+    fn version_2_card_parses() {
+        let legacy = CardConfigV2 {
+            card_id: [1; 32],
+            policy: CardPolicyV2 { carryover_pct: 25, ..Default::default() },
+            last_transaction: Some(LastTxnV2 { amount: 42, ..Default::default() }),
+            version: 2,
+            ..Default::default()
+        };
+        let data = account_data(&CardConfig::DISCRIMINATOR, &legacy, CardConfig::SIZE);
+
+        let card = parse_versioned_card_config(&data).unwrap();
+
+        assert_eq!(card.card_id, [1; 32]);
+        assert_eq!(card.version, 2);
+        assert_eq!(card.policy.carryover_pct, 25);
+        assert!(card.policy.allow_transfer_with_fee);
+        let last = card.last_transaction.unwrap();
+        assert_eq!(last.amount, 42);
+        assert_eq!(last.reference, None);
+    }
+
+    #[test]
+    fn current_card_parses_unchanged() {
+        let current = CardConfig {
+            card_id: [1; 32],
+            last_transaction: Some(LastTxn { reference: Some([9; 32]), ..Default::default() }),
+            version: 3,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        current.try_serialize(&mut data).unwrap();
+        data.resize(CardConfig::SIZE, 0);
+
+        let card = parse_versioned_card_config(&data).unwrap();
+
+        assert_eq!(card.version, 3);
+        assert_eq!(card.last_transaction.and_then(|txn| txn.reference), Some([9; 32]));
+    }
+
+    #[test]
+    fn unversioned_card_is_rejected() {
+        let data = account_data(&CardConfig::DISCRIMINATOR, &CardConfigV2::default(), CardConfig::SIZE);
+        assert!(parse_versioned_card_config(&data).is_err());
+
+        let data = account_data(&GlobalConfig::DISCRIMINATOR, &CardConfigV2::default(), CardConfig::SIZE);
+        assert!(parse_versioned_card_config(&data).is_err());
+    }
+
+    #[test]
+    fn first_deployment_global_config_converts() {
+        let admin = Pubkey::new_unique();
+        let legacy = GlobalConfigV0 {
+            admin,
+            reset_authorities: vec![Pubkey::new_unique()],
+            default_velocity_limits: VelocityLimitsV0 { daily: 500, ..Default::default() },
+            total_cards: 7,
+            total_volume: 900,
+            ..Default::default()
+        };
+        let data = account_data(&GlobalConfig::DISCRIMINATOR, &legacy, GlobalConfigV0::SIZE);
+
+        let body = global_config_body(&data).unwrap();
+        let config = GlobalConfigV0::deserialize(&mut &body[..]).unwrap().into_current();
+
+        assert_eq!(config.admin, admin);
+        assert_eq!(config.reset_authorities, legacy.reset_authorities);
+        assert_eq!(config.default_velocity_limits.daily, 500);
+        assert_eq!(config.total_cards, 7);
+        assert_eq!(config.total_volume, 900);
+        assert!(config.settlement_authorities.is_empty());
+        assert!(!config.kill_switch);
+    }
+}
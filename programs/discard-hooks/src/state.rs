@@ -6,6 +6,52 @@ use anchor_lang::prelude::*;
 pub const MAX_MERCHANTS: usize = 50;
 /// Maximum number of MCC codes in whitelist/blocklist
 pub const MAX_MCC_CODES: usize = 100;
+/// Highest valid MCC code (codes run 0001-9999)
+pub const MAX_MCC_CODE: u16 = 9999;
+/// Bytes in an MCC whitelist bitmap (one bit per code 0-9999)
+pub const MCC_BITMAP_BYTES: usize = 1250;
+/// Maximum number of past freezes retained in freeze history
+pub const MAX_FREEZE_HISTORY: usize = 8;
+/// Maximum number of distinct MCC buckets tracked per monthly statement
+pub const MAX_STATEMENT_MCC_BUCKETS: usize = 8;
+/// Maximum number of merchants tracked for per-merchant daily counts
+pub const MAX_RECENT_MERCHANTS: usize = 16;
+/// Maximum number of recorded transaction nonces kept for replay protection
+pub const MAX_RECENT_NONCES: usize = 32;
+/// Maximum number of additional (joint) owners per card
+pub const MAX_ADDITIONAL_OWNERS: usize = 4;
+/// Maximum number of scoped delegates per card
+pub const MAX_DELEGATES: usize = 4;
+/// Maximum number of open transaction holds per card
+pub const MAX_HOLDS: usize = 8;
+/// Maximum number of countries in each of a policy's allow/block lists
+pub const MAX_POLICY_COUNTRIES: usize = 50;
+/// Maximum number of destination token accounts on a card's allowlist
+pub const MAX_DESTINATIONS: usize = 8;
+/// Longest a temporary daily limit boost may last (7 days)
+pub const MAX_LIMIT_BOOST_SECS: i64 = 7 * 86_400;
+/// Current `CardConfig` schema version (accounts created before versioning read 0)
+pub const CARD_CONFIG_VERSION: u8 = 9;
+
+/// Delegate permission: update policy, velocity limits, and expiry
+pub const PERMISSION_UPDATE_POLICY: u8 = 1 << 0;
+/// Delegate permission: manage merchant and MCC lists
+pub const PERMISSION_MANAGE_MERCHANTS: u8 = 1 << 1;
+/// Delegate permission: freeze/unfreeze and pause/resume the card
+pub const PERMISSION_FREEZE: u8 = 1 << 2;
+/// Delegate permission: reset velocity counters
+pub const PERMISSION_RESET: u8 = 1 << 3;
+/// Every defined delegate permission bit
+pub const PERMISSION_ALL: u8 = PERMISSION_UPDATE_POLICY
+    | PERMISSION_MANAGE_MERCHANTS
+    | PERMISSION_FREEZE
+    | PERMISSION_RESET;
+/// Maximum number of keys in each reset/fraud/auth authority list
+pub const MAX_AUTHORITIES: usize = 10;
+/// Maximum number of authorized settlement services
+pub const MAX_SETTLEMENT_AUTHORITIES: usize = 10;
+/// Maximum number of platform token accounts exempt from the transfer hook
+pub const MAX_SYSTEM_TRANSFER_ACCOUNTS: usize = 10;
 
 // ============================================================================
 // Card Configuration (Per-Card State)
@@ -23,6 +69,28 @@ pub struct CardConfig {
     /// Owner DID commitment hash (for ownership verification)
     pub owner_did_hash: [u8; 32],
 
+    /// Joint owners who may manage the card alongside the primary owner
+    pub additional_owner_did_hashes: Vec<[u8; 32]>,
+
+    /// Delegates holding a subset of management permissions
+    pub delegates: Vec<Delegate>,
+
+    /// Token mint this card spends (transfers of any other mint are rejected)
+    pub mint: Pubkey,
+
+    /// Decimals of `mint`; every amount limit on the card is in its base units
+    pub decimals: u8,
+
+    /// Card's home country in policy form (see `merchant_registry::country::alpha2_to_u16`);
+    /// merchants elsewhere count as international
+    pub home_country: u16,
+
+    /// Proposed new owner DID hash awaiting acceptance
+    pub pending_owner: Option<[u8; 32]>,
+
+    /// Unix timestamp after which the card can no longer transact
+    pub expires_at: Option<i64>,
+
     /// Card status
     pub status: CardStatus,
 
@@ -35,23 +103,54 @@ pub struct CardConfig {
     /// Current velocity counters
     pub velocity_counters: VelocityCounters,
 
-    /// Merchant whitelist (if enabled)
+    /// Slot of the last velocity limit increase (0 if never increased)
+    pub last_limit_increase_slot: u64,
+
+    /// Today's transaction counts per merchant (cleared on daily reset)
+    pub recent_merchant_counts: Vec<MerchantCount>,
+
+    /// Nonces of the most recently recorded transactions (replay protection)
+    pub recent_nonces: Vec<u64>,
+
+    /// Open holds on disputed amounts; each reduces velocity headroom
+    pub holds: Vec<Hold>,
+
+    /// Identifier assigned to the next hold
+    pub next_hold_id: u32,
+
+    /// Merchant whitelist (if enabled), kept sorted by merchant ID for binary
+    /// search; entries may carry an expiry for temporary allowances
     pub merchant_whitelist_enabled: bool,
-    pub merchant_whitelist: Vec<[u8; 32]>,
+    pub merchant_whitelist: Vec<WhitelistedMerchant>,
 
-    /// Merchant blocklist
-    pub merchant_blocklist: Vec<[u8; 32]>,
+    /// Merchant blocklist, with the reason each merchant was blocked,
+    /// kept sorted by merchant ID for binary search
+    pub merchant_blocklist: Vec<BlockedMerchant>,
 
-    /// MCC whitelist (if enabled)
+    /// MCC whitelist (if enabled), kept sorted for binary search
     pub mcc_whitelist_enabled: bool,
     pub mcc_whitelist: Vec<u16>,
 
-    /// MCC blocklist
+    /// Store the MCC whitelist as a bitmap instead of `mcc_whitelist`
+    ///
+    /// Bitmap mode has O(1) lookups and no code cap. Its space is reserved on
+    /// every card either way (1,250 bytes vs. 200 for the vec), so it costs
+    /// no extra rent to switch.
+    pub mcc_bitmap_mode: bool,
+    pub mcc_bitmap: Vec<u8>,
+
+    /// MCC blocklist, kept sorted for binary search
     pub mcc_blocklist: Vec<u16>,
 
+    /// Consecutive velocity limit breaches (reset on success or daily reset)
+    pub breach_count: u16,
+
     /// Freeze information
     pub freeze_info: Option<FreezeInfo>,
 
+    /// Recent freezes, oldest first (bounded to MAX_FREEZE_HISTORY)
+    pub freeze_history: Vec<FreezeRecord>,
+
     /// Confidential transfer mode
     /// When true, velocity enforcement uses ZK proofs instead of plaintext amounts
     pub confidential_mode: bool,
@@ -83,29 +182,95 @@ pub struct CardConfig {
     /// If true, spending verification uses TEE instead of ZK proofs
     pub inco_enabled: bool,
 
+    /// Current monthly statement period (incremented on monthly reset)
+    pub statement_period: u32,
+
     /// Timestamps
     pub created_at: i64,
     pub updated_at: i64,
     pub last_transaction_at: Option<i64>,
+
+    /// Details of the most recent recorded transaction (dispute reference)
+    pub last_transaction: Option<LastTxn>,
+
+    /// Schema version, bumped by `migrate_card_config`
+    ///
+    /// Must stay last: fields added in later versions go after it, so older
+    /// accounts (whose trailing space is zeroed) still deserialize.
+    pub version: u8,
+
+    /// Hook rejections by category (added in v3)
+    pub decline_stats: DeclineStats,
+
+    /// One-off extra daily headroom until it expires (added in v4)
+    pub temporary_limit_boost: Option<LimitBoost>,
+
+    /// Token accounts the card may send to; empty allows any (added in v5)
+    pub destination_allowlist: Vec<Pubkey>,
+
+    /// The card's own token account; the hook only applies this card to
+    /// transfers out of it (added in v6)
+    pub token_account: Pubkey,
+
+    /// Card ID of the replacement this card was reissued onto (added in v7)
+    pub reissued_to: Option<[u8; 32]>,
+
+    /// Whether the card has a discard-state balance account the hook must
+    /// check and debit (added in v8)
+    pub balance_tracked: bool,
+
+    /// Online and in-store daily caps with their totals (added in v9)
+    pub channel_limits: ChannelLimits,
+}
+
+/// Derive the card configuration PDA for a card ID
+///
+/// Every context (init, template init, transfer hook) uses these seeds,
+/// so clients can compute the hook's `card_config` account directly.
+pub fn derive_card_config_pda(card_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CardConfig::SEED, card_id.as_ref()], &crate::ID)
 }
 
 impl CardConfig {
+    /// PDA seed prefix; the full seeds are `[SEED, card_id]`
+    pub const SEED: &'static [u8] = b"card_config";
+
+    /// Byte offset of `card_id` in the account data (after the discriminator
+    /// and bump), read by the transfer hook's extra account metas
+    pub const CARD_ID_OFFSET: u8 = 8 + 1;
+
     /// Account size calculation
     pub const SIZE: usize = 8 + // discriminator
         1 + // bump
         32 + // card_id
         32 + // owner_did_hash
+        4 + (32 * MAX_ADDITIONAL_OWNERS) + // additional_owner_did_hashes vec
+        4 + (Delegate::SIZE * MAX_DELEGATES) + // delegates vec
+        32 + // mint
+        1 + // decimals
+        2 + // home_country
+        1 + 32 + // pending_owner option
+        9 + // expires_at option
         1 + // status
         CardPolicy::SIZE +
         VelocityLimits::SIZE +
         VelocityCounters::SIZE +
+        8 + // last_limit_increase_slot
+        4 + (MerchantCount::SIZE * MAX_RECENT_MERCHANTS) + // recent_merchant_counts vec
+        4 + (8 * MAX_RECENT_NONCES) + // recent_nonces vec
+        4 + (Hold::SIZE * MAX_HOLDS) + // holds vec
+        4 + // next_hold_id
         1 + // merchant_whitelist_enabled
-        4 + (32 * MAX_MERCHANTS) + // merchant_whitelist vec
-        4 + (32 * MAX_MERCHANTS) + // merchant_blocklist vec
+        4 + (WhitelistedMerchant::SIZE * MAX_MERCHANTS) + // merchant_whitelist vec
+        4 + (BlockedMerchant::SIZE * MAX_MERCHANTS) + // merchant_blocklist vec
         1 + // mcc_whitelist_enabled
         4 + (2 * MAX_MCC_CODES) + // mcc_whitelist vec
+        1 + // mcc_bitmap_mode
+        4 + MCC_BITMAP_BYTES + // mcc_bitmap vec
         4 + (2 * MAX_MCC_CODES) + // mcc_blocklist vec
+        2 + // breach_count
         1 + FreezeInfo::SIZE + // freeze_info option
+        4 + (FreezeRecord::SIZE * MAX_FREEZE_HISTORY) + // freeze_history vec
         1 + // confidential_mode
         1 + 64 + // encrypted_daily_total option
         1 + 64 + // encrypted_weekly_total option
@@ -115,40 +280,176 @@ impl CardConfig {
         1 + 32 + // inco_public_key option
         8 + // inco_epoch
         1 + // inco_enabled
+        4 + // statement_period
         // Timestamps
         8 + // created_at
         8 + // updated_at
-        9; // last_transaction_at option
+        9 + // last_transaction_at option
+        1 + LastTxn::SIZE + // last_transaction option
+        1 + // version
+        DeclineStats::SIZE +
+        1 + LimitBoost::SIZE + // temporary_limit_boost option
+        4 + (32 * MAX_DESTINATIONS) + // destination_allowlist vec
+        32 + // token_account
+        1 + 32 + // reissued_to option
+        1 + // balance_tracked
+        ChannelLimits::SIZE;
 
     /// Check if a transaction is allowed
     pub fn is_transaction_allowed(
         &self,
         amount: u64,
-        merchant_id: Option<[u8; 32]>,
+        tx: &TransactionContext,
+    ) -> Result<()> {
+        // Check freezes, status, and expiry
+        self.check_card_usable(tx.channel, tx.mcc_code, tx.now)?;
+
+        // Check the destination allowlist (empty allows any destination)
+        if let Some(destination) = tx.destination {
+            if !self.is_destination_allowed(&destination) {
+                return Err(error!(crate::errors::HookError::DestinationNotAllowed));
+            }
+        }
+
+        // Check merchant and MCC rules
+        self.check_merchant_rules(tx)?;
+
+        // Check per-merchant daily count (card-testing protection)
+        if let (Some(key), Some(max_per_merchant)) = (tx.merchant_key(), self.policy.max_daily_per_merchant) {
+            if self.merchant_count_today(&key) >= max_per_merchant {
+                return Err(error!(crate::errors::HookError::MerchantVelocityExceeded));
+            }
+        }
+
+        // Check geographic restrictions
+        if let Some(country) = tx.country_code {
+            if !self.policy.allow_international && country != self.home_country {
+                return Err(error!(crate::errors::HookError::InternationalNotAllowed));
+            }
+            if self.policy.blocked_countries.contains(&country) {
+                return Err(error!(crate::errors::HookError::CountryBlocked));
+            }
+            if !self.policy.allowed_countries.is_empty()
+                && !self.policy.allowed_countries.contains(&country)
+            {
+                return Err(error!(crate::errors::HookError::CountryNotAllowed));
+            }
+        }
+
+        // Check fraud score (fail closed when a threshold is set but no score is supplied)
+        if let Some(max_score) = self.policy.max_fraud_score {
+            match tx.fraud_score {
+                Some(score) if score <= max_score => {}
+                _ => return Err(error!(crate::errors::HookError::FraudScoreExceeded)),
+            }
+        }
+
+        // Check the attested channel is allowed (contactless over the limit
+        // steps down to PIN, not a decline)
+        match tx.channel {
+            Some(TransactionChannel::Online) if !self.policy.allow_online => {
+                return Err(error!(crate::errors::HookError::OnlineNotAllowed));
+            }
+            Some(TransactionChannel::Atm) if !self.policy.allow_atm => {
+                return Err(error!(crate::errors::HookError::AtmNotAllowed));
+            }
+            Some(TransactionChannel::Contactless) => self.check_contactless(amount, tx.pin_verified)?,
+            _ => {}
+        }
+
+        // Check the Token-2022 transfer type
+        if tx.transfer_with_fee && !self.policy.allow_transfer_with_fee {
+            return Err(error!(crate::errors::HookError::TransferTypeNotAllowed));
+        }
+
+        // Check step-up authentication requirements
+        self.check_step_up(amount, tx.step_up_verified)?;
+
+        // Check owner pre-approval for large transfers
+        if self.requires_manual_approval(amount) && !tx.manually_approved {
+            return Err(error!(crate::errors::HookError::ManualApprovalRequired));
+        }
+
+        // Check the card can cover the transfer; cards whose checks depend
+        // on the balance can't skip it by leaving the account out
+        if tx.available_balance.is_none() && self.requires_balance_account() {
+            return Err(error!(crate::errors::HookError::BalanceAccountRequired));
+        }
+        if let Some(balance) = tx.available_balance {
+            if amount > balance {
+                return Err(error!(crate::errors::HookError::InsufficientBalance));
+            }
+        }
+
+        // Check velocity limits
+        self.check_velocity_limits(amount, tx.channel, tx.available_balance, tx.now)?;
+
+        Ok(())
+    }
+
+    /// Check the card can transact at all: not frozen for this channel and
+    /// MCC, active, and unexpired
+    ///
+    /// Amount-independent, so the confidential hook runs it too.
+    pub fn check_card_usable(
+        &self,
+        channel: Option<TransactionChannel>,
         mcc_code: Option<u16>,
+        now: i64,
     ) -> Result<()> {
-        // Check card status
-        if self.status != CardStatus::Active {
-            return Err(error!(crate::errors::HookError::CardNotActive));
+        // Freezes are checked first so a frozen card reports `CardFrozen`
+        // rather than the generic status error (a scoped freeze passes only
+        // transactions that declare a different channel or MCC)
+        if let Some(freeze_info) = &self.freeze_info {
+            if freeze_info.applies_to(channel, mcc_code) {
+                msg!("Card frozen: {:?}", freeze_info.reason);
+                return Err(error!(crate::errors::HookError::CardFrozen));
+            }
+        }
+
+        // Check card status (Terminated falls back to `CardNotActive`)
+        match self.status {
+            CardStatus::Active => {}
+            CardStatus::Frozen => return Err(error!(crate::errors::HookError::CardFrozen)),
+            CardStatus::Paused => return Err(error!(crate::errors::HookError::CardPaused)),
+            CardStatus::Pending => return Err(error!(crate::errors::HookError::CardPending)),
+            _ => return Err(error!(crate::errors::HookError::CardNotActive)),
         }
 
-        // Check if frozen
-        if self.freeze_info.is_some() {
-            return Err(error!(crate::errors::HookError::CardFrozen));
+        // Check expiry
+        if self.is_expired(now) {
+            return Err(error!(crate::errors::HookError::CardExpired));
         }
 
-        // Check merchant whitelist
+        Ok(())
+    }
+
+    /// Check the merchant whitelist/blocklist and MCC whitelist/blocklist
+    ///
+    /// Independent of amount, velocity, and card status.
+    pub fn check_merchant_rules(&self, tx: &TransactionContext) -> Result<()> {
+        let merchant_id = tx.merchant_id;
+        let mcc_code = tx.mcc_code;
+
+        // Check merchant whitelist (strict mode refuses unidentified merchants)
         if self.merchant_whitelist_enabled {
-            if let Some(mid) = merchant_id {
-                if !self.merchant_whitelist.contains(&mid) {
+            match merchant_id {
+                Some(mid)
+                    if !self.is_merchant_whitelisted(&mid, tx.now) && !self.allowed_by_risk_tier(tx) =>
+                {
                     return Err(error!(crate::errors::HookError::MerchantNotWhitelisted));
                 }
+                Some(_) => {}
+                None if self.policy.strict_merchant_mode => {
+                    return Err(error!(crate::errors::HookError::UnknownMerchant));
+                }
+                None => {}
             }
         }
 
         // Check merchant blocklist
         if let Some(mid) = merchant_id {
-            if self.merchant_blocklist.contains(&mid) {
+            if self.is_merchant_blocked(&mid) {
                 return Err(error!(crate::errors::HookError::MerchantBlocked));
             }
         }
@@ -156,7 +457,7 @@ impl CardConfig {
         // Check MCC whitelist
         if self.mcc_whitelist_enabled {
             if let Some(mcc) = mcc_code {
-                if !self.mcc_whitelist.contains(&mcc) {
+                if !self.is_mcc_whitelisted(mcc) {
                     return Err(error!(crate::errors::HookError::MccNotWhitelisted));
                 }
             }
@@ -164,36 +465,474 @@ impl CardConfig {
 
         // Check MCC blocklist
         if let Some(mcc) = mcc_code {
-            if self.mcc_blocklist.contains(&mcc) {
+            if self.is_mcc_blocked(mcc) {
                 return Err(error!(crate::errors::HookError::MccBlocked));
             }
         }
 
-        // Check velocity limits
-        self.check_velocity_limits(amount)?;
+        Ok(())
+    }
+
+    /// Append a freeze to the history, evicting the oldest entry when full
+    pub fn record_freeze(&mut self, reason: FreezeReason, frozen_by: Pubkey, frozen_at: i64) {
+        if self.freeze_history.len() >= MAX_FREEZE_HISTORY {
+            self.freeze_history.remove(0);
+        }
+        self.freeze_history.push(FreezeRecord {
+            reason,
+            frozen_by,
+            frozen_at,
+            unfrozen_at: None,
+        });
+    }
+
+    /// Mark the most recent open freeze in the history as lifted
+    pub fn record_unfreeze(&mut self, unfrozen_at: i64) {
+        if let Some(record) = self.freeze_history
+            .iter_mut()
+            .rev()
+            .find(|r| r.unfrozen_at.is_none())
+        {
+            record.unfrozen_at = Some(unfrozen_at);
+        }
+    }
+
+    /// Check biometric and 2FA requirements
+    fn check_step_up(&self, amount: u64, step_up_verified: bool) -> Result<()> {
+        if step_up_verified {
+            return Ok(());
+        }
+
+        if self.policy.require_biometric {
+            return Err(error!(crate::errors::HookError::BiometricRequired));
+        }
+
+        if let Some(threshold) = self.policy.require_2fa_above {
+            if amount > threshold {
+                return Err(error!(crate::errors::HookError::TwoFactorRequired));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check contactless permission and the no-PIN contactless limit
+    fn check_contactless(&self, amount: u64, pin_verified: bool) -> Result<()> {
+        if !self.policy.allow_contactless {
+            return Err(error!(crate::errors::HookError::ContactlessNotAllowed));
+        }
+        if amount > self.policy.contactless_limit && !pin_verified {
+            return Err(error!(crate::errors::HookError::ContactlessLimitExceeded));
+        }
+        Ok(())
+    }
+
+    /// Whether transfers must supply the card's discard-state balance account
+    pub fn requires_balance_account(&self) -> bool {
+        self.balance_tracked || self.velocity_limits.daily_pct_of_balance.is_some()
+    }
+
+    /// Whether the card may send to a destination token account
+    pub fn is_destination_allowed(&self, destination: &Pubkey) -> bool {
+        self.destination_allowlist.is_empty() || self.destination_allowlist.contains(destination)
+    }
+
+    /// Whether the policy requires an approval token for this amount
+    pub fn requires_manual_approval(&self, amount: u64) -> bool {
+        self.policy.manual_approval_above.is_some_and(|threshold| amount > threshold)
+    }
+
+    /// Replace the velocity limits, rate-limiting increases
+    ///
+    /// A change that raises any field must wait `cooldown_slots` after the
+    /// previous increase so a compromised key can't raise limits and drain
+    /// the card in quick succession; decreases always apply immediately.
+    /// No field may exceed the admin's `ceiling`.
+    pub fn set_velocity_limits(
+        &mut self,
+        limits: VelocityLimits,
+        ceiling: &VelocityLimits,
+        cooldown_slots: u64,
+        current_slot: u64,
+    ) -> Result<()> {
+        limits.validate()?;
+
+        if limits.is_increase_over(ceiling) {
+            return Err(error!(crate::errors::HookError::InvalidConfiguration));
+        }
+
+        if limits.is_increase_over(&self.velocity_limits) {
+            if self.last_limit_increase_slot != 0
+                && current_slot.saturating_sub(self.last_limit_increase_slot) < cooldown_slots
+            {
+                return Err(error!(crate::errors::HookError::LimitChangeCooldown));
+            }
+            self.last_limit_increase_slot = current_slot;
+        }
+
+        self.velocity_limits = limits;
+        Ok(())
+    }
+
+    /// Replace the online and in-store daily caps, rate-limiting increases
+    ///
+    /// Caps must be non-zero and within the daily limit; increases share the
+    /// velocity limits' cooldown. The channel totals are kept.
+    pub fn set_channel_limits(
+        &mut self,
+        online_daily: Option<u64>,
+        instore_daily: Option<u64>,
+        cooldown_slots: u64,
+        current_slot: u64,
+    ) -> Result<()> {
+        let daily_cap = self.velocity_limits.daily_cap();
+        let valid = [online_daily, instore_daily]
+            .iter()
+            .flatten()
+            .all(|&limit| limit > 0 && limit <= daily_cap);
+        if !valid {
+            return Err(error!(crate::errors::HookError::InvalidConfiguration));
+        }
+
+        let limits = ChannelLimits { online_daily, instore_daily, ..self.channel_limits };
+        if limits.is_increase_over(&self.channel_limits) {
+            if self.last_limit_increase_slot != 0
+                && current_slot.saturating_sub(self.last_limit_increase_slot) < cooldown_slots
+            {
+                return Err(error!(crate::errors::HookError::LimitChangeCooldown));
+            }
+            self.last_limit_increase_slot = current_slot;
+        }
+
+        self.channel_limits = limits;
+        Ok(())
+    }
+
+    /// Whether a DID is the primary owner or one of the additional owners
+    pub fn is_owner_did(&self, did_hash: &[u8; 32]) -> bool {
+        self.owner_did_hash == *did_hash || self.additional_owner_did_hashes.contains(did_hash)
+    }
+
+    /// Whether a DID is an owner or a delegate holding every bit in `permission`
+    pub fn is_authorized_did(&self, did_hash: &[u8; 32], permission: u8) -> bool {
+        self.is_owner_did(did_hash) || self.is_delegate_with(did_hash, permission)
+    }
+
+    /// Whether a DID is a delegate holding every bit in `permission` (owners don't count)
+    pub fn is_delegate_with(&self, did_hash: &[u8; 32], permission: u8) -> bool {
+        self.delegates.iter().any(|delegate| {
+            delegate.did_hash == *did_hash && delegate.permissions & permission == permission
+        })
+    }
+
+    /// Whether the merchant has an unexpired entry on the card's whitelist
+    ///
+    /// The merchant/MCC list lookups binary search, relying on the list
+    /// instructions keeping every list sorted.
+    pub fn is_merchant_whitelisted(&self, merchant_id: &[u8; 32], now: i64) -> bool {
+        self.merchant_whitelist
+            .binary_search_by(|entry| entry.merchant_id.cmp(merchant_id))
+            .is_ok_and(|pos| !self.merchant_whitelist[pos].is_expired(now))
+    }
+
+    /// Drop whitelist entries whose temporary allowance has lapsed
+    ///
+    /// The whitelist stays enabled even if this empties it, so an expired
+    /// allowance never widens what the card may pay. Returns how many
+    /// entries were removed.
+    pub fn evict_expired_whitelist_entries(&mut self, now: i64) -> usize {
+        let before = self.merchant_whitelist.len();
+        self.merchant_whitelist.retain(|entry| !entry.is_expired(now));
+        before - self.merchant_whitelist.len()
+    }
+
+    /// Whether the merchant is on the card's blocklist
+    pub fn is_merchant_blocked(&self, merchant_id: &[u8; 32]) -> bool {
+        self.merchant_blocklist
+            .binary_search_by(|blocked| blocked.merchant_id.cmp(merchant_id))
+            .is_ok()
+    }
+
+    /// Whether the MCC is on the card's whitelist
+    pub fn is_mcc_whitelisted(&self, mcc: u16) -> bool {
+        if self.mcc_bitmap_mode {
+            let (byte, mask) = mcc_bit(mcc);
+            return self.mcc_bitmap.get(byte).is_some_and(|bits| bits & mask != 0);
+        }
+        self.mcc_whitelist.binary_search(&mcc).is_ok()
+    }
+
+    /// Add or remove an MCC in the whitelist bitmap
+    pub fn set_mcc_bit(&mut self, mcc: u16, whitelisted: bool) {
+        if self.mcc_bitmap.len() < MCC_BITMAP_BYTES {
+            self.mcc_bitmap.resize(MCC_BITMAP_BYTES, 0);
+        }
+        let (byte, mask) = mcc_bit(mcc);
+        if whitelisted {
+            self.mcc_bitmap[byte] |= mask;
+        } else {
+            self.mcc_bitmap[byte] &= !mask;
+        }
+    }
+
+    /// Number of whitelisted MCC codes in whichever mode is active
+    pub fn mcc_whitelist_len(&self) -> usize {
+        if self.mcc_bitmap_mode {
+            return self.mcc_bitmap.iter().map(|bits| bits.count_ones() as usize).sum();
+        }
+        self.mcc_whitelist.len()
+    }
+
+    /// Whitelisted MCC codes in ascending order, in whichever mode is active
+    pub fn mcc_whitelist_codes(&self) -> Vec<u16> {
+        if !self.mcc_bitmap_mode {
+            return self.mcc_whitelist.clone();
+        }
+        (0..=MAX_MCC_CODE)
+            .filter(|mcc| {
+                let (byte, mask) = mcc_bit(*mcc);
+                self.mcc_bitmap.get(byte).is_some_and(|bits| bits & mask != 0)
+            })
+            .collect()
+    }
+
+    /// Whether the MCC is on the card's blocklist
+    pub fn is_mcc_blocked(&self, mcc: u16) -> bool {
+        self.mcc_blocklist.binary_search(&mcc).is_ok()
+    }
+
+    /// Whether the card is past its expiry date
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Whether the registry risk tier lets a non-whitelisted merchant through
+    fn allowed_by_risk_tier(&self, tx: &TransactionContext) -> bool {
+        match (self.policy.allow_by_max_risk_tier, tx.merchant_risk_tier) {
+            (Some(max_tier), Some(tier)) => tier <= max_tier,
+            _ => false,
+        }
+    }
+
+    /// Transactions recorded at a merchant since the last daily reset
+    pub fn merchant_count_today(&self, merchant_id: &[u8; 32]) -> u16 {
+        self.recent_merchant_counts
+            .iter()
+            .find(|entry| &entry.merchant_id == merchant_id)
+            .map_or(0, |entry| entry.count)
+    }
+
+    /// Count a transaction against its merchant's daily tally
+    ///
+    /// When the table is full, the merchant with the fewest transactions
+    /// today is evicted to make room.
+    pub fn record_merchant_transaction(&mut self, merchant_id: [u8; 32]) {
+        if let Some(entry) = self.recent_merchant_counts
+            .iter_mut()
+            .find(|entry| entry.merchant_id == merchant_id)
+        {
+            entry.count = entry.count.saturating_add(1);
+            return;
+        }
+
+        if self.recent_merchant_counts.len() >= MAX_RECENT_MERCHANTS {
+            if let Some(index) = self.recent_merchant_counts
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| entry.count)
+                .map(|(index, _)| index)
+            {
+                self.recent_merchant_counts.swap_remove(index);
+            }
+        }
+
+        self.recent_merchant_counts.push(MerchantCount { merchant_id, count: 1 });
+    }
+
+    /// Remember a recorded transaction's nonce, rejecting replays
+    ///
+    /// Only the last `MAX_RECENT_NONCES` nonces are kept; the oldest is
+    /// dropped to make room.
+    pub fn record_nonce(&mut self, nonce: u64) -> Result<()> {
+        if self.recent_nonces.contains(&nonce) {
+            return Err(error!(crate::errors::HookError::DuplicateTransaction));
+        }
+        if self.recent_nonces.len() >= MAX_RECENT_NONCES {
+            self.recent_nonces.remove(0);
+        }
+        self.recent_nonces.push(nonce);
+        Ok(())
+    }
+
+    /// Reset daily counters, including the encrypted total in confidential mode
+    ///
+    /// An empty encrypted counter is re-seeded by the next confidential transfer.
+    pub fn reset_daily(&mut self, current_slot: u64, now: i64) {
+        self.velocity_counters.reset_daily(current_slot, now);
+        self.channel_limits.reset_daily();
+        self.recent_merchant_counts.clear();
+        self.breach_count = 0;
+        if self.confidential_mode {
+            self.encrypted_daily_total = None;
+        }
+    }
+
+    /// Daily limit plus any unexpired temporary boost
+    pub fn effective_daily_limit(&self, balance: Option<u64>, now: i64) -> u64 {
+        let boost = self.temporary_limit_boost
+            .filter(|boost| boost.is_active(now))
+            .map_or(0, |boost| boost.amount);
+        self.velocity_limits.effective_daily(balance).saturating_add(boost)
+    }
+
+    /// Grant extra daily headroom until `expires_at`
+    ///
+    /// A boost is a limit increase, so it stays within the daily ceiling and
+    /// honors the same cooldown as `set_velocity_limits`. It replaces any
+    /// earlier boost.
+    pub fn grant_limit_boost(
+        &mut self,
+        boost: LimitBoost,
+        ceiling: &VelocityLimits,
+        cooldown_slots: u64,
+        current_slot: u64,
+        now: i64,
+    ) -> Result<()> {
+        if boost.amount == 0
+            || boost.expires_at <= now
+            || boost.expires_at.saturating_sub(now) > MAX_LIMIT_BOOST_SECS
+            || self.velocity_limits.daily_cap().saturating_add(boost.amount) > ceiling.daily_cap()
+        {
+            return Err(error!(crate::errors::HookError::InvalidConfiguration));
+        }
+
+        if self.last_limit_increase_slot != 0
+            && current_slot.saturating_sub(self.last_limit_increase_slot) < cooldown_slots
+        {
+            return Err(error!(crate::errors::HookError::LimitChangeCooldown));
+        }
+        self.last_limit_increase_slot = current_slot;
 
+        self.temporary_limit_boost = Some(boost);
         Ok(())
     }
 
+    /// Weekly limit plus the amount carried over from last week
+    pub fn effective_weekly_limit(&self) -> u64 {
+        self.velocity_limits.weekly_cap().saturating_add(self.velocity_counters.weekly_carryover)
+    }
+
+    /// Reset weekly counters, including the encrypted total in confidential mode
+    ///
+    /// With `carryover_pct` set, that share of the base limit left unused this
+    /// week becomes extra headroom next week. Carryover never compounds and is
+    /// capped at the base weekly limit.
+    pub fn reset_weekly(&mut self, current_slot: u64, now: i64) {
+        let unused = self.velocity_limits.weekly.saturating_sub(self.velocity_counters.weekly_total);
+        let carryover = (unused as u128 * self.policy.carryover_pct.min(100) as u128 / 100) as u64;
+
+        self.velocity_counters.reset_weekly(current_slot, now);
+        self.velocity_counters.weekly_carryover = carryover.min(self.velocity_limits.weekly);
+        if self.confidential_mode {
+            self.encrypted_weekly_total = None;
+        }
+    }
+
+    /// Reset monthly counters and roll over to the next statement period
+    pub fn reset_monthly(&mut self, current_slot: u64, now: i64) {
+        self.velocity_counters.reset_monthly(current_slot, now);
+        self.statement_period = self.statement_period.saturating_add(1);
+        if self.confidential_mode {
+            self.encrypted_monthly_total = None;
+        }
+    }
+
+    /// Record a velocity limit breach, auto-freezing the card once the
+    /// policy's breach threshold is reached. Returns true if the card was frozen.
+    pub fn record_velocity_breach(&mut self, now: i64) -> bool {
+        self.breach_count = self.breach_count.saturating_add(1);
+
+        let threshold = match self.policy.auto_freeze_after_breaches {
+            Some(threshold) if threshold > 0 => threshold,
+            _ => return false,
+        };
+
+        if self.breach_count < threshold || self.freeze_info.is_some() {
+            return false;
+        }
+
+        // Frozen by the program itself rather than a signer
+        self.freeze_info = Some(FreezeInfo {
+            reason: FreezeReason::VelocityBreach,
+            frozen_by: crate::ID,
+            frozen_at: now,
+            expires_at: None,
+            scope: FreezeScope::All,
+        });
+        self.record_freeze(FreezeReason::VelocityBreach, crate::ID, now);
+        self.status = CardStatus::Frozen;
+
+        true
+    }
+
+    /// Total amount reserved by open holds
+    pub fn held_total(&self) -> u64 {
+        self.holds.iter().fold(0u64, |total, hold| total.saturating_add(hold.amount))
+    }
+
     /// Check velocity limits
-    fn check_velocity_limits(&self, amount: u64) -> Result<()> {
+    ///
+    /// Open holds count against every period's headroom. With a known
+    /// `balance`, a percentage-of-balance daily cap can tighten the daily limit.
+    pub fn check_velocity_limits(
+        &self,
+        amount: u64,
+        channel: Option<TransactionChannel>,
+        balance: Option<u64>,
+        now: i64,
+    ) -> Result<()> {
         // Per-transaction limit
-        if amount > self.velocity_limits.per_transaction {
+        if amount > self.velocity_limits.per_transaction_cap() {
             return Err(error!(crate::errors::HookError::TransactionLimitExceeded));
         }
 
+        let counters = &self.velocity_counters;
+        let limits = &self.velocity_limits;
+
+        // Transaction count limits
+        if counters.daily_transaction_count.saturating_add(1) > count_cap(limits.max_daily_transactions) {
+            return Err(error!(crate::errors::HookError::DailyTransactionCountExceeded));
+        }
+        if counters.weekly_transaction_count.saturating_add(1) > count_cap(limits.max_weekly_transactions) {
+            return Err(error!(crate::errors::HookError::WeeklyTransactionCountExceeded));
+        }
+        if counters.monthly_transaction_count.saturating_add(1) > count_cap(limits.max_monthly_transactions) {
+            return Err(error!(crate::errors::HookError::MonthlyTransactionCountExceeded));
+        }
+
+        let pending = amount.saturating_add(self.held_total());
+
         // Daily limit
-        if self.velocity_counters.daily_total + amount > self.velocity_limits.daily {
+        if self.velocity_counters.daily_total.saturating_add(pending)
+            > self.effective_daily_limit(balance, now)
+        {
             return Err(error!(crate::errors::HookError::DailyLimitExceeded));
         }
 
-        // Weekly limit
-        if self.velocity_counters.weekly_total + amount > self.velocity_limits.weekly {
+        // Per-channel daily limit (online or in-store), within the daily total
+        if self.channel_limits.daily_total(channel).saturating_add(pending)
+            > self.channel_limits.daily_cap(channel)
+        {
+            return Err(error!(crate::errors::HookError::DailyLimitExceeded));
+        }
+
+        // Weekly limit (including any carryover from last week)
+        if self.velocity_counters.weekly_total.saturating_add(pending) > self.effective_weekly_limit() {
             return Err(error!(crate::errors::HookError::WeeklyLimitExceeded));
         }
 
         // Monthly limit
-        if self.velocity_counters.monthly_total + amount > self.velocity_limits.monthly {
+        if self.velocity_counters.monthly_total.saturating_add(pending) > self.velocity_limits.monthly_cap() {
             return Err(error!(crate::errors::HookError::MonthlyLimitExceeded));
         }
 
@@ -201,11 +940,81 @@ impl CardConfig {
     }
 }
 
+// ============================================================================
+// Transaction Context
+// ============================================================================
+
+/// Per-transfer inputs evaluated alongside the amount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TransactionContext {
+    /// Merchant identifier (if known)
+    pub merchant_id: Option<[u8; 32]>,
+
+    /// Merchant Category Code (if known)
+    pub mcc_code: Option<u16>,
+
+    /// Merchant country in policy form (see `merchant_registry::country::alpha2_to_u16`)
+    pub country_code: Option<u16>,
+
+    /// Registry risk tier of a valid merchant (only set from a registry record)
+    pub merchant_risk_tier: Option<u8>,
+
+    /// Channel the transaction was made through (if known)
+    pub channel: Option<TransactionChannel>,
+
+    /// Real-time fraud model score (0-255, higher is riskier)
+    pub fraud_score: Option<u8>,
+
+    /// Whether the user completed step-up authentication (2FA/biometric)
+    pub step_up_verified: bool,
+
+    /// Whether a matching, unexpired `ApprovalToken` was supplied
+    pub manually_approved: bool,
+
+    /// Whether the cardholder entered their PIN (lifts the contactless limit)
+    pub pin_verified: bool,
+
+    /// Card balance from discard-state, when the balance account is supplied
+    /// (only with the `plaintext-state` feature)
+    pub available_balance: Option<u64>,
+
+    /// Destination token account (if known)
+    pub destination: Option<Pubkey>,
+
+    /// Whether the transfer is a Token-2022 transfer-with-fee
+    pub transfer_with_fee: bool,
+
+    /// Current unix timestamp
+    pub now: i64,
+}
+
+impl TransactionContext {
+    /// Key the card's per-merchant daily count is kept under
+    ///
+    /// Token-2022 can't tell the hook which merchant is paid, but a merchant
+    /// settles into one token account (its registry record's
+    /// `token_account`), so the destination identifies it. Without a
+    /// destination the merchant ID is used.
+    pub fn merchant_key(&self) -> Option<[u8; 32]> {
+        self.destination
+            .map(|destination| destination.to_bytes())
+            .or(self.merchant_id)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransactionChannel {
+    InStore,
+    Online,
+    Contactless,
+    Atm,
+}
+
 // ============================================================================
 // Card Status
 // ============================================================================
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum CardStatus {
     #[default]
     Pending,
@@ -230,24 +1039,75 @@ pub struct CardPolicy {
     /// Allow international transactions
     pub allow_international: bool,
 
-    /// Allow online transactions
+    /// Allow online transactions (judged by the attested channel)
     pub allow_online: bool,
 
-    /// Allow ATM withdrawals
+    /// Allow ATM withdrawals (judged by the attested channel)
     pub allow_atm: bool,
 
     /// Allow contactless payments
     pub allow_contactless: bool,
 
-    /// Maximum contactless amount (before PIN required)
+    /// Maximum contactless amount (before PIN required), in mint base units
     pub contactless_limit: u64,
 
-    /// Geographic restrictions (country codes)
+    /// Geographic restrictions (country codes), judged by the country of the
+    /// merchant record. Token-2022 can't supply one, so the transfer hook
+    /// itself doesn't check them; `simulate_transfer` and
+    /// `check_merchant_allowed` do
     pub allowed_countries: Vec<u16>,
     pub blocked_countries: Vec<u16>,
+
+    /// Auto-freeze after this many consecutive velocity breaches
+    pub auto_freeze_after_breaches: Option<u16>,
+
+    /// Decline when the fraud score exceeds this (missing score also declines)
+    pub max_fraud_score: Option<u8>,
+
+    /// Maximum transactions per merchant (or destination account) per day
+    pub max_daily_per_merchant: Option<u16>,
+
+    /// Reject transactions without a merchant ID while the whitelist is enabled
+    pub strict_merchant_mode: bool,
+
+    /// Percentage (0-100) of unused weekly limit carried into the next week
+    pub carryover_pct: u8,
+
+    /// Allow any registry merchant at or below this risk tier, even if not
+    /// whitelisted. The tier comes from a merchant record, which only
+    /// `simulate_transfer` and `check_merchant_allowed` receive, so the
+    /// transfer hook itself never applies this
+    pub allow_by_max_risk_tier: Option<u8>,
+
+    /// Transfers above this amount need an owner-issued `ApprovalToken`
+    pub manual_approval_above: Option<u64>,
+
+    /// Allow Token-2022 `TransferCheckedWithFee` transfers
+    pub allow_transfer_with_fee: bool,
 }
 
 impl CardPolicy {
+    /// Reject policies with out-of-range settings
+    ///
+    /// Country lists longer than the space reserved in `SIZE` would fail to
+    /// serialize into the card account, so they are rejected up front.
+    pub fn validate(&self) -> Result<()> {
+        if self.carryover_pct > 100
+            || self.allowed_countries.len() > MAX_POLICY_COUNTRIES
+            || self.blocked_countries.len() > MAX_POLICY_COUNTRIES
+        {
+            return Err(error!(crate::errors::HookError::InvalidConfiguration));
+        }
+        Ok(())
+    }
+
+    /// Whether any country rules (allow/block lists or domestic-only) apply
+    pub fn has_country_rules(&self) -> bool {
+        !self.allow_international
+            || !self.allowed_countries.is_empty()
+            || !self.blocked_countries.is_empty()
+    }
+
     pub const SIZE: usize = 1 + // require_biometric
         9 + // require_2fa_above option
         1 + // allow_international
@@ -255,17 +1115,57 @@ impl CardPolicy {
         1 + // allow_atm
         1 + // allow_contactless
         8 + // contactless_limit
-        4 + (2 * 50) + // allowed_countries
-        4 + (2 * 50); // blocked_countries
+        4 + (2 * MAX_POLICY_COUNTRIES) + // allowed_countries
+        4 + (2 * MAX_POLICY_COUNTRIES) + // blocked_countries
+        3 + // auto_freeze_after_breaches option
+        2 + // max_fraud_score option
+        3 + // max_daily_per_merchant option
+        1 + // strict_merchant_mode
+        1 + // carryover_pct
+        2 + // allow_by_max_risk_tier option
+        9 + // manual_approval_above option
+        1; // allow_transfer_with_fee
+}
+
+/// Reject MCC codes outside 0001-9999
+pub fn validate_mcc(code: u16) -> Result<()> {
+    if code == 0 || code > MAX_MCC_CODE {
+        return Err(error!(crate::errors::HookError::InvalidMccCode));
+    }
+    Ok(())
+}
+
+/// Byte index and bit mask of an MCC in the whitelist bitmap
+fn mcc_bit(mcc: u16) -> (usize, u8) {
+    ((mcc / 8) as usize, 1 << (mcc % 8))
+}
+
+/// Contactless limit for new cards when the admin hasn't set one ($100)
+pub const DEFAULT_CONTACTLESS_LIMIT: u64 = 100;
+
+/// Convert a whole display amount (e.g. dollars) to a mint's base units
+///
+/// For a 6-decimal USDC mint, `to_base_units(100, 6)` is `100_000000`.
+/// Amounts too large for a high-decimal mint saturate to `u64::MAX`, which
+/// every limit treats as effectively unlimited.
+pub fn to_base_units(display_amount: u64, decimals: u8) -> u64 {
+    10u64
+        .checked_pow(decimals as u32)
+        .and_then(|scale| display_amount.checked_mul(scale))
+        .unwrap_or(u64::MAX)
 }
 
 // ============================================================================
 // Velocity Limits
 // ============================================================================
 
+/// Velocity limits for a card
+///
+/// A zero limit disables that limit: the period (or transaction size, or
+/// count) is unlimited.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
 pub struct VelocityLimits {
-    /// Maximum per single transaction (lamports/cents)
+    /// Maximum per single transaction (in the card mint's base units)
     pub per_transaction: u64,
 
     /// Maximum daily spending
@@ -285,16 +1185,146 @@ pub struct VelocityLimits {
 
     /// Maximum transactions per month
     pub max_monthly_transactions: u16,
+
+    /// Cap daily spend at this percentage of the card balance, when known
+    /// (only settable in `plaintext-state` builds, which track balances)
+    pub daily_pct_of_balance: Option<u8>,
 }
 
 impl VelocityLimits {
-    pub const SIZE: usize = 8 + 8 + 8 + 8 + 2 + 2 + 2;
-}
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + 2 + 2 + 2 + 2;
+
+    /// Generous defaults for new cards, in whole display units
+    ///
+    /// Also the velocity ceiling until the admin configures one.
+    pub const GENEROUS_DEFAULTS: VelocityLimits = VelocityLimits {
+        per_transaction: 1_000_000,  // $1M per transaction
+        daily: 5_000_000,            // $5M daily
+        weekly: 20_000_000,          // $20M weekly
+        monthly: 100_000_000,        // $100M monthly
+        max_daily_transactions: 1000,
+        max_weekly_transactions: 5000,
+        max_monthly_transactions: 20000,
+        daily_pct_of_balance: None,
+    };
+
+    /// Per-transaction cap (`u64::MAX` when unlimited)
+    pub fn per_transaction_cap(&self) -> u64 {
+        amount_cap(self.per_transaction)
+    }
 
-// ============================================================================
+    /// Daily cap before any balance percentage (`u64::MAX` when unlimited)
+    pub fn daily_cap(&self) -> u64 {
+        amount_cap(self.daily)
+    }
+
+    /// Weekly cap before carryover (`u64::MAX` when unlimited)
+    pub fn weekly_cap(&self) -> u64 {
+        amount_cap(self.weekly)
+    }
+
+    /// Monthly cap (`u64::MAX` when unlimited)
+    pub fn monthly_cap(&self) -> u64 {
+        amount_cap(self.monthly)
+    }
+
+    /// Whether any limit is higher (or less restricted) than in `current`
+    pub fn is_increase_over(&self, current: &VelocityLimits) -> bool {
+        self.per_transaction_cap() > current.per_transaction_cap()
+            || self.daily_cap() > current.daily_cap()
+            || self.weekly_cap() > current.weekly_cap()
+            || self.monthly_cap() > current.monthly_cap()
+            || count_cap(self.max_daily_transactions) > count_cap(current.max_daily_transactions)
+            || count_cap(self.max_weekly_transactions) > count_cap(current.max_weekly_transactions)
+            || count_cap(self.max_monthly_transactions) > count_cap(current.max_monthly_transactions)
+            || match (self.daily_pct_of_balance, current.daily_pct_of_balance) {
+                (None, Some(_)) => true,
+                (Some(pct), Some(current_pct)) => pct > current_pct,
+                _ => false,
+            }
+    }
+
+    /// Daily cap after applying `daily_pct_of_balance` to a known balance
+    pub fn effective_daily(&self, balance: Option<u64>) -> u64 {
+        match (self.daily_pct_of_balance, balance) {
+            (Some(pct), Some(balance)) => {
+                let pct_cap = (balance as u128 * pct as u128 / 100) as u64;
+                self.daily_cap().min(pct_cap)
+            }
+            _ => self.daily_cap(),
+        }
+    }
+
+    /// Scale limits written in whole display units to a mint's base units
+    ///
+    /// Transaction counts are unitless and left unchanged.
+    pub fn to_base_units(self, decimals: u8) -> Self {
+        Self {
+            per_transaction: to_base_units(self.per_transaction, decimals),
+            daily: to_base_units(self.daily, decimals),
+            weekly: to_base_units(self.weekly, decimals),
+            monthly: to_base_units(self.monthly, decimals),
+            ..self
+        }
+    }
+
+    /// Check that the limits are internally consistent
+    ///
+    /// Amount limits must be ordered `per_transaction <= daily <= weekly <=
+    /// monthly` and count limits `daily <= weekly <= monthly`, with a zero
+    /// (unlimited) limit ordering above every finite one. To block spending,
+    /// use `pause_card` or a freeze rather than limits.
+    pub fn validate(&self) -> Result<()> {
+        let amounts_ordered = self.per_transaction_cap() <= self.daily_cap()
+            && self.daily_cap() <= self.weekly_cap()
+            && self.weekly_cap() <= self.monthly_cap();
+
+        let counts_ordered = count_cap(self.max_daily_transactions)
+            <= count_cap(self.max_weekly_transactions)
+            && count_cap(self.max_weekly_transactions) <= count_cap(self.max_monthly_transactions);
+
+        if !amounts_ordered || !counts_ordered {
+            return Err(error!(crate::errors::HookError::InvalidConfiguration));
+        }
+
+        if let Some(pct) = self.daily_pct_of_balance {
+            // Without `plaintext-state` no balance account can be supplied, so
+            // the card could never transfer again
+            if !cfg!(feature = "plaintext-state") || pct == 0 || pct > 100 {
+                return Err(error!(crate::errors::HookError::InvalidConfiguration));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An amount limit with zero meaning unlimited
+fn amount_cap(limit: u64) -> u64 {
+    if limit == 0 { u64::MAX } else { limit }
+}
+
+/// A count limit with zero meaning unlimited
+fn count_cap(limit: u16) -> u16 {
+    if limit == 0 { u16::MAX } else { limit }
+}
+
+// ============================================================================
 // Velocity Counters
 // ============================================================================
 
+/// Daily transaction tally for one merchant, keyed as by
+/// `TransactionContext::merchant_key`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct MerchantCount {
+    pub merchant_id: [u8; 32],
+    pub count: u16,
+}
+
+impl MerchantCount {
+    pub const SIZE: usize = 32 + 2;
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
 pub struct VelocityCounters {
     /// Current daily spending total
@@ -303,6 +1333,9 @@ pub struct VelocityCounters {
     /// Current weekly spending total
     pub weekly_total: u64,
 
+    /// Unused limit carried over from last week (added to the weekly limit)
+    pub weekly_carryover: u64,
+
     /// Current monthly spending total
     pub monthly_total: u64,
 
@@ -315,43 +1348,336 @@ pub struct VelocityCounters {
     pub last_daily_reset_slot: u64,
     pub last_weekly_reset_slot: u64,
     pub last_monthly_reset_slot: u64,
+
+    /// Last reset unix timestamps (drive calendar-aligned resets)
+    pub last_daily_reset_unix: i64,
+    pub last_weekly_reset_unix: i64,
+    pub last_monthly_reset_unix: i64,
 }
 
 impl VelocityCounters {
-    pub const SIZE: usize = 8 + 8 + 8 + 2 + 2 + 2 + 8 + 8 + 8;
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + 2 + 2 + 2 + 8 + 8 + 8 + 8 + 8 + 8;
 
     /// Record a transaction
+    ///
+    /// Totals saturate rather than overflow: with a zero (unlimited) limit
+    /// nothing else bounds them.
     pub fn record_transaction(&mut self, amount: u64) {
-        self.daily_total += amount;
-        self.weekly_total += amount;
-        self.monthly_total += amount;
-        self.daily_transaction_count += 1;
-        self.weekly_transaction_count += 1;
-        self.monthly_transaction_count += 1;
+        self.daily_total = self.daily_total.saturating_add(amount);
+        self.weekly_total = self.weekly_total.saturating_add(amount);
+        self.monthly_total = self.monthly_total.saturating_add(amount);
+        self.daily_transaction_count = self.daily_transaction_count.saturating_add(1);
+        self.weekly_transaction_count = self.weekly_transaction_count.saturating_add(1);
+        self.monthly_transaction_count = self.monthly_transaction_count.saturating_add(1);
     }
 
     /// Reset daily counters
-    pub fn reset_daily(&mut self, current_slot: u64) {
+    pub fn reset_daily(&mut self, current_slot: u64, now: i64) {
         self.daily_total = 0;
         self.daily_transaction_count = 0;
         self.last_daily_reset_slot = current_slot;
+        self.last_daily_reset_unix = now;
     }
 
     /// Reset weekly counters
-    pub fn reset_weekly(&mut self, current_slot: u64) {
+    pub fn reset_weekly(&mut self, current_slot: u64, now: i64) {
         self.weekly_total = 0;
         self.weekly_transaction_count = 0;
         self.last_weekly_reset_slot = current_slot;
+        self.last_weekly_reset_unix = now;
     }
 
     /// Reset monthly counters
-    pub fn reset_monthly(&mut self, current_slot: u64) {
+    pub fn reset_monthly(&mut self, current_slot: u64, now: i64) {
         self.monthly_total = 0;
         self.monthly_transaction_count = 0;
         self.last_monthly_reset_slot = current_slot;
+        self.last_monthly_reset_unix = now;
+    }
+}
+
+/// Per-channel daily caps and the spend counted against them
+///
+/// Contactless counts as in-store. ATM transactions only count against the
+/// combined daily limit, which still caps the total. A transaction without an
+/// attested channel counts against a capped bucket (see `unattested_channel`),
+/// so leaving the channel out can't dodge a cap.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ChannelLimits {
+    /// Maximum daily online spending (within the daily limit)
+    pub online_daily: Option<u64>,
+
+    /// Maximum daily in-store spending, contactless included (within the daily limit)
+    pub instore_daily: Option<u64>,
+
+    /// Current daily online spending total
+    pub online_daily_total: u64,
+
+    /// Current daily in-store spending total (contactless included)
+    pub instore_daily_total: u64,
+}
+
+impl ChannelLimits {
+    pub const SIZE: usize = 9 + 9 + 8 + 8;
+
+    /// Daily cap for a channel (`u64::MAX` when the channel has no limit)
+    pub fn daily_cap(&self, channel: Option<TransactionChannel>) -> u64 {
+        match channel.or(self.unattested_channel()) {
+            Some(TransactionChannel::Online) => self.online_daily.unwrap_or(u64::MAX),
+            Some(TransactionChannel::InStore | TransactionChannel::Contactless) => {
+                self.instore_daily.unwrap_or(u64::MAX)
+            }
+            Some(TransactionChannel::Atm) | None => u64::MAX,
+        }
+    }
+
+    /// Daily spending so far through a channel's limit bucket
+    pub fn daily_total(&self, channel: Option<TransactionChannel>) -> u64 {
+        match channel.or(self.unattested_channel()) {
+            Some(TransactionChannel::Online) => self.online_daily_total,
+            Some(TransactionChannel::InStore | TransactionChannel::Contactless) => {
+                self.instore_daily_total
+            }
+            Some(TransactionChannel::Atm) | None => 0,
+        }
+    }
+
+    /// Record a transaction in its channel's bucket
+    pub fn record_transaction(&mut self, amount: u64, channel: Option<TransactionChannel>) {
+        match channel.or(self.unattested_channel()) {
+            Some(TransactionChannel::Online) => {
+                self.online_daily_total = self.online_daily_total.saturating_add(amount)
+            }
+            Some(TransactionChannel::InStore | TransactionChannel::Contactless) => {
+                self.instore_daily_total = self.instore_daily_total.saturating_add(amount)
+            }
+            Some(TransactionChannel::Atm) | None => {}
+        }
+    }
+
+    /// Bucket a transaction without an attested channel counts against
+    ///
+    /// Online, the stricter cap, whenever an online cap is set; in-store when
+    /// only that one is. Without channel caps it stays unknown.
+    pub fn unattested_channel(&self) -> Option<TransactionChannel> {
+        match (self.online_daily, self.instore_daily) {
+            (Some(_), _) => Some(TransactionChannel::Online),
+            (None, Some(_)) => Some(TransactionChannel::InStore),
+            (None, None) => None,
+        }
+    }
+
+    /// Reset the daily channel totals
+    pub fn reset_daily(&mut self) {
+        self.online_daily_total = 0;
+        self.instore_daily_total = 0;
+    }
+
+    /// Whether either cap is higher (or less restricted) than in `current`
+    pub fn is_increase_over(&self, current: &ChannelLimits) -> bool {
+        let online = Some(TransactionChannel::Online);
+        let instore = Some(TransactionChannel::InStore);
+        self.daily_cap(online) > current.daily_cap(online)
+            || self.daily_cap(instore) > current.daily_cap(instore)
+    }
+}
+
+/// Summary of the last recorded transaction, kept for chargeback/dispute lookups
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LastTxn {
+    pub amount: u64,
+    pub merchant_id: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub slot: u64,
+    /// Caller-supplied reference (e.g. an invoice number) for reconciliation
+    pub reference: Option<[u8; 32]>,
+}
+
+impl LastTxn {
+    pub const SIZE: usize = 8 + // amount
+        1 + 32 + // merchant_id option
+        1 + 2 + // mcc_code option
+        8 + // slot
+        1 + 32; // reference option
+}
+
+/// Temporary extra daily headroom (e.g. for travel or a large purchase)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LimitBoost {
+    /// Added to the daily limit while active, in base units
+    pub amount: u64,
+
+    /// When the boost stops applying
+    pub expires_at: i64,
+}
+
+impl LimitBoost {
+    pub const SIZE: usize = 8 + 8;
+
+    /// Whether the boost still applies
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// An amount reserved against velocity headroom while a transaction is disputed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Hold {
+    pub id: u32,
+    pub amount: u64,
+    pub placed_by: Pubkey,
+    pub placed_at: i64,
+}
+
+impl Hold {
+    pub const SIZE: usize = 4 + 8 + 32 + 8;
+}
+
+// ============================================================================
+// Decline Statistics
+// ============================================================================
+
+/// Broad reason a transfer was declined
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeclineCategory {
+    /// Velocity limit reached (soft: clears when the period resets)
+    Velocity,
+    /// Merchant blocked or not whitelisted (hard)
+    Merchant,
+    /// MCC blocked or not whitelisted (hard)
+    Mcc,
+    /// Card frozen (hard)
+    Frozen,
+}
+
+impl DeclineCategory {
+    /// Categorize a hook rejection code; other codes aren't tracked
+    pub fn from_rejection_code(code: u8) -> Option<Self> {
+        match code {
+            50..=57 => Some(DeclineCategory::Velocity),
+            30..=32 => Some(DeclineCategory::Merchant),
+            40..=41 => Some(DeclineCategory::Mcc),
+            21 => Some(DeclineCategory::Frozen),
+            _ => None,
+        }
+    }
+
+    /// Whether the decline is temporary rather than a policy violation
+    pub fn is_soft(&self) -> bool {
+        matches!(self, DeclineCategory::Velocity)
+    }
+}
+
+/// Lifetime decline counts per category
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DeclineStats {
+    pub velocity: u32,
+    pub merchant: u32,
+    pub mcc: u32,
+    pub frozen: u32,
+}
+
+impl DeclineStats {
+    pub const SIZE: usize = 4 + 4 + 4 + 4;
+
+    /// Count one decline (saturating)
+    pub fn record(&mut self, category: DeclineCategory) {
+        let counter = match category {
+            DeclineCategory::Velocity => &mut self.velocity,
+            DeclineCategory::Merchant => &mut self.merchant,
+            DeclineCategory::Mcc => &mut self.mcc,
+            DeclineCategory::Frozen => &mut self.frozen,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    /// Temporary-limit declines
+    pub fn soft_declines(&self) -> u32 {
+        self.velocity
+    }
+
+    /// Policy-violation declines
+    pub fn hard_declines(&self) -> u32 {
+        self.merchant.saturating_add(self.mcc).saturating_add(self.frozen)
+    }
+}
+
+// ============================================================================
+// Delegates
+// ============================================================================
+
+/// A DID granted a subset of card management permissions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Delegate {
+    pub did_hash: [u8; 32],
+
+    /// Bitmask of `PERMISSION_*` flags
+    pub permissions: u8,
+}
+
+impl Delegate {
+    pub const SIZE: usize = 32 + 1;
+}
+
+// ============================================================================
+// Merchant Whitelist
+// ============================================================================
+
+/// A whitelisted merchant, optionally allowed only until `expires_at`
+///
+/// Whitelist entries used to be bare `[u8; 32]` merchant IDs; cards from
+/// then are rewritten by `migrate_card_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct WhitelistedMerchant {
+    pub merchant_id: [u8; 32],
+    pub expires_at: Option<i64>,
+}
+
+impl WhitelistedMerchant {
+    pub const SIZE: usize = 32 + 9;
+
+    /// Whether a temporary allowance has lapsed
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
     }
 }
 
+// ============================================================================
+// Merchant Blocklist
+// ============================================================================
+
+/// A blocked merchant and why it was blocked
+///
+/// Blocklist entries used to be bare `[u8; 32]` merchant IDs; cards from
+/// then are rewritten by `migrate_card_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BlockedMerchant {
+    pub merchant_id: [u8; 32],
+    pub reason: BlockReason,
+    pub blocked_at: i64,
+}
+
+impl BlockedMerchant {
+    pub const SIZE: usize = 32 + 1 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockReason {
+    Fraud,
+    UserChoice,
+    Compliance,
+    Dispute,
+}
+
+/// Identifies one of a card's merchant/MCC lists
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListKind {
+    MerchantWhitelist,
+    MerchantBlocklist,
+    MccWhitelist,
+    MccBlocklist,
+}
+
 // ============================================================================
 // Freeze Information
 // ============================================================================
@@ -369,13 +1695,70 @@ pub struct FreezeInfo {
 
     /// Optional expiry (auto-unfreeze)
     pub expires_at: Option<i64>,
+
+    /// Which transactions the freeze applies to
+    pub scope: FreezeScope,
 }
 
 impl FreezeInfo {
+    pub const SIZE: usize = 1 + 32 + 8 + 9 + FreezeScope::SIZE;
+
+    /// Check if the freeze blocks a transaction.
+    ///
+    /// A full freeze blocks everything. Scoped freezes fail closed: they pass
+    /// only transactions that declare a different channel or MCC, so one that
+    /// leaves the scoped field unknown is blocked.
+    pub fn applies_to(&self, channel: Option<TransactionChannel>, mcc_code: Option<u16>) -> bool {
+        match self.scope {
+            FreezeScope::All => true,
+            FreezeScope::Channel(frozen) => match channel {
+                Some(channel) => channel == frozen,
+                None => true,
+            },
+            FreezeScope::Mcc(frozen) => match mcc_code {
+                Some(mcc_code) => mcc_code == frozen,
+                None => true,
+            },
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FreezeScope {
+    /// Block every transaction
+    #[default]
+    All,
+    /// Block transactions through a single channel (e.g. ATM)
+    Channel(TransactionChannel),
+    /// Block transactions in a single merchant category
+    Mcc(u16),
+}
+
+impl FreezeScope {
+    pub const SIZE: usize = 1 + 2; // variant tag + largest payload (u16)
+}
+
+/// A past freeze retained for investigations
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FreezeRecord {
+    /// Reason for freeze
+    pub reason: FreezeReason,
+
+    /// Who initiated the freeze
+    pub frozen_by: Pubkey,
+
+    /// When frozen
+    pub frozen_at: i64,
+
+    /// When unfrozen (None while the freeze is still active)
+    pub unfrozen_at: Option<i64>,
+}
+
+impl FreezeRecord {
     pub const SIZE: usize = 1 + 32 + 8 + 9;
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub enum FreezeReason {
     FraudDetected,
     UserRequest,
@@ -387,60 +1770,1496 @@ pub enum FreezeReason {
 }
 
 // ============================================================================
-// Global Configuration
+// DID Controller
 // ============================================================================
 
+/// Binds a DID hash to the key currently controlling it.
+///
+/// Written by an authorized DID service once it has verified the signer
+/// controls the key committed in the DID document. Owner-gated instructions
+/// require the signer to present the controller record for the card's DID.
+///
+/// PDA seeds: [b"did_controller", did_hash]
 #[account]
-pub struct GlobalConfig {
+#[derive(Default)]
+pub struct DidController {
     /// PDA bump seed
     pub bump: u8,
 
-    /// Program admin
-    pub admin: Pubkey,
-
-    /// Whether the entire program is paused
-    pub is_paused: bool,
-
-    /// Authorized velocity reset authorities (cron services)
-    pub reset_authorities: Vec<Pubkey>,
-
-    /// Authorized fraud detection services
-    pub fraud_authorities: Vec<Pubkey>,
+    /// DID commitment hash
+    pub did_hash: [u8; 32],
 
-    /// Default velocity limits for new cards
-    pub default_velocity_limits: VelocityLimits,
+    /// Key that currently controls the DID
+    pub controller: Pubkey,
 
-    /// Global statistics
-    pub total_cards: u64,
-    pub total_transactions: u64,
-    pub total_volume: u64,
+    /// DID service that attested the binding
+    pub attested_by: Pubkey,
 
     /// Timestamps
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-impl GlobalConfig {
+impl DidController {
     pub const SIZE: usize = 8 + // discriminator
         1 + // bump
-        32 + // admin
-        1 + // is_paused
-        4 + (32 * 10) + // reset_authorities
-        4 + (32 * 10) + // fraud_authorities
-        VelocityLimits::SIZE +
-        8 + // total_cards
-        8 + // total_transactions
-        8 + // total_volume
+        32 + // did_hash
+        32 + // controller
+        32 + // attested_by
         8 + // created_at
         8; // updated_at
 
-    /// Check if a pubkey is an authorized reset authority
-    pub fn is_authorized_reset_authority(&self, authority: Pubkey) -> bool {
-        self.admin == authority || self.reset_authorities.contains(&authority)
+    /// Check if `authority` is the key controlling this DID
+    pub fn is_controlled_by(&self, authority: Pubkey) -> bool {
+        self.controller == authority
     }
 
-    /// Check if a pubkey is an authorized fraud authority
-    pub fn is_authorized_fraud_authority(&self, authority: Pubkey) -> bool {
-        self.admin == authority || self.fraud_authorities.contains(&authority)
+    /// Check if `authority` controls this DID and it is `did_hash`
+    pub fn proves(&self, authority: Pubkey, did_hash: &[u8; 32]) -> bool {
+        self.is_controlled_by(authority) && self.did_hash == *did_hash
+    }
+
+    /// Check if `authority` controls this DID and it is one of the card's owners
+    pub fn proves_owner_of(&self, authority: Pubkey, card_config: &CardConfig) -> bool {
+        self.is_controlled_by(authority) && card_config.is_owner_did(&self.did_hash)
+    }
+
+    /// Check if `authority` controls this DID and it is an owner of the card or
+    /// a delegate holding `permission`
+    pub fn proves_permission_on(
+        &self,
+        authority: Pubkey,
+        card_config: &CardConfig,
+        permission: u8,
+    ) -> bool {
+        self.is_controlled_by(authority) && card_config.is_authorized_did(&self.did_hash, permission)
+    }
+
+    /// Check if `authority` controls this DID and it is a delegate of the
+    /// card holding `permission` (owners don't count)
+    pub fn proves_delegate_on(
+        &self,
+        authority: Pubkey,
+        card_config: &CardConfig,
+        permission: u8,
+    ) -> bool {
+        self.is_controlled_by(authority) && card_config.is_delegate_with(&self.did_hash, permission)
+    }
+}
+
+// ============================================================================
+// Owner Index
+// ============================================================================
+
+/// Number of live (non-terminated) cards created for an owner DID
+///
+/// Caps how many cards a single compromised DID can spin up.
+///
+/// PDA seeds: [b"owner_index", owner_did_hash]
+#[account]
+#[derive(Default)]
+pub struct OwnerIndex {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// The owner DID this index counts cards for
+    pub owner_did_hash: [u8; 32],
+
+    /// Live cards currently counted against the owner
+    pub card_count: u32,
+}
+
+impl OwnerIndex {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // owner_did_hash
+        4; // card_count
+
+    /// Count a new card, failing once `max_cards` is reached (0 = unlimited)
+    pub fn register_card(&mut self, owner_did_hash: [u8; 32], bump: u8, max_cards: u32) -> Result<()> {
+        if max_cards > 0 && self.card_count >= max_cards {
+            return Err(error!(crate::errors::HookError::CardLimitReached));
+        }
+        self.bump = bump;
+        self.owner_did_hash = owner_did_hash;
+        self.card_count = self.card_count
+            .checked_add(1)
+            .ok_or(error!(crate::errors::HookError::Overflow))?;
+        Ok(())
+    }
+
+    /// Stop counting a card that was closed
+    pub fn release_card(&mut self) {
+        self.card_count = self.card_count.saturating_sub(1);
+    }
+}
+
+// ============================================================================
+// Card Token Link
+// ============================================================================
+
+/// Maps a card's token account to its card ID
+///
+/// Token-2022 resolves the hook's extra accounts from the transfer accounts
+/// alone, so it reads `card_id` from this link to derive the card config PDA,
+/// and `statement_period` to derive the open statement. Both sit at fixed
+/// offsets read by the extra account metas.
+///
+/// Platform token accounts (treasury, merchant settlement, refunds) get a
+/// link with `PLATFORM_CARD_ID` so Token-2022 can still resolve the extras
+/// when they send funds; no card config exists under that ID.
+///
+/// PDA seeds: [b"card_link", token_account]
+#[account]
+#[derive(Default)]
+pub struct CardTokenLink {
+    /// Card the token account belongs to
+    pub card_id: [u8; 32],
+
+    /// Period of the card's most recently opened statement
+    pub statement_period: u32,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl CardTokenLink {
+    pub const SEED: &'static [u8] = b"card_link";
+
+    /// Card ID of links for token accounts that don't belong to a card
+    pub const PLATFORM_CARD_ID: [u8; 32] = [0; 32];
+
+    /// Byte offset of `card_id` in the account data (after the discriminator)
+    pub const CARD_ID_OFFSET: u8 = 8;
+
+    /// Byte offset of `statement_period` in the account data
+    pub const STATEMENT_PERIOD_OFFSET: u8 = 8 + 32;
+
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // card_id
+        4 + // statement_period
+        1; // bump
+
+    /// Whether the linked token account is a platform account, not a card
+    pub fn is_platform_account(&self) -> bool {
+        self.card_id == Self::PLATFORM_CARD_ID
+    }
+}
+
+// ============================================================================
+// Step-Up Attestation
+// ============================================================================
+
+/// Default validity window for a step-up attestation (5 minutes)
+pub const DEFAULT_STEP_UP_VALIDITY_SECS: i64 = 300;
+/// Maximum validity window for a step-up attestation (1 hour)
+pub const MAX_STEP_UP_VALIDITY_SECS: i64 = 3600;
+
+/// PDA seeds: [b"step_up", card_config.key()]
+#[account]
+#[derive(Default)]
+pub struct StepUpAttestation {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// The card configuration this attestation applies to
+    pub card_config: Pubkey,
+
+    /// The authentication service that issued the attestation
+    pub attested_by: Pubkey,
+
+    /// When the user completed 2FA/biometric verification
+    pub verified_at: i64,
+
+    /// When the attestation stops satisfying step-up requirements
+    pub expires_at: i64,
+
+    /// Whether the verification included PIN entry (chip+PIN fallback)
+    pub pin_verified: bool,
+
+    /// Channel the verified transaction is taking place on, as seen by the
+    /// authentication service (drives the online/in-store daily caps)
+    pub channel: Option<TransactionChannel>,
+}
+
+impl StepUpAttestation {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // card_config
+        32 + // attested_by
+        8 + // verified_at
+        8 + // expires_at
+        1 + // pin_verified
+        1 + 1; // channel option
+
+    /// Check if the attestation is still valid
+    pub fn is_valid(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
+// ============================================================================
+// Manual Approval
+// ============================================================================
+
+/// Maximum lifetime of an owner-issued approval token (1 day)
+pub const MAX_APPROVAL_VALIDITY_SECS: i64 = 86_400;
+
+/// Owner pre-approval for one large transfer
+///
+/// PDA seeds: [b"approval", card_config.key()]
+#[account]
+#[derive(Default)]
+pub struct ApprovalToken {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// The card configuration this approval applies to
+    pub card_config: Pubkey,
+
+    /// Exact transfer amount approved
+    pub amount: u64,
+
+    /// Merchant the transfer must go to (None approves any merchant)
+    pub merchant_id: Option<[u8; 32]>,
+
+    /// The owner key that issued the approval
+    pub approved_by: Pubkey,
+
+    /// When the approval was issued
+    pub created_at: i64,
+
+    /// When the approval lapses
+    pub expires_at: i64,
+
+    /// Set once a transfer has used the approval
+    pub consumed: bool,
+}
+
+impl ApprovalToken {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // card_config
+        8 + // amount
+        1 + 32 + // merchant_id option
+        32 + // approved_by
+        8 + // created_at
+        8 + // expires_at
+        1; // consumed
+
+    /// Whether the approval covers this transfer
+    pub fn covers(&self, amount: u64, merchant_id: Option<[u8; 32]>, now: i64) -> bool {
+        !self.consumed
+            && now < self.expires_at
+            && self.amount == amount
+            && self.merchant_id.is_none_or(|approved| merchant_id == Some(approved))
+    }
+}
+
+// ============================================================================
+// Fraud Score
+// ============================================================================
+
+/// How long a submitted fraud score can be used by the hook (1 minute)
+pub const FRAUD_SCORE_VALIDITY_SECS: i64 = 60;
+
+/// PDA seeds: [b"fraud_score", card_config.key()]
+#[account]
+#[derive(Default)]
+pub struct FraudScore {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// The card configuration this score applies to
+    pub card_config: Pubkey,
+
+    /// Fraud model score (higher is riskier)
+    pub score: u8,
+
+    /// The fraud service that submitted the score
+    pub scored_by: Pubkey,
+
+    /// When the score was submitted
+    pub scored_at: i64,
+
+    /// When the score stops being used by the hook
+    pub expires_at: i64,
+}
+
+impl FraudScore {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // card_config
+        1 + // score
+        32 + // scored_by
+        8 + // scored_at
+        8; // expires_at
+
+    /// Check if the score is still fresh
+    pub fn is_valid(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
+// ============================================================================
+// Monthly Statement (Per-Card, Per-Period)
+// ============================================================================
+
+/// PDA seeds: [b"statement", card_config.key(), &period.to_le_bytes()]
+#[account]
+#[derive(Default)]
+pub struct MonthlyStatement {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// The card configuration this statement belongs to
+    pub card_config: Pubkey,
+
+    /// Statement period (matches `CardConfig.statement_period` while open)
+    pub period: u32,
+
+    /// Total amount spent during the period
+    pub total_spent: u64,
+
+    /// Number of transactions recorded during the period
+    pub transaction_count: u32,
+
+    /// Spending broken down by MCC (bounded to MAX_STATEMENT_MCC_BUCKETS)
+    pub mcc_histogram: Vec<MccBucket>,
+
+    /// Spending with no MCC or that did not fit in the histogram
+    pub other_total: u64,
+    pub other_count: u32,
+
+    /// Whether the statement has been finalized
+    pub is_finalized: bool,
+
+    /// Timestamps
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+}
+
+impl MonthlyStatement {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // card_config
+        4 + // period
+        8 + // total_spent
+        4 + // transaction_count
+        4 + (MccBucket::SIZE * MAX_STATEMENT_MCC_BUCKETS) + // mcc_histogram vec
+        8 + // other_total
+        4 + // other_count
+        1 + // is_finalized
+        8 + // opened_at
+        9; // closed_at option
+
+    /// Whether this is the card's open statement for its current period
+    pub fn is_open_for(&self, card_config: Pubkey, period: u32) -> bool {
+        self.card_config == card_config && self.period == period && !self.is_finalized
+    }
+
+    /// Accumulate a transaction into the statement totals
+    pub fn record(&mut self, amount: u64, mcc_code: Option<u16>) -> Result<()> {
+        self.total_spent = self.total_spent
+            .checked_add(amount)
+            .ok_or(error!(crate::errors::HookError::Overflow))?;
+        self.transaction_count = self.transaction_count
+            .checked_add(1)
+            .ok_or(error!(crate::errors::HookError::Overflow))?;
+
+        let bucket = match mcc_code {
+            Some(mcc) => match self.mcc_histogram.iter().position(|b| b.mcc_code == mcc) {
+                Some(pos) => Some(pos),
+                None if self.mcc_histogram.len() < MAX_STATEMENT_MCC_BUCKETS => {
+                    self.mcc_histogram.push(MccBucket { mcc_code: mcc, count: 0, total: 0 });
+                    Some(self.mcc_histogram.len() - 1)
+                }
+                None => None,
+            },
+            None => None,
+        };
+
+        match bucket {
+            Some(pos) => {
+                let bucket = &mut self.mcc_histogram[pos];
+                bucket.count = bucket.count.saturating_add(1);
+                bucket.total = bucket.total.saturating_add(amount);
+            }
+            None => {
+                self.other_count = self.other_count.saturating_add(1);
+                self.other_total = self.other_total.saturating_add(amount);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct MccBucket {
+    /// Merchant Category Code
+    pub mcc_code: u16,
+
+    /// Number of transactions in this category
+    pub count: u32,
+
+    /// Total spent in this category
+    pub total: u64,
+}
+
+impl MccBucket {
+    pub const SIZE: usize = 2 + 4 + 8;
+}
+
+// ============================================================================
+// Statistics Snapshot (Per-Period)
+// ============================================================================
+
+/// PDA seeds: [b"stats_snapshot", &period_start.to_le_bytes()]
+#[account]
+pub struct StatsSnapshot {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// Start of the period (`GlobalConfig.stats_since` when taken)
+    pub period_start: i64,
+
+    /// When the snapshot was taken; the next period starts here
+    pub period_end: i64,
+
+    /// Transactions recorded during the period
+    pub total_transactions: u64,
+
+    /// Volume recorded during the period
+    pub total_volume: u64,
+
+    /// Live card count when the snapshot was taken (not reset)
+    pub total_cards: u64,
+}
+
+impl StatsSnapshot {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        8 + // period_start
+        8 + // period_end
+        8 + // total_transactions
+        8 + // total_volume
+        8; // total_cards
+}
+
+// ============================================================================
+// Global Configuration
+// ============================================================================
+
+#[account]
+pub struct GlobalConfig {
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// Program admin
+    pub admin: Pubkey,
+
+    /// Proposed new admin awaiting acceptance
+    pub pending_admin: Option<Pubkey>,
+
+    /// Whether the entire program is paused
+    pub is_paused: bool,
+
+    /// Total protocol halt: the hook rejects every transfer, including
+    /// system transfers that normally bypass card rules
+    pub kill_switch: bool,
+
+    /// Key allowed to engage the kill switch (a hardware-key multisig vault)
+    pub kill_switch_authority: Option<Pubkey>,
+
+    /// Authorized velocity reset authorities (cron services)
+    pub reset_authorities: Vec<Pubkey>,
+
+    /// Authorized fraud detection services
+    pub fraud_authorities: Vec<Pubkey>,
+
+    /// Authorized authentication services (issue step-up attestations)
+    pub auth_authorities: Vec<Pubkey>,
+
+    /// Authorized settlement services (record transactions)
+    pub settlement_authorities: Vec<Pubkey>,
+
+    /// Platform token accounts (treasury, funding) whose transfers skip card rules
+    pub system_transfer_accounts: Vec<Pubkey>,
+
+    /// Default velocity limits for new cards
+    pub default_velocity_limits: VelocityLimits,
+
+    /// Highest velocity limits any card may set, in whole display units
+    /// (all zero = `VelocityLimits::GENEROUS_DEFAULTS`)
+    pub max_allowed_velocity_limits: VelocityLimits,
+
+    /// Minimum slots between two velocity limit increases on a card
+    pub min_slots_between_limit_increases: u64,
+
+    /// Weekday weekly velocity periods start on (0 = Sunday .. 6 = Saturday)
+    pub week_start_day: u8,
+
+    /// Whether card activity resets elapsed velocity periods on the fly
+    ///
+    /// When false, only the reset authorities' scheduled reset instructions
+    /// reset counters, so a cron reset and a lazy reset can never race.
+    pub lazy_reset_enabled: bool,
+
+    /// Home country for new cards that don't specify one (policy form)
+    pub default_home_country: u16,
+
+    /// Maximum live cards per owner DID (0 = unlimited)
+    pub max_cards_per_owner: u32,
+
+    /// Contactless limit for new cards, in whole display units (0 = $100)
+    pub default_contactless_limit: u64,
+
+    /// Global statistics (transactions count settled spend from
+    /// `record_transaction`; the transfer hooks leave them alone so every
+    /// transfer doesn't write-lock this account)
+    pub total_cards: u64,
+    pub total_transactions: u64,
+    pub total_volume: u64,
+
+    /// When the transaction/volume totals were last snapshotted and zeroed
+    /// (0 = never; they count since deployment)
+    pub stats_since: i64,
+
+    /// Timestamps
+    pub created_at: i64,
+    pub updated_at: i64,
+
+    /// Whether the transfer hooks log per-transfer details
+    ///
+    /// Off by default: every `msg!` costs compute in the authorization path,
+    /// so only the final decision is logged unless this is on. Appended last
+    /// so a grown singleton's zeroed trailing space reads as off.
+    pub verbose_logging: bool,
+}
+
+impl GlobalConfig {
+    pub const SIZE: usize = 8 + // discriminator
+        1 + // bump
+        32 + // admin
+        1 + 32 + // pending_admin option
+        1 + // is_paused
+        1 + // kill_switch
+        1 + 32 + // kill_switch_authority option
+        4 + (32 * MAX_AUTHORITIES) + // reset_authorities
+        4 + (32 * MAX_AUTHORITIES) + // fraud_authorities
+        4 + (32 * MAX_AUTHORITIES) + // auth_authorities
+        4 + (32 * MAX_SETTLEMENT_AUTHORITIES) + // settlement_authorities
+        4 + (32 * MAX_SYSTEM_TRANSFER_ACCOUNTS) + // system_transfer_accounts
+        VelocityLimits::SIZE + // default_velocity_limits
+        VelocityLimits::SIZE + // max_allowed_velocity_limits
+        8 + // min_slots_between_limit_increases
+        1 + // week_start_day
+        1 + // lazy_reset_enabled
+        2 + // default_home_country
+        4 + // max_cards_per_owner
+        8 + // default_contactless_limit
+        8 + // total_cards
+        8 + // total_transactions
+        8 + // total_volume
+        8 + // stats_since
+        8 + // created_at
+        8 + // updated_at
+        1; // verbose_logging
+
+    /// The velocity ceiling in base units for a mint with `decimals`
+    pub fn velocity_ceiling(&self, decimals: u8) -> VelocityLimits {
+        // A zero field is an unlimited ceiling for that field, so only an
+        // entirely unset ceiling falls back to the defaults
+        let max = &self.max_allowed_velocity_limits;
+        let unset = max.per_transaction == 0 && max.daily == 0 && max.weekly == 0 && max.monthly == 0;
+        let ceiling = if unset {
+            VelocityLimits::GENEROUS_DEFAULTS
+        } else {
+            self.max_allowed_velocity_limits
+        };
+        ceiling.to_base_units(decimals)
+    }
+
+    /// The contactless limit new cards get, in base units of a mint with `decimals`
+    pub fn default_contactless_limit(&self, decimals: u8) -> u64 {
+        let limit = if self.default_contactless_limit == 0 {
+            DEFAULT_CONTACTLESS_LIMIT
+        } else {
+            self.default_contactless_limit
+        };
+        to_base_units(limit, decimals)
+    }
+
+    /// Count a newly initialized card
+    pub fn record_card_created(&mut self) -> Result<()> {
+        self.total_cards = self.total_cards
+            .checked_add(1)
+            .ok_or(error!(crate::errors::HookError::Overflow))?;
+        Ok(())
+    }
+
+    /// Count a recorded transaction
+    pub fn record_transaction(&mut self, amount: u64) -> Result<()> {
+        self.total_transactions = self.total_transactions
+            .checked_add(1)
+            .ok_or(error!(crate::errors::HookError::Overflow))?;
+        self.total_volume = self.total_volume
+            .checked_add(amount)
+            .ok_or(error!(crate::errors::HookError::Overflow))?;
+        Ok(())
+    }
+
+    /// Check if a pubkey is an authorized reset authority
+    pub fn is_authorized_reset_authority(&self, authority: Pubkey) -> bool {
+        self.admin == authority || self.reset_authorities.contains(&authority)
+    }
+
+    /// Check if a pubkey is an authorized fraud authority
+    pub fn is_authorized_fraud_authority(&self, authority: Pubkey) -> bool {
+        self.admin == authority || self.fraud_authorities.contains(&authority)
+    }
+
+    /// Check if a pubkey is an authorized authentication service
+    pub fn is_authorized_auth_authority(&self, authority: Pubkey) -> bool {
+        self.admin == authority || self.auth_authorities.contains(&authority)
+    }
+
+    /// Check if a token account is an admin-listed platform account
+    pub fn is_system_transfer_account(&self, account: Pubkey) -> bool {
+        self.system_transfer_accounts.contains(&account)
+    }
+
+    /// Check if a pubkey is an authorized settlement service
+    pub fn is_authorized_settlement_authority(&self, authority: Pubkey) -> bool {
+        self.admin == authority || self.settlement_authorities.contains(&authority)
+    }
+
+    /// The authority list for `role` and its capacity
+    pub fn authorities_mut(&mut self, role: AuthorityRole) -> (&mut Vec<Pubkey>, usize) {
+        match role {
+            AuthorityRole::Reset => (&mut self.reset_authorities, MAX_AUTHORITIES),
+            AuthorityRole::Fraud => (&mut self.fraud_authorities, MAX_AUTHORITIES),
+            AuthorityRole::Auth => (&mut self.auth_authorities, MAX_AUTHORITIES),
+            AuthorityRole::Settlement => (&mut self.settlement_authorities, MAX_SETTLEMENT_AUTHORITIES),
+        }
+    }
+}
+
+/// Service authority lists maintained by the admin
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthorityRole {
+    /// Velocity reset services (`reset_authorities`)
+    Reset,
+    /// Fraud detection services (`fraud_authorities`)
+    Fraud,
+    /// Authentication services issuing step-up attestations (`auth_authorities`)
+    Auth,
+    /// Settlement services recording transactions (`settlement_authorities`)
+    Settlement,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialized length of an account, discriminator included
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.len()
+    }
+
+    fn full_limits() -> VelocityLimits {
+        VelocityLimits {
+            daily_pct_of_balance: Some(u8::MAX),
+            ..Default::default()
+        }
+    }
+
+    /// A card with every list at capacity and every option set to its largest variant
+    fn full_card_config() -> CardConfig {
+        CardConfig {
+            additional_owner_did_hashes: vec![[1; 32]; MAX_ADDITIONAL_OWNERS],
+            delegates: vec![Delegate { did_hash: [2; 32], permissions: u8::MAX }; MAX_DELEGATES],
+            pending_owner: Some([3; 32]),
+            expires_at: Some(i64::MAX),
+            policy: CardPolicy {
+                require_2fa_above: Some(u64::MAX),
+                allowed_countries: vec![1; MAX_POLICY_COUNTRIES],
+                blocked_countries: vec![2; MAX_POLICY_COUNTRIES],
+                auto_freeze_after_breaches: Some(u16::MAX),
+                max_fraud_score: Some(u8::MAX),
+                max_daily_per_merchant: Some(u16::MAX),
+                allow_by_max_risk_tier: Some(u8::MAX),
+                manual_approval_above: Some(u64::MAX),
+                ..Default::default()
+            },
+            velocity_limits: full_limits(),
+            recent_merchant_counts: vec![MerchantCount::default(); MAX_RECENT_MERCHANTS],
+            recent_nonces: vec![u64::MAX; MAX_RECENT_NONCES],
+            holds: vec![Hold::default(); MAX_HOLDS],
+            merchant_whitelist: vec![
+                WhitelistedMerchant { merchant_id: [4; 32], expires_at: Some(i64::MAX) };
+                MAX_MERCHANTS
+            ],
+            merchant_blocklist: vec![
+                BlockedMerchant { merchant_id: [5; 32], reason: BlockReason::Fraud, blocked_at: 0 };
+                MAX_MERCHANTS
+            ],
+            mcc_whitelist: vec![1; MAX_MCC_CODES],
+            mcc_bitmap: vec![u8::MAX; MCC_BITMAP_BYTES],
+            mcc_blocklist: vec![2; MAX_MCC_CODES],
+            freeze_info: Some(FreezeInfo {
+                reason: FreezeReason::AdminAction,
+                frozen_by: Pubkey::default(),
+                frozen_at: 0,
+                expires_at: Some(i64::MAX),
+                scope: FreezeScope::Mcc(u16::MAX),
+            }),
+            freeze_history: vec![
+                FreezeRecord {
+                    reason: FreezeReason::AdminAction,
+                    frozen_by: Pubkey::default(),
+                    frozen_at: 0,
+                    unfrozen_at: Some(i64::MAX),
+                };
+                MAX_FREEZE_HISTORY
+            ],
+            encrypted_daily_total: Some([6; 64]),
+            encrypted_weekly_total: Some([7; 64]),
+            encrypted_monthly_total: Some([8; 64]),
+            encrypted_balance_handle: Some([9; 16]),
+            inco_public_key: Some([10; 32]),
+            last_transaction_at: Some(i64::MAX),
+            last_transaction: Some(LastTxn {
+                amount: u64::MAX,
+                merchant_id: Some([11; 32]),
+                mcc_code: Some(u16::MAX),
+                slot: u64::MAX,
+                reference: Some([12; 32]),
+            }),
+            temporary_limit_boost: Some(LimitBoost { amount: u64::MAX, expires_at: i64::MAX }),
+            destination_allowlist: vec![Pubkey::default(); MAX_DESTINATIONS],
+            reissued_to: Some([13; 32]),
+            channel_limits: ChannelLimits {
+                online_daily: Some(u64::MAX),
+                instore_daily: Some(u64::MAX),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn full_card_config_fits_size() {
+        assert_eq!(serialized_len(&full_card_config()), CardConfig::SIZE);
+    }
+
+    #[test]
+    fn full_global_config_fits_size() {
+        let authorities = vec![Pubkey::default(); MAX_AUTHORITIES];
+        let config = GlobalConfig {
+            bump: 0,
+            admin: Pubkey::default(),
+            pending_admin: Some(Pubkey::default()),
+            is_paused: false,
+            kill_switch: false,
+            kill_switch_authority: Some(Pubkey::default()),
+            reset_authorities: authorities.clone(),
+            fraud_authorities: authorities.clone(),
+            auth_authorities: authorities,
+            settlement_authorities: vec![Pubkey::default(); MAX_SETTLEMENT_AUTHORITIES],
+            system_transfer_accounts: vec![Pubkey::default(); MAX_SYSTEM_TRANSFER_ACCOUNTS],
+            default_velocity_limits: full_limits(),
+            max_allowed_velocity_limits: full_limits(),
+            min_slots_between_limit_increases: 0,
+            week_start_day: 0,
+            lazy_reset_enabled: false,
+            default_home_country: 0,
+            max_cards_per_owner: 0,
+            default_contactless_limit: 0,
+            total_cards: 0,
+            total_transactions: 0,
+            total_volume: 0,
+            stats_since: 0,
+            created_at: 0,
+            updated_at: 0,
+            verbose_logging: false,
+        };
+
+        assert_eq!(serialized_len(&config), GlobalConfig::SIZE);
+    }
+
+    fn assert_rejected<T>(result: Result<T>, expected: crate::errors::HookError) {
+        match result {
+            Ok(_) => panic!("expected {expected:?}, got Ok"),
+            Err(err) => assert_eq!(crate::errors::rejection_code(&err), expected.rejection_code()),
+        }
+    }
+
+    fn card_requiring_2fa_above(threshold: u64) -> CardConfig {
+        let mut card = active_card(VelocityLimits::default());
+        card.policy.require_2fa_above = Some(threshold);
+        card
+    }
+
+    #[test]
+    fn transfers_above_the_2fa_threshold_need_step_up() {
+        let card = card_requiring_2fa_above(500);
+        let unverified = TransactionContext::default();
+        let verified = TransactionContext { step_up_verified: true, ..Default::default() };
+
+        assert!(card.is_transaction_allowed(500, &unverified).is_ok());
+        assert_rejected(
+            card.is_transaction_allowed(501, &unverified),
+            crate::errors::HookError::TwoFactorRequired,
+        );
+        assert!(card.is_transaction_allowed(501, &verified).is_ok());
+    }
+
+    #[test]
+    fn biometric_cards_need_step_up_for_any_amount() {
+        let mut card = active_card(VelocityLimits::default());
+        card.policy.require_biometric = true;
+
+        assert_rejected(
+            card.is_transaction_allowed(1, &TransactionContext::default()),
+            crate::errors::HookError::BiometricRequired,
+        );
+        let verified = TransactionContext { step_up_verified: true, ..Default::default() };
+        assert!(card.is_transaction_allowed(1, &verified).is_ok());
+    }
+
+    #[test]
+    fn fraud_score_over_threshold_is_declined() {
+        let mut card = active_card(VelocityLimits::default());
+        card.policy.max_fraud_score = Some(70);
+        let scored = |score| TransactionContext { fraud_score: Some(score), ..Default::default() };
+
+        assert!(card.is_transaction_allowed(10, &scored(69)).is_ok());
+        assert!(card.is_transaction_allowed(10, &scored(70)).is_ok());
+        assert_rejected(
+            card.is_transaction_allowed(10, &scored(71)),
+            crate::errors::HookError::FraudScoreExceeded,
+        );
+    }
+
+    #[test]
+    fn missing_fraud_score_fails_closed_only_with_a_threshold() {
+        let mut card = active_card(VelocityLimits::default());
+        assert!(card.is_transaction_allowed(10, &TransactionContext::default()).is_ok());
+
+        card.policy.max_fraud_score = Some(70);
+        assert_rejected(
+            card.is_transaction_allowed(10, &TransactionContext::default()),
+            crate::errors::HookError::FraudScoreExceeded,
+        );
+    }
+
+    #[test]
+    fn global_totals_count_cards_and_transactions() {
+        let mut config = crate::test_utils::zeroed_global_config();
+
+        config.record_card_created().unwrap();
+        config.record_transaction(250).unwrap();
+        config.record_transaction(750).unwrap();
+
+        assert_eq!(config.total_cards, 1);
+        assert_eq!(config.total_transactions, 2);
+        assert_eq!(config.total_volume, 1_000);
+
+        config.total_volume = u64::MAX;
+        assert_rejected(config.record_transaction(1), crate::errors::HookError::Overflow);
+    }
+
+    fn weekly_card_after_reset(carryover_pct: u8) -> CardConfig {
+        let mut card = active_card(VelocityLimits { weekly: 1_000, ..Default::default() });
+        card.policy.carryover_pct = carryover_pct;
+        card.reset_weekly(0, 0);
+        card
+    }
+
+    #[test]
+    fn half_of_unused_weekly_limit_carries_over() {
+        let mut card = weekly_card_after_reset(50);
+        assert_eq!(card.effective_weekly_limit(), 1_500);
+        assert!(card.check_velocity_limits(1_500, None, None, 0).is_ok());
+        assert_rejected(
+            card.check_velocity_limits(1_501, None, None, 0),
+            crate::errors::HookError::WeeklyLimitExceeded,
+        );
+
+        // Carryover is judged against the base limit, so it never compounds
+        card.reset_weekly(0, 0);
+        assert_eq!(card.velocity_counters.weekly_carryover, 500);
+    }
+
+    #[test]
+    fn zero_carryover_keeps_the_base_weekly_limit() {
+        let card = weekly_card_after_reset(0);
+
+        assert_eq!(card.effective_weekly_limit(), 1_000);
+        assert_rejected(
+            card.check_velocity_limits(1_001, None, None, 0),
+            crate::errors::HookError::WeeklyLimitExceeded,
+        );
+    }
+
+    #[test]
+    fn display_amounts_scale_to_mint_base_units() {
+        assert_eq!(to_base_units(100, 6), 100_000000);
+        assert_eq!(to_base_units(100, 0), 100);
+        assert_eq!(to_base_units(u64::MAX / 10, 6), u64::MAX);
+        assert_eq!(to_base_units(1, 255), u64::MAX);
+    }
+
+    #[test]
+    fn limit_scaling_leaves_counts_alone() {
+        let limits = VelocityLimits { daily: 100, max_daily_transactions: 7, ..Default::default() };
+
+        let scaled = limits.to_base_units(6);
+
+        assert_eq!(scaled.daily, 100_000000);
+        assert_eq!(scaled.max_daily_transactions, 7);
+    }
+
+    fn card_frozen_for(scope: FreezeScope) -> CardConfig {
+        let mut card = active_card(VelocityLimits::default());
+        card.policy.allow_atm = true;
+        card.policy.allow_online = true;
+        card.freeze_info = Some(FreezeInfo {
+            reason: FreezeReason::SuspiciousActivity,
+            frozen_by: Pubkey::default(),
+            frozen_at: 0,
+            expires_at: None,
+            scope,
+        });
+        card
+    }
+
+    fn on_channel(channel: Option<TransactionChannel>) -> TransactionContext {
+        TransactionContext { channel, ..Default::default() }
+    }
+
+    #[test]
+    fn channel_freeze_blocks_only_that_channel() {
+        let card = card_frozen_for(FreezeScope::Channel(TransactionChannel::Atm));
+
+        assert_rejected(
+            card.is_transaction_allowed(10, &on_channel(Some(TransactionChannel::Atm))),
+            crate::errors::HookError::CardFrozen,
+        );
+        assert!(card.is_transaction_allowed(10, &on_channel(Some(TransactionChannel::Online))).is_ok());
+        assert_rejected(card.is_transaction_allowed(10, &on_channel(None)), crate::errors::HookError::CardFrozen);
+    }
+
+    #[test]
+    fn mcc_freeze_blocks_only_that_category() {
+        let card = card_frozen_for(FreezeScope::Mcc(7995));
+        let with_mcc = |mcc_code| TransactionContext { mcc_code, ..Default::default() };
+
+        assert_rejected(card.is_transaction_allowed(10, &with_mcc(Some(7995))), crate::errors::HookError::CardFrozen);
+        assert!(card.is_transaction_allowed(10, &with_mcc(Some(5411))).is_ok());
+        assert_rejected(card.is_transaction_allowed(10, &with_mcc(None)), crate::errors::HookError::CardFrozen);
+    }
+
+    #[test]
+    fn full_freeze_blocks_everything() {
+        let card = card_frozen_for(FreezeScope::All);
+
+        for channel in [None, Some(TransactionChannel::Online), Some(TransactionChannel::InStore)] {
+            assert_rejected(card.is_transaction_allowed(10, &on_channel(channel)), crate::errors::HookError::CardFrozen);
+        }
+    }
+
+    #[test]
+    fn freeze_history_keeps_the_most_recent_freezes() {
+        let mut card = CardConfig::default();
+
+        for frozen_at in 0..=MAX_FREEZE_HISTORY as i64 {
+            card.record_freeze(FreezeReason::UserRequest, Pubkey::default(), frozen_at);
+            card.record_unfreeze(frozen_at + 100);
+        }
+
+        assert_eq!(card.freeze_history.len(), MAX_FREEZE_HISTORY);
+        assert_eq!(card.freeze_history[0].frozen_at, 1);
+        assert_eq!(card.freeze_history.last().unwrap().frozen_at, MAX_FREEZE_HISTORY as i64);
+    }
+
+    #[test]
+    fn unfreeze_closes_only_the_open_freeze() {
+        let mut card = CardConfig::default();
+        card.record_freeze(FreezeReason::UserRequest, Pubkey::default(), 10);
+        card.record_unfreeze(20);
+        card.record_freeze(FreezeReason::FraudDetected, Pubkey::default(), 30);
+
+        card.record_unfreeze(40);
+        card.record_unfreeze(50);
+
+        let unfrozen: Vec<_> = card.freeze_history.iter().map(|r| r.unfrozen_at).collect();
+        assert_eq!(unfrozen, vec![Some(20), Some(40)]);
+    }
+
+    #[test]
+    fn did_controller_proves_only_its_own_did_and_key() {
+        let controller = Pubkey::new_unique();
+        let record = DidController { did_hash: [1; 32], controller, ..Default::default() };
+
+        assert!(record.proves(controller, &[1; 32]));
+        assert!(!record.proves(Pubkey::new_unique(), &[1; 32]));
+        assert!(!record.proves(controller, &[2; 32]));
+    }
+
+    #[test]
+    fn did_controller_proves_primary_and_joint_owners() {
+        let controller = Pubkey::new_unique();
+        let mut card = CardConfig { owner_did_hash: [1; 32], ..Default::default() };
+        let joint_owner = DidController { did_hash: [2; 32], controller, ..Default::default() };
+
+        assert!(!joint_owner.proves_owner_of(controller, &card));
+        card.additional_owner_did_hashes.push([2; 32]);
+        assert!(joint_owner.proves_owner_of(controller, &card));
+        assert!(!joint_owner.proves_owner_of(Pubkey::new_unique(), &card));
+
+        let primary = DidController { did_hash: [1; 32], controller, ..Default::default() };
+        assert!(primary.proves_owner_of(controller, &card));
+    }
+
+    #[test]
+    fn repeated_velocity_breaches_freeze_the_card() {
+        let mut card = active_card(VelocityLimits::default());
+        card.policy.auto_freeze_after_breaches = Some(3);
+
+        assert!(!card.record_velocity_breach(10));
+        assert!(!card.record_velocity_breach(20));
+        assert_eq!(card.status, CardStatus::Active);
+        assert!(card.record_velocity_breach(30));
+
+        assert_eq!(card.status, CardStatus::Frozen);
+        let freeze_info = card.freeze_info.as_ref().unwrap();
+        assert!(matches!(freeze_info.reason, FreezeReason::VelocityBreach));
+        assert_eq!(freeze_info.frozen_at, 30);
+        assert!(freeze_info.scope == FreezeScope::All);
+        assert_eq!(card.freeze_history.len(), 1);
+        assert!(!card.record_velocity_breach(40));
+    }
+
+    #[test]
+    fn breaches_never_freeze_without_a_threshold() {
+        for threshold in [None, Some(0)] {
+            let mut card = active_card(VelocityLimits::default());
+            card.policy.auto_freeze_after_breaches = threshold;
+
+            for now in 0..10 {
+                assert!(!card.record_velocity_breach(now));
+            }
+            assert!(card.freeze_info.is_none());
+        }
+    }
+
+    #[test]
+    fn daily_reset_clears_the_breach_count() {
+        let mut card = active_card(VelocityLimits::default());
+        card.policy.auto_freeze_after_breaches = Some(2);
+        card.record_velocity_breach(10);
+
+        card.reset_daily(1, 100);
+
+        assert!(!card.record_velocity_breach(110));
+        assert_eq!(card.status, CardStatus::Active);
+    }
+
+    /// Merchant rules for a transaction with no merchant ID under an
+    /// enabled whitelist
+    fn check_unidentified_merchant(strict_merchant_mode: bool) -> Result<()> {
+        let card = CardConfig {
+            merchant_whitelist_enabled: true,
+            merchant_whitelist: vec![WhitelistedMerchant { merchant_id: [1; 32], expires_at: None }],
+            policy: CardPolicy { strict_merchant_mode, ..Default::default() },
+            ..Default::default()
+        };
+        card.check_merchant_rules(&TransactionContext::default())
+    }
+
+    #[test]
+    fn strict_merchant_mode_refuses_unidentified_merchants() {
+        let err = check_unidentified_merchant(true).unwrap_err();
+        assert_eq!(
+            crate::errors::rejection_code(&err),
+            crate::errors::HookError::UnknownMerchant.rejection_code()
+        );
+    }
+
+    #[test]
+    fn lenient_merchant_mode_allows_unidentified_merchants() {
+        assert!(check_unidentified_merchant(false).is_ok());
+    }
+
+    fn active_card(limits: VelocityLimits) -> CardConfig {
+        CardConfig {
+            status: CardStatus::Active,
+            velocity_limits: limits,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn zero_daily_limit_allows_spend() {
+        let mut card = active_card(VelocityLimits::default());
+        card.velocity_counters.daily_total = u64::MAX / 2;
+
+        assert!(card.check_velocity_limits(u64::MAX / 4, None, None, 0).is_ok());
+    }
+
+    #[test]
+    fn nonzero_daily_limit_caps_spend() {
+        let mut card = active_card(VelocityLimits { daily: 1_000, ..Default::default() });
+        card.velocity_counters.daily_total = 900;
+
+        assert!(card.check_velocity_limits(100, None, None, 0).is_ok());
+        let err = card.check_velocity_limits(101, None, None, 0).unwrap_err();
+        assert_eq!(
+            crate::errors::rejection_code(&err),
+            crate::errors::HookError::DailyLimitExceeded.rejection_code()
+        );
+    }
+
+    #[test]
+    fn statement_totals_and_histogram_cover_every_transaction() {
+        let mut statement = MonthlyStatement::default();
+
+        statement.record(100, Some(5411)).unwrap();
+        statement.record(250, Some(5812)).unwrap();
+        statement.record(50, Some(5411)).unwrap();
+        statement.record(75, None).unwrap();
+
+        assert_eq!(statement.total_spent, 475);
+        assert_eq!(statement.transaction_count, 4);
+        let buckets: Vec<_> = statement.mcc_histogram.iter()
+            .map(|b| (b.mcc_code, b.count, b.total))
+            .collect();
+        assert_eq!(buckets, vec![(5411, 2, 150), (5812, 1, 250)]);
+        assert_eq!((statement.other_count, statement.other_total), (1, 75));
+    }
+
+    #[test]
+    fn statement_histogram_overflows_into_other() {
+        let mut statement = MonthlyStatement::default();
+        for mcc in 0..MAX_STATEMENT_MCC_BUCKETS as u16 {
+            statement.record(10, Some(mcc)).unwrap();
+        }
+
+        statement.record(30, Some(9999)).unwrap();
+        statement.record(20, Some(0)).unwrap();
+
+        assert_eq!(statement.mcc_histogram.len(), MAX_STATEMENT_MCC_BUCKETS);
+        assert_eq!(statement.mcc_histogram[0].total, 30);
+        assert_eq!((statement.other_count, statement.other_total), (1, 30));
+        assert_eq!(statement.total_spent, 10 * MAX_STATEMENT_MCC_BUCKETS as u64 + 50);
+        assert_eq!(statement.transaction_count, MAX_STATEMENT_MCC_BUCKETS as u32 + 2);
+    }
+
+    #[test]
+    fn count_limits_cap_transactions() {
+        let limits = VelocityLimits {
+            max_daily_transactions: 2,
+            max_weekly_transactions: 3,
+            max_monthly_transactions: 4,
+            ..Default::default()
+        };
+        let cases = [
+            (2, 2, 2, crate::errors::HookError::DailyTransactionCountExceeded),
+            (0, 3, 3, crate::errors::HookError::WeeklyTransactionCountExceeded),
+            (0, 0, 4, crate::errors::HookError::MonthlyTransactionCountExceeded),
+        ];
+
+        for (daily, weekly, monthly, expected) in cases {
+            let mut card = active_card(limits);
+            card.velocity_counters.daily_transaction_count = daily;
+            card.velocity_counters.weekly_transaction_count = weekly;
+            card.velocity_counters.monthly_transaction_count = monthly;
+
+            let err = card.check_velocity_limits(1, None, None, 0).unwrap_err();
+            assert_eq!(crate::errors::rejection_code(&err), expected.rejection_code());
+        }
+
+        let mut card = active_card(limits);
+        card.velocity_counters.daily_transaction_count = 1;
+        card.velocity_counters.weekly_transaction_count = 2;
+        card.velocity_counters.monthly_transaction_count = 3;
+        assert!(card.check_velocity_limits(1, None, None, 0).is_ok());
+    }
+
+    fn count_limits(daily: u16, weekly: u16, monthly: u16) -> VelocityLimits {
+        VelocityLimits {
+            max_daily_transactions: daily,
+            max_weekly_transactions: weekly,
+            max_monthly_transactions: monthly,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn count_limits_must_be_ordered() {
+        assert!(count_limits(10, 50, 200).validate().is_ok());
+        assert!(count_limits(10, 10, 10).validate().is_ok());
+        assert!(count_limits(10, 0, 0).validate().is_ok());
+
+        // daily > weekly, weekly > monthly, and a finite limit above an unlimited one
+        assert!(count_limits(51, 50, 200).validate().is_err());
+        assert!(count_limits(10, 201, 200).validate().is_err());
+        assert!(count_limits(0, 50, 200).validate().is_err());
+        assert!(count_limits(10, 0, 200).validate().is_err());
+    }
+
+    #[test]
+    fn unlimited_counters_saturate() {
+        let mut counters = VelocityCounters {
+            daily_total: u64::MAX - 1,
+            daily_transaction_count: u16::MAX,
+            ..Default::default()
+        };
+
+        counters.record_transaction(10);
+
+        assert_eq!(counters.daily_total, u64::MAX);
+        assert_eq!(counters.weekly_total, 10);
+        assert_eq!(counters.daily_transaction_count, u16::MAX);
+    }
+
+    /// A card with a 1,000 daily limit of which online spend may use 300
+    fn card_with_online_cap() -> CardConfig {
+        let mut card = active_card(VelocityLimits { daily: 1_000, ..Default::default() });
+        card.channel_limits.online_daily = Some(300);
+        card
+    }
+
+    #[test]
+    fn online_cap_trips_before_the_daily_limit() {
+        let mut card = card_with_online_cap();
+        let online = Some(TransactionChannel::Online);
+        card.velocity_counters.record_transaction(300);
+        card.channel_limits.record_transaction(300, online);
+
+        let err = card.check_velocity_limits(1, online, None, 0).unwrap_err();
+
+        assert_eq!(
+            crate::errors::rejection_code(&err),
+            crate::errors::HookError::DailyLimitExceeded.rejection_code()
+        );
+        assert!(card.velocity_counters.daily_total < card.velocity_limits.daily);
+    }
+
+    #[test]
+    fn online_cap_leaves_instore_spend_alone() {
+        let mut card = card_with_online_cap();
+        card.velocity_counters.record_transaction(300);
+        card.channel_limits.record_transaction(300, Some(TransactionChannel::Online));
+
+        assert!(card.check_velocity_limits(700, Some(TransactionChannel::InStore), None, 0).is_ok());
+        assert!(card.check_velocity_limits(701, Some(TransactionChannel::InStore), None, 0).is_err());
+    }
+
+    fn daily_limit_of(daily: u64) -> VelocityLimits {
+        VelocityLimits { per_transaction: 100, daily, ..VelocityLimits::GENEROUS_DEFAULTS }
+    }
+
+    #[test]
+    fn limit_increases_wait_out_the_cooldown() {
+        use crate::errors::HookError;
+        const COOLDOWN: u64 = 100;
+        let ceiling = VelocityLimits::GENEROUS_DEFAULTS;
+        let mut card = CardConfig { velocity_limits: daily_limit_of(1000), ..Default::default() };
+
+        card.set_velocity_limits(daily_limit_of(2000), &ceiling, COOLDOWN, 500).unwrap();
+        assert_eq!(card.last_limit_increase_slot, 500);
+
+        let result = card.set_velocity_limits(daily_limit_of(3000), &ceiling, COOLDOWN, 599);
+        assert_rejected(result, HookError::LimitChangeCooldown);
+
+        // Decreases apply immediately and don't restart the cooldown
+        card.set_velocity_limits(daily_limit_of(1500), &ceiling, COOLDOWN, 599).unwrap();
+        assert_eq!(card.velocity_limits.daily, 1500);
+        assert_eq!(card.last_limit_increase_slot, 500);
+
+        card.set_velocity_limits(daily_limit_of(3000), &ceiling, COOLDOWN, 600).unwrap();
+        assert_eq!(card.velocity_limits.daily, 3000);
+        assert_eq!(card.last_limit_increase_slot, 600);
+    }
+
+    #[test]
+    fn limits_cannot_exceed_the_ceiling() {
+        let ceiling = VelocityLimits::GENEROUS_DEFAULTS;
+        let mut card = CardConfig { velocity_limits: daily_limit_of(1000), ..Default::default() };
+
+        let result = card.set_velocity_limits(daily_limit_of(ceiling.daily + 1), &ceiling, 0, 1);
+        assert_rejected(result, crate::errors::HookError::InvalidConfiguration);
+        assert_eq!(card.velocity_limits.daily, 1000);
+    }
+
+    #[test]
+    fn limit_boost_adds_daily_headroom_until_it_expires() {
+        use crate::errors::HookError;
+        const NOW: i64 = 1_700_000_000;
+        let ceiling = VelocityLimits::GENEROUS_DEFAULTS;
+        let limits = VelocityLimits { daily: 1_000, ..VelocityLimits::GENEROUS_DEFAULTS };
+        let mut card = CardConfig { velocity_limits: limits, ..Default::default() };
+        card.velocity_counters.record_transaction(900);
+        let unboosted = card.check_velocity_limits(300, None, None, NOW);
+        assert_rejected(unboosted, HookError::DailyLimitExceeded);
+
+        let boost = LimitBoost { amount: 500, expires_at: NOW + 3_600 };
+        card.grant_limit_boost(boost, &ceiling, 0, 1, NOW).unwrap();
+
+        assert!(card.check_velocity_limits(300, None, None, NOW).is_ok());
+        assert!(card.check_velocity_limits(601, None, None, NOW).is_err());
+        let expired = card.check_velocity_limits(300, None, None, NOW + 3_600);
+        assert_rejected(expired, HookError::DailyLimitExceeded);
+    }
+
+    #[test]
+    fn limit_boost_must_be_short_lived_and_under_the_ceiling() {
+        use crate::errors::HookError::InvalidConfiguration;
+        const NOW: i64 = 1_700_000_000;
+        let ceiling = VelocityLimits::GENEROUS_DEFAULTS;
+        let mut card = CardConfig { velocity_limits: daily_limit_of(1_000), ..Default::default() };
+        let mut grant = |amount, expires_at| {
+            card.grant_limit_boost(LimitBoost { amount, expires_at }, &ceiling, 0, 1, NOW)
+        };
+
+        assert_rejected(grant(500, NOW), InvalidConfiguration);
+        assert_rejected(grant(500, NOW + MAX_LIMIT_BOOST_SECS + 1), InvalidConfiguration);
+        assert_rejected(grant(ceiling.daily, NOW + 3_600), InvalidConfiguration);
+        assert_rejected(grant(0, NOW + 3_600), InvalidConfiguration);
+        grant(500, NOW + MAX_LIMIT_BOOST_SECS).unwrap();
+    }
+
+    #[test]
+    fn over_limit_contactless_steps_down_to_pin() {
+        let card = CardConfig {
+            policy: CardPolicy { allow_contactless: true, contactless_limit: 50, ..Default::default() },
+            ..active_card(VelocityLimits::default())
+        };
+        let tapped = |pin_verified| TransactionContext {
+            channel: Some(TransactionChannel::Contactless),
+            pin_verified,
+            ..Default::default()
+        };
+
+        assert!(card.is_transaction_allowed(51, &tapped(true)).is_ok());
+        assert_rejected(
+            card.is_transaction_allowed(51, &tapped(false)),
+            crate::errors::HookError::ContactlessLimitExceeded,
+        );
+        assert!(card.is_transaction_allowed(50, &tapped(false)).is_ok());
+        assert!(card.is_transaction_allowed(50, &tapped(true)).is_ok());
+    }
+
+    #[test]
+    fn transfers_cannot_exceed_the_available_balance() {
+        use crate::errors::HookError;
+        let card = active_card(VelocityLimits { daily: 100, ..Default::default() });
+        let with_balance = |available_balance| TransactionContext { available_balance, ..Default::default() };
+
+        assert!(card.is_transaction_allowed(80, &with_balance(Some(80))).is_ok());
+        assert_rejected(card.is_transaction_allowed(81, &with_balance(Some(80))), HookError::InsufficientBalance);
+        // The balance is checked before velocity
+        assert_rejected(card.is_transaction_allowed(150, &with_balance(Some(80))), HookError::InsufficientBalance);
+        // Without a balance account only velocity applies
+        assert!(card.is_transaction_allowed(100, &with_balance(None)).is_ok());
+
+        let tracked = CardConfig { balance_tracked: true, ..card };
+        assert_rejected(tracked.is_transaction_allowed(10, &with_balance(None)), HookError::BalanceAccountRequired);
+    }
+
+    #[test]
+    fn home_country_decides_what_is_international() {
+        use merchant_registry::country::alpha2_to_u16;
+        let card_from = |home: [u8; 2]| CardConfig {
+            home_country: alpha2_to_u16(home).unwrap(),
+            policy: CardPolicy { allow_international: false, ..Default::default() },
+            ..active_card(VelocityLimits::default())
+        };
+        let in_gb = TransactionContext { country_code: alpha2_to_u16(*b"GB"), ..Default::default() };
+
+        assert!(card_from(*b"GB").is_transaction_allowed(10, &in_gb).is_ok());
+        assert_rejected(
+            card_from(*b"US").is_transaction_allowed(10, &in_gb),
+            crate::errors::HookError::InternationalNotAllowed,
+        );
+
+        let international = CardConfig {
+            policy: CardPolicy { allow_international: true, ..Default::default() },
+            ..card_from(*b"US")
+        };
+        assert!(international.is_transaction_allowed(10, &in_gb).is_ok());
+    }
+
+    #[test]
+    fn owner_card_cap_frees_up_when_a_card_closes() {
+        let mut index = OwnerIndex::default();
+        for _ in 0..3 {
+            index.register_card([1; 32], 254, 3).unwrap();
+        }
+        assert_eq!(index.card_count, 3);
+
+        assert_rejected(index.register_card([1; 32], 254, 3), crate::errors::HookError::CardLimitReached);
+        assert_eq!(index.card_count, 3);
+
+        index.release_card();
+        index.register_card([1; 32], 254, 3).unwrap();
+        assert_eq!(index.card_count, 3);
+    }
+
+    #[test]
+    fn zero_card_cap_is_unlimited() {
+        let mut index = OwnerIndex { card_count: 1_000, ..Default::default() };
+        index.register_card([1; 32], 254, 0).unwrap();
+        assert_eq!(index.card_count, 1_001);
+    }
+
+    #[test]
+    fn percentage_of_balance_cap_binds_only_when_tighter() {
+        use crate::errors::HookError;
+        let limits = VelocityLimits { daily: 1_000, daily_pct_of_balance: Some(20), ..Default::default() };
+        let card = active_card(limits);
+        let with_balance = |available_balance| TransactionContext {
+            available_balance: Some(available_balance),
+            ..Default::default()
+        };
+
+        // 20% of a 2_000 balance is 400, below the absolute 1_000
+        assert_eq!(limits.effective_daily(Some(2_000)), 400);
+        assert!(card.is_transaction_allowed(400, &with_balance(2_000)).is_ok());
+        assert_rejected(card.is_transaction_allowed(401, &with_balance(2_000)), HookError::DailyLimitExceeded);
+
+        // 20% of a 50_000 balance is 10_000, so the absolute cap binds
+        assert_eq!(limits.effective_daily(Some(50_000)), 1_000);
+        assert!(card.is_transaction_allowed(1_000, &with_balance(50_000)).is_ok());
+        assert_rejected(card.is_transaction_allowed(1_001, &with_balance(50_000)), HookError::DailyLimitExceeded);
+
+        // Without a balance only the absolute cap is known
+        assert_eq!(limits.effective_daily(None), 1_000);
+    }
+
+    #[test]
+    fn percentage_of_balance_must_be_a_percentage() {
+        for pct in [0, 101] {
+            let limits = VelocityLimits { daily_pct_of_balance: Some(pct), ..Default::default() };
+            assert!(limits.validate().is_err(), "{pct}");
+        }
+        let limits = VelocityLimits { daily_pct_of_balance: Some(100), ..Default::default() };
+        assert_eq!(limits.validate().is_ok(), cfg!(feature = "plaintext-state"));
+    }
+
+    #[test]
+    fn mcc_codes_must_be_in_range() {
+        for code in [1, 9999] {
+            assert!(validate_mcc(code).is_ok(), "{code}");
+        }
+        for code in [0, 10_000] {
+            assert_rejected(validate_mcc(code), crate::errors::HookError::InvalidMccCode);
+        }
     }
 }
@@ -0,0 +1,242 @@
+//! Off-chain harness for running instructions through the program entrypoint
+
+use std::cell::RefCell;
+
+use anchor_lang::prelude::*;
+pub use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::{
+    entrypoint::SUCCESS,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    system_program,
+};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{Event, InstructionData};
+use anchor_spl::token_2022::spl_token_2022::state::{
+    Account as TokenAccount2022, AccountState, Mint as Mint2022,
+};
+
+use crate::errors::HookError;
+use crate::state::{CardConfig, DidController, GlobalConfig, OwnerIndex};
+
+pub const NOW: i64 = 1_700_000_000;
+pub const SLOT: u64 = 250_000_000;
+
+thread_local! {
+    /// Return data set by the instruction under test on this thread
+    pub static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+
+    /// Event data logged by the instruction under test on this thread
+    static LOGGED_DATA: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+
+    /// Messages logged by the instruction under test on this thread
+    static LOGGED_MESSAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Serves `Clock::get` and `Rent::get` off-chain and captures return data,
+/// events and log messages
+///
+/// CPIs are left to the default no-op stub, so `init_if_needed` accounts must
+/// be passed in already created (see `card_config_account`).
+struct TestSyscalls;
+
+impl SyscallStubs for TestSyscalls {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock { slot: SLOT, unix_timestamp: NOW, ..Default::default() };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = data.to_vec());
+    }
+
+    fn sol_log(&self, message: &str) {
+        LOGGED_MESSAGES.with(|logged| logged.borrow_mut().push(message.to_string()));
+    }
+
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        LOGGED_DATA.with(|logged| logged.borrow_mut().push(data.concat()));
+    }
+}
+
+pub fn install_syscall_stubs() {
+    set_syscall_stubs(Box::new(TestSyscalls));
+}
+
+/// An account leaked for the `'static` lifetime the entrypoint borrows for
+pub fn account(key: Pubkey, owner: Pubkey, is_writable: bool, data: Vec<u8>) -> AccountInfo<'static> {
+    AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        is_writable,
+        Box::leak(Box::new(1_000_000_000)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        0,
+    )
+}
+
+pub fn empty_account(key: Pubkey, is_writable: bool) -> AccountInfo<'static> {
+    account(key, system_program::ID, is_writable, vec![])
+}
+
+pub fn signer(key: Pubkey) -> AccountInfo<'static> {
+    AccountInfo { is_signer: true, ..empty_account(key, true) }
+}
+
+pub fn anchor_data<T: AccountSerialize>(account: &T, len: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(len);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    data
+}
+
+/// A Token-2022 mint with 6 decimals and no extensions
+pub fn mint_data() -> Vec<u8> {
+    let mut data = vec![0; Mint2022::LEN];
+    let mint = Mint2022 { decimals: 6, is_initialized: true, ..Default::default() };
+    Mint2022::pack(mint, &mut data).unwrap();
+    data
+}
+
+/// A Token-2022 account of `mint` with no extensions
+pub fn token_account_data(mint: Pubkey) -> Vec<u8> {
+    let mut data = vec![0; TokenAccount2022::LEN];
+    let account = TokenAccount2022 {
+        mint,
+        owner: Pubkey::new_unique(),
+        state: AccountState::Initialized,
+        ..Default::default()
+    };
+    TokenAccount2022::pack(account, &mut data).unwrap();
+    data
+}
+
+/// A zeroed global config: no authorities, no system transfer accounts, and
+/// the kill switch off
+pub fn zeroed_global_config() -> GlobalConfig {
+    GlobalConfig::deserialize(&mut &[0; GlobalConfig::SIZE][..]).unwrap()
+}
+
+/// The global config PDA holding `config`
+pub fn global_config_account(mut config: GlobalConfig) -> AccountInfo<'static> {
+    let (key, bump) = Pubkey::find_program_address(&[b"global_config"], &crate::ID);
+    config.bump = bump;
+    account(key, crate::ID, true, anchor_data(&config, GlobalConfig::SIZE))
+}
+
+/// The card config PDA holding `card`
+pub fn card_config_account(mut card: CardConfig) -> AccountInfo<'static> {
+    let (key, bump) = Pubkey::find_program_address(&[CardConfig::SEED, &card.card_id], &crate::ID);
+    card.bump = bump;
+    account(key, crate::ID, true, anchor_data(&card, CardConfig::SIZE))
+}
+
+/// A DID controller record binding `did_hash` to `controller`
+pub fn did_controller_account(did_hash: [u8; 32], controller: Pubkey) -> AccountInfo<'static> {
+    let (key, bump) = Pubkey::find_program_address(&[b"did_controller", &did_hash], &crate::ID);
+    let record = DidController { bump, did_hash, controller, ..Default::default() };
+    account(key, crate::ID, false, anchor_data(&record, DidController::SIZE))
+}
+
+/// The owner index PDA holding `index`
+pub fn owner_index_account(mut index: OwnerIndex) -> AccountInfo<'static> {
+    let (key, bump) =
+        Pubkey::find_program_address(&[b"owner_index", &index.owner_did_hash], &crate::ID);
+    index.bump = bump;
+    account(key, crate::ID, true, anchor_data(&index, OwnerIndex::SIZE))
+}
+
+/// A zeroed program-owned account standing in for one `init` would create
+pub fn uninitialized_account(key: Pubkey, space: usize) -> AccountInfo<'static> {
+    account(key, crate::ID, true, vec![0; space])
+}
+
+/// The system program account
+pub fn system_program() -> AccountInfo<'static> {
+    let program = account(system_program::ID, Pubkey::default(), false, vec![]);
+    AccountInfo { executable: true, ..program }
+}
+
+/// The Token-2022 program account
+pub fn token_2022_program() -> AccountInfo<'static> {
+    AccountInfo { executable: true, ..empty_account(anchor_spl::token_2022::ID, false) }
+}
+
+/// The entrypoint's placeholder for an optional account left out
+pub fn absent() -> AccountInfo<'static> {
+    empty_account(crate::ID, false)
+}
+
+/// Run `initialize_card_config` for a new card owned by `owner`, returning
+/// the result and the card config account
+pub fn initialize_card(
+    card_id: [u8; 32],
+    owner_did_hash: [u8; 32],
+    owner: Pubkey,
+) -> (ProgramResult, AccountInfo<'static>) {
+    let mint = Pubkey::new_unique();
+    let card = card_config_account(CardConfig { card_id, ..Default::default() });
+    let accounts = vec![
+        signer(Pubkey::new_unique()),
+        signer(owner),
+        did_controller_account(owner_did_hash, owner),
+        account(mint, anchor_spl::token_2022::ID, false, mint_data()),
+        account(Pubkey::new_unique(), anchor_spl::token_2022::ID, false, token_account_data(mint)),
+        card.clone(),
+        owner_index_account(OwnerIndex { owner_did_hash, ..Default::default() }),
+        global_config_account(zeroed_global_config()),
+        system_program(),
+    ];
+    let ix = crate::instruction::InitializeCardConfig {
+        card_id,
+        owner_did_hash,
+        expires_at: None,
+        home_country: None,
+    };
+    (run(accounts, ix), card)
+}
+
+/// Return data set by the last instruction run on this thread
+pub fn return_data() -> Vec<u8> {
+    RETURN_DATA.with(|return_data| return_data.borrow().clone())
+}
+
+/// Run an instruction through the program entrypoint
+pub fn run<I: InstructionData>(accounts: Vec<AccountInfo<'static>>, ix: I) -> ProgramResult {
+    install_syscall_stubs();
+    LOGGED_DATA.with(|logged| logged.borrow_mut().clear());
+    LOGGED_MESSAGES.with(|logged| logged.borrow_mut().clear());
+    let accounts: &'static [AccountInfo<'static>] = Box::leak(accounts.into_boxed_slice());
+    crate::entry(&crate::ID, accounts, &ix.data())
+}
+
+/// Deserialize an Anchor account left behind by an instruction
+pub fn read<T: AccountDeserialize>(info: &AccountInfo) -> T {
+    T::try_deserialize(&mut &info.try_borrow_data().unwrap()[..]).unwrap()
+}
+
+/// Events of type `E` emitted by the last instruction run on this thread
+pub fn emitted<E: Event>() -> Vec<E> {
+    LOGGED_DATA.with(|logged| {
+        logged.borrow().iter()
+            .filter_map(|data| data.strip_prefix(E::DISCRIMINATOR.as_slice()))
+            .map(|mut body| E::deserialize(&mut body).unwrap())
+            .collect()
+    })
+}
+
+/// Messages logged by the last instruction run on this thread
+pub fn logged_messages() -> Vec<String> {
+    LOGGED_MESSAGES.with(|logged| logged.borrow().clone())
+}
+
+pub fn assert_rejected(result: ProgramResult, expected: HookError) {
+    assert_eq!(result, Err(ProgramError::Custom(expected.into())), "expected {expected:?}");
+}
@@ -39,4 +39,16 @@ pub enum DisCardError {
 
     #[msg("Program paused")]
     ProgramPaused,
+
+    #[msg("Audit anchor does not extend the authority's current chain")]
+    AuditChainMismatch,
+
+    #[msg("Funding exceeds the tokens held in the card's token account")]
+    UnbackedFunding,
+
+    #[msg("Plaintext card state is not enabled in this build")]
+    PlaintextStateDisabled,
+
+    #[msg("Compressed state is not enabled in this build")]
+    CompressedStateDisabled,
 }
@@ -0,0 +1,25 @@
+//! Event definitions for DisCard State
+
+use anchor_lang::prelude::*;
+
+/// Emitted when a spend leaves a card's balance below its warning threshold
+#[event]
+pub struct LowBalanceWarning {
+    pub card_id: [u8; 32],
+    pub balance: u64,
+    pub threshold: u64,
+}
+
+/// Emitted when spending is recorded against a card
+///
+/// `reference` carries the caller's invoice or order number so accounting
+/// systems can reconcile on-chain spend.
+#[event]
+pub struct TransactionRecorded {
+    pub card_id: [u8; 32],
+    pub amount: u64,
+    pub merchant_id: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub reference: Option<[u8; 32]>,
+    pub slot: u64,
+}
@@ -5,28 +5,41 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::AuditAnchorState;
+use crate::state::{AuditAnchorHead, AuditAnchorState};
 
 /// Anchor a batch of audit log entries by storing their Merkle root on-chain.
 ///
+/// `prev_root` and `sequence` must extend the authority's current chain tip;
+/// a gap or fork fails with `AuditChainMismatch`.
+///
 /// PDA seeds: [b"audit_anchor", authority.key(), &timestamp.to_le_bytes()]
 pub fn anchor_audit_merkle_root(
     ctx: Context<AnchorAuditMerkleRoot>,
     merkle_root: [u8; 32],
     batch_size: u32,
     timestamp: i64,
+    prev_root: [u8; 32],
+    sequence: u64,
 ) -> Result<()> {
+    let head = &mut ctx.accounts.audit_anchor_head;
     let anchor_state = &mut ctx.accounts.audit_anchor;
 
-    anchor_state.authority = ctx.accounts.authority.key();
+    let authority = ctx.accounts.authority.key();
+    head.advance(authority, merkle_root, &prev_root, sequence, timestamp)?;
+    head.bump = ctx.bumps.audit_anchor_head;
+
+    anchor_state.authority = authority;
     anchor_state.merkle_root = merkle_root;
+    anchor_state.prev_root = prev_root;
+    anchor_state.sequence = sequence;
     anchor_state.batch_size = batch_size;
     anchor_state.anchored_at = timestamp;
     anchor_state.anchor_slot = Clock::get()?.slot;
     anchor_state.bump = ctx.bumps.audit_anchor;
 
     msg!(
-        "Audit anchor created: batch_size={}, slot={}",
+        "Audit anchor created: sequence={}, batch_size={}, slot={}",
+        sequence,
         batch_size,
         anchor_state.anchor_slot
     );
@@ -49,5 +62,15 @@ pub struct AnchorAuditMerkleRoot<'info> {
     )]
     pub audit_anchor: Account<'info, AuditAnchorState>,
 
+    /// The authority's chain tip (created on the first anchor)
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AuditAnchorHead::SIZE,
+        seeds = [b"audit_anchor_head", authority.key().as_ref()],
+        bump,
+    )]
+    pub audit_anchor_head: Account<'info, AuditAnchorHead>,
+
     pub system_program: Program<'info, System>,
 }
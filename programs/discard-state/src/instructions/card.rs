@@ -1,22 +1,34 @@
 //! Card instruction handlers
 
 use anchor_lang::prelude::*;
+use light_sdk::compressed_account::{CompressedAccount, CompressedAccountWithMerkleContext};
+use crate::error::DisCardError;
+use crate::events::TransactionRecorded;
 use crate::state::card::{CardState, FreezeReasonState, CardError};
+use crate::instructions::policy::{load_whitelist_entries, validate_policy};
 use crate::{
     CreateCompressedCard, UpdateCardBalance, RecordSpending,
-    FreezeCard, UnfreezeCard, UpdateCardLimits,
-    CompressedProof, FreezeReason,
+    FreezeCard, UnfreezeCard, UpdateCardLimits, UpdateReserve,
+    CardLimits, CardLimitsUpdate, CompressedProof, FreezeReason, RecordedSpend,
 };
 
 /// Create a new compressed card state
 pub fn create_compressed_card(
-    ctx: Context<CreateCompressedCard>,
+    _ctx: Context<CreateCompressedCard>,
     card_id: [u8; 32],
     owner_did_commitment: [u8; 32],
-    spending_limit: u64,
-    daily_limit: u64,
-    monthly_limit: u64,
+    limits: CardLimits,
 ) -> Result<()> {
+    let CardLimits {
+        spending_limit,
+        daily_limit,
+        weekly_limit,
+        monthly_limit,
+        max_transaction_counts,
+        max_balance,
+        low_balance_threshold_bps,
+    } = limits;
+    require!(low_balance_threshold_bps <= 10_000, CardError::InvalidCardState);
     let clock = Clock::get()?;
     let current_slot = clock.slot;
 
@@ -37,10 +49,24 @@ pub fn create_compressed_card(
         mcc_whitelist_count: 0,
         created_at_slot: current_slot,
         updated_at_slot: current_slot,
+        weekly_limit,
+        current_weekly_spend: 0,
+        last_daily_reset_unix: clock.unix_timestamp,
+        last_weekly_reset_unix: clock.unix_timestamp,
+        last_monthly_reset_unix: clock.unix_timestamp,
+        max_daily_transactions: max_transaction_counts.daily,
+        max_weekly_transactions: max_transaction_counts.weekly,
+        max_monthly_transactions: max_transaction_counts.monthly,
+        daily_transaction_count: 0,
+        weekly_transaction_count: 0,
+        monthly_transaction_count: 0,
+        max_balance,
+        reserved_balance: 0,
+        low_balance_threshold_bps,
     };
 
     // Serialize state
-    let state_bytes = card_state.try_to_vec()?;
+    let _state_bytes = card_state.try_to_vec()?;
 
     // In production, call Light Protocol to create compressed account
     // light_sdk::compress_account(ctx.accounts.light_system_program, state_bytes, ...)?;
@@ -48,6 +74,7 @@ pub fn create_compressed_card(
     msg!("Created compressed card: {:?}", card_id);
     msg!("Spending limit: {}", spending_limit);
     msg!("Daily limit: {}", daily_limit);
+    msg!("Weekly limit: {}", weekly_limit);
     msg!("Monthly limit: {}", monthly_limit);
 
     Ok(())
@@ -55,13 +82,11 @@ pub fn create_compressed_card(
 
 /// Update card balance
 pub fn update_card_balance(
-    ctx: Context<UpdateCardBalance>,
+    _ctx: Context<UpdateCardBalance>,
     card_id: [u8; 32],
     new_balance: u64,
-    proof: CompressedProof,
+    _proof: CompressedProof,
 ) -> Result<()> {
-    let clock = Clock::get()?;
-
     // In production:
     // 1. Verify the proof
     // 2. Decompress current state
@@ -73,23 +98,70 @@ pub fn update_card_balance(
     Ok(())
 }
 
+/// Read a `CardState` out of a compressed account
+///
+/// The account must be owned by this program, carry data, and be proven to
+/// exist in the state tree by `proof`.
+pub fn decompress_card_state(
+    compressed_card: &CompressedAccountWithMerkleContext,
+    proof: &CompressedProof,
+) -> Result<CardState> {
+    verify_compressed_account(compressed_card, proof)?;
+    read_card_state(&compressed_card.compressed_account)
+}
+
+/// Deserialize the `CardState` held by a compressed account of this program
+///
+/// Doesn't prove the account exists; `decompress_card_state` does that first.
+fn read_card_state(account: &CompressedAccount) -> Result<CardState> {
+    if account.owner != crate::ID {
+        return Err(error!(DisCardError::InvalidAccountType));
+    }
+
+    let data = account.data.as_ref().ok_or(error!(DisCardError::AccountNotFound))?;
+    CardState::deserialize(&mut &data.data[..])
+        .map_err(|_| error!(DisCardError::InvalidAccountType))
+}
+
+/// Verify the validity proof for a compressed account
+///
+/// Light Protocol validity-proof verification is not wired in yet, so builds
+/// without the `compressed-state` feature reject compressed state outright,
+/// and builds with it still reject every proof: client-supplied card and DID
+/// state must never be trusted unproven.
+pub(crate) fn verify_compressed_account(
+    _compressed_account: &CompressedAccountWithMerkleContext,
+    _proof: &CompressedProof,
+) -> Result<()> {
+    require!(cfg!(feature = "compressed-state"), DisCardError::CompressedStateDisabled);
+
+    // In production, verify `proof` against the account hash and the Merkle
+    // context's root through the Light system program
+    Err(error!(DisCardError::InvalidProof))
+}
+
 /// Record spending transaction
+///
+/// Remaining accounts carry the card's whitelist entries (see
+/// `load_whitelist_entries`).
+///
+/// Until validity proofs are verified on-chain, `verify_compressed_account`
+/// rejects every proof, so this instruction always fails. Enforcement today
+/// goes through `record_spending_plaintext`.
 pub fn record_spending(
     ctx: Context<RecordSpending>,
     card_id: [u8; 32],
-    spend_amount: u64,
-    merchant_id: Option<[u8; 32]>,
-    mcc_code: Option<u16>,
+    spend: RecordedSpend,
+    compressed_card: CompressedAccountWithMerkleContext,
     proof: CompressedProof,
 ) -> Result<()> {
+    let RecordedSpend { amount: spend_amount, merchant_id, mcc_code, reference } = spend;
     let clock = Clock::get()?;
 
-    // In production:
-    // 1. Verify the proof
-    // 2. Decompress current state
-    // 3. Check if transaction is allowed (limits, merchant, MCC)
-    // 4. Apply spending
-    // 5. Recompress with new state
+    // In production, apply the spending and recompress with the new state
+
+    let card_state = decompress_card_state(&compressed_card, &proof)?;
+    check_spending(&card_state, card_id, spend_amount, merchant_id, mcc_code, ctx.remaining_accounts)?;
 
     msg!("Recorded spending: {:?} amount={}", card_id, spend_amount);
     if let Some(mid) = merchant_id {
@@ -99,15 +171,54 @@ pub fn record_spending(
         msg!("MCC: {}", mcc);
     }
 
+    emit!(TransactionRecorded {
+        card_id,
+        amount: spend_amount,
+        merchant_id,
+        mcc_code,
+        reference,
+        slot: clock.slot,
+    });
+
     Ok(())
 }
 
+/// Check a spend against the card's whitelists, freeze, balance and limits
+fn check_spending(
+    card_state: &CardState,
+    card_id: [u8; 32],
+    spend_amount: u64,
+    merchant_id: Option<[u8; 32]>,
+    mcc_code: Option<u16>,
+    whitelist_accounts: &[AccountInfo],
+) -> Result<()> {
+    if card_state.card_id != card_id {
+        return Err(error!(CardError::InvalidCardState));
+    }
+
+    // Merchant/MCC locking is enabled whenever the card has whitelist entries
+    let (merchant_whitelist, mcc_whitelist) =
+        load_whitelist_entries(card_state, whitelist_accounts)?;
+    validate_policy(
+        card_id,
+        merchant_id,
+        mcc_code,
+        &merchant_whitelist,
+        &mcc_whitelist,
+        card_state.merchant_whitelist_count > 0,
+        card_state.mcc_whitelist_count > 0,
+    )?;
+
+    // Frozen cards, balance, and velocity limits
+    card_state.can_process_transaction(spend_amount)
+}
+
 /// Freeze a card
 pub fn freeze_card(
-    ctx: Context<FreezeCard>,
+    _ctx: Context<FreezeCard>,
     card_id: [u8; 32],
     reason: FreezeReason,
-    proof: CompressedProof,
+    _proof: CompressedProof,
 ) -> Result<()> {
     let freeze_reason = match reason {
         FreezeReason::FraudDetected => FreezeReasonState::FraudDetected,
@@ -130,9 +241,9 @@ pub fn freeze_card(
 
 /// Unfreeze a card
 pub fn unfreeze_card(
-    ctx: Context<UnfreezeCard>,
+    _ctx: Context<UnfreezeCard>,
     card_id: [u8; 32],
-    proof: CompressedProof,
+    _proof: CompressedProof,
 ) -> Result<()> {
     // In production:
     // 1. Verify the proof
@@ -145,15 +256,39 @@ pub fn unfreeze_card(
     Ok(())
 }
 
+/// Set the part of a card's balance that can't be spent
+///
+/// The reserve may exceed the current balance; spending stays blocked until
+/// the card is funded past it. Plaintext cards set theirs through
+/// `update_card_state_pda_reserve`.
+pub fn update_reserve(
+    _ctx: Context<UpdateReserve>,
+    card_id: [u8; 32],
+    reserved_balance: u64,
+    _proof: CompressedProof,
+) -> Result<()> {
+    // In production:
+    // 1. Verify the proof
+    // 2. Decompress current state
+    // 3. Set reserved_balance
+    // 4. Recompress with new state
+
+    msg!("Updated card reserve: {:?} -> {}", card_id, reserved_balance);
+
+    Ok(())
+}
+
 /// Update card spending limits
 pub fn update_card_limits(
-    ctx: Context<UpdateCardLimits>,
+    _ctx: Context<UpdateCardLimits>,
     card_id: [u8; 32],
-    new_spending_limit: Option<u64>,
-    new_daily_limit: Option<u64>,
-    new_monthly_limit: Option<u64>,
-    proof: CompressedProof,
+    update: CardLimitsUpdate,
+    _proof: CompressedProof,
 ) -> Result<()> {
+    if let Some(bps) = update.low_balance_threshold_bps {
+        require!(bps <= 10_000, CardError::InvalidCardState);
+    }
+
     // In production:
     // 1. Verify the proof
     // 2. Decompress current state
@@ -161,15 +296,177 @@ pub fn update_card_limits(
     // 4. Recompress with new state
 
     msg!("Updated card limits: {:?}", card_id);
-    if let Some(limit) = new_spending_limit {
+    if let Some(limit) = update.spending_limit {
         msg!("New spending limit: {}", limit);
     }
-    if let Some(limit) = new_daily_limit {
+    if let Some(limit) = update.daily_limit {
         msg!("New daily limit: {}", limit);
     }
-    if let Some(limit) = new_monthly_limit {
+    if let Some(limit) = update.weekly_limit {
+        msg!("New weekly limit: {}", limit);
+    }
+    if let Some(limit) = update.monthly_limit {
         msg!("New monthly limit: {}", limit);
     }
+    if let Some(counts) = update.max_transaction_counts {
+        msg!(
+            "New transaction count limits: daily={} weekly={} monthly={}",
+            counts.daily,
+            counts.weekly,
+            counts.monthly
+        );
+    }
+    if let Some(max_balance) = update.max_balance {
+        msg!("New max balance: {}", max_balance);
+    }
+    if let Some(bps) = update.low_balance_threshold_bps {
+        msg!("New low-balance threshold: {} bps", bps);
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::card::{MccWhitelistEntry, MerchantWhitelistEntry};
+    use light_sdk::compressed_account::CompressedAccountData;
+
+    const CARD_ID: [u8; 32] = [7; 32];
+
+    /// A compressed account holding `card_state`, as the indexer returns it
+    fn compressed_card(card_state: &CardState) -> CompressedAccount {
+        CompressedAccount {
+            owner: crate::ID,
+            lamports: 0,
+            address: None,
+            data: Some(CompressedAccountData {
+                discriminator: [0; 8],
+                data: card_state.try_to_vec().unwrap(),
+                data_hash: [0; 32],
+            }),
+        }
+    }
+
+    fn funded_card() -> CardState {
+        CardState {
+            card_id: CARD_ID,
+            balance: 1_000,
+            spending_limit: 1_000,
+            daily_limit: 5_000,
+            monthly_limit: 50_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn frozen_compressed_card_rejects_spending() {
+        let mut card = funded_card();
+        card.freeze(FreezeReasonState::FraudDetected);
+
+        let card_state = read_card_state(&compressed_card(&card)).unwrap();
+
+        assert!(card_state.is_frozen);
+        assert!(check_spending(&card_state, CARD_ID, 100, None, None, &[]).is_err());
+    }
+
+    #[test]
+    fn compressed_card_allows_spending_within_balance() {
+        let card_state = read_card_state(&compressed_card(&funded_card())).unwrap();
+
+        assert!(check_spending(&card_state, CARD_ID, 100, None, None, &[]).is_ok());
+        assert!(check_spending(&card_state, CARD_ID, 1_001, None, None, &[]).is_err());
+        assert!(check_spending(&card_state, [8; 32], 100, None, None, &[]).is_err());
+    }
+
+    /// A whitelist entry account owned by `owner`
+    fn entry_account<T: AnchorSerialize>(entry: &T, owner: Pubkey) -> AccountInfo<'static> {
+        let key = Box::leak(Box::new(Pubkey::new_unique()));
+        let owner = Box::leak(Box::new(owner));
+        let lamports = Box::leak(Box::new(0u64));
+        let data = Box::leak(entry.try_to_vec().unwrap().into_boxed_slice());
+        AccountInfo::new(key, false, false, lamports, data, owner, false, 0)
+    }
+
+    fn merchant_entry(card_id: [u8; 32], merchant_id: [u8; 32]) -> AccountInfo<'static> {
+        let entry = MerchantWhitelistEntry { card_id, merchant_id, added_at_slot: 0 };
+        entry_account(&entry, crate::ID)
+    }
+
+    fn merchant_locked_card() -> CardState {
+        CardState { merchant_whitelist_count: 1, ..funded_card() }
+    }
+
+    #[test]
+    fn whitelisted_merchant_can_spend() {
+        let entries = [merchant_entry(CARD_ID, [1; 32])];
+
+        let result = check_spending(&merchant_locked_card(), CARD_ID, 100, Some([1; 32]), None, &entries);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn unlisted_merchant_is_rejected() {
+        let entries = [merchant_entry(CARD_ID, [1; 32])];
+        let card = merchant_locked_card();
+
+        for merchant_id in [Some([2; 32]), None] {
+            let err = check_spending(&card, CARD_ID, 100, merchant_id, None, &entries).unwrap_err();
+            assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::MerchantNotWhitelisted.into()));
+        }
+    }
+
+    #[test]
+    fn unlisted_mcc_is_rejected() {
+        let entry = MccWhitelistEntry { card_id: CARD_ID, mcc_code: 5411, added_at_slot: 0 };
+        let entries = [entry_account(&entry, crate::ID)];
+        let card = CardState { mcc_whitelist_count: 1, ..funded_card() };
+
+        assert!(check_spending(&card, CARD_ID, 100, None, Some(5411), &entries).is_ok());
+        let err = check_spending(&card, CARD_ID, 100, None, Some(5812), &entries).unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::MccNotAllowed.into()));
+    }
+
+    #[test]
+    fn whitelist_entries_must_be_complete_and_belong_to_the_card() {
+        let card = merchant_locked_card();
+
+        assert!(check_spending(&card, CARD_ID, 100, Some([1; 32]), None, &[]).is_err());
+
+        let foreign = [merchant_entry([8; 32], [1; 32])];
+        assert!(check_spending(&card, CARD_ID, 100, Some([1; 32]), None, &foreign).is_err());
+
+        let entry = MerchantWhitelistEntry { card_id: CARD_ID, merchant_id: [1; 32], added_at_slot: 0 };
+        let spoofed = [entry_account(&entry, Pubkey::new_unique())];
+        assert!(check_spending(&card, CARD_ID, 100, Some([1; 32]), None, &spoofed).is_err());
+    }
+
+    #[test]
+    fn compressed_card_keeps_its_reserve() {
+        let card = CardState { reserved_balance: 400, ..funded_card() };
+        let card_state = read_card_state(&compressed_card(&card)).unwrap();
+
+        assert!(check_spending(&card_state, CARD_ID, 600, None, None, &[]).is_ok());
+        assert!(check_spending(&card_state, CARD_ID, 601, None, None, &[]).is_err());
+    }
+
+    #[test]
+    fn foreign_compressed_accounts_are_rejected() {
+        let mut account = compressed_card(&funded_card());
+        account.owner = Pubkey::new_unique();
+
+        assert!(read_card_state(&account).is_err());
+    }
+
+    #[test]
+    fn unverified_proofs_are_rejected() {
+        let compressed_card = CompressedAccountWithMerkleContext {
+            compressed_account: compressed_card(&funded_card()),
+            merkle_context: Default::default(),
+        };
+        let proof = CompressedProof { a: [0; 64], b: [0; 128], c: [0; 64] };
+
+        assert!(decompress_card_state(&compressed_card, &proof).is_err());
+    }
+}
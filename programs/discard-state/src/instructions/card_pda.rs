@@ -0,0 +1,550 @@
+//! Plaintext card state instructions
+//!
+//! Creates, funds, and spends from a `CardStatePda`. Unlike the compressed
+//! handlers, spending here loads the state, runs `apply_spending`, and
+//! persists the result. Anchor's `#[program]` can't cfg out instructions,
+//! so they're always compiled but reject calls without the `plaintext-state`
+//! feature.
+//!
+//! Every instruction must be signed by the card's discard-hooks config PDA,
+//! so they're only reachable through the hooks program, which checks the card
+//! owner or a settlement authority and debits hook-approved transfers.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::error::DisCardError;
+use crate::instructions::velocity::{
+    should_reset_daily, should_reset_monthly, should_reset_weekly, PLAINTEXT_WEEK_START_DAY,
+};
+use crate::state::card::{CardError, CardState};
+use crate::state::card_pda::CardStatePda;
+use crate::{CardLimits, CardLimitsUpdate};
+
+/// Create a plaintext card state PDA
+pub fn create_card_state_pda(
+    ctx: Context<CreateCardStatePda>,
+    card_id: [u8; 32],
+    owner_did_commitment: [u8; 32],
+    limits: CardLimits,
+    token_account: Pubkey,
+) -> Result<()> {
+    require_plaintext_state()?;
+    require!(limits.low_balance_threshold_bps <= 10_000, CardError::InvalidCardState);
+    let card = &mut ctx.accounts.card_state;
+    let clock = Clock::get()?;
+
+    card.authority = ctx.accounts.card_authority.key();
+    card.token_account = token_account;
+    card.state = CardState {
+        card_id,
+        owner_did_commitment,
+        spending_limit: limits.spending_limit,
+        daily_limit: limits.daily_limit,
+        weekly_limit: limits.weekly_limit,
+        monthly_limit: limits.monthly_limit,
+        max_daily_transactions: limits.max_transaction_counts.daily,
+        max_weekly_transactions: limits.max_transaction_counts.weekly,
+        max_monthly_transactions: limits.max_transaction_counts.monthly,
+        max_balance: limits.max_balance,
+        low_balance_threshold_bps: limits.low_balance_threshold_bps,
+        last_reset_slot: clock.slot,
+        last_daily_reset_unix: clock.unix_timestamp,
+        last_weekly_reset_unix: clock.unix_timestamp,
+        last_monthly_reset_unix: clock.unix_timestamp,
+        created_at_slot: clock.slot,
+        updated_at_slot: clock.slot,
+        ..Default::default()
+    };
+    card.bump = ctx.bumps.card_state;
+
+    msg!("Created plaintext card state: {:?}", card_id);
+
+    Ok(())
+}
+
+/// Credit tokens already transferred into the card's token account
+///
+/// The balance can never exceed what the token account actually holds.
+pub fn fund_card_state_pda(ctx: Context<FundCardStatePda>, amount: u64) -> Result<()> {
+    require_plaintext_state()?;
+    let held = ctx.accounts.token_account.amount;
+    let state = &mut ctx.accounts.card_state.state;
+    let clock = Clock::get()?;
+
+    let new_balance = state.balance.checked_add(amount).ok_or(DisCardError::ArithmeticOverflow)?;
+    if new_balance > held {
+        return Err(error!(DisCardError::UnbackedFunding));
+    }
+    state.add_funds(amount)?;
+    state.updated_at_slot = clock.slot;
+
+    msg!("Funded plaintext card: {:?} balance={}", state.card_id, state.balance);
+
+    Ok(())
+}
+
+/// Change a plaintext card's limits, leaving those not in `update` as they are
+pub fn update_card_state_pda_limits(
+    ctx: Context<UpdateCardStatePda>,
+    update: CardLimitsUpdate,
+) -> Result<()> {
+    require_plaintext_state()?;
+    if let Some(bps) = update.low_balance_threshold_bps {
+        require!(bps <= 10_000, CardError::InvalidCardState);
+    }
+    let state = &mut ctx.accounts.card_state.state;
+    let clock = Clock::get()?;
+
+    if let Some(limit) = update.spending_limit {
+        state.spending_limit = limit;
+    }
+    if let Some(limit) = update.daily_limit {
+        state.daily_limit = limit;
+    }
+    if let Some(limit) = update.weekly_limit {
+        state.weekly_limit = limit;
+    }
+    if let Some(limit) = update.monthly_limit {
+        state.monthly_limit = limit;
+    }
+    if let Some(counts) = update.max_transaction_counts {
+        state.max_daily_transactions = counts.daily;
+        state.max_weekly_transactions = counts.weekly;
+        state.max_monthly_transactions = counts.monthly;
+    }
+    if let Some(max_balance) = update.max_balance {
+        state.max_balance = max_balance;
+    }
+    if let Some(bps) = update.low_balance_threshold_bps {
+        state.low_balance_threshold_bps = bps;
+    }
+    state.updated_at_slot = clock.slot;
+
+    msg!("Updated plaintext card limits: {:?}", state.card_id);
+
+    Ok(())
+}
+
+/// Set the part of a plaintext card's balance that can't be spent
+///
+/// The reserve may exceed the current balance; spending stays blocked until
+/// the card is funded past it.
+pub fn update_card_state_pda_reserve(
+    ctx: Context<UpdateCardStatePda>,
+    reserved_balance: u64,
+) -> Result<()> {
+    require_plaintext_state()?;
+    let state = &mut ctx.accounts.card_state.state;
+    let clock = Clock::get()?;
+
+    state.reserved_balance = reserved_balance;
+    state.updated_at_slot = clock.slot;
+
+    msg!("Updated plaintext card reserve: {:?} -> {}", state.card_id, reserved_balance);
+
+    Ok(())
+}
+
+/// Record spending against a plaintext card, enforcing freeze, balance, and limits
+pub fn record_spending_plaintext(
+    ctx: Context<UpdateCardStatePda>,
+    spend_amount: u64,
+) -> Result<()> {
+    require_plaintext_state()?;
+    let state = &mut ctx.accounts.card_state.state;
+    let clock = Clock::get()?;
+
+    // Roll over any elapsed velocity periods before checking limits
+    if should_reset_daily(state.last_daily_reset_unix, clock.unix_timestamp) {
+        state.reset_daily(clock.slot, clock.unix_timestamp);
+    }
+    if should_reset_weekly(state.last_weekly_reset_unix, clock.unix_timestamp, PLAINTEXT_WEEK_START_DAY) {
+        state.reset_weekly(clock.slot, clock.unix_timestamp);
+    }
+    if should_reset_monthly(state.last_monthly_reset_unix, clock.unix_timestamp) {
+        state.reset_monthly(clock.slot, clock.unix_timestamp);
+    }
+
+    state.apply_spending(spend_amount)?;
+    state.updated_at_slot = clock.slot;
+
+    msg!(
+        "Recorded plaintext spending: {:?} amount={} balance={}",
+        state.card_id,
+        spend_amount,
+        state.balance
+    );
+
+    Ok(())
+}
+
+/// Deduct a transfer the hooks program approved from a plaintext card's balance
+///
+/// Only the balance moves: the hook has already enforced the card's limits.
+pub fn debit_card_state_pda(ctx: Context<UpdateCardStatePda>, amount: u64) -> Result<()> {
+    require_plaintext_state()?;
+    let state = &mut ctx.accounts.card_state.state;
+    let clock = Clock::get()?;
+
+    state.debit(amount)?;
+    state.updated_at_slot = clock.slot;
+
+    msg!("Debited plaintext card: {:?} amount={} balance={}", state.card_id, amount, state.balance);
+
+    Ok(())
+}
+
+/// Reject plaintext instructions in builds without `plaintext-state`
+fn require_plaintext_state() -> Result<()> {
+    require!(cfg!(feature = "plaintext-state"), DisCardError::PlaintextStateDisabled);
+    Ok(())
+}
+
+// ============================================================================
+// Account Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(card_id: [u8; 32])]
+pub struct CreateCardStatePda<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The card's discard-hooks config PDA, signing through the hooks program
+    #[account(
+        address = CardStatePda::hook_authority(&card_id) @ DisCardError::Unauthorized,
+    )]
+    pub card_authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CardStatePda::SIZE,
+        seeds = [CardStatePda::SEED, card_id.as_ref()],
+        bump,
+    )]
+    pub card_state: Account<'info, CardStatePda>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCardStatePda<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CardStatePda::SEED, card_state.state.card_id.as_ref()],
+        bump = card_state.bump,
+        constraint = card_state.authority == authority.key() @ DisCardError::Unauthorized,
+    )]
+    pub card_state: Account<'info, CardStatePda>,
+}
+
+#[derive(Accounts)]
+pub struct FundCardStatePda<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CardStatePda::SEED, card_state.state.card_id.as_ref()],
+        bump = card_state.bump,
+        constraint = card_state.authority == authority.key() @ DisCardError::Unauthorized,
+    )]
+    pub card_state: Account<'info, CardStatePda>,
+
+    /// The card's token account, whose holdings back the balance
+    #[account(address = card_state.token_account @ DisCardError::Unauthorized)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, card_state_account, read_card_state, run, signer, system_program,
+        uninitialized_card_state, ProgramResult, NOW,
+    };
+    use crate::TransactionCountLimits;
+
+    const CARD_ID: [u8; 32] = [7; 32];
+
+    /// A card funded with 1,000, spending at most 500 per transaction and
+    /// 800 per day, with its periods started now
+    fn funded_card() -> CardState {
+        CardState {
+            card_id: CARD_ID,
+            balance: 1_000,
+            spending_limit: 500,
+            daily_limit: 800,
+            monthly_limit: 10_000,
+            last_daily_reset_unix: NOW,
+            last_weekly_reset_unix: NOW,
+            last_monthly_reset_unix: NOW,
+            ..Default::default()
+        }
+    }
+
+    /// Run `ix` against a plaintext card holding `state`, signed by its
+    /// authority, returning the result and the card account
+    fn run_on_card<I: anchor_lang::InstructionData>(
+        state: CardState,
+        ix: I,
+    ) -> (ProgramResult, AccountInfo<'static>) {
+        let authority = Pubkey::new_unique();
+        let card = card_state_account(authority, state);
+        (run(vec![signer(authority), card.clone()], ix), card)
+    }
+
+    /// Run `record_spending_plaintext` through the program entrypoint,
+    /// returning its result and the card state it leaves behind
+    fn record_spending(state: CardState, amount: u64) -> (ProgramResult, CardState) {
+        let ix = crate::instruction::RecordSpendingPlaintext { spend_amount: amount };
+        let (result, card) = run_on_card(state, ix);
+        (result, read_card_state(&card))
+    }
+
+    fn counts(daily: u16, weekly: u16, monthly: u16) -> TransactionCountLimits {
+        TransactionCountLimits { daily, weekly, monthly }
+    }
+
+    fn limits() -> CardLimits {
+        CardLimits {
+            spending_limit: 500,
+            daily_limit: 800,
+            weekly_limit: 2_000,
+            monthly_limit: 10_000,
+            max_transaction_counts: counts(3, 10, 40),
+            max_balance: 5_000,
+            low_balance_threshold_bps: 2_000,
+        }
+    }
+
+    /// Run `create_card_state_pda` for `CARD_ID`, signed by its hooks card PDA
+    fn create_card(limits: CardLimits) -> (ProgramResult, AccountInfo<'static>) {
+        let card = uninitialized_card_state(CARD_ID);
+        let accounts = vec![
+            signer(Pubkey::new_unique()),
+            signer(CardStatePda::hook_authority(&CARD_ID)),
+            card.clone(),
+            system_program(),
+        ];
+        let ix = crate::instruction::CreateCardStatePda {
+            card_id: CARD_ID,
+            owner_did_commitment: [8; 32],
+            limits,
+            token_account: Pubkey::new_unique(),
+        };
+        (run(accounts, ix), card)
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn spending_is_applied_and_persisted() {
+        let (result, state) = record_spending(funded_card(), 300);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(state.balance, 700);
+        assert_eq!(state.current_daily_spend, 300);
+        assert_eq!(state.daily_transaction_count, 1);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn frozen_card_rejects_spending() {
+        let mut card = funded_card();
+        card.freeze(crate::state::card::FreezeReasonState::FraudDetected);
+
+        let (result, state) = record_spending(card, 100);
+
+        assert_rejected(result, crate::state::card::CardError::CardFrozen);
+        assert_eq!(state.balance, 1_000);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn limits_reject_spending() {
+        let (result, _) = record_spending(funded_card(), 501);
+        assert_rejected(result, crate::state::card::CardError::ExceedsSpendingLimit);
+
+        let mut card = funded_card();
+        card.current_daily_spend = 500;
+        let (result, state) = record_spending(card, 400);
+        assert_rejected(result, crate::state::card::CardError::ExceedsDailyLimit);
+        assert_eq!(state.current_daily_spend, 500);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn balance_rejects_overspending() {
+        let mut card = funded_card();
+        card.balance = 200;
+
+        let (result, state) = record_spending(card, 300);
+
+        assert_rejected(result, crate::state::card::CardError::InsufficientBalance);
+        assert_eq!(state.balance, 200);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn created_card_keeps_its_limits_and_transaction_counts() {
+        let (result, card) = create_card(limits());
+
+        result.unwrap();
+        let state = read_card_state(&card);
+        assert_eq!(state.card_id, CARD_ID);
+        assert_eq!((state.spending_limit, state.daily_limit), (500, 800));
+        assert_eq!((state.weekly_limit, state.monthly_limit), (2_000, 10_000));
+        assert_eq!(state.max_daily_transactions, 3);
+        assert_eq!(state.max_weekly_transactions, 10);
+        assert_eq!(state.max_monthly_transactions, 40);
+        assert_eq!((state.max_balance, state.low_balance_threshold_bps), (5_000, 2_000));
+        assert_eq!(state.last_daily_reset_unix, NOW);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn updated_transaction_counts_are_enforced() {
+        let update = CardLimitsUpdate {
+            daily_limit: Some(0),
+            max_transaction_counts: Some(counts(2, 0, 0)),
+            ..Default::default()
+        };
+
+        let authority = Pubkey::new_unique();
+        let card = card_state_account(authority, funded_card());
+        let spend = |spend_amount| {
+            let ix = crate::instruction::RecordSpendingPlaintext { spend_amount };
+            run(vec![signer(authority), card.clone()], ix)
+        };
+
+        let ix = crate::instruction::UpdateCardStatePdaLimits { update };
+        run(vec![signer(authority), card.clone()], ix).unwrap();
+
+        let state = read_card_state(&card);
+        assert_eq!((state.daily_limit, state.max_daily_transactions), (0, 2));
+        assert_eq!((state.spending_limit, state.monthly_limit), (500, 10_000));
+        spend(100).unwrap();
+        spend(500).unwrap();
+        assert_rejected(spend(100), crate::state::card::CardError::ExceedsDailyTransactionCount);
+        assert_eq!(read_card_state(&card).balance, 400);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn warning_thresholds_must_be_at_most_the_whole_limit() {
+        let (result, _) = create_card(CardLimits { low_balance_threshold_bps: 10_001, ..limits() });
+        assert_rejected(result, crate::state::card::CardError::InvalidCardState);
+
+        let update = CardLimitsUpdate {
+            low_balance_threshold_bps: Some(10_001),
+            ..Default::default()
+        };
+        let ix = crate::instruction::UpdateCardStatePdaLimits { update };
+        let (result, card) = run_on_card(funded_card(), ix);
+        assert_rejected(result, crate::state::card::CardError::InvalidCardState);
+        assert_eq!(read_card_state(&card).low_balance_threshold_bps, 0);
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn updated_warning_threshold_warns_on_spending() {
+        use crate::events::LowBalanceWarning;
+        use crate::test_utils::emitted;
+
+        let update = CardLimitsUpdate {
+            max_balance: Some(2_000),
+            low_balance_threshold_bps: Some(5_000),
+            ..Default::default()
+        };
+        let authority = Pubkey::new_unique();
+        let card = card_state_account(authority, funded_card());
+        let spend = |spend_amount| {
+            let ix = crate::instruction::RecordSpendingPlaintext { spend_amount };
+            run(vec![signer(authority), card.clone()], ix)
+        };
+
+        let ix = crate::instruction::UpdateCardStatePdaLimits { update };
+        run(vec![signer(authority), card.clone()], ix).unwrap();
+
+        let state = read_card_state(&card);
+        assert_eq!((state.max_balance, state.low_balance_threshold_bps), (2_000, 5_000));
+        spend(500).unwrap();
+        assert!(emitted::<LowBalanceWarning>().is_empty());
+        spend(300).unwrap();
+        let warnings = emitted::<LowBalanceWarning>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].card_id, CARD_ID);
+        assert_eq!((warnings[0].balance, warnings[0].threshold), (200, 250));
+    }
+
+    #[cfg(feature = "plaintext-state")]
+    #[test]
+    fn reserve_is_kept_back_from_spending() {
+        let authority = Pubkey::new_unique();
+        let state = CardState { spending_limit: 1_000, daily_limit: 2_000, ..funded_card() };
+        let card = card_state_account(authority, state);
+        let spend = |spend_amount| {
+            let ix = crate::instruction::RecordSpendingPlaintext { spend_amount };
+            run(vec![signer(authority), card.clone()], ix)
+        };
+
+        let ix = crate::instruction::UpdateCardStatePdaReserve { reserved_balance: 400 };
+        run(vec![signer(authority), card.clone()], ix).unwrap();
+
+        assert_eq!(read_card_state(&card).reserved_balance, 400);
+        assert_rejected(spend(601), crate::state::card::CardError::InsufficientBalance);
+        assert_eq!(read_card_state(&card).balance, 1_000);
+        spend(600).unwrap();
+        assert_eq!(read_card_state(&card).balance, 400);
+    }
+
+    #[test]
+    fn reserve_updates_need_the_card_authority() {
+        let card = card_state_account(Pubkey::new_unique(), funded_card());
+        let ix = crate::instruction::UpdateCardStatePdaReserve { reserved_balance: 1_000 };
+
+        let result = run(vec![signer(Pubkey::new_unique()), card.clone()], ix);
+
+        assert_rejected(result, DisCardError::Unauthorized);
+        assert_eq!(read_card_state(&card).reserved_balance, 0);
+    }
+
+    #[test]
+    fn limit_updates_need_the_card_authority() {
+        let card = card_state_account(Pubkey::new_unique(), funded_card());
+        let update = CardLimitsUpdate { spending_limit: Some(1), ..Default::default() };
+        let ix = crate::instruction::UpdateCardStatePdaLimits { update };
+
+        let result = run(vec![signer(Pubkey::new_unique()), card.clone()], ix);
+
+        assert_rejected(result, DisCardError::Unauthorized);
+        assert_eq!(read_card_state(&card).spending_limit, 500);
+    }
+
+    #[cfg(not(feature = "plaintext-state"))]
+    #[test]
+    fn spending_is_rejected_without_the_feature() {
+        let (result, state) = record_spending(funded_card(), 100);
+
+        assert_rejected(result, DisCardError::PlaintextStateDisabled);
+        assert_eq!(state.balance, 1_000);
+    }
+
+    #[cfg(not(feature = "plaintext-state"))]
+    #[test]
+    fn limits_are_rejected_without_the_feature() {
+        let (result, _) = create_card(limits());
+        assert_rejected(result, DisCardError::PlaintextStateDisabled);
+
+        let update = CardLimitsUpdate {
+            max_transaction_counts: Some(counts(1, 1, 1)),
+            ..Default::default()
+        };
+        let ix = crate::instruction::UpdateCardStatePdaLimits { update };
+        let (result, card) = run_on_card(funded_card(), ix);
+        assert_rejected(result, DisCardError::PlaintextStateDisabled);
+        assert_eq!(read_card_state(&card).max_daily_transactions, 0);
+    }
+}
@@ -0,0 +1,64 @@
+//! Program Configuration Instructions
+//!
+//! Creates and updates the program-wide `StateConfig` PDA.
+
+use anchor_lang::prelude::*;
+
+use crate::error::DisCardError;
+use crate::state::{StateConfig, DEFAULT_MIN_KEY_ROTATION_INTERVAL_SLOTS};
+
+/// Create the program configuration; the signer becomes its authority
+pub fn initialize_state_config(ctx: Context<InitializeStateConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.state_config;
+
+    config.authority = ctx.accounts.authority.key();
+    config.min_key_rotation_interval_slots = DEFAULT_MIN_KEY_ROTATION_INTERVAL_SLOTS;
+    config.bump = ctx.bumps.state_config;
+
+    msg!("State config initialized: authority={}", config.authority);
+
+    Ok(())
+}
+
+/// Set the minimum slot gap between DID key rotations (0 disables the limit)
+pub fn set_key_rotation_interval(
+    ctx: Context<UpdateStateConfig>,
+    min_key_rotation_interval_slots: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.state_config;
+    config.min_key_rotation_interval_slots = min_key_rotation_interval_slots;
+
+    msg!("Key rotation interval set: {} slots", min_key_rotation_interval_slots);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeStateConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = StateConfig::SIZE,
+        seeds = [b"state_config"],
+        bump,
+    )]
+    pub state_config: Account<'info, StateConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state_config"],
+        bump = state_config.bump,
+        constraint = state_config.authority == authority.key() @ DisCardError::Unauthorized,
+    )]
+    pub state_config: Account<'info, StateConfig>,
+}
@@ -1,23 +1,35 @@
 //! DID instruction handlers
 
 use anchor_lang::prelude::*;
-use crate::state::did::{DIDCommitmentState, DIDStatus, DIDError};
+use light_sdk::compressed_account::CompressedAccountWithMerkleContext;
+use crate::error::DisCardError;
+use crate::instructions::card::verify_compressed_account;
+use crate::state::did::{
+    check_attestation_age, verify_document_commitment, DIDCommitmentState, DIDStatus, DIDError,
+    DEFAULT_MAX_ATTESTATION_AGE_SECS,
+};
 use crate::{
-    StoreDIDCommitment, UpdateDIDCommitment, VerifyRecovery,
+    StoreDIDCommitment, UpdateDIDCommitment, UpdateDIDStatus, VerifyRecovery,
     CompressedProof, GuardianAttestation,
 };
 
 /// Store a DID commitment on-chain
+///
+/// The payer becomes the DID's controller.
 pub fn store_did_commitment(
     ctx: Context<StoreDIDCommitment>,
     did_string: String,
     commitment_hash: [u8; 32],
     document_hash: [u8; 32],
+    salt: [u8; 32],
     recovery_threshold: u8,
 ) -> Result<()> {
     let clock = Clock::get()?;
     let current_slot = clock.slot;
 
+    // The commitment must actually commit to the document it's stored with
+    verify_document_commitment(&commitment_hash, &document_hash, &salt)?;
+
     // Hash the DID string for lookup
     let did_hash = hash_did_string(&did_string);
 
@@ -34,10 +46,12 @@ pub fn store_did_commitment(
         key_rotation_count: 0,
         created_at_slot: current_slot,
         updated_at_slot: current_slot,
+        max_attestation_age_secs: DEFAULT_MAX_ATTESTATION_AGE_SECS,
+        controller: ctx.accounts.payer.key(),
     };
 
     // Serialize state
-    let state_bytes = did_state.try_to_vec()?;
+    let _state_bytes = did_state.try_to_vec()?;
 
     // In production, call Light Protocol to create compressed account
     // light_sdk::compress_account(ctx.accounts.light_system_program, state_bytes, ...)?;
@@ -55,33 +69,43 @@ pub fn update_did_commitment(
     did_string: String,
     new_commitment_hash: [u8; 32],
     new_document_hash: [u8; 32],
+    salt: [u8; 32],
+    compressed_did: CompressedAccountWithMerkleContext,
     proof: CompressedProof,
 ) -> Result<()> {
     let clock = Clock::get()?;
     let current_slot = clock.slot;
 
-    // In production:
-    // 1. Verify the proof
-    // 2. Decompress current state
-    // 3. Apply key rotation
-    // 4. Recompress with new state
+    verify_document_commitment(&new_commitment_hash, &new_document_hash, &salt)?;
+
+    // In production, recompress with the new state
+
+    let mut did_state = load_did_state(&compressed_did, &proof, &did_string)?;
+    did_state.check_controller(&ctx.accounts.authority.key())?;
+
+    // Rapid rotations can hide a takeover, so they're spaced out
+    did_state.check_rotation_interval(
+        current_slot,
+        ctx.accounts.state_config.min_key_rotation_interval_slots,
+    )?;
+    did_state.rotate_key(new_commitment_hash, new_document_hash, current_slot);
 
     msg!("Updated DID commitment: {}", did_string);
     msg!("New commitment hash: {:?}", new_commitment_hash);
+    msg!("Key rotation count: {}", did_state.key_rotation_count);
 
     Ok(())
 }
 
 /// Verify a DID recovery using guardian attestations
 pub fn verify_recovery(
-    ctx: Context<VerifyRecovery>,
+    _ctx: Context<VerifyRecovery>,
     did_string: String,
     new_key_commitment: [u8; 32],
     guardian_attestations: Vec<GuardianAttestation>,
-    recovery_proof: CompressedProof,
+    _recovery_proof: CompressedProof,
 ) -> Result<()> {
     let clock = Clock::get()?;
-    let current_slot = clock.slot;
 
     // In production:
     // 1. Verify the recovery proof (ZK proof that threshold is met)
@@ -106,6 +130,14 @@ pub fn verify_recovery(
             attestation.timestamp
         );
 
+        // Stale approvals can't be replayed. In production the limit comes
+        // from the decompressed DID state (`DIDCommitmentState::check_attestation_age`)
+        check_attestation_age(
+            attestation.timestamp,
+            clock.unix_timestamp,
+            DEFAULT_MAX_ATTESTATION_AGE_SECS,
+        )?;
+
         // In production, verify signature
         // verify_signature(attestation.guardian_did_commitment, attestation.attestation_hash, attestation.signature)?;
     }
@@ -113,6 +145,110 @@ pub fn verify_recovery(
     Ok(())
 }
 
+/// Suspend a DID (owner only)
+///
+/// Suspension is reversible with `reactivate_did`.
+pub fn suspend_did(
+    ctx: Context<UpdateDIDStatus>,
+    did_string: String,
+    compressed_did: CompressedAccountWithMerkleContext,
+    proof: CompressedProof,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // In production, recompress with the new state
+
+    let mut did_state = load_did_state(&compressed_did, &proof, &did_string)?;
+    did_state.check_controller(&ctx.accounts.authority.key())?;
+    did_state.suspend(clock.slot)?;
+
+    msg!("Suspended DID: {}", did_string);
+
+    Ok(())
+}
+
+/// Reactivate a suspended DID (owner only)
+pub fn reactivate_did(
+    ctx: Context<UpdateDIDStatus>,
+    did_string: String,
+    compressed_did: CompressedAccountWithMerkleContext,
+    proof: CompressedProof,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // In production, recompress with the new state
+
+    let mut did_state = load_did_state(&compressed_did, &proof, &did_string)?;
+    did_state.check_controller(&ctx.accounts.authority.key())?;
+    did_state.reactivate(clock.slot)?;
+
+    msg!("Reactivated DID: {}", did_string);
+
+    Ok(())
+}
+
+/// Permanently revoke a DID
+///
+/// Revocation is terminal, so it needs fresh attestations from at least
+/// `recovery_threshold` (and never fewer than one) distinct guardians rather
+/// than the owner key alone.
+pub fn revoke_did(
+    _ctx: Context<UpdateDIDStatus>,
+    did_string: String,
+    guardian_attestations: Vec<GuardianAttestation>,
+    compressed_did: CompressedAccountWithMerkleContext,
+    proof: CompressedProof,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // In production:
+    // 1. Verify each attestation is signed by an active guardian of this DID
+    // 2. Recompress with new state
+
+    let mut did_state = load_did_state(&compressed_did, &proof, &did_string)?;
+
+    let mut guardians: Vec<[u8; 32]> = Vec::with_capacity(guardian_attestations.len());
+    for attestation in &guardian_attestations {
+        did_state.check_attestation_age(attestation.timestamp, clock.unix_timestamp)?;
+        if !guardians.contains(&attestation.guardian_did_commitment) {
+            guardians.push(attestation.guardian_did_commitment);
+        }
+    }
+    // A DID stored without guardians still needs one to revoke it
+    let required = did_state.recovery_threshold.max(1) as usize;
+    require!(guardians.len() >= required, DIDError::InsufficientAttestations);
+
+    did_state.revoke(clock.slot)?;
+
+    msg!("Revoked DID: {} ({} guardians)", did_string, guardians.len());
+
+    Ok(())
+}
+
+/// Read a `DIDCommitmentState` out of a compressed account
+///
+/// The account must be owned by this program, belong to `did_string`, and be
+/// proven to exist in the state tree by `proof`.
+fn load_did_state(
+    compressed_did: &CompressedAccountWithMerkleContext,
+    proof: &CompressedProof,
+    did_string: &str,
+) -> Result<DIDCommitmentState> {
+    verify_compressed_account(compressed_did, proof)?;
+
+    let account = &compressed_did.compressed_account;
+    if account.owner != crate::ID {
+        return Err(error!(DisCardError::InvalidAccountType));
+    }
+
+    let data = account.data.as_ref().ok_or(error!(DIDError::DIDNotFound))?;
+    let did_state = DIDCommitmentState::deserialize(&mut &data.data[..])
+        .map_err(|_| error!(DisCardError::InvalidAccountType))?;
+    require!(did_state.did_hash == hash_did_string(did_string), DIDError::DIDNotFound);
+
+    Ok(did_state)
+}
+
 /// Hash a DID string to 32 bytes
 fn hash_did_string(did: &str) -> [u8; 32] {
     use std::collections::hash_map::DefaultHasher;
@@ -2,12 +2,16 @@
 
 pub mod audit_anchor;
 pub mod card;
+pub mod config;
 pub mod did;
 pub mod policy;
 pub mod velocity;
+pub mod card_pda;
 
 pub use audit_anchor::*;
 pub use card::*;
+pub use config::*;
 pub use did::*;
 pub use policy::*;
 pub use velocity::*;
+pub use card_pda::*;
@@ -1,15 +1,17 @@
 //! Policy instruction handlers
 
 use anchor_lang::prelude::*;
+use crate::error::DisCardError;
+use crate::state::card::{validate_mcc, CardState, CardError, MerchantWhitelistEntry, MccWhitelistEntry};
 use crate::{UpdateMerchantWhitelist, UpdateMccWhitelist, CompressedProof};
 
 /// Update merchant whitelist for a card
 pub fn update_merchant_whitelist(
-    ctx: Context<UpdateMerchantWhitelist>,
+    _ctx: Context<UpdateMerchantWhitelist>,
     card_id: [u8; 32],
     merchants_to_add: Vec<[u8; 32]>,
     merchants_to_remove: Vec<[u8; 32]>,
-    proof: CompressedProof,
+    _proof: CompressedProof,
 ) -> Result<()> {
     // In production:
     // 1. Verify the proof
@@ -34,11 +36,11 @@ pub fn update_merchant_whitelist(
 
 /// Update MCC whitelist for a card
 pub fn update_mcc_whitelist(
-    ctx: Context<UpdateMccWhitelist>,
+    _ctx: Context<UpdateMccWhitelist>,
     card_id: [u8; 32],
     mcc_codes_to_add: Vec<u16>,
     mcc_codes_to_remove: Vec<u16>,
-    proof: CompressedProof,
+    _proof: CompressedProof,
 ) -> Result<()> {
     // In production:
     // 1. Verify the proof
@@ -46,6 +48,10 @@ pub fn update_mcc_whitelist(
     // 3. Update MCC whitelist entries
     // 4. Recompress with new state
 
+    for mcc in &mcc_codes_to_add {
+        validate_mcc(*mcc)?;
+    }
+
     msg!("Updating MCC whitelist for card: {:?}", card_id);
     msg!("Adding {} MCC codes", mcc_codes_to_add.len());
     msg!("Removing {} MCC codes", mcc_codes_to_remove.len());
@@ -66,26 +72,78 @@ pub fn validate_policy(
     card_id: [u8; 32],
     merchant_id: Option<[u8; 32]>,
     mcc_code: Option<u16>,
-    _merchant_whitelist: &[[u8; 32]],
-    _mcc_whitelist: &[u16],
+    merchant_whitelist: &[[u8; 32]],
+    mcc_whitelist: &[u16],
     merchant_locking_enabled: bool,
     mcc_locking_enabled: bool,
 ) -> Result<()> {
-    // If merchant locking is enabled, check whitelist
+    // If merchant locking is enabled, the merchant must be whitelisted
     if merchant_locking_enabled {
-        if let Some(mid) = merchant_id {
-            // In production, check if merchant is in whitelist
-            msg!("Checking merchant {:?} against whitelist", mid);
+        let allowed = merchant_id.is_some_and(|mid| merchant_whitelist.contains(&mid));
+        if !allowed {
+            msg!("Merchant {:?} not whitelisted for card {:?}", merchant_id, card_id);
+            return Err(error!(CardError::MerchantNotWhitelisted));
         }
     }
 
-    // If MCC locking is enabled, check whitelist
+    // If MCC locking is enabled, the MCC must be whitelisted
     if mcc_locking_enabled {
-        if let Some(mcc) = mcc_code {
-            // In production, check if MCC is in whitelist
-            msg!("Checking MCC {} against whitelist", mcc);
+        let allowed = mcc_code.is_some_and(|mcc| mcc_whitelist.contains(&mcc));
+        if !allowed {
+            msg!("MCC {:?} not whitelisted for card {:?}", mcc_code, card_id);
+            return Err(error!(CardError::MccNotAllowed));
         }
     }
 
     Ok(())
 }
+
+/// Load a card's whitelist entries from the instruction's remaining accounts
+///
+/// The caller must pass every entry for the card: `merchant_whitelist_count`
+/// merchant entries followed by `mcc_whitelist_count` MCC entries. Requiring
+/// the full set prevents a whitelisted merchant from being hidden (or a
+/// foreign entry from being substituted) to sway the check.
+pub fn load_whitelist_entries(
+    card_state: &CardState,
+    remaining_accounts: &[AccountInfo],
+) -> Result<(Vec<[u8; 32]>, Vec<u16>)> {
+    let merchant_count = card_state.merchant_whitelist_count as usize;
+    let mcc_count = card_state.mcc_whitelist_count as usize;
+
+    if remaining_accounts.len() != merchant_count + mcc_count {
+        return Err(error!(DisCardError::AccountNotFound));
+    }
+
+    let (merchant_accounts, mcc_accounts) = remaining_accounts.split_at(merchant_count);
+
+    let mut merchants = Vec::with_capacity(merchant_count);
+    for account in merchant_accounts {
+        let entry: MerchantWhitelistEntry = read_entry(account)?;
+        if entry.card_id != card_state.card_id {
+            return Err(error!(CardError::InvalidCardState));
+        }
+        merchants.push(entry.merchant_id);
+    }
+
+    let mut mcc_codes = Vec::with_capacity(mcc_count);
+    for account in mcc_accounts {
+        let entry: MccWhitelistEntry = read_entry(account)?;
+        if entry.card_id != card_state.card_id {
+            return Err(error!(CardError::InvalidCardState));
+        }
+        mcc_codes.push(entry.mcc_code);
+    }
+
+    Ok((merchants, mcc_codes))
+}
+
+/// Deserialize a whitelist entry owned by this program
+fn read_entry<T: AnchorDeserialize>(account: &AccountInfo) -> Result<T> {
+    if account.owner != &crate::ID {
+        return Err(error!(DisCardError::InvalidAccountType));
+    }
+
+    let data = account.try_borrow_data()?;
+    T::deserialize(&mut &data[..]).map_err(|_| error!(DisCardError::InvalidAccountType))
+}
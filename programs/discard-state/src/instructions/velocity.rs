@@ -1,11 +1,11 @@
 //! Velocity reset instruction handlers
 
 use anchor_lang::prelude::*;
-use crate::{ResetDailySpending, ResetMonthlySpending};
+use crate::{ResetDailySpending, ResetWeeklySpending, ResetMonthlySpending};
 
 /// Reset daily spending counters for multiple cards
 pub fn reset_daily_spending(
-    ctx: Context<ResetDailySpending>,
+    _ctx: Context<ResetDailySpending>,
     card_ids: Vec<[u8; 32]>,
 ) -> Result<()> {
     let clock = Clock::get()?;
@@ -28,9 +28,34 @@ pub fn reset_daily_spending(
     Ok(())
 }
 
+/// Reset weekly spending counters for multiple cards
+pub fn reset_weekly_spending(
+    _ctx: Context<ResetWeeklySpending>,
+    card_ids: Vec<[u8; 32]>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_slot = clock.slot;
+
+    // In production:
+    // 1. For each card, decompress state
+    // 2. Reset weekly spending counter
+    // 3. Update last_reset_slot
+    // 4. Recompress with new state
+
+    msg!("Resetting weekly spending for {} cards", card_ids.len());
+    msg!("Current slot: {}", current_slot);
+
+    for card_id in &card_ids {
+        msg!("Resetting weekly spending for card: {:?}", card_id);
+        // reset_card_weekly_spending(card_id, current_slot)?;
+    }
+
+    Ok(())
+}
+
 /// Reset monthly spending counters for multiple cards
 pub fn reset_monthly_spending(
-    ctx: Context<ResetMonthlySpending>,
+    _ctx: Context<ResetMonthlySpending>,
     card_ids: Vec<[u8; 32]>,
 ) -> Result<()> {
     let clock = Clock::get()?;
@@ -53,18 +78,96 @@ pub fn reset_monthly_spending(
     Ok(())
 }
 
-/// Calculate if daily reset is needed based on slot time
-pub fn should_reset_daily(last_reset_slot: u64, current_slot: u64) -> bool {
-    // Assuming ~400ms slots, roughly 216000 slots per day
-    const SLOTS_PER_DAY: u64 = 216_000;
+// Calendar timing constants (UTC)
+const SECONDS_PER_DAY: i64 = 86_400;
+/// 1970-01-01 was a Thursday (weekday 4, counting from Sunday = 0)
+const EPOCH_WEEKDAY: i64 = 4;
+/// Weekday plaintext card weeks start on
+pub const PLAINTEXT_WEEK_START_DAY: u8 = 1; // Monday
 
-    current_slot.saturating_sub(last_reset_slot) >= SLOTS_PER_DAY
+/// Calculate if daily reset is needed (a UTC day boundary has passed)
+///
+/// Uses `Clock.unix_timestamp` rather than slot counts so resets stay
+/// wall-clock accurate regardless of slot speed.
+pub fn should_reset_daily(last_reset_unix: i64, now: i64) -> bool {
+    day_index(now) > day_index(last_reset_unix)
 }
 
-/// Calculate if monthly reset is needed based on slot time
-pub fn should_reset_monthly(last_reset_slot: u64, current_slot: u64) -> bool {
-    // Assuming ~400ms slots, roughly 6480000 slots per month (30 days)
-    const SLOTS_PER_MONTH: u64 = 6_480_000;
+/// Calculate if weekly reset is needed (a UTC week boundary has passed)
+///
+/// Weeks start on `week_start_day` (0 = Sunday .. 6 = Saturday).
+pub fn should_reset_weekly(last_reset_unix: i64, now: i64, week_start_day: u8) -> bool {
+    week_index(now, week_start_day) > week_index(last_reset_unix, week_start_day)
+}
 
-    current_slot.saturating_sub(last_reset_slot) >= SLOTS_PER_MONTH
+/// Calculate if monthly reset is needed (a UTC calendar month boundary has passed)
+pub fn should_reset_monthly(last_reset_unix: i64, now: i64) -> bool {
+    month_index(now) > month_index(last_reset_unix)
+}
+
+/// Days since the Unix epoch
+fn day_index(unix_timestamp: i64) -> i64 {
+    unix_timestamp.div_euclid(SECONDS_PER_DAY)
+}
+
+/// Weeks since the Unix epoch, aligned to `week_start_day`
+fn week_index(unix_timestamp: i64, week_start_day: u8) -> i64 {
+    (day_index(unix_timestamp) + EPOCH_WEEKDAY - week_start_day as i64).div_euclid(7)
+}
+
+/// Calendar months since the Unix epoch (year * 12 + zero-based month)
+fn month_index(unix_timestamp: i64) -> i64 {
+    let (year, month) = civil_year_month(day_index(unix_timestamp));
+    year * 12 + (month - 1)
+}
+
+/// Convert days since the Unix epoch to a (year, month) civil date
+/// (Howard Hinnant's `civil_from_days` algorithm)
+fn civil_year_month(days: i64) -> (i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-01 00:00:00 UTC, a Monday
+    const JAN_1_2024: i64 = 1_704_067_200;
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    #[test]
+    fn weekly_reset_fires_at_the_week_boundary() {
+        let sunday_night = JAN_1_2024 - 1;
+
+        assert!(should_reset_weekly(sunday_night, JAN_1_2024, PLAINTEXT_WEEK_START_DAY));
+        assert!(!should_reset_weekly(JAN_1_2024, JAN_1_2024 + 7 * DAY - 1, PLAINTEXT_WEEK_START_DAY));
+        assert!(should_reset_weekly(JAN_1_2024, JAN_1_2024 + 7 * DAY, PLAINTEXT_WEEK_START_DAY));
+    }
+
+    #[test]
+    fn weekly_reset_follows_week_start_day() {
+        let saturday = JAN_1_2024 - 2 * DAY;
+        let sunday = JAN_1_2024 - DAY;
+
+        assert!(should_reset_weekly(saturday, sunday, 0));
+        assert!(!should_reset_weekly(saturday, sunday, 1));
+    }
+
+    #[test]
+    fn daily_and_monthly_resets_follow_the_calendar() {
+        let feb_1 = JAN_1_2024 + 31 * DAY;
+
+        assert!(should_reset_daily(JAN_1_2024 - 1, JAN_1_2024));
+        assert!(!should_reset_daily(JAN_1_2024, JAN_1_2024 + DAY - 1));
+        assert!(!should_reset_monthly(JAN_1_2024, feb_1 - 1));
+        assert!(should_reset_monthly(feb_1 - 1, feb_1));
+    }
 }
@@ -11,15 +11,16 @@
 
 use anchor_lang::prelude::*;
 use light_sdk::compressed_account::CompressedAccountWithMerkleContext;
-use light_sdk::merkle_context::PackedMerkleContext;
 
 pub mod instructions;
 pub mod state;
 pub mod error;
+pub mod events;
+#[cfg(test)]
+mod test_utils;
 
 use instructions::*;
 use state::*;
-use error::*;
 
 declare_id!("DCrd1111111111111111111111111111111111111111");
 
@@ -36,18 +37,9 @@ pub mod discard_state {
         ctx: Context<CreateCompressedCard>,
         card_id: [u8; 32],
         owner_did_commitment: [u8; 32],
-        spending_limit: u64,
-        daily_limit: u64,
-        monthly_limit: u64,
+        limits: CardLimits,
     ) -> Result<()> {
-        instructions::card::create_compressed_card(
-            ctx,
-            card_id,
-            owner_did_commitment,
-            spending_limit,
-            daily_limit,
-            monthly_limit,
-        )
+        instructions::card::create_compressed_card(ctx, card_id, owner_did_commitment, limits)
     }
 
     /// Update card balance after funding or spending
@@ -61,22 +53,17 @@ pub mod discard_state {
     }
 
     /// Record spending and update velocity counters
+    ///
+    /// Needs the `compressed-state` feature; plaintext cards spend through
+    /// `record_spending_plaintext`.
     pub fn record_spending(
         ctx: Context<RecordSpending>,
         card_id: [u8; 32],
-        spend_amount: u64,
-        merchant_id: Option<[u8; 32]>,
-        mcc_code: Option<u16>,
+        spend: RecordedSpend,
+        compressed_card: CompressedAccountWithMerkleContext,
         proof: CompressedProof,
     ) -> Result<()> {
-        instructions::card::record_spending(
-            ctx,
-            card_id,
-            spend_amount,
-            merchant_id,
-            mcc_code,
-            proof,
-        )
+        instructions::card::record_spending(ctx, card_id, spend, compressed_card, proof)
     }
 
     /// Freeze a card (emergency action)
@@ -98,25 +85,81 @@ pub mod discard_state {
         instructions::card::unfreeze_card(ctx, card_id, proof)
     }
 
+    /// Set the unspendable reserve kept on a card
+    pub fn update_reserve(
+        ctx: Context<UpdateReserve>,
+        card_id: [u8; 32],
+        reserved_balance: u64,
+        proof: CompressedProof,
+    ) -> Result<()> {
+        instructions::card::update_reserve(ctx, card_id, reserved_balance, proof)
+    }
+
     /// Update card spending limits
     pub fn update_card_limits(
         ctx: Context<UpdateCardLimits>,
         card_id: [u8; 32],
-        new_spending_limit: Option<u64>,
-        new_daily_limit: Option<u64>,
-        new_monthly_limit: Option<u64>,
+        update: CardLimitsUpdate,
         proof: CompressedProof,
     ) -> Result<()> {
-        instructions::card::update_card_limits(
+        instructions::card::update_card_limits(ctx, card_id, update, proof)
+    }
+
+    // ========================================================================
+    // Plaintext Card State Instructions (feature = "plaintext-state")
+    // ========================================================================
+
+    /// Create an uncompressed card state PDA
+    pub fn create_card_state_pda(
+        ctx: Context<CreateCardStatePda>,
+        card_id: [u8; 32],
+        owner_did_commitment: [u8; 32],
+        limits: CardLimits,
+        token_account: Pubkey,
+    ) -> Result<()> {
+        instructions::card_pda::create_card_state_pda(
             ctx,
             card_id,
-            new_spending_limit,
-            new_daily_limit,
-            new_monthly_limit,
-            proof,
+            owner_did_commitment,
+            limits,
+            token_account,
         )
     }
 
+    /// Credit tokens already held by an uncompressed card's token account
+    pub fn fund_card_state_pda(ctx: Context<FundCardStatePda>, amount: u64) -> Result<()> {
+        instructions::card_pda::fund_card_state_pda(ctx, amount)
+    }
+
+    /// Deduct a hook-approved transfer from an uncompressed card's balance
+    pub fn debit_card_state_pda(ctx: Context<UpdateCardStatePda>, amount: u64) -> Result<()> {
+        instructions::card_pda::debit_card_state_pda(ctx, amount)
+    }
+
+    /// Change an uncompressed card's limits
+    pub fn update_card_state_pda_limits(
+        ctx: Context<UpdateCardStatePda>,
+        update: CardLimitsUpdate,
+    ) -> Result<()> {
+        instructions::card_pda::update_card_state_pda_limits(ctx, update)
+    }
+
+    /// Set the unspendable reserve kept on an uncompressed card
+    pub fn update_card_state_pda_reserve(
+        ctx: Context<UpdateCardStatePda>,
+        reserved_balance: u64,
+    ) -> Result<()> {
+        instructions::card_pda::update_card_state_pda_reserve(ctx, reserved_balance)
+    }
+
+    /// Record spending against an uncompressed card and persist the result
+    pub fn record_spending_plaintext(
+        ctx: Context<UpdateCardStatePda>,
+        spend_amount: u64,
+    ) -> Result<()> {
+        instructions::card_pda::record_spending_plaintext(ctx, spend_amount)
+    }
+
     // ========================================================================
     // DID Commitment Instructions
     // ========================================================================
@@ -127,6 +170,7 @@ pub mod discard_state {
         did_string: String,
         commitment_hash: [u8; 32],
         document_hash: [u8; 32],
+        salt: [u8; 32],
         recovery_threshold: u8,
     ) -> Result<()> {
         instructions::did::store_did_commitment(
@@ -134,6 +178,7 @@ pub mod discard_state {
             did_string,
             commitment_hash,
             document_hash,
+            salt,
             recovery_threshold,
         )
     }
@@ -144,6 +189,8 @@ pub mod discard_state {
         did_string: String,
         new_commitment_hash: [u8; 32],
         new_document_hash: [u8; 32],
+        salt: [u8; 32],
+        compressed_did: CompressedAccountWithMerkleContext,
         proof: CompressedProof,
     ) -> Result<()> {
         instructions::did::update_did_commitment(
@@ -151,6 +198,45 @@ pub mod discard_state {
             did_string,
             new_commitment_hash,
             new_document_hash,
+            salt,
+            compressed_did,
+            proof,
+        )
+    }
+
+    /// Suspend a DID (owner only, reversible)
+    pub fn suspend_did(
+        ctx: Context<UpdateDIDStatus>,
+        did_string: String,
+        compressed_did: CompressedAccountWithMerkleContext,
+        proof: CompressedProof,
+    ) -> Result<()> {
+        instructions::did::suspend_did(ctx, did_string, compressed_did, proof)
+    }
+
+    /// Reactivate a suspended DID (owner only)
+    pub fn reactivate_did(
+        ctx: Context<UpdateDIDStatus>,
+        did_string: String,
+        compressed_did: CompressedAccountWithMerkleContext,
+        proof: CompressedProof,
+    ) -> Result<()> {
+        instructions::did::reactivate_did(ctx, did_string, compressed_did, proof)
+    }
+
+    /// Permanently revoke a DID with guardian attestations
+    pub fn revoke_did(
+        ctx: Context<UpdateDIDStatus>,
+        did_string: String,
+        guardian_attestations: Vec<GuardianAttestation>,
+        compressed_did: CompressedAccountWithMerkleContext,
+        proof: CompressedProof,
+    ) -> Result<()> {
+        instructions::did::revoke_did(
+            ctx,
+            did_string,
+            guardian_attestations,
+            compressed_did,
             proof,
         )
     }
@@ -172,6 +258,23 @@ pub mod discard_state {
         )
     }
 
+    // ========================================================================
+    // Configuration Instructions
+    // ========================================================================
+
+    /// Create the program configuration (signer becomes its authority)
+    pub fn initialize_state_config(ctx: Context<InitializeStateConfig>) -> Result<()> {
+        instructions::config::initialize_state_config(ctx)
+    }
+
+    /// Set the minimum slot gap between DID key rotations
+    pub fn set_key_rotation_interval(
+        ctx: Context<UpdateStateConfig>,
+        min_key_rotation_interval_slots: u64,
+    ) -> Result<()> {
+        instructions::config::set_key_rotation_interval(ctx, min_key_rotation_interval_slots)
+    }
+
     // ========================================================================
     // Audit Anchoring Instructions
     // ========================================================================
@@ -182,12 +285,16 @@ pub mod discard_state {
         merkle_root: [u8; 32],
         batch_size: u32,
         timestamp: i64,
+        prev_root: [u8; 32],
+        sequence: u64,
     ) -> Result<()> {
         instructions::audit_anchor::anchor_audit_merkle_root(
             ctx,
             merkle_root,
             batch_size,
             timestamp,
+            prev_root,
+            sequence,
         )
     }
 
@@ -241,6 +348,14 @@ pub mod discard_state {
         instructions::velocity::reset_daily_spending(ctx, card_ids)
     }
 
+    /// Reset weekly spending counters (called by cron)
+    pub fn reset_weekly_spending(
+        ctx: Context<ResetWeeklySpending>,
+        card_ids: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::velocity::reset_weekly_spending(ctx, card_ids)
+    }
+
     /// Reset monthly spending counters (called by cron)
     pub fn reset_monthly_spending(
         ctx: Context<ResetMonthlySpending>,
@@ -344,6 +459,19 @@ pub struct UnfreezeCard<'info> {
     pub merkle_tree: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateReserve<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Verified by Light Protocol
+    pub light_system_program: AccountInfo<'info>,
+
+    /// CHECK: Verified by Light Protocol
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+}
+
 #[derive(Accounts)]
 pub struct UpdateCardLimits<'info> {
     #[account(mut)]
@@ -381,6 +509,26 @@ pub struct UpdateDIDCommitment<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Program configuration holding the key rotation interval
+    #[account(
+        seeds = [b"state_config"],
+        bump = state_config.bump,
+    )]
+    pub state_config: Account<'info, StateConfig>,
+
+    /// CHECK: Verified by Light Protocol
+    pub light_system_program: AccountInfo<'info>,
+
+    /// CHECK: Verified by Light Protocol
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDIDStatus<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
     /// CHECK: Verified by Light Protocol
     pub light_system_program: AccountInfo<'info>,
 
@@ -441,6 +589,19 @@ pub struct ResetDailySpending<'info> {
     pub merkle_tree: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+pub struct ResetWeeklySpending<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Verified by Light Protocol
+    pub light_system_program: AccountInfo<'info>,
+
+    /// CHECK: Verified by Light Protocol
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+}
+
 #[derive(Accounts)]
 pub struct ResetMonthlySpending<'info> {
     #[account(mut)]
@@ -466,6 +627,47 @@ pub struct CompressedProof {
     pub c: [u8; 64],
 }
 
+/// Limits a card is created with
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CardLimits {
+    pub spending_limit: u64,
+    pub daily_limit: u64,
+    pub weekly_limit: u64,
+    pub monthly_limit: u64,
+    pub max_transaction_counts: TransactionCountLimits,
+    pub max_balance: u64,
+    pub low_balance_threshold_bps: u16,
+}
+
+/// Limit changes for a card; `None` leaves a limit as it is
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct CardLimitsUpdate {
+    pub spending_limit: Option<u64>,
+    pub daily_limit: Option<u64>,
+    pub weekly_limit: Option<u64>,
+    pub monthly_limit: Option<u64>,
+    pub max_transaction_counts: Option<TransactionCountLimits>,
+    pub max_balance: Option<u64>,
+    pub low_balance_threshold_bps: Option<u16>,
+}
+
+/// A spend being recorded against a card
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RecordedSpend {
+    pub amount: u64,
+    pub merchant_id: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub reference: Option<[u8; 32]>,
+}
+
+/// Per-period transaction count limits (0 = unlimited)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TransactionCountLimits {
+    pub daily: u16,
+    pub weekly: u16,
+    pub monthly: u16,
+}
+
 /// Reason for freezing a card
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum FreezeReason {
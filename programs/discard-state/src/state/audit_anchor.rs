@@ -2,9 +2,15 @@
 //!
 //! On-chain PDA that stores the Merkle root of a batch of audit log entries.
 //! Used for tamper-evident anchoring of off-chain audit data to Solana.
+//!
+//! Each anchor links to the previous one through `prev_root` and `sequence`,
+//! and a per-authority `AuditAnchorHead` tracks the chain tip, so a skipped
+//! or forked batch is rejected at anchoring time.
 
 use anchor_lang::prelude::*;
 
+use crate::error::DisCardError;
+
 /// PDA seeds: [b"audit_anchor", authority.key(), &timestamp.to_le_bytes()]
 #[account]
 pub struct AuditAnchorState {
@@ -14,6 +20,12 @@ pub struct AuditAnchorState {
     /// SHA-256 Merkle root of the batch of audit event hashes
     pub merkle_root: [u8; 32],
 
+    /// Merkle root of the authority's previous anchor (zeroed for the first)
+    pub prev_root: [u8; 32],
+
+    /// Position in the authority's chain, starting at 1
+    pub sequence: u64,
+
     /// Number of audit entries included in this batch
     pub batch_size: u32,
 
@@ -28,7 +40,175 @@ pub struct AuditAnchorState {
 }
 
 impl AuditAnchorState {
-    /// Account discriminator (8) + pubkey (32) + merkle_root (32) + batch_size (4)
-    /// + anchored_at (8) + anchor_slot (8) + bump (1)
-    pub const SIZE: usize = 8 + 32 + 32 + 4 + 8 + 8 + 1;
+    /// Account discriminator (8) + pubkey (32) + merkle_root (32) + prev_root (32)
+    /// + sequence (8) + batch_size (4) + anchored_at (8) + anchor_slot (8) + bump (1)
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 4 + 8 + 8 + 1;
+}
+
+/// Tip of an authority's audit anchor chain
+///
+/// Updated on every anchor, giving clients one deterministic account to read
+/// the current audit tip instead of scanning timestamp-seeded anchors.
+///
+/// PDA seeds: [b"audit_anchor_head", authority.key()]
+#[account]
+pub struct AuditAnchorHead {
+    /// The authority whose chain this tracks
+    pub authority: Pubkey,
+
+    /// Merkle root of the latest anchor (zeroed before the first)
+    pub latest_root: [u8; 32],
+
+    /// Sequence of the latest anchor (0 before the first)
+    pub latest_sequence: u64,
+
+    /// Timestamp (milliseconds) of the latest anchor; also its PDA seed
+    pub latest_timestamp: i64,
+
+    /// Number of anchors submitted by this authority
+    pub count: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl AuditAnchorHead {
+    /// Account discriminator (8) + pubkey (32) + latest_root (32)
+    /// + latest_sequence (8) + latest_timestamp (8) + count (8) + bump (1)
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+
+    /// Whether an anchor with `prev_root`/`sequence` directly follows the tip
+    pub fn is_next(&self, prev_root: &[u8; 32], sequence: u64) -> bool {
+        self.latest_root == *prev_root && self.latest_sequence.checked_add(1) == Some(sequence)
+    }
+
+    /// Move the tip to a new anchor, rejecting a gap or fork
+    pub fn advance(
+        &mut self,
+        authority: Pubkey,
+        merkle_root: [u8; 32],
+        prev_root: &[u8; 32],
+        sequence: u64,
+        timestamp: i64,
+    ) -> Result<()> {
+        if !self.is_next(prev_root, sequence) {
+            msg!(
+                "Audit chain mismatch: head sequence={}, got sequence={}",
+                self.latest_sequence,
+                sequence
+            );
+            return Err(error!(DisCardError::AuditChainMismatch));
+        }
+
+        self.authority = authority;
+        self.latest_root = merkle_root;
+        self.latest_sequence = sequence;
+        self.latest_timestamp = timestamp;
+        self.count = self.count
+            .checked_add(1)
+            .ok_or(error!(DisCardError::ArithmeticOverflow))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialized length of an account, discriminator included
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.len()
+    }
+
+    #[test]
+    fn anchor_state_fits_size() {
+        let anchor = AuditAnchorState {
+            authority: Pubkey::default(),
+            merkle_root: [1; 32],
+            prev_root: [2; 32],
+            sequence: u64::MAX,
+            batch_size: u32::MAX,
+            anchored_at: i64::MAX,
+            anchor_slot: u64::MAX,
+            bump: u8::MAX,
+        };
+
+        assert_eq!(serialized_len(&anchor), AuditAnchorState::SIZE);
+    }
+
+    #[test]
+    fn anchor_head_fits_size() {
+        let head = AuditAnchorHead {
+            authority: Pubkey::default(),
+            latest_root: [1; 32],
+            latest_sequence: u64::MAX,
+            latest_timestamp: i64::MAX,
+            count: u64::MAX,
+            bump: u8::MAX,
+        };
+
+        assert_eq!(serialized_len(&head), AuditAnchorHead::SIZE);
+    }
+
+    fn empty_head() -> AuditAnchorHead {
+        AuditAnchorHead {
+            authority: Pubkey::default(),
+            latest_root: [0; 32],
+            latest_sequence: 0,
+            latest_timestamp: 0,
+            count: 0,
+            bump: 0,
+        }
+    }
+
+    fn assert_chain_mismatch(result: Result<()>) {
+        assert_eq!(
+            result.map_err(ProgramError::from),
+            Err(ProgramError::Custom(DisCardError::AuditChainMismatch.into()))
+        );
+    }
+
+    #[test]
+    fn chained_anchor_extends_the_tip() {
+        let authority = Pubkey::new_unique();
+        let mut head = empty_head();
+
+        head.advance(authority, [1; 32], &[0; 32], 1, 100).unwrap();
+        head.advance(authority, [2; 32], &[1; 32], 2, 200).unwrap();
+
+        assert_eq!(head.latest_root, [2; 32]);
+        assert_eq!(head.latest_sequence, 2);
+    }
+
+    #[test]
+    fn gap_or_fork_is_rejected() {
+        let authority = Pubkey::new_unique();
+        let mut head = empty_head();
+        head.advance(authority, [1; 32], &[0; 32], 1, 100).unwrap();
+
+        // Skipped sequence, replayed sequence, and a fork off an unknown root
+        assert_chain_mismatch(head.advance(authority, [3; 32], &[1; 32], 3, 300));
+        assert_chain_mismatch(head.advance(authority, [3; 32], &[0; 32], 1, 300));
+        assert_chain_mismatch(head.advance(authority, [3; 32], &[9; 32], 2, 300));
+        assert_eq!(head.latest_root, [1; 32]);
+        assert_eq!(head.count, 1);
+    }
+
+    #[test]
+    fn head_tracks_the_latest_of_three_anchors() {
+        let authority = Pubkey::new_unique();
+        let mut head = empty_head();
+
+        head.advance(authority, [1; 32], &[0; 32], 1, 1_000).unwrap();
+        head.advance(authority, [2; 32], &[1; 32], 2, 2_000).unwrap();
+        head.advance(authority, [3; 32], &[2; 32], 3, 3_000).unwrap();
+
+        assert_eq!(head.authority, authority);
+        assert_eq!(head.latest_root, [3; 32]);
+        assert_eq!(head.latest_sequence, 3);
+        assert_eq!(head.latest_timestamp, 3_000);
+        assert_eq!(head.count, 3);
+    }
 }
@@ -1,6 +1,7 @@
 //! Card state for compressed PDAs
 
 use anchor_lang::prelude::*;
+use crate::events::LowBalanceWarning;
 
 /// Compressed card state stored in Light Protocol Merkle tree
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
@@ -17,10 +18,10 @@ pub struct CardState {
     /// Per-transaction spending limit in cents
     pub spending_limit: u64,
 
-    /// Daily spending limit in cents
+    /// Daily spending limit in cents (0 = unlimited)
     pub daily_limit: u64,
 
-    /// Monthly spending limit in cents
+    /// Monthly spending limit in cents (0 = unlimited)
     pub monthly_limit: u64,
 
     /// Current daily spending in cents
@@ -49,24 +50,114 @@ pub struct CardState {
 
     /// Slot when card was last updated
     pub updated_at_slot: u64,
+
+    // Fields added since the first release go below, so earlier encodings
+    // stay a prefix of the current one
+
+    /// Weekly spending limit in cents (0 = unlimited)
+    pub weekly_limit: u64,
+
+    /// Current weekly spending in cents
+    pub current_weekly_spend: u64,
+
+    /// Unix timestamp when daily spending was last reset
+    pub last_daily_reset_unix: i64,
+
+    /// Unix timestamp when weekly spending was last reset
+    pub last_weekly_reset_unix: i64,
+
+    /// Unix timestamp when monthly spending was last reset
+    pub last_monthly_reset_unix: i64,
+
+    /// Maximum transactions per day (0 = unlimited)
+    pub max_daily_transactions: u16,
+
+    /// Maximum transactions per week (0 = unlimited)
+    pub max_weekly_transactions: u16,
+
+    /// Maximum transactions per month (0 = unlimited)
+    pub max_monthly_transactions: u16,
+
+    /// Transactions applied today
+    pub daily_transaction_count: u16,
+
+    /// Transactions applied this week
+    pub weekly_transaction_count: u16,
+
+    /// Transactions applied this month
+    pub monthly_transaction_count: u16,
+
+    /// Maximum balance the card may be funded to, in cents (0 = unlimited)
+    pub max_balance: u64,
+
+    /// Part of the balance that can't be spent (e.g. held for fees), in cents
+    pub reserved_balance: u64,
+
+    /// Low-balance warning threshold as basis points of the spending limit (0 = disabled)
+    pub low_balance_threshold_bps: u16,
 }
 
 impl CardState {
-    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 2 + 1 + 1 + 8 + 8;
+    pub const SIZE: usize = 32 + // card_id
+        32 + // owner_did_commitment
+        8 + // balance
+        8 + // spending_limit
+        8 + // daily_limit
+        8 + // monthly_limit
+        8 + // current_daily_spend
+        8 + // current_monthly_spend
+        8 + // last_reset_slot
+        1 + // is_frozen
+        2 + // freeze_reason option
+        1 + // merchant_whitelist_count
+        1 + // mcc_whitelist_count
+        8 + // created_at_slot
+        8 + // updated_at_slot
+        8 + // weekly_limit
+        8 + // current_weekly_spend
+        8 + // last_daily_reset_unix
+        8 + // last_weekly_reset_unix
+        8 + // last_monthly_reset_unix
+        2 + 2 + 2 + // max daily/weekly/monthly transactions
+        2 + 2 + 2 + // daily/weekly/monthly transaction counts
+        8 + // max_balance
+        8 + // reserved_balance
+        2; // low_balance_threshold_bps
+
+    /// Balance available to spend after the reserve
+    pub fn spendable_balance(&self) -> u64 {
+        self.balance.saturating_sub(self.reserved_balance)
+    }
 
     /// Check if a transaction can be processed
     pub fn can_process_transaction(&self, amount: u64) -> Result<()> {
         require!(!self.is_frozen, CardError::CardFrozen);
-        require!(self.balance >= amount, CardError::InsufficientBalance);
+        require!(self.spendable_balance() >= amount, CardError::InsufficientBalance);
         require!(amount <= self.spending_limit, CardError::ExceedsSpendingLimit);
         require!(
-            self.current_daily_spend.checked_add(amount).unwrap_or(u64::MAX) <= self.daily_limit,
+            !spend_limit_exceeded(self.current_daily_spend, amount, self.daily_limit),
             CardError::ExceedsDailyLimit
         );
         require!(
-            self.current_monthly_spend.checked_add(amount).unwrap_or(u64::MAX) <= self.monthly_limit,
+            !spend_limit_exceeded(self.current_weekly_spend, amount, self.weekly_limit),
+            CardError::ExceedsWeeklyLimit
+        );
+        require!(
+            !spend_limit_exceeded(self.current_monthly_spend, amount, self.monthly_limit),
             CardError::ExceedsMonthlyLimit
         );
+        require!(
+            !count_limit_reached(self.daily_transaction_count, self.max_daily_transactions),
+            CardError::ExceedsDailyTransactionCount
+        );
+        require!(
+            !count_limit_reached(self.weekly_transaction_count, self.max_weekly_transactions),
+            CardError::ExceedsWeeklyTransactionCount
+        );
+        require!(
+            !count_limit_reached(self.monthly_transaction_count, self.max_monthly_transactions),
+            CardError::ExceedsMonthlyTransactionCount
+        );
         Ok(())
     }
 
@@ -74,17 +165,52 @@ impl CardState {
     pub fn apply_spending(&mut self, amount: u64) -> Result<()> {
         self.can_process_transaction(amount)?;
         self.balance = self.balance.checked_sub(amount).ok_or(CardError::InsufficientBalance)?;
-        self.current_daily_spend = self.current_daily_spend.checked_add(amount).unwrap_or(u64::MAX);
-        self.current_monthly_spend = self.current_monthly_spend.checked_add(amount).unwrap_or(u64::MAX);
+        self.current_daily_spend = self.current_daily_spend.saturating_add(amount);
+        self.current_weekly_spend = self.current_weekly_spend.saturating_add(amount);
+        self.current_monthly_spend = self.current_monthly_spend.saturating_add(amount);
+        self.daily_transaction_count = self.daily_transaction_count.saturating_add(1);
+        self.weekly_transaction_count = self.weekly_transaction_count.saturating_add(1);
+        self.monthly_transaction_count = self.monthly_transaction_count.saturating_add(1);
+
+        if let Some(warning) = self.low_balance_warning() {
+            emit!(warning);
+        }
+
         Ok(())
     }
 
     /// Add funds to the card
     pub fn add_funds(&mut self, amount: u64) -> Result<()> {
-        self.balance = self.balance.checked_add(amount).ok_or(CardError::Overflow)?;
+        let new_balance = self.balance.checked_add(amount).ok_or(CardError::Overflow)?;
+        if self.max_balance > 0 && new_balance > self.max_balance {
+            return Err(CardError::ExceedsMaxBalance.into());
+        }
+        self.balance = new_balance;
+        Ok(())
+    }
+
+    /// Deduct a spend another program already approved (the hooks transfer hook)
+    pub fn debit(&mut self, amount: u64) -> Result<()> {
+        require!(self.spendable_balance() >= amount, CardError::InsufficientBalance);
+        self.balance -= amount;
         Ok(())
     }
 
+    /// Balance below which a low-balance warning is emitted
+    pub fn low_balance_threshold(&self) -> u64 {
+        (self.spending_limit as u128 * self.low_balance_threshold_bps as u128 / 10_000) as u64
+    }
+
+    /// Warning to emit when the balance has dropped below the threshold
+    pub fn low_balance_warning(&self) -> Option<LowBalanceWarning> {
+        let threshold = self.low_balance_threshold();
+        (self.balance < threshold).then_some(LowBalanceWarning {
+            card_id: self.card_id,
+            balance: self.balance,
+            threshold,
+        })
+    }
+
     /// Freeze the card
     pub fn freeze(&mut self, reason: FreezeReasonState) {
         self.is_frozen = true;
@@ -97,21 +223,43 @@ impl CardState {
         self.freeze_reason = None;
     }
 
-    /// Reset daily spending counter
-    pub fn reset_daily(&mut self, current_slot: u64) {
+    /// Reset daily spending and transaction counters
+    pub fn reset_daily(&mut self, current_slot: u64, now: i64) {
         self.current_daily_spend = 0;
+        self.daily_transaction_count = 0;
+        self.last_reset_slot = current_slot;
+        self.last_daily_reset_unix = now;
+    }
+
+    /// Reset weekly spending and transaction counters
+    pub fn reset_weekly(&mut self, current_slot: u64, now: i64) {
+        self.current_weekly_spend = 0;
+        self.weekly_transaction_count = 0;
         self.last_reset_slot = current_slot;
+        self.last_weekly_reset_unix = now;
     }
 
-    /// Reset monthly spending counter
-    pub fn reset_monthly(&mut self, current_slot: u64) {
+    /// Reset monthly spending and transaction counters
+    pub fn reset_monthly(&mut self, current_slot: u64, now: i64) {
         self.current_monthly_spend = 0;
+        self.monthly_transaction_count = 0;
         self.last_reset_slot = current_slot;
+        self.last_monthly_reset_unix = now;
     }
 }
 
+/// Whether adding `amount` to `spent` goes over `limit` (a zero limit is unlimited)
+fn spend_limit_exceeded(spent: u64, amount: u64, limit: u64) -> bool {
+    limit > 0 && spent.saturating_add(amount) > limit
+}
+
+/// Whether a transaction counter has hit its limit (a zero limit is unlimited)
+fn count_limit_reached(count: u16, limit: u16) -> bool {
+    limit > 0 && count >= limit
+}
+
 /// Freeze reason stored in state
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum FreezeReasonState {
     FraudDetected,
     UserRequest,
@@ -135,15 +283,33 @@ pub enum CardError {
     #[msg("Transaction would exceed daily spending limit")]
     ExceedsDailyLimit,
 
+    #[msg("Transaction would exceed weekly spending limit")]
+    ExceedsWeeklyLimit,
+
     #[msg("Transaction would exceed monthly spending limit")]
     ExceedsMonthlyLimit,
 
+    #[msg("Daily transaction count limit reached")]
+    ExceedsDailyTransactionCount,
+
+    #[msg("Weekly transaction count limit reached")]
+    ExceedsWeeklyTransactionCount,
+
+    #[msg("Monthly transaction count limit reached")]
+    ExceedsMonthlyTransactionCount,
+
     #[msg("Merchant not in whitelist")]
     MerchantNotWhitelisted,
 
     #[msg("Merchant category not allowed")]
     MccNotAllowed,
 
+    #[msg("Invalid MCC code (must be 0001-9999)")]
+    InvalidMccCode,
+
+    #[msg("Top-up would exceed the card's maximum balance")]
+    ExceedsMaxBalance,
+
     #[msg("Arithmetic overflow")]
     Overflow,
 
@@ -154,6 +320,14 @@ pub enum CardError {
     InvalidCardState,
 }
 
+/// Reject MCC codes outside 0001-9999
+pub fn validate_mcc(code: u16) -> Result<()> {
+    if code == 0 || code > 9999 {
+        return Err(error!(CardError::InvalidMccCode));
+    }
+    Ok(())
+}
+
 /// Merchant whitelist entry (stored separately)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct MerchantWhitelistEntry {
@@ -169,3 +343,245 @@ pub struct MccWhitelistEntry {
     pub mcc_code: u16,
     pub added_at_slot: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{emitted, install_syscall_stubs};
+
+    fn funded_card(balance: u64) -> CardState {
+        CardState {
+            balance,
+            spending_limit: 1_000,
+            daily_limit: 5_000,
+            monthly_limit: 50_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn spending_updates_balance_and_counters() {
+        let mut card = funded_card(2_000);
+
+        card.apply_spending(300).unwrap();
+        card.apply_spending(200).unwrap();
+
+        assert_eq!(card.balance, 1_500);
+        assert_eq!(card.current_daily_spend, 500);
+        assert_eq!(card.current_weekly_spend, 500);
+        assert_eq!(card.current_monthly_spend, 500);
+        assert_eq!(card.daily_transaction_count, 2);
+        assert_eq!(card.weekly_transaction_count, 2);
+        assert_eq!(card.monthly_transaction_count, 2);
+    }
+
+    #[test]
+    fn frozen_card_rejects_spending() {
+        let mut card = funded_card(2_000);
+        card.freeze(FreezeReasonState::UserRequest);
+
+        assert!(card.apply_spending(100).is_err());
+
+        card.unfreeze();
+        assert!(card.apply_spending(100).is_ok());
+    }
+
+    #[test]
+    fn zero_weekly_limit_is_unlimited() {
+        let mut card = funded_card(10_000);
+        card.current_weekly_spend = u64::MAX - 1;
+
+        assert!(card.can_process_transaction(500).is_ok());
+
+        card.weekly_limit = 1_000;
+        card.current_weekly_spend = 600;
+        let err = card.can_process_transaction(500).unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::ExceedsWeeklyLimit.into()));
+    }
+
+    #[test]
+    fn zero_daily_and_monthly_limits_are_unlimited() {
+        let mut card = CardState { daily_limit: 0, monthly_limit: 0, ..funded_card(10_000) };
+        card.current_daily_spend = u64::MAX - 1;
+        card.current_monthly_spend = u64::MAX - 1;
+
+        card.apply_spending(500).unwrap();
+
+        card.daily_limit = 1_000;
+        let err = card.can_process_transaction(500).unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::ExceedsDailyLimit.into()));
+
+        card.daily_limit = 0;
+        card.monthly_limit = 1_000;
+        let err = card.can_process_transaction(500).unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::ExceedsMonthlyLimit.into()));
+    }
+
+    #[test]
+    fn transaction_counts_cap_spending() {
+        let cases = [
+            (2, 0, 0, CardError::ExceedsDailyTransactionCount),
+            (0, 2, 0, CardError::ExceedsWeeklyTransactionCount),
+            (0, 0, 2, CardError::ExceedsMonthlyTransactionCount),
+        ];
+
+        for (daily, weekly, monthly, expected) in cases {
+            let mut card = funded_card(10_000);
+            card.max_daily_transactions = daily;
+            card.max_weekly_transactions = weekly;
+            card.max_monthly_transactions = monthly;
+
+            card.apply_spending(100).unwrap();
+            card.apply_spending(100).unwrap();
+            let err = card.apply_spending(100).unwrap_err();
+            assert_eq!(ProgramError::from(err), ProgramError::Custom(expected.into()));
+            assert_eq!(card.balance, 9_800);
+        }
+    }
+
+    #[test]
+    fn weekly_reset_clears_the_count() {
+        let mut card = funded_card(10_000);
+        card.max_weekly_transactions = 2;
+
+        card.apply_spending(100).unwrap();
+        card.apply_spending(100).unwrap();
+        assert!(card.apply_spending(100).is_err());
+
+        card.reset_weekly(1, 0);
+        assert!(card.apply_spending(100).is_ok());
+    }
+
+    #[test]
+    fn reserve_is_not_spendable() {
+        let mut card = funded_card(1_000);
+        card.reserved_balance = 400;
+
+        assert_eq!(card.spendable_balance(), 600);
+        let err = card.can_process_transaction(601).unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::InsufficientBalance.into()));
+        assert!(card.debit(601).is_err());
+        card.debit(600).unwrap();
+        assert_eq!(card.balance, 400);
+    }
+
+    #[test]
+    fn reserve_above_the_balance_blocks_spending() {
+        let mut card = funded_card(300);
+        card.reserved_balance = 500;
+
+        assert_eq!(card.spendable_balance(), 0);
+        assert!(card.apply_spending(1).is_err());
+    }
+
+    #[test]
+    fn funding_respects_max_balance() {
+        let mut card = funded_card(900);
+        card.max_balance = 1_000;
+
+        let err = card.add_funds(101).unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::ExceedsMaxBalance.into()));
+        card.add_funds(100).unwrap();
+        assert_eq!(card.balance, 1_000);
+
+        card.max_balance = 0;
+        card.add_funds(5_000).unwrap();
+        assert_eq!(card.balance, 6_000);
+    }
+
+    #[test]
+    fn low_balance_threshold_is_bps_of_spending_limit() {
+        let mut card = funded_card(0);
+        assert_eq!(card.low_balance_threshold(), 0);
+
+        card.low_balance_threshold_bps = 2_500;
+        assert_eq!(card.low_balance_threshold(), 250);
+    }
+
+    #[test]
+    fn spending_below_the_threshold_warns() {
+        let mut card = CardState { card_id: [7; 32], ..funded_card(600) };
+        card.low_balance_threshold_bps = 2_500;
+
+        install_syscall_stubs();
+        card.apply_spending(350).unwrap();
+        assert!(emitted::<LowBalanceWarning>().is_empty());
+
+        install_syscall_stubs();
+        card.apply_spending(1).unwrap();
+        let warnings = emitted::<LowBalanceWarning>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].card_id, [7; 32]);
+        assert_eq!((warnings[0].balance, warnings[0].threshold), (249, 250));
+    }
+
+    /// `CardState` as first released, before any fields were appended
+    #[derive(AnchorSerialize)]
+    struct FirstReleaseCardState {
+        card_id: [u8; 32],
+        owner_did_commitment: [u8; 32],
+        balance: u64,
+        spending_limit: u64,
+        daily_limit: u64,
+        monthly_limit: u64,
+        current_daily_spend: u64,
+        current_monthly_spend: u64,
+        last_reset_slot: u64,
+        is_frozen: bool,
+        freeze_reason: Option<FreezeReasonState>,
+        merchant_whitelist_count: u8,
+        mcc_whitelist_count: u8,
+        created_at_slot: u64,
+        updated_at_slot: u64,
+    }
+
+    #[test]
+    fn first_release_encoding_is_a_prefix() {
+        let mut card = CardState {
+            card_id: [7; 32],
+            owner_did_commitment: [8; 32],
+            current_daily_spend: 300,
+            last_reset_slot: 9,
+            merchant_whitelist_count: 2,
+            created_at_slot: 10,
+            updated_at_slot: 11,
+            weekly_limit: 20_000,
+            reserved_balance: 100,
+            ..funded_card(2_000)
+        };
+        card.freeze(FreezeReasonState::VelocityBreach);
+        let first_release = FirstReleaseCardState {
+            card_id: [7; 32],
+            owner_did_commitment: [8; 32],
+            balance: 2_000,
+            spending_limit: 1_000,
+            daily_limit: 5_000,
+            monthly_limit: 50_000,
+            current_daily_spend: 300,
+            current_monthly_spend: 0,
+            last_reset_slot: 9,
+            is_frozen: true,
+            freeze_reason: Some(FreezeReasonState::VelocityBreach),
+            merchant_whitelist_count: 2,
+            mcc_whitelist_count: 0,
+            created_at_slot: 10,
+            updated_at_slot: 11,
+        };
+
+        let encoded = card.try_to_vec().unwrap();
+
+        assert!(encoded.starts_with(&first_release.try_to_vec().unwrap()));
+        assert_eq!(encoded.len(), CardState::SIZE);
+    }
+
+    #[test]
+    fn mcc_codes_must_be_in_range() {
+        for code in [1, 9999] {
+            assert!(validate_mcc(code).is_ok(), "{code}");
+        }
+        for code in [0, 10_000] {
+            let err = validate_mcc(code).unwrap_err();
+            assert_eq!(ProgramError::from(err), ProgramError::Custom(CardError::InvalidMccCode.into()), "{code}");
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Plaintext card state
+//!
+//! A regular (uncompressed) PDA wrapping `CardState`, so spending limits are
+//! enforced end-to-end while the Light Protocol path is still stubbed. The
+//! instructions using it only run with the `plaintext-state` feature.
+
+use anchor_lang::prelude::*;
+use crate::state::card::CardState;
+
+/// discard-hooks program, whose card config PDAs own plaintext card states
+pub const DISCARD_HOOKS_PROGRAM_ID: Pubkey =
+    pubkey!("HooK1111111111111111111111111111111111111111");
+
+/// Seed prefix of the hooks program's card config PDA (`[SEED, card_id]`)
+pub const HOOK_CARD_CONFIG_SEED: &[u8] = b"card_config";
+
+/// PDA seeds: [b"card_state", card_id]
+#[account]
+pub struct CardStatePda {
+    /// The card's discard-hooks config PDA, the only key allowed to fund the
+    /// card and record spending (the hooks program gates those on the card
+    /// owner or a settlement authority)
+    pub authority: Pubkey,
+
+    /// The card's token account; funding can't credit more than it holds
+    pub token_account: Pubkey,
+
+    /// The card state, identical in layout to the compressed form
+    pub state: CardState,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl CardStatePda {
+    pub const SEED: &'static [u8] = b"card_state";
+
+    /// Account discriminator (8) + authority (32) + token_account (32) + state + bump (1)
+    pub const SIZE: usize = 8 + 32 + 32 + CardState::SIZE + 1;
+
+    /// The hooks card config PDA that must own the state for `card_id`
+    pub fn hook_authority(card_id: &[u8; 32]) -> Pubkey {
+        Pubkey::find_program_address(
+            &[HOOK_CARD_CONFIG_SEED, card_id.as_ref()],
+            &DISCARD_HOOKS_PROGRAM_ID,
+        ).0
+    }
+}
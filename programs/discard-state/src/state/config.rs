@@ -0,0 +1,24 @@
+//! Program-wide configuration for DisCard State
+
+use anchor_lang::prelude::*;
+
+/// Default minimum gap between DID key rotations (~1 day of 400ms slots)
+pub const DEFAULT_MIN_KEY_ROTATION_INTERVAL_SLOTS: u64 = 216_000;
+
+/// PDA seeds: [b"state_config"]
+#[account]
+pub struct StateConfig {
+    /// Key allowed to change the configuration
+    pub authority: Pubkey,
+
+    /// Minimum slots between key rotations on one DID (0 = no limit)
+    pub min_key_rotation_interval_slots: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl StateConfig {
+    /// Account discriminator (8) + authority (32) + interval (8) + bump (1)
+    pub const SIZE: usize = 8 + 32 + 8 + 1;
+}
@@ -1,6 +1,10 @@
 //! DID commitment state for compressed PDAs
 
 use anchor_lang::prelude::*;
+use light_hasher::{Hasher, Poseidon};
+
+/// Default maximum age of a guardian attestation accepted for recovery (7 days)
+pub const DEFAULT_MAX_ATTESTATION_AGE_SECS: i64 = 7 * 86_400;
 
 /// Compressed DID commitment stored in Light Protocol Merkle tree
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
@@ -8,7 +12,7 @@ pub struct DIDCommitmentState {
     /// DID string hash (for lookup)
     pub did_hash: [u8; 32],
 
-    /// Poseidon commitment hash of the DID document
+    /// Poseidon commitment to the DID document (see `document_commitment`)
     pub commitment_hash: [u8; 32],
 
     /// SHA-256 hash of the canonical DID document
@@ -37,10 +41,16 @@ pub struct DIDCommitmentState {
 
     /// Slot when DID was last updated
     pub updated_at_slot: u64,
+
+    /// Oldest guardian attestation (in seconds) accepted for recovery
+    pub max_attestation_age_secs: i64,
+
+    /// Key that must sign owner actions (key rotation, suspend, reactivate)
+    pub controller: Pubkey,
 }
 
 impl DIDCommitmentState {
-    pub const SIZE: usize = 32 + 32 + 32 + 1 + 1 + 1 + 1 + 8 + 4 + 8 + 8;
+    pub const SIZE: usize = 32 + 32 + 32 + 1 + 1 + 1 + 1 + 8 + 4 + 8 + 8 + 8 + 32;
 
     /// Check if recovery is possible
     pub fn can_recover(&self) -> bool {
@@ -48,6 +58,22 @@ impl DIDCommitmentState {
         self.active_guardians_count >= self.recovery_threshold
     }
 
+    /// Reject owner actions not signed by the DID's controller
+    pub fn check_controller(&self, signer: &Pubkey) -> Result<()> {
+        require!(self.controller == *signer, DIDError::NotController);
+        Ok(())
+    }
+
+    /// Reject a key rotation within `min_interval_slots` of the previous one
+    pub fn check_rotation_interval(&self, current_slot: u64, min_interval_slots: u64) -> Result<()> {
+        if self.key_rotation_count == 0 {
+            return Ok(());
+        }
+        let next_allowed = self.last_key_rotation_slot.saturating_add(min_interval_slots);
+        require!(current_slot >= next_allowed, DIDError::KeyRotationTooSoon);
+        Ok(())
+    }
+
     /// Apply key rotation
     pub fn rotate_key(
         &mut self,
@@ -62,16 +88,21 @@ impl DIDCommitmentState {
         self.updated_at_slot = current_slot;
     }
 
-    /// Suspend the DID
-    pub fn suspend(&mut self, current_slot: u64) {
+    /// Suspend an active DID
+    pub fn suspend(&mut self, current_slot: u64) -> Result<()> {
+        require!(self.status != DIDStatus::Revoked, DIDError::DIDRevoked);
+        require!(self.status == DIDStatus::Active, DIDError::DIDSuspended);
         self.status = DIDStatus::Suspended;
         self.updated_at_slot = current_slot;
+        Ok(())
     }
 
-    /// Revoke the DID (permanent)
-    pub fn revoke(&mut self, current_slot: u64) {
+    /// Revoke the DID (permanent: a revoked DID can't be suspended or reactivated)
+    pub fn revoke(&mut self, current_slot: u64) -> Result<()> {
+        require!(self.status != DIDStatus::Revoked, DIDError::DIDRevoked);
         self.status = DIDStatus::Revoked;
         self.updated_at_slot = current_slot;
+        Ok(())
     }
 
     /// Reactivate a suspended DID
@@ -82,6 +113,14 @@ impl DIDCommitmentState {
         Ok(())
     }
 
+    /// Reject guardian attestations older than the DID's maximum age
+    ///
+    /// Future-dated attestations are rejected too, since they would otherwise
+    /// stay fresh indefinitely.
+    pub fn check_attestation_age(&self, attestation_timestamp: i64, now: i64) -> Result<()> {
+        check_attestation_age(attestation_timestamp, now, self.max_attestation_age_secs)
+    }
+
     /// Update guardian count
     pub fn update_guardian_count(&mut self, count: u8, current_slot: u64) {
         self.active_guardians_count = count;
@@ -89,6 +128,40 @@ impl DIDCommitmentState {
     }
 }
 
+/// Poseidon commitment binding a document hash to a salt
+///
+/// Both inputs have their first byte cleared so they fit the BN254 scalar
+/// field: `poseidon(document_hash[1..] || salt[1..])`.
+pub fn document_commitment(document_hash: &[u8; 32], salt: &[u8; 32]) -> Result<[u8; 32]> {
+    let mut document = *document_hash;
+    document[0] = 0;
+    let mut salt = *salt;
+    salt[0] = 0;
+
+    Poseidon::hashv(&[&document, &salt])
+        .map_err(|_| error!(DIDError::CommitmentMismatch))
+}
+
+/// Reject a commitment that doesn't commit to `document_hash` under `salt`
+pub fn verify_document_commitment(
+    commitment_hash: &[u8; 32],
+    document_hash: &[u8; 32],
+    salt: &[u8; 32],
+) -> Result<()> {
+    require!(
+        document_commitment(document_hash, salt)? == *commitment_hash,
+        DIDError::CommitmentMismatch
+    );
+    Ok(())
+}
+
+/// Reject an attestation made more than `max_age_secs` before `now`
+pub fn check_attestation_age(attestation_timestamp: i64, now: i64, max_age_secs: i64) -> Result<()> {
+    let age = now.saturating_sub(attestation_timestamp);
+    require!(age >= 0 && age <= max_age_secs, DIDError::AttestationExpired);
+    Ok(())
+}
+
 /// DID status
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub enum DIDStatus {
@@ -163,4 +236,111 @@ pub enum DIDError {
 
     #[msg("Commitment hash mismatch")]
     CommitmentMismatch,
+
+    #[msg("Guardian attestation has expired")]
+    AttestationExpired,
+
+    #[msg("Key rotated too soon after the previous rotation")]
+    KeyRotationTooSoon,
+
+    #[msg("Signer does not control this DID")]
+    NotController,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn assert_did_error(result: Result<()>, expected: DIDError) {
+        let err = result.unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::Custom(expected.into()));
+    }
+
+    #[test]
+    fn fresh_attestations_are_accepted_and_stale_ones_rejected() {
+        let did = DIDCommitmentState { max_attestation_age_secs: 3_600, ..Default::default() };
+
+        did.check_attestation_age(NOW, NOW).unwrap();
+        did.check_attestation_age(NOW - 3_600, NOW).unwrap();
+        assert_did_error(did.check_attestation_age(NOW - 3_601, NOW), DIDError::AttestationExpired);
+        // Future-dated attestations would otherwise never expire
+        assert_did_error(did.check_attestation_age(NOW + 1, NOW), DIDError::AttestationExpired);
+    }
+
+    #[test]
+    fn recovery_default_allows_a_week() {
+        let week = DEFAULT_MAX_ATTESTATION_AGE_SECS;
+
+        check_attestation_age(NOW - week, NOW, week).unwrap();
+        let stale = check_attestation_age(NOW - week - 1, NOW, week);
+        assert_did_error(stale, DIDError::AttestationExpired);
+    }
+
+    #[test]
+    fn suspended_did_reactivates() {
+        let mut did = DIDCommitmentState::default();
+
+        did.suspend(10).unwrap();
+        assert!(did.status == DIDStatus::Suspended);
+        assert_did_error(did.suspend(11), DIDError::DIDSuspended);
+
+        did.reactivate(12).unwrap();
+        assert!(did.status == DIDStatus::Active);
+        assert_eq!(did.updated_at_slot, 12);
+        assert_did_error(did.reactivate(13), DIDError::CannotReactivate);
+    }
+
+    #[test]
+    fn revocation_is_terminal() {
+        let mut did = DIDCommitmentState::default();
+        did.suspend(10).unwrap();
+
+        did.revoke(11).unwrap();
+
+        assert_did_error(did.reactivate(12), DIDError::CannotReactivate);
+        assert_did_error(did.suspend(12), DIDError::DIDRevoked);
+        assert_did_error(did.revoke(12), DIDError::DIDRevoked);
+        assert!(did.status == DIDStatus::Revoked);
+    }
+
+    #[test]
+    fn only_the_controller_acts_as_owner() {
+        let controller = Pubkey::new_unique();
+        let did = DIDCommitmentState { controller, ..Default::default() };
+
+        did.check_controller(&controller).unwrap();
+        assert_did_error(did.check_controller(&Pubkey::new_unique()), DIDError::NotController);
+    }
+
+    #[test]
+    fn key_rotations_are_spaced_by_the_interval() {
+        let mut did = DIDCommitmentState::default();
+
+        // The first rotation has nothing to wait for
+        did.check_rotation_interval(100, 50).unwrap();
+        did.rotate_key([1; 32], [2; 32], 100);
+        assert_eq!((did.key_rotation_count, did.last_key_rotation_slot), (1, 100));
+
+        let too_soon = did.check_rotation_interval(149, 50);
+        assert_did_error(too_soon, DIDError::KeyRotationTooSoon);
+
+        did.check_rotation_interval(150, 50).unwrap();
+        did.rotate_key([3; 32], [4; 32], 150);
+        assert_eq!((did.key_rotation_count, did.last_key_rotation_slot), (2, 150));
+        assert_eq!((did.commitment_hash, did.document_hash), ([3; 32], [4; 32]));
+    }
+
+    #[test]
+    fn commitment_binds_the_document_and_salt() {
+        let (document, salt) = ([7; 32], [9; 32]);
+        let commitment = document_commitment(&document, &salt).unwrap();
+
+        verify_document_commitment(&commitment, &document, &salt).unwrap();
+        let other_document = verify_document_commitment(&commitment, &[8; 32], &salt);
+        assert_did_error(other_document, DIDError::CommitmentMismatch);
+        let other_salt = verify_document_commitment(&commitment, &document, &[1; 32]);
+        assert_did_error(other_salt, DIDError::CommitmentMismatch);
+    }
 }
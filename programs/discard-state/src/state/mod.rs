@@ -1,11 +1,13 @@
 //! State definitions for DisCard compressed accounts
 
-use anchor_lang::prelude::*;
-
 pub mod audit_anchor;
 pub mod card;
+pub mod config;
 pub mod did;
+pub mod card_pda;
 
 pub use audit_anchor::*;
 pub use card::*;
+pub use config::*;
 pub use did::*;
+pub use card_pda::*;
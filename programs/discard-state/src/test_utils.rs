@@ -0,0 +1,118 @@
+//! Off-chain harness for running instructions through the program entrypoint
+
+use std::cell::RefCell;
+
+use anchor_lang::prelude::*;
+pub use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::{
+    entrypoint::SUCCESS,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    system_program,
+};
+use anchor_lang::{Event, InstructionData};
+
+use crate::state::card::CardState;
+use crate::state::card_pda::CardStatePda;
+
+pub const NOW: i64 = 1_700_000_000;
+pub const SLOT: u64 = 1;
+
+thread_local! {
+    /// Event data logged by the code under test on this thread
+    static LOGGED_DATA: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Serves `Clock::get` and `Rent::get` off-chain and captures events
+///
+/// CPIs are left to the default no-op stub, so `init` accounts must be passed
+/// in already owned by the program (see `uninitialized_card_state`).
+struct TestSyscalls;
+
+impl SyscallStubs for TestSyscalls {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock { slot: SLOT, unix_timestamp: NOW, ..Default::default() };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        LOGGED_DATA.with(|logged| logged.borrow_mut().push(data.concat()));
+    }
+}
+
+/// Install the stubs and forget events logged so far, for code called
+/// directly rather than through `run`
+pub fn install_syscall_stubs() {
+    set_syscall_stubs(Box::new(TestSyscalls));
+    LOGGED_DATA.with(|logged| logged.borrow_mut().clear());
+}
+
+/// An account leaked for the `'static` lifetime the entrypoint borrows for
+pub fn account(key: Pubkey, owner: Pubkey, is_writable: bool, data: Vec<u8>) -> AccountInfo<'static> {
+    AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        is_writable,
+        Box::leak(Box::new(1_000_000_000)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        0,
+    )
+}
+
+pub fn signer(key: Pubkey) -> AccountInfo<'static> {
+    AccountInfo { is_signer: true, ..account(key, system_program::ID, true, vec![]) }
+}
+
+/// The system program account
+pub fn system_program() -> AccountInfo<'static> {
+    let program = account(system_program::ID, Pubkey::default(), false, vec![]);
+    AccountInfo { executable: true, ..program }
+}
+
+/// The plaintext card state PDA for `state.card_id`, writable by `authority`
+pub fn card_state_account(authority: Pubkey, state: CardState) -> AccountInfo<'static> {
+    let (key, bump) = Pubkey::find_program_address(&[CardStatePda::SEED, &state.card_id], &crate::ID);
+    let card_state = CardStatePda { authority, token_account: Pubkey::new_unique(), state, bump };
+    let mut data = Vec::with_capacity(CardStatePda::SIZE);
+    card_state.try_serialize(&mut data).unwrap();
+    data.resize(CardStatePda::SIZE, 0);
+    account(key, crate::ID, true, data)
+}
+
+/// The plaintext card state PDA for `card_id`, allocated but not yet written
+pub fn uninitialized_card_state(card_id: [u8; 32]) -> AccountInfo<'static> {
+    let (key, _) = Pubkey::find_program_address(&[CardStatePda::SEED, &card_id], &crate::ID);
+    account(key, crate::ID, true, vec![0; CardStatePda::SIZE])
+}
+
+pub fn run<I: InstructionData>(accounts: Vec<AccountInfo<'static>>, ix: I) -> ProgramResult {
+    install_syscall_stubs();
+    let accounts: &'static [AccountInfo<'static>] = Box::leak(accounts.into_boxed_slice());
+    crate::entry(&crate::ID, accounts, &ix.data())
+}
+
+/// The card state held by a plaintext card state PDA
+pub fn read_card_state(info: &AccountInfo) -> CardState {
+    CardStatePda::try_deserialize(&mut &info.try_borrow_data().unwrap()[..]).unwrap().state
+}
+
+/// Events of type `E` emitted since the stubs were last installed on this thread
+pub fn emitted<E: Event>() -> Vec<E> {
+    LOGGED_DATA.with(|logged| {
+        logged.borrow().iter()
+            .filter_map(|data| data.strip_prefix(E::DISCRIMINATOR.as_slice()))
+            .map(|mut body| E::deserialize(&mut body).unwrap())
+            .collect()
+    })
+}
+
+pub fn assert_rejected(result: ProgramResult, expected: impl Into<u32>) {
+    assert_eq!(result, Err(ProgramError::Custom(expected.into())));
+}
@@ -0,0 +1,51 @@
+//! ISO 3166-1 alpha-2 country code conversion
+//!
+//! Merchant records store `country_code` as two ASCII bytes, while card
+//! policy (in discard-hooks) keys countries by `u16`. Both programs convert
+//! through these helpers so the two forms always agree: the bytes are packed
+//! big-endian, so `b"US"` becomes `0x5553`.
+
+/// Whether a code is two uppercase ASCII letters
+pub fn is_valid_alpha2(alpha2: [u8; 2]) -> bool {
+    alpha2[0].is_ascii_uppercase() && alpha2[1].is_ascii_uppercase()
+}
+
+/// Pack an alpha-2 code into its `u16` policy form (None if not uppercase ASCII)
+pub fn alpha2_to_u16(alpha2: [u8; 2]) -> Option<u16> {
+    if !is_valid_alpha2(alpha2) {
+        return None;
+    }
+    Some(u16::from_be_bytes(alpha2))
+}
+
+/// Unpack a `u16` policy code back into alpha-2 bytes (None if not a valid code)
+pub fn u16_to_alpha2(code: u16) -> Option<[u8; 2]> {
+    let alpha2 = code.to_be_bytes();
+    if !is_valid_alpha2(alpha2) {
+        return None;
+    }
+    Some(alpha2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso_codes_round_trip() {
+        for alpha2 in [*b"US", *b"GB", *b"DE", *b"JP", *b"BR", *b"ZA", *b"AA", *b"ZZ"] {
+            let code = alpha2_to_u16(alpha2).unwrap();
+            assert_eq!(u16_to_alpha2(code), Some(alpha2));
+        }
+        assert_eq!(alpha2_to_u16(*b"US"), Some(0x5553));
+    }
+
+    #[test]
+    fn invalid_codes_are_rejected() {
+        for alpha2 in [*b"us", *b"Us", *b"U1", *b"  ", *b"@[", [0, 0], [0xC3, 0x9C]] {
+            assert!(!is_valid_alpha2(alpha2), "{alpha2:?}");
+            assert_eq!(alpha2_to_u16(alpha2), None, "{alpha2:?}");
+            assert_eq!(u16_to_alpha2(u16::from_be_bytes(alpha2)), None, "{alpha2:?}");
+        }
+    }
+}
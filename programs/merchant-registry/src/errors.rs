@@ -33,4 +33,28 @@ pub enum MerchantRegistryError {
 
     #[msg("Invalid MCC code")]
     InvalidMccCode,
+
+    #[msg("New authority must differ from the current authority")]
+    InvalidNewAuthority,
+
+    #[msg("Merchant is not blocked")]
+    MerchantNotBlocked,
+
+    #[msg("Registry is paused")]
+    RegistryPaused,
+
+    #[msg("Merchant country is blocked")]
+    CountryBlocked,
+
+    #[msg("Blocked country list is full")]
+    BlockedCountriesFull,
+
+    #[msg("Invalid sweep batch size")]
+    InvalidBatchSize,
+
+    #[msg("Metadata hash requires a metadata URI")]
+    MetadataHashWithoutUri,
+
+    #[msg("Metadata URI changed without a new metadata hash")]
+    StaleMetadataHash,
 }
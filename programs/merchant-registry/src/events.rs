@@ -0,0 +1,89 @@
+//! Event definitions for the merchant registry
+
+use anchor_lang::prelude::*;
+
+/// Emitted when the authority proposes a new authority
+#[event]
+pub struct AuthorityTransferInitiated {
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// Emitted when a proposed authority accepts and takes over
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+/// Emitted when the authority pauses or resumes the registry
+#[event]
+pub struct RegistryPauseChanged {
+    pub authority: Pubkey,
+    pub is_paused: bool,
+    pub changed_at: i64,
+}
+
+/// Emitted when a merchant is registered
+#[event]
+pub struct MerchantRegistered {
+    pub merchant_id: [u8; 32],
+    pub registered_by: Pubkey,
+    pub merchant_name: String,
+    pub mcc_code: u16,
+    pub risk_tier: u8,
+    pub country_code: [u8; 2],
+    pub token_account: Pubkey,
+    pub registered_at: i64,
+}
+
+/// Emitted when a merchant is revoked (set to blocked)
+#[event]
+pub struct MerchantRevoked {
+    pub merchant_id: [u8; 32],
+    pub revoked_by: Pubkey,
+    pub old_risk_tier: u8,
+    pub revoked_at: i64,
+}
+
+/// Emitted when a blocked merchant is reactivated
+#[event]
+pub struct MerchantReactivated {
+    pub merchant_id: [u8; 32],
+    pub reactivated_by: Pubkey,
+    pub new_risk_tier: u8,
+    pub reactivated_at: i64,
+}
+
+/// Emitted when a country is blocked or unblocked registry-wide
+#[event]
+pub struct CountryBlockChanged {
+    pub country_code: [u8; 2],
+    pub is_blocked: bool,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
+
+/// Emitted when a merchant record is updated
+///
+/// Old/new values are only populated for fields that actually changed.
+#[event]
+pub struct MerchantUpdated {
+    pub merchant_id: [u8; 32],
+    pub updated_by: Pubkey,
+    pub old_risk_tier: Option<u8>,
+    pub new_risk_tier: Option<u8>,
+    pub old_is_active: Option<bool>,
+    pub new_is_active: Option<bool>,
+    pub old_metadata_uri: Option<String>,
+    pub new_metadata_uri: Option<String>,
+    pub old_metadata_hash: Option<[u8; 32]>,
+    pub new_metadata_hash: Option<[u8; 32]>,
+    pub old_mcc_code: Option<u16>,
+    pub new_mcc_code: Option<u16>,
+    pub old_merchant_name: Option<String>,
+    pub new_merchant_name: Option<String>,
+    pub old_token_account: Option<Pubkey>,
+    pub new_token_account: Option<Pubkey>,
+    pub updated_at: i64,
+}
@@ -0,0 +1,92 @@
+//! Accept a pending registry authority transfer
+
+use anchor_lang::prelude::*;
+use crate::state::MerchantRegistryConfig;
+use crate::errors::MerchantRegistryError;
+use crate::events::AuthorityTransferred;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.pending_authority == Some(new_authority.key()) @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    pub new_authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let previous_authority = config.authority;
+    config.authority = ctx.accounts.new_authority.key();
+    config.pending_authority = None;
+    config.last_updated = Clock::get()?.unix_timestamp;
+
+    emit!(AuthorityTransferred {
+        previous_authority,
+        new_authority: config.authority,
+    });
+
+    msg!("Registry authority transferred: {} -> {}", previous_authority, config.authority);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, config_account, read, registry_config, run, signer, ProgramResult,
+    };
+
+    fn propose(authority: Pubkey, config: AccountInfo<'static>, new_authority: Pubkey) -> ProgramResult {
+        run(vec![config, signer(authority)], crate::instruction::TransferAuthority { new_authority })
+    }
+
+    fn accept(new_authority: Pubkey, config: AccountInfo<'static>) -> ProgramResult {
+        run(vec![config, signer(new_authority)], crate::instruction::AcceptAuthority {})
+    }
+
+    #[test]
+    fn proposed_authority_takes_over_on_accept() {
+        let (authority, new_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = config_account(registry_config(authority));
+
+        propose(authority, config.clone(), new_authority).unwrap();
+        assert_eq!(read::<MerchantRegistryConfig>(&config).authority, authority);
+        accept(new_authority, config.clone()).unwrap();
+
+        let registry = read::<MerchantRegistryConfig>(&config);
+        assert_eq!(registry.authority, new_authority);
+        assert_eq!(registry.pending_authority, None);
+        assert_rejected(propose(authority, config, authority), MerchantRegistryError::Unauthorized);
+    }
+
+    #[test]
+    fn only_the_proposed_authority_can_accept() {
+        let (authority, new_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = config_account(registry_config(authority));
+
+        assert_rejected(accept(new_authority, config.clone()), MerchantRegistryError::Unauthorized);
+        propose(authority, config.clone(), new_authority).unwrap();
+
+        assert_rejected(accept(Pubkey::new_unique(), config.clone()), MerchantRegistryError::Unauthorized);
+        assert_rejected(accept(authority, config), MerchantRegistryError::Unauthorized);
+    }
+
+    #[test]
+    fn only_the_authority_can_propose() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+
+        assert_rejected(
+            propose(Pubkey::new_unique(), config.clone(), Pubkey::new_unique()),
+            MerchantRegistryError::Unauthorized,
+        );
+        assert_rejected(propose(authority, config, authority), MerchantRegistryError::InvalidNewAuthority);
+    }
+}
@@ -0,0 +1,57 @@
+//! Block a country registry-wide
+
+use anchor_lang::prelude::*;
+use crate::state::{MerchantRegistryConfig, MAX_BLOCKED_COUNTRIES};
+use crate::errors::MerchantRegistryError;
+use crate::events::CountryBlockChanged;
+use crate::country::is_valid_alpha2;
+
+#[derive(Accounts)]
+pub struct AddBlockedCountry<'info> {
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<AddBlockedCountry>, country_code: [u8; 2]) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(
+        is_valid_alpha2(country_code),
+        MerchantRegistryError::InvalidCountryCode
+    );
+
+    if config.is_country_blocked(country_code) {
+        return Ok(());
+    }
+
+    require!(
+        config.blocked_countries.len() < MAX_BLOCKED_COUNTRIES,
+        MerchantRegistryError::BlockedCountriesFull
+    );
+
+    config.blocked_countries.push(country_code);
+    config.last_updated = clock.unix_timestamp;
+
+    emit!(CountryBlockChanged {
+        country_code,
+        is_blocked: true,
+        changed_by: ctx.accounts.authority.key(),
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Blocked country: {}{} (existing merchants need sweep_blocked_merchants)",
+        country_code[0] as char,
+        country_code[1] as char
+    );
+
+    Ok(())
+}
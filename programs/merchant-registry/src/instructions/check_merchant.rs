@@ -0,0 +1,83 @@
+//! Read-only merchant validity check for authorization paths
+
+use anchor_lang::prelude::*;
+use crate::state::MerchantRecord;
+
+/// Summary of a merchant's standing, returned via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MerchantCheck {
+    /// Whether the merchant is active and not blocked
+    pub is_valid: bool,
+
+    /// Risk tier (1=low, 2=medium, 3=high, 4=blocked)
+    pub risk_tier: u8,
+
+    /// Merchant Category Code
+    pub mcc_code: u16,
+
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: [u8; 2],
+}
+
+#[derive(Accounts)]
+pub struct CheckMerchant<'info> {
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.merchant_id.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, MerchantRecord>,
+}
+
+pub(crate) fn handler(ctx: Context<CheckMerchant>) -> Result<MerchantCheck> {
+    let merchant = &ctx.accounts.merchant;
+
+    Ok(MerchantCheck {
+        is_valid: merchant.is_valid(),
+        risk_tier: merchant.risk_tier,
+        mcc_code: merchant.mcc_code,
+        country_code: merchant.country_code,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::risk_tier;
+    use crate::test_utils::{merchant_account, merchant_record, return_data, run};
+
+    fn check(record: MerchantRecord) -> MerchantCheck {
+        run(vec![merchant_account(record)], crate::instruction::CheckMerchant {}).unwrap();
+        MerchantCheck::try_from_slice(&return_data()).unwrap()
+    }
+
+    #[test]
+    fn active_merchant_is_valid() {
+        let check = check(merchant_record([9; 32], *b"US"));
+
+        assert!(check.is_valid);
+        assert_eq!(check.risk_tier, risk_tier::MEDIUM);
+        assert_eq!(check.mcc_code, 5411);
+        assert_eq!(check.country_code, *b"US");
+    }
+
+    #[test]
+    fn blocked_merchant_is_invalid() {
+        let record = MerchantRecord { risk_tier: risk_tier::BLOCKED, ..merchant_record([9; 32], *b"GB") };
+
+        let check = check(record);
+
+        assert!(!check.is_valid);
+        assert_eq!(check.risk_tier, risk_tier::BLOCKED);
+        assert_eq!(check.country_code, *b"GB");
+    }
+
+    #[test]
+    fn inactive_merchant_is_invalid() {
+        let record = MerchantRecord { is_active: false, ..merchant_record([9; 32], *b"US") };
+
+        let check = check(record);
+
+        assert!(!check.is_valid);
+        assert_eq!(check.risk_tier, risk_tier::MEDIUM);
+    }
+}
@@ -20,12 +20,15 @@ pub struct InitializeRegistry<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<InitializeRegistry>) -> Result<()> {
+pub(crate) fn handler(ctx: Context<InitializeRegistry>) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
     config.authority = ctx.accounts.authority.key();
+    config.pending_authority = None;
     config.total_merchants = 0;
     config.blocked_count = 0;
+    config.is_paused = false;
+    config.blocked_countries = vec![];
     config.last_updated = Clock::get()?.unix_timestamp;
     config.bump = ctx.bumps.config;
 
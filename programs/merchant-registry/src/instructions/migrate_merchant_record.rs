@@ -0,0 +1,59 @@
+//! Grow a first-deployment merchant record to the current layout
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::{MerchantRecord, MerchantRegistryConfig};
+use crate::errors::MerchantRegistryError;
+use crate::migration::grow_account;
+
+#[derive(Accounts)]
+#[instruction(merchant_id: [u8; 32])]
+pub struct MigrateMerchantRecord<'info> {
+    #[account(
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    /// CHECK: May still be in the first-deployment layout, which `Account`
+    /// cannot always load; the discriminator is checked in the handler
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant_id.as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub merchant: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<MigrateMerchantRecord>, _merchant_id: [u8; 32]) -> Result<()> {
+    let merchant = ctx.accounts.merchant.to_account_info();
+
+    {
+        let data = merchant.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == MerchantRecord::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+
+    // The earlier layout is a prefix of the current one, so growing is the
+    // whole migration. The settlement account reads as the default key until
+    // `update_merchant` sets it, so no transfer matches the merchant meanwhile.
+    grow_account(
+        &merchant,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        MerchantRecord::space(),
+    )?;
+
+    msg!("Merchant record migrated");
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+//! Grow a first-deployment registry config to the current layout
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::MerchantRegistryConfig;
+use crate::errors::MerchantRegistryError;
+use crate::migration::grow_account;
+
+#[derive(Accounts)]
+pub struct MigrateRegistryConfig<'info> {
+    /// CHECK: May still be in the first-deployment layout, which `Account`
+    /// cannot load; the discriminator and authority are checked in the handler
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump,
+        owner = crate::ID
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<MigrateRegistryConfig>) -> Result<()> {
+    let config = ctx.accounts.config.to_account_info();
+
+    {
+        let data = config.try_borrow_data()?;
+        require!(
+            data.len() >= MerchantRegistryConfig::V0_SIZE,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        require!(
+            data[..8] == MerchantRegistryConfig::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        // `authority` leads every layout
+        require!(
+            data[8..40] == ctx.accounts.authority.key().to_bytes(),
+            MerchantRegistryError::Unauthorized
+        );
+    }
+
+    // The earlier layout is a prefix of the current one, so growing is the
+    // whole migration
+    grow_account(
+        &config,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + MerchantRegistryConfig::INIT_SPACE,
+    )?;
+
+    msg!("Merchant registry config migrated");
+
+    Ok(())
+}
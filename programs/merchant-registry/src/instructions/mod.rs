@@ -4,8 +4,31 @@ pub mod initialize_registry;
 pub mod register_merchant;
 pub mod update_merchant;
 pub mod revoke_merchant;
+pub mod reactivate_merchant;
+pub mod transfer_authority;
+pub mod accept_authority;
+pub mod set_registry_paused;
+pub mod check_merchant;
+pub mod add_blocked_country;
+pub mod remove_blocked_country;
+pub mod sweep_blocked_merchants;
+pub mod migrate_registry_config;
+pub mod migrate_merchant_record;
 
+// The #[program] macro looks up each context's generated client modules at the
+// crate root, so contexts are re-exported wholesale. Handlers are crate-private
+// and called by module path, which keeps the many `handler`s out of the exports.
 pub use initialize_registry::*;
 pub use register_merchant::*;
 pub use update_merchant::*;
 pub use revoke_merchant::*;
+pub use reactivate_merchant::*;
+pub use transfer_authority::*;
+pub use accept_authority::*;
+pub use set_registry_paused::*;
+pub use check_merchant::*;
+pub use add_blocked_country::*;
+pub use remove_blocked_country::*;
+pub use sweep_blocked_merchants::*;
+pub use migrate_registry_config::*;
+pub use migrate_merchant_record::*;
@@ -0,0 +1,128 @@
+//! Reactivate a previously revoked (blocked) merchant
+
+use anchor_lang::prelude::*;
+use crate::state::{MerchantRecord, MerchantRegistryConfig, risk_tier};
+use crate::errors::MerchantRegistryError;
+use crate::events::MerchantReactivated;
+
+#[derive(Accounts)]
+pub struct ReactivateMerchant<'info> {
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized,
+        constraint = !config.is_paused @ MerchantRegistryError::RegistryPaused
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.merchant_id.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, MerchantRecord>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<ReactivateMerchant>, new_risk_tier: u8) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(
+        merchant.risk_tier == risk_tier::BLOCKED,
+        MerchantRegistryError::MerchantNotBlocked
+    );
+
+    require!(
+        !config.is_country_blocked(merchant.country_code),
+        MerchantRegistryError::CountryBlocked
+    );
+
+    // A reactivated merchant must land on a non-blocked tier
+    require!(
+        (risk_tier::LOW..risk_tier::BLOCKED).contains(&new_risk_tier),
+        MerchantRegistryError::InvalidRiskTier
+    );
+
+    merchant.risk_tier = new_risk_tier;
+    merchant.is_active = true;
+    merchant.updated_at = clock.unix_timestamp;
+
+    config.blocked_count = config.blocked_count.checked_sub(1).unwrap();
+    config.last_updated = clock.unix_timestamp;
+
+    emit!(MerchantReactivated {
+        merchant_id: merchant.merchant_id,
+        reactivated_by: ctx.accounts.authority.key(),
+        new_risk_tier,
+        reactivated_at: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Reactivated merchant: {} (risk: {} -> {})",
+        merchant.merchant_name,
+        risk_tier::BLOCKED,
+        new_risk_tier
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, config_account, emitted, merchant_account, merchant_record, read,
+        registry_config, run, signer, ProgramResult, NOW,
+    };
+
+    fn reactivate(
+        authority: Pubkey,
+        merchant: MerchantRecord,
+        new_risk_tier: u8,
+    ) -> (ProgramResult, AccountInfo<'static>) {
+        let config = config_account(MerchantRegistryConfig { blocked_count: 1, ..registry_config(authority) });
+        let merchant = merchant_account(merchant);
+        let result = run(
+            vec![config, merchant.clone(), signer(authority)],
+            crate::instruction::ReactivateMerchant { new_risk_tier },
+        );
+        (result, merchant)
+    }
+
+    fn revoked() -> MerchantRecord {
+        MerchantRecord { risk_tier: risk_tier::BLOCKED, is_active: false, ..merchant_record([9; 32], *b"US") }
+    }
+
+    #[test]
+    fn reactivation_restores_the_merchant_and_is_announced() {
+        let authority = Pubkey::new_unique();
+
+        let (result, merchant) = reactivate(authority, revoked(), risk_tier::LOW);
+
+        result.unwrap();
+        let record = read::<MerchantRecord>(&merchant);
+        assert_eq!(record.risk_tier, risk_tier::LOW);
+        assert!(record.is_active);
+        let events = emitted::<MerchantReactivated>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].merchant_id, [9; 32]);
+        assert_eq!((events[0].reactivated_by, events[0].reactivated_at), (authority, NOW));
+        assert_eq!(events[0].new_risk_tier, risk_tier::LOW);
+    }
+
+    #[test]
+    fn only_blocked_merchants_reactivate_onto_an_unblocked_tier() {
+        let authority = Pubkey::new_unique();
+
+        let (result, _) = reactivate(authority, merchant_record([9; 32], *b"US"), risk_tier::LOW);
+        assert_rejected(result, MerchantRegistryError::MerchantNotBlocked);
+        let (result, _) = reactivate(authority, revoked(), risk_tier::BLOCKED);
+        assert_rejected(result, MerchantRegistryError::InvalidRiskTier);
+        assert!(emitted::<MerchantReactivated>().is_empty());
+    }
+}
@@ -3,15 +3,32 @@
 use anchor_lang::prelude::*;
 use crate::state::{MerchantRecord, MerchantRegistryConfig, MAX_MERCHANT_NAME_LEN, MAX_VISA_MID_LEN, MAX_METADATA_URI_LEN};
 use crate::errors::MerchantRegistryError;
+use crate::events::MerchantRegistered;
+use crate::country::is_valid_alpha2;
+
+/// Details of a merchant being registered
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MerchantRegistration {
+    pub merchant_id: [u8; 32],
+    pub merchant_name: String,
+    pub visa_mid: String,
+    pub mcc_code: u16,
+    pub risk_tier: u8,
+    pub country_code: [u8; 2],
+    pub metadata_uri: Option<String>,
+    pub metadata_hash: Option<[u8; 32]>,
+    pub token_account: Pubkey,
+}
 
 #[derive(Accounts)]
-#[instruction(merchant_id: [u8; 32])]
+#[instruction(registration: MerchantRegistration)]
 pub struct RegisterMerchant<'info> {
     #[account(
         mut,
         seeds = [MerchantRegistryConfig::SEED],
         bump = config.bump,
-        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized,
+        constraint = !config.is_paused @ MerchantRegistryError::RegistryPaused
     )]
     pub config: Account<'info, MerchantRegistryConfig>,
 
@@ -19,7 +36,7 @@ pub struct RegisterMerchant<'info> {
         init,
         payer = authority,
         space = MerchantRecord::space(),
-        seeds = [MerchantRecord::SEED, merchant_id.as_ref()],
+        seeds = [MerchantRecord::SEED, registration.merchant_id.as_ref()],
         bump
     )]
     pub merchant: Account<'info, MerchantRecord>,
@@ -30,16 +47,22 @@ pub struct RegisterMerchant<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
+pub(crate) fn handler(
     ctx: Context<RegisterMerchant>,
-    merchant_id: [u8; 32],
-    merchant_name: String,
-    visa_mid: String,
-    mcc_code: u16,
-    risk_tier: u8,
-    country_code: [u8; 2],
-    metadata_uri: Option<String>,
+    registration: MerchantRegistration,
 ) -> Result<()> {
+    let MerchantRegistration {
+        merchant_id,
+        merchant_name,
+        visa_mid,
+        mcc_code,
+        risk_tier,
+        country_code,
+        metadata_uri,
+        metadata_hash,
+        token_account,
+    } = registration;
+
     // Validate inputs
     require!(
         merchant_name.len() <= MAX_MERCHANT_NAME_LEN,
@@ -52,7 +75,12 @@ pub fn handler(
     );
 
     require!(
-        risk_tier >= 1 && risk_tier <= 4,
+        (1..=9999).contains(&mcc_code),
+        MerchantRegistryError::InvalidMccCode
+    );
+
+    require!(
+        (1..=4).contains(&risk_tier),
         MerchantRegistryError::InvalidRiskTier
     );
 
@@ -63,12 +91,22 @@ pub fn handler(
         );
     }
 
-    // Validate country code (basic ASCII check)
     require!(
-        country_code[0].is_ascii_uppercase() && country_code[1].is_ascii_uppercase(),
+        metadata_hash.is_none() || metadata_uri.is_some(),
+        MerchantRegistryError::MetadataHashWithoutUri
+    );
+
+    // Validate country code (uppercase ASCII alpha-2)
+    require!(
+        is_valid_alpha2(country_code),
         MerchantRegistryError::InvalidCountryCode
     );
 
+    require!(
+        !ctx.accounts.config.is_country_blocked(country_code),
+        MerchantRegistryError::CountryBlocked
+    );
+
     let clock = Clock::get()?;
     let merchant = &mut ctx.accounts.merchant;
 
@@ -83,6 +121,8 @@ pub fn handler(
     merchant.updated_at = clock.unix_timestamp;
     merchant.registered_by = ctx.accounts.authority.key();
     merchant.metadata_uri = metadata_uri;
+    merchant.metadata_hash = metadata_hash;
+    merchant.token_account = token_account;
     merchant.bump = ctx.bumps.merchant;
 
     // Update config
@@ -94,6 +134,17 @@ pub fn handler(
         config.blocked_count = config.blocked_count.checked_add(1).unwrap();
     }
 
+    emit!(MerchantRegistered {
+        merchant_id,
+        registered_by: merchant.registered_by,
+        merchant_name: merchant.merchant_name.clone(),
+        mcc_code,
+        risk_tier,
+        country_code,
+        token_account,
+        registered_at: clock.unix_timestamp,
+    });
+
     msg!(
         "Registered merchant: {} (MCC: {}, Risk: {})",
         merchant.merchant_name,
@@ -103,3 +154,135 @@ pub fn handler(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::risk_tier;
+    use crate::test_utils::{
+        assert_rejected, config_account, emitted, read, registry_config, run, signer,
+        system_program, uninitialized_account, ProgramResult, NOW,
+    };
+
+    const MERCHANT_ID: [u8; 32] = [9; 32];
+
+    fn register(authority: Pubkey, config: AccountInfo<'static>) -> (ProgramResult, AccountInfo<'static>) {
+        register_in(authority, config, *b"US")
+    }
+
+    fn register_in(
+        authority: Pubkey,
+        config: AccountInfo<'static>,
+        country_code: [u8; 2],
+    ) -> (ProgramResult, AccountInfo<'static>) {
+        register_with(authority, config, registration(country_code))
+    }
+
+    fn registration(country_code: [u8; 2]) -> MerchantRegistration {
+        MerchantRegistration {
+            merchant_id: MERCHANT_ID,
+            merchant_name: "Corner Shop".to_string(),
+            visa_mid: "MID1".to_string(),
+            mcc_code: 5411,
+            risk_tier: risk_tier::MEDIUM,
+            country_code,
+            metadata_uri: None,
+            metadata_hash: None,
+            token_account: Pubkey::new_unique(),
+        }
+    }
+
+    fn register_with(
+        authority: Pubkey,
+        config: AccountInfo<'static>,
+        registration: MerchantRegistration,
+    ) -> (ProgramResult, AccountInfo<'static>) {
+        let (key, _) = Pubkey::find_program_address(&[MerchantRecord::SEED, &MERCHANT_ID], &crate::ID);
+        let merchant = uninitialized_account(key, MerchantRecord::space());
+        let accounts = vec![config, merchant.clone(), signer(authority), system_program()];
+        (run(accounts, crate::instruction::RegisterMerchant { registration }), merchant)
+    }
+
+    fn set_paused(authority: Pubkey, config: AccountInfo<'static>, paused: bool) {
+        run(vec![config, signer(authority)], crate::instruction::SetRegistryPaused { paused }).unwrap();
+    }
+
+    #[test]
+    fn registration_fails_while_paused_and_succeeds_after_resume() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+
+        set_paused(authority, config.clone(), true);
+        assert_rejected(register(authority, config.clone()).0, MerchantRegistryError::RegistryPaused);
+        assert_eq!(read::<MerchantRegistryConfig>(&config).total_merchants, 0);
+
+        set_paused(authority, config.clone(), false);
+        let (result, merchant) = register(authority, config.clone());
+        result.unwrap();
+        let record = read::<MerchantRecord>(&merchant);
+        assert_eq!(record.merchant_id, MERCHANT_ID);
+        assert!(record.is_active);
+        assert_eq!(read::<MerchantRegistryConfig>(&config).total_merchants, 1);
+    }
+
+    #[test]
+    fn registration_is_announced() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+
+        let (result, merchant) = register(authority, config);
+
+        result.unwrap();
+        let record = read::<MerchantRecord>(&merchant);
+        let events = emitted::<MerchantRegistered>();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.merchant_id, MERCHANT_ID);
+        assert_eq!((event.registered_by, event.registered_at), (authority, NOW));
+        assert_eq!(event.merchant_name, "Corner Shop");
+        assert_eq!((event.mcc_code, event.risk_tier), (5411, risk_tier::MEDIUM));
+        assert_eq!(event.country_code, *b"US");
+        assert_eq!(event.token_account, record.token_account);
+    }
+
+    #[test]
+    fn registration_fails_in_a_blocked_country_and_succeeds_elsewhere() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+        let block = crate::instruction::AddBlockedCountry { country_code: *b"KP" };
+        run(vec![config.clone(), signer(authority)], block).unwrap();
+
+        let (result, _) = register_in(authority, config.clone(), *b"KP");
+        assert_rejected(result, MerchantRegistryError::CountryBlocked);
+        assert_eq!(read::<MerchantRegistryConfig>(&config).total_merchants, 0);
+
+        let (result, merchant) = register_in(authority, config.clone(), *b"US");
+        result.unwrap();
+        assert_eq!(read::<MerchantRecord>(&merchant).country_code, *b"US");
+        assert_eq!(read::<MerchantRegistryConfig>(&config).total_merchants, 1);
+    }
+
+    #[test]
+    fn registration_pins_the_metadata_hash_only_with_a_uri() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+
+        let unpinned = MerchantRegistration {
+            metadata_hash: Some([2; 32]),
+            ..registration(*b"US")
+        };
+        let (result, _) = register_with(authority, config.clone(), unpinned);
+        assert_rejected(result, MerchantRegistryError::MetadataHashWithoutUri);
+
+        let pinned = MerchantRegistration {
+            metadata_uri: Some("ipfs://meta".to_string()),
+            metadata_hash: Some([2; 32]),
+            ..registration(*b"US")
+        };
+        let (result, merchant) = register_with(authority, config, pinned);
+        result.unwrap();
+        let record = read::<MerchantRecord>(&merchant);
+        assert_eq!(record.metadata_uri.as_deref(), Some("ipfs://meta"));
+        assert_eq!(record.metadata_hash, Some([2; 32]));
+    }
+}
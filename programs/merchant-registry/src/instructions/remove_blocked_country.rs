@@ -0,0 +1,47 @@
+//! Unblock a country registry-wide
+
+use anchor_lang::prelude::*;
+use crate::state::MerchantRegistryConfig;
+use crate::errors::MerchantRegistryError;
+use crate::events::CountryBlockChanged;
+
+#[derive(Accounts)]
+pub struct RemoveBlockedCountry<'info> {
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Swept merchants stay inactive; reactivate them with `update_merchant`
+pub(crate) fn handler(ctx: Context<RemoveBlockedCountry>, country_code: [u8; 2]) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let Some(pos) = config.blocked_countries.iter().position(|c| *c == country_code) else {
+        return Ok(());
+    };
+
+    config.blocked_countries.remove(pos);
+    config.last_updated = clock.unix_timestamp;
+
+    emit!(CountryBlockChanged {
+        country_code,
+        is_blocked: false,
+        changed_by: ctx.accounts.authority.key(),
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Unblocked country: {}{}",
+        country_code[0] as char,
+        country_code[1] as char
+    );
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 use anchor_lang::prelude::*;
 use crate::state::{MerchantRecord, MerchantRegistryConfig, risk_tier};
 use crate::errors::MerchantRegistryError;
+use crate::events::MerchantRevoked;
 
 #[derive(Accounts)]
 pub struct RevokeMerchant<'info> {
@@ -10,7 +11,8 @@ pub struct RevokeMerchant<'info> {
         mut,
         seeds = [MerchantRegistryConfig::SEED],
         bump = config.bump,
-        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized,
+        constraint = !config.is_paused @ MerchantRegistryError::RegistryPaused
     )]
     pub config: Account<'info, MerchantRegistryConfig>,
 
@@ -25,7 +27,7 @@ pub struct RevokeMerchant<'info> {
     pub authority: Signer<'info>,
 }
 
-pub fn handler(ctx: Context<RevokeMerchant>) -> Result<()> {
+pub(crate) fn handler(ctx: Context<RevokeMerchant>) -> Result<()> {
     let merchant = &mut ctx.accounts.merchant;
     let config = &mut ctx.accounts.config;
     let clock = Clock::get()?;
@@ -47,6 +49,13 @@ pub fn handler(ctx: Context<RevokeMerchant>) -> Result<()> {
     config.blocked_count = config.blocked_count.checked_add(1).unwrap();
     config.last_updated = clock.unix_timestamp;
 
+    emit!(MerchantRevoked {
+        merchant_id: merchant.merchant_id,
+        revoked_by: ctx.accounts.authority.key(),
+        old_risk_tier: old_tier,
+        revoked_at: clock.unix_timestamp,
+    });
+
     msg!(
         "Revoked merchant: {} (risk: {} -> {})",
         merchant.merchant_name,
@@ -56,3 +65,37 @@ pub fn handler(ctx: Context<RevokeMerchant>) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, config_account, emitted, merchant_account, merchant_record, read,
+        registry_config, run, signer, ProgramResult, NOW,
+    };
+
+    fn revoke(authority: Pubkey, config: &AccountInfo<'static>, merchant: &AccountInfo<'static>) -> ProgramResult {
+        run(vec![config.clone(), merchant.clone(), signer(authority)], crate::instruction::RevokeMerchant {})
+    }
+
+    #[test]
+    fn revocation_blocks_the_merchant_and_is_announced() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+        let merchant = merchant_account(merchant_record([9; 32], *b"US"));
+
+        revoke(authority, &config, &merchant).unwrap();
+
+        let record = read::<MerchantRecord>(&merchant);
+        assert_eq!(record.risk_tier, risk_tier::BLOCKED);
+        assert!(!record.is_active);
+        assert_eq!(read::<MerchantRegistryConfig>(&config).blocked_count, 1);
+        let events = emitted::<MerchantRevoked>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].merchant_id, [9; 32]);
+        assert_eq!((events[0].revoked_by, events[0].revoked_at), (authority, NOW));
+        assert_eq!(events[0].old_risk_tier, risk_tier::MEDIUM);
+
+        assert_rejected(revoke(authority, &config, &merchant), MerchantRegistryError::MerchantAlreadyBlocked);
+    }
+}
@@ -0,0 +1,78 @@
+//! Pause or resume merchant registrations and updates
+
+use anchor_lang::prelude::*;
+use crate::state::MerchantRegistryConfig;
+use crate::errors::MerchantRegistryError;
+use crate::events::RegistryPauseChanged;
+
+#[derive(Accounts)]
+pub struct SetRegistryPaused<'info> {
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<SetRegistryPaused>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    config.is_paused = paused;
+    config.last_updated = clock.unix_timestamp;
+
+    emit!(RegistryPauseChanged {
+        authority: config.authority,
+        is_paused: paused,
+        changed_at: clock.unix_timestamp,
+    });
+
+    msg!("Merchant registry {}", if paused { "paused" } else { "resumed" });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, config_account, emitted, merchant_account, merchant_record, read,
+        registry_config, run, signer, NOW,
+    };
+
+    #[test]
+    fn pausing_blocks_revocation_and_is_announced() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+
+        run(vec![config.clone(), signer(authority)], crate::instruction::SetRegistryPaused { paused: true })
+            .unwrap();
+
+        assert!(read::<MerchantRegistryConfig>(&config).is_paused);
+        let events = emitted::<RegistryPauseChanged>();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].is_paused);
+        assert_eq!((events[0].authority, events[0].changed_at), (authority, NOW));
+
+        let merchant = merchant_account(merchant_record([9; 32], *b"US"));
+        let result = run(vec![config, merchant, signer(authority)], crate::instruction::RevokeMerchant {});
+        assert_rejected(result, MerchantRegistryError::RegistryPaused);
+    }
+
+    #[test]
+    fn only_the_authority_can_pause() {
+        let config = config_account(registry_config(Pubkey::new_unique()));
+
+        let result = run(
+            vec![config.clone(), signer(Pubkey::new_unique())],
+            crate::instruction::SetRegistryPaused { paused: true },
+        );
+
+        assert_rejected(result, MerchantRegistryError::Unauthorized);
+        assert!(!read::<MerchantRegistryConfig>(&config).is_paused);
+    }
+}
@@ -0,0 +1,118 @@
+//! Deactivate merchants registered in a blocked country
+//!
+//! Blocking a country only stops new registrations; this sweep marks the
+//! existing merchants there inactive. Pass the `MerchantRecord`s as writable
+//! remaining accounts (up to `MAX_SWEEP_BATCH` per call). Merchants outside
+//! blocked countries, or already inactive, are skipped.
+
+use anchor_lang::prelude::*;
+use crate::state::{MerchantRecord, MerchantRegistryConfig, MAX_SWEEP_BATCH};
+use crate::errors::MerchantRegistryError;
+use crate::events::MerchantUpdated;
+
+#[derive(Accounts)]
+pub struct SweepBlockedMerchants<'info> {
+    #[account(
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    pub authority: Signer<'info>,
+    // Remaining accounts: writable `MerchantRecord`s to check
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SweepBlockedMerchants<'info>>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    let merchants = ctx.remaining_accounts;
+
+    require!(
+        !merchants.is_empty() && merchants.len() <= MAX_SWEEP_BATCH,
+        MerchantRegistryError::InvalidBatchSize
+    );
+
+    let mut deactivated: u32 = 0;
+    for info in merchants {
+        require!(info.is_writable, MerchantRegistryError::MerchantNotFound);
+
+        let mut merchant: Account<MerchantRecord> = Account::try_from(info)?;
+        if !merchant.is_active || !config.is_country_blocked(merchant.country_code) {
+            continue;
+        }
+
+        merchant.is_active = false;
+        merchant.updated_at = clock.unix_timestamp;
+        merchant.exit(&crate::ID)?;
+
+        emit!(MerchantUpdated {
+            merchant_id: merchant.merchant_id,
+            updated_by: ctx.accounts.authority.key(),
+            old_risk_tier: None,
+            new_risk_tier: None,
+            old_is_active: Some(true),
+            new_is_active: Some(false),
+            old_metadata_uri: None,
+            new_metadata_uri: None,
+            old_metadata_hash: None,
+            new_metadata_hash: None,
+            old_mcc_code: None,
+            new_mcc_code: None,
+            old_merchant_name: None,
+            new_merchant_name: None,
+            old_token_account: None,
+            new_token_account: None,
+            updated_at: clock.unix_timestamp,
+        });
+
+        deactivated += 1;
+    }
+
+    msg!("Swept {} merchants, deactivated {}", merchants.len(), deactivated);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_rejected, config_account, merchant_account, merchant_record, read, registry_config,
+        run, signer,
+    };
+
+    #[test]
+    fn sweep_deactivates_only_merchants_in_blocked_countries() {
+        let authority = Pubkey::new_unique();
+        let config = config_account(MerchantRegistryConfig {
+            blocked_countries: vec![*b"KP"],
+            ..registry_config(authority)
+        });
+        let blocked = merchant_account(merchant_record([1; 32], *b"KP"));
+        let allowed = merchant_account(merchant_record([2; 32], *b"US"));
+
+        let accounts = vec![config, signer(authority), blocked.clone(), allowed.clone()];
+        run(accounts, crate::instruction::SweepBlockedMerchants {}).unwrap();
+
+        assert!(!read::<MerchantRecord>(&blocked).is_active);
+        assert!(read::<MerchantRecord>(&allowed).is_active);
+    }
+
+    #[test]
+    fn only_the_authority_sweeps() {
+        let config = config_account(MerchantRegistryConfig {
+            blocked_countries: vec![*b"KP"],
+            ..registry_config(Pubkey::new_unique())
+        });
+        let blocked = merchant_account(merchant_record([1; 32], *b"KP"));
+
+        let accounts = vec![config, signer(Pubkey::new_unique()), blocked.clone()];
+        let result = run(accounts, crate::instruction::SweepBlockedMerchants {});
+
+        assert_rejected(result, MerchantRegistryError::Unauthorized);
+        assert!(read::<MerchantRecord>(&blocked).is_active);
+    }
+}
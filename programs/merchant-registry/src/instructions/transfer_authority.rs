@@ -0,0 +1,40 @@
+//! Propose a new registry authority (two-step transfer)
+
+use anchor_lang::prelude::*;
+use crate::state::MerchantRegistryConfig;
+use crate::errors::MerchantRegistryError;
+use crate::events::AuthorityTransferInitiated;
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [MerchantRegistryConfig::SEED],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+    )]
+    pub config: Account<'info, MerchantRegistryConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    require!(
+        new_authority != config.authority,
+        MerchantRegistryError::InvalidNewAuthority
+    );
+
+    config.pending_authority = Some(new_authority);
+    config.last_updated = Clock::get()?.unix_timestamp;
+
+    emit!(AuthorityTransferInitiated {
+        current_authority: config.authority,
+        pending_authority: new_authority,
+    });
+
+    msg!("Registry authority transfer initiated: {} -> {}", config.authority, new_authority);
+
+    Ok(())
+}
@@ -1,8 +1,21 @@
-//! Update an existing merchant's status or risk tier
+//! Update an existing merchant's status, risk tier, or registration details
 
 use anchor_lang::prelude::*;
-use crate::state::{MerchantRecord, MerchantRegistryConfig, MAX_METADATA_URI_LEN};
+use crate::state::{MerchantRecord, MerchantRegistryConfig, MAX_MERCHANT_NAME_LEN, MAX_METADATA_URI_LEN};
 use crate::errors::MerchantRegistryError;
+use crate::events::MerchantUpdated;
+
+/// Changes to a merchant record; `None` leaves a field as it is
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MerchantUpdate {
+    pub risk_tier: Option<u8>,
+    pub is_active: Option<bool>,
+    pub metadata_uri: Option<String>,
+    pub metadata_hash: Option<[u8; 32]>,
+    pub mcc_code: Option<u16>,
+    pub merchant_name: Option<String>,
+    pub token_account: Option<Pubkey>,
+}
 
 #[derive(Accounts)]
 pub struct UpdateMerchant<'info> {
@@ -10,7 +23,8 @@ pub struct UpdateMerchant<'info> {
         mut,
         seeds = [MerchantRegistryConfig::SEED],
         bump = config.bump,
-        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized
+        constraint = config.authority == authority.key() @ MerchantRegistryError::Unauthorized,
+        constraint = !config.is_paused @ MerchantRegistryError::RegistryPaused
     )]
     pub config: Account<'info, MerchantRegistryConfig>,
 
@@ -25,12 +39,16 @@ pub struct UpdateMerchant<'info> {
     pub authority: Signer<'info>,
 }
 
-pub fn handler(
-    ctx: Context<UpdateMerchant>,
-    risk_tier: Option<u8>,
-    is_active: Option<bool>,
-    metadata_uri: Option<String>,
-) -> Result<()> {
+pub(crate) fn handler(ctx: Context<UpdateMerchant>, update: MerchantUpdate) -> Result<()> {
+    let MerchantUpdate {
+        risk_tier,
+        is_active,
+        metadata_uri,
+        metadata_hash,
+        mcc_code,
+        merchant_name,
+        token_account,
+    } = update;
     let merchant = &mut ctx.accounts.merchant;
     let config = &mut ctx.accounts.config;
     let clock = Clock::get()?;
@@ -38,10 +56,30 @@ pub fn handler(
     let old_risk_tier = merchant.risk_tier;
     let old_is_active = merchant.is_active;
 
+    let mut event = MerchantUpdated {
+        merchant_id: merchant.merchant_id,
+        updated_by: ctx.accounts.authority.key(),
+        old_risk_tier: None,
+        new_risk_tier: None,
+        old_is_active: None,
+        new_is_active: None,
+        old_metadata_uri: None,
+        new_metadata_uri: None,
+        old_metadata_hash: None,
+        new_metadata_hash: None,
+        old_mcc_code: None,
+        new_mcc_code: None,
+        old_merchant_name: None,
+        new_merchant_name: None,
+        old_token_account: None,
+        new_token_account: None,
+        updated_at: clock.unix_timestamp,
+    };
+
     // Update risk tier if provided
     if let Some(tier) = risk_tier {
         require!(
-            tier >= 1 && tier <= 4,
+            (1..=4).contains(&tier),
             MerchantRegistryError::InvalidRiskTier
         );
 
@@ -52,11 +90,23 @@ pub fn handler(
             config.blocked_count = config.blocked_count.checked_sub(1).unwrap();
         }
 
+        if tier != old_risk_tier {
+            event.old_risk_tier = Some(old_risk_tier);
+            event.new_risk_tier = Some(tier);
+        }
         merchant.risk_tier = tier;
     }
 
     // Update active status if provided
     if let Some(active) = is_active {
+        require!(
+            !active || !config.is_country_blocked(merchant.country_code),
+            MerchantRegistryError::CountryBlocked
+        );
+        if active != old_is_active {
+            event.old_is_active = Some(old_is_active);
+            event.new_is_active = Some(active);
+        }
         merchant.is_active = active;
     }
 
@@ -66,20 +116,209 @@ pub fn handler(
             uri.len() <= MAX_METADATA_URI_LEN,
             MerchantRegistryError::MetadataUriTooLong
         );
+        if merchant.metadata_uri.as_ref() != Some(&uri) {
+            // A pinned hash describes the old document, so it must move with the URI
+            require!(
+                merchant.metadata_hash.is_none() || metadata_hash.is_some(),
+                MerchantRegistryError::StaleMetadataHash
+            );
+            event.old_metadata_uri = merchant.metadata_uri.clone();
+            event.new_metadata_uri = Some(uri.clone());
+        }
         merchant.metadata_uri = Some(uri);
     }
 
+    // Update metadata hash if provided
+    if let Some(hash) = metadata_hash {
+        require!(
+            merchant.metadata_uri.is_some(),
+            MerchantRegistryError::MetadataHashWithoutUri
+        );
+        if merchant.metadata_hash != Some(hash) {
+            event.old_metadata_hash = merchant.metadata_hash;
+            event.new_metadata_hash = Some(hash);
+        }
+        merchant.metadata_hash = Some(hash);
+    }
+
+    // Correct the MCC if provided
+    if let Some(mcc) = mcc_code {
+        require!(
+            (1..=9999).contains(&mcc),
+            MerchantRegistryError::InvalidMccCode
+        );
+        if mcc != merchant.mcc_code {
+            event.old_mcc_code = Some(merchant.mcc_code);
+            event.new_mcc_code = Some(mcc);
+        }
+        merchant.mcc_code = mcc;
+    }
+
+    // Correct the merchant name if provided
+    if let Some(name) = merchant_name {
+        require!(
+            name.len() <= MAX_MERCHANT_NAME_LEN,
+            MerchantRegistryError::MerchantNameTooLong
+        );
+        if name != merchant.merchant_name {
+            event.old_merchant_name = Some(merchant.merchant_name.clone());
+            event.new_merchant_name = Some(name.clone());
+        }
+        merchant.merchant_name = name;
+    }
+
+    // Move the settlement account if provided
+    if let Some(account) = token_account {
+        if account != merchant.token_account {
+            event.old_token_account = Some(merchant.token_account);
+            event.new_token_account = Some(account);
+        }
+        merchant.token_account = account;
+    }
+
     merchant.updated_at = clock.unix_timestamp;
     config.last_updated = clock.unix_timestamp;
 
+    emit!(event);
+
     msg!(
-        "Updated merchant: {} (active: {} -> {}, risk: {} -> {})",
+        "Updated merchant: {} (active: {} -> {}, risk: {} -> {}, MCC: {})",
         merchant.merchant_name,
         old_is_active,
         merchant.is_active,
         old_risk_tier,
-        merchant.risk_tier
+        merchant.risk_tier,
+        merchant.mcc_code
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::risk_tier;
+    use crate::test_utils::{
+        assert_rejected, config_account, emitted, merchant_account, merchant_record, read,
+        registry_config, run, signer, ProgramResult,
+    };
+
+    const MERCHANT_ID: [u8; 32] = [9; 32];
+
+    struct Update {
+        risk_tier: Option<u8>,
+        mcc_code: Option<u16>,
+        merchant_name: Option<String>,
+    }
+
+    /// Apply `update` to a fresh merchant, returning the result, the
+    /// registry config and the merchant record
+    fn update(update: Update) -> (ProgramResult, AccountInfo<'static>, AccountInfo<'static>) {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+        let merchant = merchant_account(merchant_record(MERCHANT_ID, *b"US"));
+        let ix = crate::instruction::UpdateMerchant {
+            update: MerchantUpdate {
+                risk_tier: update.risk_tier,
+                mcc_code: update.mcc_code,
+                merchant_name: update.merchant_name,
+                ..MerchantUpdate::default()
+            },
+        };
+
+        let result = run(vec![config.clone(), merchant.clone(), signer(authority)], ix);
+        (result, config, merchant)
+    }
+
+    #[test]
+    fn mcc_and_name_corrections_are_recorded_in_the_event() {
+        let (result, _, merchant) = update(Update {
+            risk_tier: None,
+            mcc_code: Some(5812),
+            merchant_name: Some("Corner Cafe".to_string()),
+        });
+
+        result.unwrap();
+        let record = read::<MerchantRecord>(&merchant);
+        assert_eq!(record.mcc_code, 5812);
+        assert_eq!(record.merchant_name, "Corner Cafe");
+
+        let events = emitted::<MerchantUpdated>();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!((event.old_mcc_code, event.new_mcc_code), (Some(5411), Some(5812)));
+        assert_eq!(event.old_merchant_name.as_deref(), Some("Corner Shop"));
+        assert_eq!(event.new_merchant_name.as_deref(), Some("Corner Cafe"));
+        assert_eq!((event.old_risk_tier, event.new_risk_tier), (None, None));
+    }
+
+    #[test]
+    fn corrections_are_validated_like_registration() {
+        let invalid_mcc = Update { risk_tier: None, mcc_code: Some(10_000), merchant_name: None };
+        assert_rejected(update(invalid_mcc).0, MerchantRegistryError::InvalidMccCode);
+
+        let long_name = Update {
+            risk_tier: None,
+            mcc_code: None,
+            merchant_name: Some("n".repeat(MAX_MERCHANT_NAME_LEN + 1)),
+        };
+        assert_rejected(update(long_name).0, MerchantRegistryError::MerchantNameTooLong);
+    }
+
+    #[test]
+    fn blocking_a_merchant_is_counted() {
+        let block = Update { risk_tier: Some(risk_tier::BLOCKED), mcc_code: None, merchant_name: None };
+
+        let (result, config, _) = update(block);
+
+        result.unwrap();
+        assert_eq!(read::<MerchantRegistryConfig>(&config).blocked_count, 1);
+        let event = &emitted::<MerchantUpdated>()[0];
+        assert_eq!(event.new_risk_tier, Some(risk_tier::BLOCKED));
+        assert_eq!(event.old_mcc_code, None);
+    }
+
+    fn update_metadata(
+        merchant: &AccountInfo<'static>,
+        metadata_uri: Option<String>,
+        metadata_hash: Option<[u8; 32]>,
+    ) -> ProgramResult {
+        let authority = Pubkey::new_unique();
+        let config = config_account(registry_config(authority));
+        let ix = crate::instruction::UpdateMerchant {
+            update: MerchantUpdate { metadata_uri, metadata_hash, ..MerchantUpdate::default() },
+        };
+        run(vec![config, merchant.clone(), signer(authority)], ix)
+    }
+
+    #[test]
+    fn metadata_uri_and_hash_move_together() {
+        let merchant = merchant_account(MerchantRecord {
+            metadata_uri: Some("ipfs://old".to_string()),
+            metadata_hash: Some([1; 32]),
+            ..merchant_record(MERCHANT_ID, *b"US")
+        });
+
+        let stale = update_metadata(&merchant, Some("ipfs://new".to_string()), None);
+        assert_rejected(stale, MerchantRegistryError::StaleMetadataHash);
+        assert_eq!(read::<MerchantRecord>(&merchant).metadata_uri.as_deref(), Some("ipfs://old"));
+
+        update_metadata(&merchant, Some("ipfs://new".to_string()), Some([2; 32])).unwrap();
+
+        let record = read::<MerchantRecord>(&merchant);
+        assert_eq!(record.metadata_uri.as_deref(), Some("ipfs://new"));
+        assert_eq!(record.metadata_hash, Some([2; 32]));
+        let event = &emitted::<MerchantUpdated>()[0];
+        assert_eq!((event.old_metadata_hash, event.new_metadata_hash), (Some([1; 32]), Some([2; 32])));
+        assert_eq!(event.new_metadata_uri.as_deref(), Some("ipfs://new"));
+    }
+
+    #[test]
+    fn metadata_hash_needs_a_uri() {
+        let merchant = merchant_account(merchant_record(MERCHANT_ID, *b"US"));
+
+        let result = update_metadata(&merchant, None, Some([2; 32]));
+
+        assert_rejected(result, MerchantRegistryError::MetadataHashWithoutUri);
+    }
+}
@@ -8,6 +8,11 @@ use anchor_lang::prelude::*;
 pub mod state;
 pub mod instructions;
 pub mod errors;
+pub mod events;
+pub mod country;
+pub mod migration;
+#[cfg(test)]
+mod test_utils;
 
 use instructions::*;
 
@@ -25,38 +30,76 @@ pub mod merchant_registry {
     /// Register a new merchant
     pub fn register_merchant(
         ctx: Context<RegisterMerchant>,
-        merchant_id: [u8; 32],
-        merchant_name: String,
-        visa_mid: String,
-        mcc_code: u16,
-        risk_tier: u8,
-        country_code: [u8; 2],
-        metadata_uri: Option<String>,
+        registration: MerchantRegistration,
     ) -> Result<()> {
-        instructions::register_merchant::handler(
-            ctx,
-            merchant_id,
-            merchant_name,
-            visa_mid,
-            mcc_code,
-            risk_tier,
-            country_code,
-            metadata_uri,
-        )
+        instructions::register_merchant::handler(ctx, registration)
     }
 
     /// Update merchant status or risk tier
-    pub fn update_merchant(
-        ctx: Context<UpdateMerchant>,
-        risk_tier: Option<u8>,
-        is_active: Option<bool>,
-        metadata_uri: Option<String>,
-    ) -> Result<()> {
-        instructions::update_merchant::handler(ctx, risk_tier, is_active, metadata_uri)
+    pub fn update_merchant(ctx: Context<UpdateMerchant>, update: MerchantUpdate) -> Result<()> {
+        instructions::update_merchant::handler(ctx, update)
     }
 
     /// Revoke a merchant (set to blocked)
     pub fn revoke_merchant(ctx: Context<RevokeMerchant>) -> Result<()> {
         instructions::revoke_merchant::handler(ctx)
     }
+
+    /// Reactivate a revoked merchant at a non-blocked risk tier
+    pub fn reactivate_merchant(ctx: Context<ReactivateMerchant>, new_risk_tier: u8) -> Result<()> {
+        instructions::reactivate_merchant::handler(ctx, new_risk_tier)
+    }
+
+    /// Propose a new registry authority (takes effect once accepted)
+    pub fn transfer_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::transfer_authority::handler(ctx, new_authority)
+    }
+
+    /// Accept a pending registry authority transfer
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority::handler(ctx)
+    }
+
+    /// Report a merchant's validity, risk tier, MCC, and country (read-only)
+    pub fn check_merchant(ctx: Context<CheckMerchant>) -> Result<MerchantCheck> {
+        instructions::check_merchant::handler(ctx)
+    }
+
+    /// Pause or resume merchant registrations, updates, and revocations
+    pub fn set_registry_paused(ctx: Context<SetRegistryPaused>, paused: bool) -> Result<()> {
+        instructions::set_registry_paused::handler(ctx, paused)
+    }
+
+    /// Block merchant registration in a country registry-wide
+    pub fn add_blocked_country(ctx: Context<AddBlockedCountry>, country_code: [u8; 2]) -> Result<()> {
+        instructions::add_blocked_country::handler(ctx, country_code)
+    }
+
+    /// Lift a registry-wide country block
+    pub fn remove_blocked_country(
+        ctx: Context<RemoveBlockedCountry>,
+        country_code: [u8; 2],
+    ) -> Result<()> {
+        instructions::remove_blocked_country::handler(ctx, country_code)
+    }
+
+    /// Deactivate existing merchants located in blocked countries
+    pub fn sweep_blocked_merchants<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepBlockedMerchants<'info>>,
+    ) -> Result<()> {
+        instructions::sweep_blocked_merchants::handler(ctx)
+    }
+
+    /// Grow a registry config written by the first deployment to the current layout
+    pub fn migrate_registry_config(ctx: Context<MigrateRegistryConfig>) -> Result<()> {
+        instructions::migrate_registry_config::handler(ctx)
+    }
+
+    /// Grow a merchant record written by the first deployment to the current layout
+    pub fn migrate_merchant_record(
+        ctx: Context<MigrateMerchantRecord>,
+        merchant_id: [u8; 32],
+    ) -> Result<()> {
+        instructions::migrate_merchant_record::handler(ctx, merchant_id)
+    }
 }
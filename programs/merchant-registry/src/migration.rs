@@ -0,0 +1,37 @@
+//! Account migration helpers
+//!
+//! Fields added after the first deployment are appended after `bump`, so an
+//! earlier account keeps its layout as a prefix and only needs to grow. The
+//! zeroed tail decodes as `None`, `false`, `0`, or an empty `Vec`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Grow a program account to `new_size`, topping up rent from `payer`
+///
+/// New bytes are zeroed. Accounts already at least `new_size` are left alone.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_size: usize,
+) -> Result<()> {
+    if account.data_len() >= new_size {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(new_size);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer { from: payer.clone(), to: account.clone() },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.realloc(new_size, true)?;
+    Ok(())
+}
@@ -11,6 +11,12 @@ pub const MAX_VISA_MID_LEN: usize = 16;
 /// Maximum length for metadata URI
 pub const MAX_METADATA_URI_LEN: usize = 200;
 
+/// Maximum number of registry-wide blocked countries
+pub const MAX_BLOCKED_COUNTRIES: usize = 32;
+
+/// Maximum number of merchants deactivated in one sweep
+pub const MAX_SWEEP_BATCH: usize = 16;
+
 /// Merchant Registry Configuration
 #[account]
 #[derive(InitSpace)]
@@ -29,10 +35,31 @@ pub struct MerchantRegistryConfig {
 
     /// PDA bump seed
     pub bump: u8,
+
+    // Fields below were added after the first deployment; they stay after
+    // `bump` so earlier accounts only need to grow (see `migrate_registry_config`)
+
+    /// Proposed new authority awaiting acceptance
+    pub pending_authority: Option<Pubkey>,
+
+    /// Whether merchant mutations are halted (emergency stop)
+    pub is_paused: bool,
+
+    /// ISO 3166-1 alpha-2 countries no merchant may be registered or active in
+    #[max_len(MAX_BLOCKED_COUNTRIES)]
+    pub blocked_countries: Vec<[u8; 2]>,
 }
 
 impl MerchantRegistryConfig {
     pub const SEED: &'static [u8] = b"merchant_config";
+
+    /// Account size written by the first deployment, which ended at `bump`
+    pub const V0_SIZE: usize = 8 + 32 + 8 + 8 + 8 + 1;
+
+    /// Check if a country is blocked registry-wide
+    pub fn is_country_blocked(&self, country_code: [u8; 2]) -> bool {
+        self.blocked_countries.contains(&country_code)
+    }
 }
 
 /// Individual Merchant Record
@@ -77,6 +104,16 @@ pub struct MerchantRecord {
 
     /// PDA bump seed
     pub bump: u8,
+
+    // Fields below were added after the first deployment; they stay after
+    // `bump` so earlier records only need to grow (see `migrate_merchant_record`)
+
+    /// SHA-256 of the metadata document, so clients can verify what they fetch
+    pub metadata_hash: Option<[u8; 32]>,
+
+    /// Token account the merchant settles into; card transfers are only
+    /// attributed to this merchant when they pay this account
+    pub token_account: Pubkey,
 }
 
 impl MerchantRecord {
@@ -101,7 +138,9 @@ impl MerchantRecord {
         8 + // updated_at
         32 + // registered_by
         1 + 4 + MAX_METADATA_URI_LEN + // metadata_uri (optional string)
-        1 // bump
+        1 + // bump
+        1 + 32 + // metadata_hash (optional)
+        32 // token_account
     }
 }
 
@@ -116,3 +155,53 @@ pub mod risk_tier {
     /// Blocked - always deny
     pub const BLOCKED: u8 = 4;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialized length of an account, discriminator included
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.len()
+    }
+
+    #[test]
+    fn full_merchant_record_fits_space() {
+        let record = MerchantRecord {
+            merchant_id: [1; 32],
+            merchant_name: "n".repeat(MAX_MERCHANT_NAME_LEN),
+            visa_mid: "v".repeat(MAX_VISA_MID_LEN),
+            mcc_code: 5411,
+            risk_tier: risk_tier::BLOCKED,
+            is_active: true,
+            country_code: *b"US",
+            registered_at: i64::MAX,
+            updated_at: i64::MAX,
+            registered_by: Pubkey::default(),
+            metadata_uri: Some("u".repeat(MAX_METADATA_URI_LEN)),
+            bump: u8::MAX,
+            metadata_hash: Some([2; 32]),
+            token_account: Pubkey::default(),
+        };
+
+        assert_eq!(serialized_len(&record), MerchantRecord::space());
+    }
+
+    #[test]
+    fn full_registry_config_fits_space() {
+        let config = MerchantRegistryConfig {
+            authority: Pubkey::default(),
+            total_merchants: u64::MAX,
+            blocked_count: u64::MAX,
+            last_updated: i64::MAX,
+            bump: u8::MAX,
+            pending_authority: Some(Pubkey::default()),
+            is_paused: true,
+            blocked_countries: vec![*b"KP"; MAX_BLOCKED_COUNTRIES],
+        };
+
+        assert_eq!(serialized_len(&config), 8 + MerchantRegistryConfig::INIT_SPACE);
+    }
+}
@@ -0,0 +1,170 @@
+//! Off-chain harness for running instructions through the program entrypoint
+
+use std::cell::RefCell;
+
+use anchor_lang::prelude::*;
+pub use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::{
+    entrypoint::SUCCESS,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    system_program,
+};
+use anchor_lang::{Event, InstructionData};
+
+use crate::errors::MerchantRegistryError;
+use crate::state::{risk_tier, MerchantRecord, MerchantRegistryConfig};
+
+pub const NOW: i64 = 1_700_000_000;
+
+thread_local! {
+    /// Data logged by `emit!` on this thread
+    static LOGGED_DATA: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+
+    /// Return data set by the instruction under test on this thread
+    static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Serves `Clock::get` and `Rent::get` off-chain and captures emitted events
+/// and return data
+///
+/// CPIs are left to the default no-op stub, so `init` accounts must be
+/// passed in already owned by the program (see `uninitialized_account`).
+struct TestSyscalls;
+
+impl SyscallStubs for TestSyscalls {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock { slot: 1, unix_timestamp: NOW, ..Default::default() };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        LOGGED_DATA.with(|logged| logged.borrow_mut().push(data.concat()));
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = data.to_vec());
+    }
+}
+
+/// An account leaked for the `'static` lifetime the entrypoint borrows for
+pub fn account(key: Pubkey, owner: Pubkey, is_writable: bool, data: Vec<u8>) -> AccountInfo<'static> {
+    AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        is_writable,
+        Box::leak(Box::new(1_000_000_000)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        0,
+    )
+}
+
+pub fn signer(key: Pubkey) -> AccountInfo<'static> {
+    AccountInfo { is_signer: true, ..account(key, system_program::ID, true, vec![]) }
+}
+
+pub fn anchor_data<T: AccountSerialize>(account: &T, len: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(len);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    data
+}
+
+/// A registry config governed by `authority`, unpaused and with no blocked countries
+pub fn registry_config(authority: Pubkey) -> MerchantRegistryConfig {
+    MerchantRegistryConfig {
+        authority,
+        total_merchants: 0,
+        blocked_count: 0,
+        last_updated: 0,
+        bump: 0,
+        pending_authority: None,
+        is_paused: false,
+        blocked_countries: vec![],
+    }
+}
+
+/// The registry config PDA holding `config`
+pub fn config_account(mut config: MerchantRegistryConfig) -> AccountInfo<'static> {
+    let (key, bump) = Pubkey::find_program_address(&[MerchantRegistryConfig::SEED], &crate::ID);
+    config.bump = bump;
+    let space = 8 + MerchantRegistryConfig::INIT_SPACE;
+    account(key, crate::ID, true, anchor_data(&config, space))
+}
+
+/// An active, medium-risk merchant
+pub fn merchant_record(merchant_id: [u8; 32], country_code: [u8; 2]) -> MerchantRecord {
+    MerchantRecord {
+        merchant_id,
+        merchant_name: "Corner Shop".to_string(),
+        visa_mid: "MID1".to_string(),
+        mcc_code: 5411,
+        risk_tier: risk_tier::MEDIUM,
+        is_active: true,
+        country_code,
+        registered_at: 0,
+        updated_at: 0,
+        registered_by: Pubkey::default(),
+        metadata_uri: None,
+        bump: 0,
+        metadata_hash: None,
+        token_account: Pubkey::new_unique(),
+    }
+}
+
+/// The merchant record PDA holding `record`
+pub fn merchant_account(mut record: MerchantRecord) -> AccountInfo<'static> {
+    let (key, bump) = Pubkey::find_program_address(&[MerchantRecord::SEED, &record.merchant_id], &crate::ID);
+    record.bump = bump;
+    account(key, crate::ID, true, anchor_data(&record, 8 + MerchantRecord::INIT_SPACE))
+}
+
+/// A zeroed program-owned account standing in for one `init` would create
+pub fn uninitialized_account(key: Pubkey, space: usize) -> AccountInfo<'static> {
+    account(key, crate::ID, true, vec![0; space])
+}
+
+/// The system program account
+pub fn system_program() -> AccountInfo<'static> {
+    AccountInfo { executable: true, ..account(system_program::ID, Pubkey::default(), false, vec![]) }
+}
+
+/// Run an instruction through the program entrypoint
+pub fn run<I: InstructionData>(accounts: Vec<AccountInfo<'static>>, ix: I) -> ProgramResult {
+    set_syscall_stubs(Box::new(TestSyscalls));
+    LOGGED_DATA.with(|logged| logged.borrow_mut().clear());
+    RETURN_DATA.with(|return_data| return_data.borrow_mut().clear());
+    let accounts: &'static [AccountInfo<'static>] = Box::leak(accounts.into_boxed_slice());
+    crate::entry(&crate::ID, accounts, &ix.data())
+}
+
+/// Deserialize an Anchor account left behind by an instruction
+pub fn read<T: AccountDeserialize>(info: &AccountInfo) -> T {
+    T::try_deserialize(&mut &info.try_borrow_data().unwrap()[..]).unwrap()
+}
+
+/// Return data set by the last instruction run on this thread
+pub fn return_data() -> Vec<u8> {
+    RETURN_DATA.with(|return_data| return_data.borrow().clone())
+}
+
+/// Events of type `E` emitted by the last instruction run on this thread
+pub fn emitted<E: Event>() -> Vec<E> {
+    LOGGED_DATA.with(|logged| {
+        logged.borrow().iter()
+            .filter_map(|data| data.strip_prefix(E::DISCRIMINATOR.as_slice()))
+            .map(|mut body| E::deserialize(&mut body).unwrap())
+            .collect()
+    })
+}
+
+pub fn assert_rejected(result: ProgramResult, expected: MerchantRegistryError) {
+    assert_eq!(result, Err(ProgramError::Custom(expected.into())), "expected {expected:?}");
+}